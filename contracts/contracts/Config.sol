@@ -25,6 +25,12 @@ contract Config {
     /// @notice Ethereum signature r/s bytes length
     uint8 constant ETH_SIGN_RS_BYTES = 32;
 
+    /// @notice Ethereum signature (r, s, v) bytes length
+    uint8 constant ETH_SIGNATURE_BYTES = 2 * ETH_SIGN_RS_BYTES + 1;
+
+    /// @notice Time range (uint64 validFrom, uint64 validUntil) bytes length
+    uint8 constant TIME_RANGE_BYTES = 16;
+
     /// @notice Success flag bytes length
     uint8 constant SUCCESS_FLAG_BYTES = 1;
 
@@ -57,6 +63,12 @@ contract Config {
     /// @notice ChangePubKey operation length
     uint256 constant CHANGE_PUBKEY_BYTES = 6 * CHUNK_BYTES;
 
+    /// @notice ForcedExit operation length
+    uint256 constant FORCED_EXIT_BYTES = 6 * CHUNK_BYTES;
+
+    /// @notice Swap operation length
+    uint256 constant SWAP_BYTES = 5 * CHUNK_BYTES;
+
     /// @notice Expiration delta for priority request to be satisfied (in seconds)
     /// NOTE: Priority expiration should be > (EXPECT_VERIFICATION_IN * BLOCK_PERIOD), otherwise incorrect block with priority op could not be reverted.
     uint256 constant PRIORITY_EXPIRATION_PERIOD = 3 days;
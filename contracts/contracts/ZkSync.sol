@@ -253,24 +253,66 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         uint32[] calldata _ethWitnessSizes
     ) external nonReentrant {
         requireActive();
-        require(_blockNumber == totalBlocksCommitted + 1, "fck11"); // only commit next block
         governance.requireActiveValidator(msg.sender);
         require(_newBlockInfo.length == 1, "fck13"); // This version of the contract expects only account tree root hash
 
-        bytes memory publicData = _publicData;
-
-        // Unpack onchain operations and store them.
-        // Get priority operations number for this block.
-        uint64 prevTotalCommittedPriorityRequests = totalCommittedPriorityRequests;
-
-        bytes32 withdrawalsDataHash = collectOnchainOps(_blockNumber, publicData, _ethWitness, _ethWitnessSizes);
+        commitOneBlock(_blockNumber, _feeAccount, _newBlockInfo[0], _publicData, _ethWitness, _ethWitnessSizes);
+    }
+
+    /// @notice Commit several consecutive blocks within one call, see `commitBlock` for details.
+    /// @notice Data of every block is concatenated with the data of other blocks, sizes are used to split it.
+    /// @param _blockNumberFrom Number of the first committed block
+    /// @param _feeAccounts Accounts to collect fees, one per block
+    /// @param _newRoots Account tree root hashes, one per block
+    /// @param _publicData Concatenated operations pubdata of the blocks
+    /// @param _publicDataSizes Amount of pubdata bytes for the corresponding block
+    /// @param _ethWitness Concatenated data passed to ethereum outside pubdata of the circuit
+    /// @param _ethWitnessSizes Concatenated amounts of eth witness bytes for the operations of the blocks
+    /// @param _ethWitnessSizesCounts Amount of `_ethWitnessSizes` elements for the corresponding block
+    function commitMultipleBlocks(
+        uint32 _blockNumberFrom,
+        uint32[] calldata _feeAccounts,
+        bytes32[] calldata _newRoots,
+        bytes calldata _publicData,
+        uint32[] calldata _publicDataSizes,
+        bytes calldata _ethWitness,
+        uint32[] calldata _ethWitnessSizes,
+        uint32[] calldata _ethWitnessSizesCounts
+    ) external nonReentrant {
+        requireActive();
+        governance.requireActiveValidator(msg.sender);
+        uint256 nBlocks = _feeAccounts.length;
+        require(nBlocks > 0, "fcm11"); // at least one block should be committed
+        require(
+            _newRoots.length == nBlocks && _publicDataSizes.length == nBlocks && _ethWitnessSizesCounts.length == nBlocks,
+            "fcm12"
+        ); // data should be provided for every block
+
+        uint256 publicDataOffset = 0;
+        uint256 ethWitnessOffset = 0;
+        uint256 ethWitnessSizesOffset = 0;
+        for (uint256 i = 0; i < nBlocks; ++i) {
+            bytes memory publicData = Bytes.slice(_publicData, publicDataOffset, _publicDataSizes[i]);
+            publicDataOffset += _publicDataSizes[i];
+
+            require(ethWitnessSizesOffset + _ethWitnessSizesCounts[i] <= _ethWitnessSizes.length, "fcm13"); // not enough eth witness sizes
+            uint32[] memory ethWitnessSizes = new uint32[](_ethWitnessSizesCounts[i]);
+            uint256 ethWitnessLength = 0;
+            for (uint256 j = 0; j < ethWitnessSizes.length; ++j) {
+                ethWitnessSizes[j] = _ethWitnessSizes[ethWitnessSizesOffset + j];
+                ethWitnessLength += ethWitnessSizes[j];
+            }
+            ethWitnessSizesOffset += ethWitnessSizes.length;
 
-        uint64 nPriorityRequestProcessed = totalCommittedPriorityRequests - prevTotalCommittedPriorityRequests;
+            bytes memory ethWitness = Bytes.slice(_ethWitness, ethWitnessOffset, ethWitnessLength);
+            ethWitnessOffset += ethWitnessLength;
 
-        createCommittedBlock(_blockNumber, _feeAccount, _newBlockInfo[0], publicData, withdrawalsDataHash, nPriorityRequestProcessed);
-        totalBlocksCommitted++;
-
-        emit BlockCommit(_blockNumber);
+            commitOneBlock(uint32(_blockNumberFrom + i), _feeAccounts[i], _newRoots[i], publicData, ethWitness, ethWitnessSizes);
+        }
+        require(
+            publicDataOffset == _publicData.length && ethWitnessOffset == _ethWitness.length && ethWitnessSizesOffset == _ethWitnessSizes.length,
+            "fcm14"
+        ); // all the provided data should be used
     }
 
     /// @notice Block verification.
@@ -282,9 +324,78 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         external nonReentrant
     {
         requireActive();
-        require(_blockNumber == totalBlocksVerified + 1, "fvk11"); // only verify next block
         governance.requireActiveValidator(msg.sender);
 
+        verifyOneBlock(_blockNumber, _proof, _withdrawalsData);
+    }
+
+    /// @notice Verify several consecutive blocks within one call, see `verifyBlock` for details.
+    /// @notice Data of every block is concatenated with the data of other blocks, sizes are used to split it.
+    /// @param _blockNumberFrom Number of the first verified block
+    /// @param _proofs Concatenated block proofs
+    /// @param _proofSizes Amount of proof elements for the corresponding block
+    /// @param _withdrawalsData Concatenated block withdrawals data
+    /// @param _withdrawalsDataSizes Amount of withdrawals data bytes for the corresponding block
+    function verifyMultipleBlocks(
+        uint32 _blockNumberFrom,
+        uint256[] calldata _proofs,
+        uint32[] calldata _proofSizes,
+        bytes calldata _withdrawalsData,
+        uint32[] calldata _withdrawalsDataSizes
+    ) external nonReentrant {
+        requireActive();
+        governance.requireActiveValidator(msg.sender);
+        uint256 nBlocks = _proofSizes.length;
+        require(nBlocks > 0, "fvm11"); // at least one block should be verified
+        require(_withdrawalsDataSizes.length == nBlocks, "fvm12"); // data should be provided for every block
+
+        uint256 proofOffset = 0;
+        uint256 withdrawalsDataOffset = 0;
+        for (uint256 i = 0; i < nBlocks; ++i) {
+            require(proofOffset + _proofSizes[i] <= _proofs.length, "fvm13"); // not enough proof elements
+            uint256[] memory proof = new uint256[](_proofSizes[i]);
+            for (uint256 j = 0; j < proof.length; ++j) {
+                proof[j] = _proofs[proofOffset + j];
+            }
+            proofOffset += proof.length;
+
+            bytes memory withdrawalsData = Bytes.slice(_withdrawalsData, withdrawalsDataOffset, _withdrawalsDataSizes[i]);
+            withdrawalsDataOffset += _withdrawalsDataSizes[i];
+
+            verifyOneBlock(uint32(_blockNumberFrom + i), proof, withdrawalsData);
+        }
+        require(proofOffset == _proofs.length && withdrawalsDataOffset == _withdrawalsData.length, "fvm14"); // all the provided data should be used
+    }
+
+    /// @notice Commits the next block, caller is expected to check the validator and the contract state.
+    function commitOneBlock(
+        uint32 _blockNumber,
+        uint32 _feeAccount,
+        bytes32 _newRoot,
+        bytes memory _publicData,
+        bytes memory _ethWitness,
+        uint32[] memory _ethWitnessSizes
+    ) internal {
+        require(_blockNumber == totalBlocksCommitted + 1, "fck11"); // only commit next block
+
+        // Unpack onchain operations and store them.
+        // Get priority operations number for this block.
+        uint64 prevTotalCommittedPriorityRequests = totalCommittedPriorityRequests;
+
+        bytes32 withdrawalsDataHash = collectOnchainOps(_blockNumber, _publicData, _ethWitness, _ethWitnessSizes);
+
+        uint64 nPriorityRequestProcessed = totalCommittedPriorityRequests - prevTotalCommittedPriorityRequests;
+
+        createCommittedBlock(_blockNumber, _feeAccount, _newRoot, _publicData, withdrawalsDataHash, nPriorityRequestProcessed);
+        totalBlocksCommitted++;
+
+        emit BlockCommit(_blockNumber);
+    }
+
+    /// @notice Verifies the next block, caller is expected to check the validator and the contract state.
+    function verifyOneBlock(uint32 _blockNumber, uint256[] memory _proof, bytes memory _withdrawalsData) internal {
+        require(_blockNumber == totalBlocksVerified + 1, "fvk11"); // only verify next block
+
         require(verifier.verifyBlockProof(_proof, blocks[_blockNumber].commitment, blocks[_blockNumber].chunks), "fvk13"); // proof verification failed
 
         processOnchainWithdrawals(_withdrawalsData, blocks[_blockNumber].withdrawalsDataHash);
@@ -491,6 +602,8 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
                 pubDataPtr += NOOP_BYTES;
             } else if (opType == Operations.OpType.TransferToNew) {
                 pubDataPtr += TRANSFER_TO_NEW_BYTES;
+            } else if (opType == Operations.OpType.Swap) {
+                pubDataPtr += SWAP_BYTES;
             } else {
                 // other operations processing
 
@@ -553,6 +666,13 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
                     processedOperationsRequiringEthWitness++;
 
                     pubDataPtr += CHANGE_PUBKEY_BYTES;
+                } else if (opType == Operations.OpType.ForcedExit) {
+                    Operations.ForcedExit memory data = Operations.readForcedExitPubdata(_publicData, pubdataOffset + 1);
+
+                    bool addToPendingWithdrawalsQueue = true;
+                    withdrawalsDataHash = keccak256(abi.encode(withdrawalsDataHash, addToPendingWithdrawalsQueue, data.target, data.tokenId, data.amount));
+
+                    pubDataPtr += FORCED_EXIT_BYTES;
                 } else {
                     revert("fpp14"); // unsupported op
                 }
@@ -567,22 +687,35 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
     }
 
     /// @notice Checks that signature is valid for pubkey change message
-    /// @param _signature Signature
+    /// @param _ethWitness Signature, optionally followed by the signed time range (uint64 validFrom, uint64 validUntil)
     /// @param _newPkHash New pubkey hash
     /// @param _nonce Nonce used for message
     /// @param _ethAddress Account's ethereum address
     /// @param _accountId Id of zkSync account
-    function verifyChangePubkeySignature(bytes memory _signature, bytes20 _newPkHash, uint32 _nonce, address _ethAddress, uint32 _accountId) internal pure returns (bool) {
+    function verifyChangePubkeySignature(bytes memory _ethWitness, bytes20 _newPkHash, uint32 _nonce, address _ethAddress, uint32 _accountId) internal pure returns (bool) {
+        bytes memory signature = _ethWitness;
+        bytes memory messageLength = "152";
+        bytes memory timeRangeMessage = "";
+        if (_ethWitness.length == ETH_SIGNATURE_BYTES + TIME_RANGE_BYTES) {
+            signature = Bytes.slice(_ethWitness, 0, ETH_SIGNATURE_BYTES);
+            messageLength = "215";
+            timeRangeMessage = abi.encodePacked(
+                "\nvalid from: 0x", Bytes.bytesToHexASCIIBytes(Bytes.slice(_ethWitness, ETH_SIGNATURE_BYTES, TIME_RANGE_BYTES / 2)),
+                "\nvalid until: 0x", Bytes.bytesToHexASCIIBytes(Bytes.slice(_ethWitness, ETH_SIGNATURE_BYTES + TIME_RANGE_BYTES / 2, TIME_RANGE_BYTES / 2))
+            );
+        }
+
         bytes memory signedMessage = abi.encodePacked(
-            "\x19Ethereum Signed Message:\n152",
+            "\x19Ethereum Signed Message:\n", messageLength,
             "Register zkSync pubkey:\n\n",
             Bytes.bytesToHexASCIIBytes(abi.encodePacked(_newPkHash)), "\n",
             "nonce: 0x", Bytes.bytesToHexASCIIBytes(Bytes.toBytesFromUInt32(_nonce)), "\n",
             "account id: 0x", Bytes.bytesToHexASCIIBytes(Bytes.toBytesFromUInt32(_accountId)),
+            timeRangeMessage,
             "\n\n",
             "Only sign this message for a trusted client!"
         );
-        address recoveredAddress = Utils.recoverAddressFromEthSignature(_signature, signedMessage);
+        address recoveredAddress = Utils.recoverAddressFromEthSignature(signature, signedMessage);
         return recoveredAddress == _ethAddress;
     }
 
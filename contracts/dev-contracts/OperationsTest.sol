@@ -79,6 +79,21 @@ contract OperationsTest {
         require(x.amount == r.amount,   "amount mismatch");
     }
 
+    function testForcedExit() external pure {
+        Operations.ForcedExit memory x = Operations.ForcedExit({
+            tokenId: 0x3132,
+            amount: 0x101112131415161718191a1b1c1d1e1f,
+            target: 0x823B747710C5bC9b8A47243f2c3d1805F1aA00c5
+        });
+
+        bytes memory pubdata = Operations.writeForcedExitPubdata(x);
+        Operations.ForcedExit memory r = Operations.readForcedExitPubdata(pubdata, 0);
+
+        require(x.target == r.target,   "target mismatch");
+        require(x.tokenId == r.tokenId, "tokenId mismatch");
+        require(x.amount == r.amount,   "amount mismatch");
+    }
+
     function parseDepositFromPubdata(bytes calldata _pubdata) external pure returns (uint16 tokenId, uint128 amount, address owner) {
         Operations.Deposit memory r = Operations.readDepositPubdata(_pubdata);
         return (r.tokenId, r.amount, r.owner);
@@ -9,8 +9,8 @@ contract ZkSyncProcessOpUnitTest is ZkSyncTest {
         bytes calldata _publicData,
         bytes calldata _ethWitness,
         uint32[] calldata _ethWitnessSizes
-    ) external {
-        collectOnchainOps(0, _publicData, _ethWitness, _ethWitnessSizes);
+    ) external returns (bytes32 withdrawalsDataHash) {
+        withdrawalsDataHash = collectOnchainOps(0, _publicData, _ethWitness, _ethWitnessSizes);
     }
 
 }
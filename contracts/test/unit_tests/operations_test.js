@@ -97,4 +97,10 @@ describe("Operations unit tests", function () {
         await testContract.testPartialExit()
     });
 
+    // Forced exit
+
+    it("should convert ForcedExit pubdata", async () => {
+        await testContract.testForcedExit()
+    });
+
 });
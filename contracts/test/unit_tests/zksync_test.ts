@@ -39,6 +39,26 @@ describe("zkSync signature verification unit tests", function() {
         expect(result).eq(true);
     });
 
+    it("pubkey hash signature verification with time range success", async () => {
+        const pubkeyHash = "sync:fefefefefefefefefefefefefefefefefefefefe";
+        const nonce = 0x11223344;
+        const accountId = 0xdeadba;
+        const validFrom = 100;
+        const validUntil = 200;
+        const signature = await randomWallet.signMessage(
+            zksync.utils.getChangePubkeyMessage(pubkeyHash, nonce, accountId, validFrom, validUntil));
+        const ethWitness = ethers.utils.hexConcat([signature, zksync.utils.serializeTimeRange(validFrom, validUntil)]);
+        const {result} = await getCallRevertReason(() =>
+            testContract.changePubkeySignatureCheck(ethWitness, pubkeyHash.replace("sync:", "0x"), nonce, randomWallet.address, accountId));
+        expect(result).eq(true);
+
+        // Time range is a part of the signed message.
+        const incorrectWitness = ethers.utils.hexConcat([signature, zksync.utils.serializeTimeRange(validFrom, validUntil + 1)]);
+        const {result: incorrectResult} = await getCallRevertReason(() =>
+            testContract.changePubkeySignatureCheck(incorrectWitness, pubkeyHash.replace("sync:", "0x"), nonce, randomWallet.address, accountId));
+        expect(incorrectResult).eq(false);
+    });
+
     it("pubkey hash signature verification incorrect nonce", async () => {
         const incorrectNonce = 0x11223345;
         const pubkeyHash = "sync:fefefefefefefefefefefefefefefefefefefefe";
@@ -548,6 +568,22 @@ describe("zkSync test process next operation", function() {
         expect(committedPriorityRequestsAfter, "priority request number").eq(committedPriorityRequestsBefore);
     });
 
+    it("Process swap", async () => {
+        zksyncContract.connect(wallet);
+
+        const committedPriorityRequestsBefore = await zksyncContract.totalCommittedPriorityRequests();
+
+        const pubdata = Buffer.alloc(CHUNK_SIZE * 5, 0xff);
+        pubdata[0] = 0x09;
+        const withdrawalsDataHash = await zksyncContract.callStatic.testProcessOperation(pubdata, "0x", []);
+        expect(withdrawalsDataHash, "withdrawals data hash").eq(ethers.utils.keccak256("0x"));
+
+        await zksyncContract.testProcessOperation(pubdata, "0x", []);
+
+        const committedPriorityRequestsAfter = await zksyncContract.totalCommittedPriorityRequests();
+        expect(committedPriorityRequestsAfter, "priority request number").eq(committedPriorityRequestsBefore);
+    });
+
     it("Process deposit", async () => {
         zksyncContract.connect(wallet);
         const depositAmount = BigNumber.from("2");
@@ -588,6 +624,43 @@ describe("zkSync test process next operation", function() {
         expect(committedPriorityRequestsAfter, "priority request number").eq(committedPriorityRequestsBefore);
     });
 
+    it("Process forced exit", async () => {
+        zksyncContract.connect(wallet);
+
+        const committedPriorityRequestsBefore = await zksyncContract.totalCommittedPriorityRequests();
+
+        const tokenId = 0x0102;
+        const amount = parseEther("0.7");
+
+        // construct forced exit pubdata
+        const pubdata = Buffer.alloc(CHUNK_SIZE * 6, 0);
+        pubdata[0] = 0x08;
+        let offset = 1;
+        pubdata.writeUInt32BE(0x00aabbcc, offset); // initiator account id
+        offset += 4;
+        pubdata.writeUInt32BE(0x00ccbbaa, offset); // target account id
+        offset += 4;
+        pubdata.writeUInt16BE(tokenId, offset);
+        offset += 2;
+        Buffer.from(amount.toHexString().substr(2).padStart(16 * 2, "0"), "hex").copy(pubdata, offset);
+        offset += 16;
+        pubdata.writeUInt16BE(0xffff, offset); // fee
+        offset += 2;
+        Buffer.from(wallet.address.substr(2), "hex").copy(pubdata, offset);
+
+        const withdrawalsDataHash = await zksyncContract.callStatic.testProcessOperation(pubdata, "0x", []);
+        const expectedWithdrawalsDataHash = ethers.utils.keccak256(ethers.utils.defaultAbiCoder.encode(
+            ["bytes32", "bool", "address", "uint16", "uint128"],
+            [ethers.utils.keccak256("0x"), true, wallet.address, tokenId, amount],
+        ));
+        expect(withdrawalsDataHash, "withdrawals data hash").eq(expectedWithdrawalsDataHash);
+
+        await zksyncContract.testProcessOperation(pubdata, "0x", []);
+
+        const committedPriorityRequestsAfter = await zksyncContract.totalCommittedPriorityRequests();
+        expect(committedPriorityRequestsAfter, "priority request number").eq(committedPriorityRequestsBefore);
+    });
+
     it("Process full exit", async () => {
         zksyncContract.connect(wallet);
         const tokenId = await ethProxy.resolveTokenId(tokenContract.address);
@@ -673,3 +746,127 @@ describe("zkSync test process next operation", function() {
         expect(committedPriorityRequestsAfter, "priority request number").eq(committedPriorityRequestsBefore);
     });
 });
+
+describe("zkSync multiple blocks commit and verify unit tests", function() {
+    this.timeout(50000);
+
+    let zksyncContract;
+    before(async () => {
+        const contracts = readTestContracts();
+        const deployer = new Deployer({deployWallet: wallet, contracts});
+        await deployer.deployAll({gasLimit: 6500000});
+        zksyncContract = deployer.zkSyncContract(wallet);
+
+        const govContract = deployer.governanceContract(wallet);
+        await (await govContract.setValidator(wallet.address, true)).wait();
+    });
+
+    // Block with a single noop operation.
+    const noopPubdata = Buffer.alloc(CHUNK_SIZE, 0);
+    const newRoot = "0x" + "00".repeat(32);
+
+    async function commitMultipleBlocks(blockNumberFrom: number, nBlocks: number) {
+        return await zksyncContract.commitMultipleBlocks(
+            blockNumberFrom,
+            Array(nBlocks).fill(0),
+            Array(nBlocks).fill(newRoot),
+            Buffer.concat(Array(nBlocks).fill(noopPubdata)),
+            Array(nBlocks).fill(noopPubdata.length),
+            "0x",
+            [],
+            Array(nBlocks).fill(0),
+        );
+    }
+
+    async function verifyMultipleBlocks(blockNumberFrom: number, nBlocks: number) {
+        // Proofs are not checked by the test verifier.
+        return await zksyncContract.verifyMultipleBlocks(
+            blockNumberFrom,
+            Array(nBlocks).fill(0),
+            Array(nBlocks).fill(1),
+            "0x",
+            Array(nBlocks).fill(0),
+        );
+    }
+
+    it("Commit multiple blocks", async () => {
+        const committedBefore = await zksyncContract.totalBlocksCommitted();
+
+        await (await commitMultipleBlocks(committedBefore + 1, 2)).wait();
+
+        const committedAfter = await zksyncContract.totalBlocksCommitted();
+        expect(committedAfter, "committed blocks").eq(committedBefore + 2);
+    });
+
+    it("Commit multiple blocks with inconsistent data", async () => {
+        const committedBefore = await zksyncContract.totalBlocksCommitted();
+
+        // Only the next blocks can be committed.
+        let {revertReason} = await getCallRevertReason(() => commitMultipleBlocks(committedBefore, 2));
+        expect(revertReason, "wrong revert reason").eq("fck11");
+
+        // At least one block should be committed.
+        ({revertReason} = await getCallRevertReason(() => commitMultipleBlocks(committedBefore + 1, 0)));
+        expect(revertReason, "wrong revert reason").eq("fcm11");
+
+        // Data should be provided for every block.
+        ({revertReason} = await getCallRevertReason(() => zksyncContract.commitMultipleBlocks(
+            committedBefore + 1, [0, 0], [newRoot], Buffer.concat([noopPubdata, noopPubdata]),
+            [noopPubdata.length, noopPubdata.length], "0x", [], [0, 0],
+        )));
+        expect(revertReason, "wrong revert reason").eq("fcm12");
+
+        // All the provided data should be used.
+        ({revertReason} = await getCallRevertReason(() => zksyncContract.commitMultipleBlocks(
+            committedBefore + 1, [0], [newRoot], Buffer.concat([noopPubdata, noopPubdata]),
+            [noopPubdata.length], "0x", [], [0],
+        )));
+        expect(revertReason, "wrong revert reason").eq("fcm14");
+
+        const committedAfter = await zksyncContract.totalBlocksCommitted();
+        expect(committedAfter, "committed blocks").eq(committedBefore);
+    });
+
+    it("Verify multiple blocks", async () => {
+        const verifiedBefore = await zksyncContract.totalBlocksVerified();
+
+        await (await verifyMultipleBlocks(verifiedBefore + 1, 2)).wait();
+
+        const verifiedAfter = await zksyncContract.totalBlocksVerified();
+        expect(verifiedAfter, "verified blocks").eq(verifiedBefore + 2);
+    });
+
+    it("Verify multiple blocks with inconsistent data", async () => {
+        await (await commitMultipleBlocks((await zksyncContract.totalBlocksCommitted()) + 1, 2)).wait();
+        const verifiedBefore = await zksyncContract.totalBlocksVerified();
+
+        // Only the next blocks can be verified.
+        let {revertReason} = await getCallRevertReason(() => verifyMultipleBlocks(verifiedBefore + 2, 1));
+        expect(revertReason, "wrong revert reason").eq("fvk11");
+
+        // At least one block should be verified.
+        ({revertReason} = await getCallRevertReason(() => verifyMultipleBlocks(verifiedBefore + 1, 0)));
+        expect(revertReason, "wrong revert reason").eq("fvm11");
+
+        // Data should be provided for every block.
+        ({revertReason} = await getCallRevertReason(() => zksyncContract.verifyMultipleBlocks(
+            verifiedBefore + 1, [0, 0], [1, 1], "0x", [0],
+        )));
+        expect(revertReason, "wrong revert reason").eq("fvm12");
+
+        // Not enough proof elements.
+        ({revertReason} = await getCallRevertReason(() => zksyncContract.verifyMultipleBlocks(
+            verifiedBefore + 1, [0], [1, 1], "0x", [0, 0],
+        )));
+        expect(revertReason, "wrong revert reason").eq("fvm13");
+
+        // All the provided data should be used.
+        ({revertReason} = await getCallRevertReason(() => zksyncContract.verifyMultipleBlocks(
+            verifiedBefore + 1, [0, 0, 0], [1, 1], "0x", [0, 0],
+        )));
+        expect(revertReason, "wrong revert reason").eq("fvm14");
+
+        const verifiedAfter = await zksyncContract.totalBlocksVerified();
+        expect(verifiedAfter, "verified blocks").eq(verifiedBefore);
+    });
+});
@@ -16,7 +16,7 @@ use models::{
     config_options::ConfigurationOptions,
     fe_from_hex,
     node::{
-        tokens::{get_genesis_token_list, Token},
+        tokens::{get_genesis_token_list, Token, TokenFeeSettings},
         TokenId,
     },
 };
@@ -37,9 +37,8 @@ async fn add_tokens_to_db(pool: &ConnectionPool, eth_network: &str) {
             token.address,
             token.decimals
         );
-        pool.access_storage()
-            .await
-            .expect("failed to access db")
+        let mut storage = pool.access_storage().await.expect("failed to access db");
+        storage
             .tokens_schema()
             .store_token(Token {
                 id: id as TokenId,
@@ -51,6 +50,12 @@ async fn add_tokens_to_db(pool: &ConnectionPool, eth_network: &str) {
             })
             .await
             .expect("failed to store token");
+        // Same as on the server genesis, genesis tokens are accepted for paying fees.
+        storage
+            .tokens_schema()
+            .store_token_fee_settings(id as TokenId, TokenFeeSettings::risk_free())
+            .await
+            .expect("failed to store token fee settings");
     }
 }
 
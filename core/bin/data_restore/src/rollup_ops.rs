@@ -105,8 +105,9 @@ mod test {
     use models::node::operations::ChangePubKeyOp;
     use models::node::tx::{ChangePubKey, TxSignature};
     use models::node::{
-        Close, CloseOp, Deposit, DepositOp, FranklinOp, FullExit, FullExitOp, PubKeyHash, Transfer,
-        TransferOp, TransferToNewOp, Withdraw, WithdrawOp,
+        Close, CloseOp, Deposit, DepositOp, ForcedExit, ForcedExitOp, FranklinOp, FullExit,
+        FullExitOp, Order, PubKeyHash, Swap, SwapOp, Transfer, TransferOp, TransferToNewOp,
+        Withdraw, WithdrawOp,
     };
     use num::BigUint;
 
@@ -141,6 +142,7 @@ mod test {
             20u32.into(),
             10u32.into(),
             2,
+            Default::default(),
             None,
         );
         let op1 = FranklinOp::Withdraw(Box::new(WithdrawOp { tx, account_id: 3 }));
@@ -153,6 +155,65 @@ mod test {
         assert_eq!(pub_data1, pub_data2);
     }
 
+    #[test]
+    fn test_forced_exit() {
+        let tx = ForcedExit::new(
+            3,
+            "7777777777777777777777777777777777777777".parse().unwrap(),
+            1,
+            10u32.into(),
+            2,
+            None,
+        );
+        let op1 = FranklinOp::ForcedExit(Box::new(ForcedExitOp {
+            tx,
+            target_account_id: 11,
+            withdraw_amount: Some(BigUint::from(444u32).into()),
+        }));
+        let pub_data1 = op1.public_data();
+        let op2 = RollupOpsBlock::get_rollup_ops_from_data(&pub_data1)
+            .expect("cant get ops from data")
+            .pop()
+            .expect("empty ops array");
+        let pub_data2 = op2.public_data();
+        assert_eq!(pub_data1, pub_data2);
+    }
+
+    #[test]
+    fn test_swap() {
+        let order = |account_id, token_sell, token_buy, amount: u32| {
+            Order::new(
+                account_id,
+                Default::default(),
+                0,
+                token_sell,
+                token_buy,
+                (1u32.into(), 1u32.into()),
+                amount.into(),
+                Default::default(),
+                None,
+            )
+        };
+        let tx = Swap::new(
+            3,
+            "7777777777777777777777777777777777777777".parse().unwrap(),
+            1,
+            (order(5, 1, 2, 50), order(6, 2, 1, 70)),
+            (50u32.into(), 70u32.into()),
+            10u32.into(),
+            3,
+            None,
+        );
+        let op1 = FranklinOp::Swap(Box::new(SwapOp { tx }));
+        let pub_data1 = op1.public_data();
+        let op2 = RollupOpsBlock::get_rollup_ops_from_data(&pub_data1)
+            .expect("cant get ops from data")
+            .pop()
+            .expect("empty ops array");
+        let pub_data2 = op2.public_data();
+        assert_eq!(pub_data1, pub_data2);
+    }
+
     #[test]
     fn test_successfull_full_exit() {
         let priority_op = FullExit {
@@ -203,6 +264,7 @@ mod test {
             20u32.into(),
             20u32.into(),
             3,
+            Default::default(),
             None,
         );
         let op1 = FranklinOp::TransferToNew(Box::new(TransferToNewOp {
@@ -229,6 +291,7 @@ mod test {
             20u32.into(),
             10u32.into(),
             3,
+            Default::default(),
             None,
         );
         let op1 = FranklinOp::Transfer(Box::new(TransferOp {
@@ -270,6 +333,7 @@ mod test {
             new_pk_hash: PubKeyHash::from_hex("sync:0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f")
                 .unwrap(),
             nonce: 3,
+            time_range: Default::default(),
             eth_signature: None,
         };
         let op1 = FranklinOp::ChangePubKeyOffchain(Box::new(ChangePubKeyOp { tx, account_id: 11 }));
@@ -151,6 +151,75 @@ impl TreeState {
                         &mut ops,
                     );
                 }
+                FranklinOp::ForcedExit(mut op) => {
+                    // Forced exit op comes with empty Nonce field
+                    let initiator_account = self
+                        .state
+                        .get_account(op.tx.initiator_account_id)
+                        .ok_or_else(|| format_err!("ForcedExit fail: Nonexistent account"))?;
+                    op.tx.nonce = initiator_account.nonce;
+
+                    let tx = FranklinTx::ForcedExit(Box::new(op.tx.clone()));
+                    let (fee, updates) = self
+                        .state
+                        .apply_forced_exit_op(&op)
+                        .map_err(|e| format_err!("ForcedExit fail: {}", e))?;
+                    let tx_result = OpSuccess {
+                        fee: Some(fee),
+                        updates,
+                        executed_op: FranklinOp::ForcedExit(op),
+                    };
+                    current_op_block_index = self.update_from_tx(
+                        tx,
+                        tx_result,
+                        &mut fees,
+                        &mut accounts_updated,
+                        current_op_block_index,
+                        &mut ops,
+                    );
+                }
+                FranklinOp::Swap(mut op) => {
+                    // Swap op comes with empty Account Address and Nonce fields
+                    // of both the submitter and the orders
+                    let submitter = self
+                        .state
+                        .get_account(op.tx.submitter_id)
+                        .ok_or_else(|| format_err!("Swap fail: Nonexistent account"))?;
+                    op.tx.submitter_address = submitter.address;
+                    op.tx.nonce = submitter.nonce;
+                    for order in [&mut op.tx.orders.0, &mut op.tx.orders.1].iter_mut() {
+                        let account = self
+                            .state
+                            .get_account(order.account_id)
+                            .ok_or_else(|| format_err!("Swap fail: Nonexistent account"))?;
+                        order.address = account.address;
+                        // Submitter nonce is incremented before the orders are filled.
+                        order.nonce = if order.account_id == op.tx.submitter_id {
+                            account.nonce + 1
+                        } else {
+                            account.nonce
+                        };
+                    }
+
+                    let tx = FranklinTx::Swap(Box::new(op.tx.clone()));
+                    let (fee, updates) = self
+                        .state
+                        .apply_swap_op(&op)
+                        .map_err(|e| format_err!("Swap fail: {}", e))?;
+                    let tx_result = OpSuccess {
+                        fee: Some(fee),
+                        updates,
+                        executed_op: FranklinOp::Swap(op),
+                    };
+                    current_op_block_index = self.update_from_tx(
+                        tx,
+                        tx_result,
+                        &mut fees,
+                        &mut accounts_updated,
+                        current_op_block_index,
+                        &mut ops,
+                    );
+                }
                 FranklinOp::Close(mut op) => {
                     // Close op comes with empty Account Address and Nonce fields
                     let account = self
@@ -432,6 +501,7 @@ mod test {
             BigUint::from(20u32),
             BigUint::from(1u32),
             1,
+            Default::default(),
             None,
         );
         let op2 = FranklinOp::Withdraw(Box::new(WithdrawOp {
@@ -455,6 +525,7 @@ mod test {
             BigUint::from(20u32),
             BigUint::from(1u32),
             3,
+            Default::default(),
             None,
         );
         let op3 = FranklinOp::TransferToNew(Box::new(TransferToNewOp {
@@ -479,6 +550,7 @@ mod test {
             BigUint::from(19u32),
             BigUint::from(1u32),
             1,
+            Default::default(),
             None,
         );
         let op4 = FranklinOp::Transfer(Box::new(TransferOp {
@@ -559,6 +631,7 @@ mod test {
             BigUint::from(20u32),
             BigUint::from(1u32),
             1,
+            Default::default(),
             None,
         );
         let op2 = FranklinOp::Withdraw(Box::new(WithdrawOp {
@@ -575,6 +648,7 @@ mod test {
             BigUint::from(20u32),
             BigUint::from(1u32),
             3,
+            Default::default(),
             None,
         );
         let op3 = FranklinOp::TransferToNew(Box::new(TransferToNewOp {
@@ -592,6 +666,7 @@ mod test {
             BigUint::from(19u32),
             BigUint::from(1u32),
             1,
+            Default::default(),
             None,
         );
         let op4 = FranklinOp::Transfer(Box::new(TransferOp {
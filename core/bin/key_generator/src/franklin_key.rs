@@ -92,6 +92,15 @@ fn zksync_circuit(block_chunks: usize) -> impl Circuit<Engine> + Clone {
             pub_nonce: None,
             new_pub_key_hash: None,
             eth_address: None,
+            valid_from: None,
+            valid_until: None,
+            second_amount_packed: None,
+            special_accounts: vec![None; 3],
+            special_tokens: vec![None; 3],
+            special_prices: vec![None; 4],
+            special_amounts: vec![None; 2],
+            second_valid_from: None,
+            second_valid_until: None,
         },
         lhs: OperationBranch {
             address: None,
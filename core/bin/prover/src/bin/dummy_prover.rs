@@ -50,22 +50,34 @@ impl<C: ApiClient> ProverImpl<C> for DummyProver<C> {
         let mut block = 0;
         let mut job_id = 0;
 
-        for block_size in &self.config.block_sizes {
-            let block_to_prove = self.api_client.block_to_prove(*block_size).map_err(|e| {
-                let e = format!("failed to get block to prove {}", e);
-                BabyProverError::Api(e)
-            })?;
-
-            let (current_request_block, current_request_job_id) =
-                block_to_prove.unwrap_or_else(|| {
-                    log::trace!("no block to prove from the server for size: {}", block_size);
-                    (0, 0)
-                });
-
-            if current_request_job_id != 0 {
-                block = current_request_block;
-                job_id = current_request_job_id;
-                break;
+        // Latency-sensitive blocks of any size are taken first. With the only block size
+        // the oldest block is handed out anyway, so there is no need to ask for them.
+        let priority_passes: &[bool] = if self.config.block_sizes.len() > 1 {
+            &[true, false]
+        } else {
+            &[false]
+        };
+        'passes: for &high_priority_only in priority_passes {
+            for block_size in &self.config.block_sizes {
+                let block_to_prove = self
+                    .api_client
+                    .block_to_prove(*block_size, high_priority_only)
+                    .map_err(|e| {
+                        let e = format!("failed to get block to prove {}", e);
+                        BabyProverError::Api(e)
+                    })?;
+
+                let (current_request_block, current_request_job_id) = block_to_prove
+                    .unwrap_or_else(|| {
+                        log::trace!("no block to prove from the server for size: {}", block_size);
+                        (0, 0)
+                    });
+
+                if current_request_job_id != 0 {
+                    block = current_request_block;
+                    job_id = current_request_job_id;
+                    break 'passes;
+                }
             }
         }
 
@@ -17,7 +17,13 @@ use crate::{client, start, ApiClient, ProverConfig, ProverImpl, ShutdownRequest}
 fn api_client_from_env(worker_name: &str) -> client::ApiClient {
     let server_api_url = parse_env("PROVER_SERVER_URL");
     let request_timout = Duration::from_secs(parse_env::<u64>("REQ_SERVER_TIMEOUT"));
-    client::ApiClient::new(&server_api_url, worker_name, request_timout)
+    let auth_token = std::env::var("PROVER_SERVER_AUTH_TOKEN").ok();
+    client::ApiClient::new(
+        &server_api_url,
+        worker_name,
+        request_timout,
+        auth_token.as_deref(),
+    )
 }
 
 pub fn main_for_prover_impl<P: ProverImpl<client::ApiClient> + 'static + Send + Sync>() {
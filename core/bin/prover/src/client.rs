@@ -6,11 +6,12 @@ use backoff::Operation;
 use failure::bail;
 use failure::format_err;
 use log::*;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 // Workspace deps
 use crate::client;
-use crate::prover_data::ProverData;
+use crate::prover_data::{ProverData, BINARY_PROVER_DATA_CONTENT_TYPE};
 use circuit::circuit::FranklinCircuit;
 use models::node::Engine;
 use models::prover_utils::EncodedProofPlonk;
@@ -21,6 +22,16 @@ pub struct ProverReq {
     pub block_size: usize,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct BlockToProveReq {
+    pub name: String,
+    pub block_size: usize,
+    /// Whether only the blocks with the high proof priority should be handed out.
+    /// Provers which don't set it get any block.
+    #[serde(default)]
+    pub high_priority_only: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockToProveRes {
     pub prover_run_id: i32,
@@ -34,6 +45,7 @@ pub struct WorkingOnReq {
 
 #[derive(Serialize, Deserialize)]
 pub struct PublishReq {
+    pub name: String,
     pub block: u32,
     pub proof: EncodedProofPlonk,
 }
@@ -52,12 +64,27 @@ pub struct ApiClient {
 }
 
 impl ApiClient {
-    pub fn new(base_url: &Url, worker: &str, req_server_timeout: time::Duration) -> Self {
+    /// Creates the client for the prover server API.
+    /// `auth_token` is required if the server requires authentication of the provers.
+    pub fn new(
+        base_url: &Url,
+        worker: &str,
+        req_server_timeout: time::Duration,
+        auth_token: Option<&str>,
+    ) -> Self {
         if worker == "" {
             panic!("worker name cannot be empty")
         }
+        let mut headers = HeaderMap::new();
+        if let Some(auth_token) = auth_token {
+            let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", auth_token))
+                .expect("auth token contains invalid characters");
+            auth_value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, auth_value);
+        }
         let http_client = reqwest::blocking::ClientBuilder::new()
             .timeout(req_server_timeout)
+            .default_headers(headers)
             .build()
             .expect("Failed to create request client");
         Self {
@@ -133,15 +160,20 @@ impl ApiClient {
 }
 
 impl crate::ApiClient for ApiClient {
-    fn block_to_prove(&self, block_size: usize) -> Result<Option<(i64, i32)>, failure::Error> {
+    fn block_to_prove(
+        &self,
+        block_size: usize,
+        high_priority_only: bool,
+    ) -> Result<Option<(i64, i32)>, failure::Error> {
         let op = || -> Result<Option<(i64, i32)>, failure::Error> {
             trace!("sending block_to_prove");
             let res = self
                 .http_client
                 .get(self.block_to_prove_url.as_str())
-                .json(&client::ProverReq {
+                .json(&client::BlockToProveReq {
                     name: self.worker.clone(),
                     block_size,
+                    high_priority_only,
                 })
                 .send()
                 .map_err(|e| format_err!("block to prove request failed: {}", e))?;
@@ -179,17 +211,35 @@ impl crate::ApiClient for ApiClient {
     fn prover_data(&self, block: i64) -> Result<FranklinCircuit<'_, Engine>, failure::Error> {
         let op = || -> Result<ProverData, failure::Error> {
             trace!("sending prover_data");
+            // Ask for the compact binary form, the server falls back to JSON if it doesn't support it.
             let res = self
                 .http_client
                 .get(self.prover_data_url.as_str())
+                .header(
+                    ACCEPT,
+                    format!("{}, application/json", BINARY_PROVER_DATA_CONTENT_TYPE),
+                )
                 .json(&block)
                 .send()
                 .map_err(|e| format_err!("failed to request prover data: {}", e))?;
-            let text = res
-                .text()
+            let is_binary = res
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map_or(false, |value| {
+                    value.starts_with(BINARY_PROVER_DATA_CONTENT_TYPE)
+                });
+            let body = res
+                .bytes()
                 .map_err(|e| format_err!("failed to read prover data response: {}", e))?;
-            let res: Option<ProverData> = serde_json::from_str(&text)
-                .map_err(|e| format_err!("failed to parse prover data response: {}", e))?;
+            let res: Option<ProverData> = if is_binary {
+                let prover_data = ProverData::decode_binary(&body)
+                    .map_err(|e| format_err!("failed to decode prover data response: {}", e))?;
+                Some(prover_data)
+            } else {
+                serde_json::from_slice(&body)
+                    .map_err(|e| format_err!("failed to parse prover data response: {}", e))?
+            };
             Ok(res.ok_or_else(|| format_err!("ProverData for block {} is not ready yet", block))?)
         };
 
@@ -205,6 +255,7 @@ impl crate::ApiClient for ApiClient {
                 .http_client
                 .post(self.publish_url.as_str())
                 .json(&client::PublishReq {
+                    name: self.worker.clone(),
                     block: block as u32,
                     proof,
                 })
@@ -84,7 +84,13 @@ pub trait ProverImpl<C: ApiClient> {
 }
 
 pub trait ApiClient: Debug {
-    fn block_to_prove(&self, block_size: usize) -> Result<Option<(i64, i32)>, failure::Error>;
+    /// Requests the next block of the given size to prove. If `high_priority_only` is set,
+    /// only the latency-sensitive blocks are handed out.
+    fn block_to_prove(
+        &self,
+        block_size: usize,
+        high_priority_only: bool,
+    ) -> Result<Option<(i64, i32)>, failure::Error>;
     fn working_on(&self, job_id: i32) -> Result<(), failure::Error>;
     fn prover_data(
         &self,
@@ -68,33 +68,44 @@ impl<C: ApiClient> ProverImpl<C> for PlonkStepByStepProver<C> {
                 0
             };
 
-        let (mut block, mut job_id, mut block_size) = (0, 0, 0);
-        for offset_idx in 0..self.config.block_sizes.len() {
-            let idx = (block_size_idx_to_try_first + offset_idx) % self.config.block_sizes.len();
-            let current_block_size = self.config.block_sizes[idx];
+        // Latency-sensitive blocks of any size are taken first. With the only block size
+        // the oldest block is handed out anyway, so there is no need to ask for them.
+        let priority_passes: &[bool] = if self.config.block_sizes.len() > 1 {
+            &[true, false]
+        } else {
+            &[false]
+        };
 
-            let block_to_prove =
-                self.api_client
-                    .block_to_prove(current_block_size)
+        let (mut block, mut job_id, mut block_size) = (0, 0, 0);
+        'passes: for &high_priority_only in priority_passes {
+            for offset_idx in 0..self.config.block_sizes.len() {
+                let idx =
+                    (block_size_idx_to_try_first + offset_idx) % self.config.block_sizes.len();
+                let current_block_size = self.config.block_sizes[idx];
+
+                let block_to_prove = self
+                    .api_client
+                    .block_to_prove(current_block_size, high_priority_only)
                     .map_err(|e| {
                         let e = format!("failed to get block to prove {}", e);
                         BabyProverError::Api(e)
                     })?;
 
-            let (current_request_block, current_request_job_id) =
-                block_to_prove.unwrap_or_else(|| {
-                    log::trace!(
-                        "no block to prove from the server for size: {}",
-                        current_block_size
-                    );
-                    (0, 0)
-                });
-
-            if current_request_job_id != 0 {
-                block = current_request_block;
-                job_id = current_request_job_id;
-                block_size = current_block_size;
-                break;
+                let (current_request_block, current_request_job_id) = block_to_prove
+                    .unwrap_or_else(|| {
+                        log::trace!(
+                            "no block to prove from the server for size: {}",
+                            current_block_size
+                        );
+                        (0, 0)
+                    });
+
+                if current_request_job_id != 0 {
+                    block = current_request_block;
+                    job_id = current_request_job_id;
+                    block_size = current_block_size;
+                    break 'passes;
+                }
             }
         }
 
@@ -1,5 +1,7 @@
 // Built-in
+use std::io::Write;
 // External
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
 // Workspace
 use circuit::account::AccountWitness;
@@ -14,6 +16,10 @@ use models::node::{Engine, Fr};
 // Local
 use crate::serialization::*;
 
+/// Content type of the compact binary encoding of `ProverData`:
+/// CBOR document compressed with gzip.
+pub const BINARY_PROVER_DATA_CONTENT_TYPE: &str = "application/vnd.zksync.prover-data+cbor";
+
 /// ProverData is data prover needs to calculate proof of the given block.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProverData {
@@ -38,6 +44,24 @@ pub struct ProverData {
 }
 
 impl ProverData {
+    /// Encodes prover data into the compact binary form, which is used both
+    /// for storing witnesses in the database and for sending them to provers.
+    ///
+    /// Field elements are stored as raw bytes instead of hexadecimal strings,
+    /// and the resulting CBOR document is compressed with gzip.
+    pub fn encode_binary(&self) -> Result<Vec<u8>, failure::Error> {
+        let cbor = serde_cbor::ser::to_vec_packed(self)?;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&cbor)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decodes prover data encoded with `ProverData::encode_binary`.
+    pub fn decode_binary(bytes: &[u8]) -> Result<Self, failure::Error> {
+        Ok(serde_cbor::from_reader(GzDecoder::new(bytes))?)
+    }
+
     pub fn into_circuit(self, block: i64) -> FranklinCircuit<'static, Engine> {
         FranklinCircuit {
             rescue_params: &models::params::RESCUE_PARAMS as &Bn256RescueParams,
@@ -118,6 +118,22 @@ fn prover_proves_a_block_and_publishes_result() {
         .expect("didn't receive proof"); // if proof is received - then proof is verified
 }
 
+#[test]
+fn prover_data_binary_encoding_roundtrip() {
+    let prover_data = new_test_data_for_prover();
+
+    let encoded = prover_data
+        .encode_binary()
+        .expect("failed to encode prover data");
+    let decoded = ProverData::decode_binary(&encoded).expect("failed to decode prover data");
+
+    // `ProverData` doesn't implement `PartialEq`, so the JSON representations are compared.
+    let json = serde_json::to_vec(&prover_data).unwrap();
+    assert_eq!(serde_json::to_vec(&decoded).unwrap(), json);
+    // Binary form is expected to be much more compact than JSON.
+    assert!(encoded.len() < json.len() / 2);
+}
+
 fn new_test_data_for_prover() -> ProverData {
     let mut circuit_account_tree = CircuitAccountTree::new(models::params::account_tree_depth());
     let fee_account_id = 0;
@@ -180,7 +196,11 @@ impl<F> fmt::Debug for MockApiClient<F> {
 }
 
 impl<F: Fn() -> Option<ProverData>> prover::ApiClient for MockApiClient<F> {
-    fn block_to_prove(&self, _block_size: usize) -> Result<Option<(i64, i32)>, failure::Error> {
+    fn block_to_prove(
+        &self,
+        _block_size: usize,
+        _high_priority_only: bool,
+    ) -> Result<Option<(i64, i32)>, failure::Error> {
         let block_to_prove = self.block_to_prove.lock().unwrap();
         Ok(*block_to_prove)
     }
@@ -1,6 +1,7 @@
 // Built-in deps
 use std::net::SocketAddr;
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 // External uses
 use actix_web::dev::ServiceRequest;
@@ -10,14 +11,19 @@ use actix_web_httpauth::extractors::{
     AuthenticationError,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
-use futures::channel::mpsc;
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
 use jsonwebtoken::errors::Error as JwtError;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 
 // Local uses
+use crate::fee_ticker::TickerRequest;
+use crate::prover_server::auth::encode_prover_token;
 use models::config_options::ThreadPanicNotify;
-use models::node::{tokens, Address, TokenId};
+use models::node::{tokens, Address, TokenFeeSettings, TokenId};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
@@ -28,7 +34,10 @@ struct PayloadAuthToken {
 #[derive(Debug, Clone)]
 struct AppState {
     secret_auth: String,
+    /// Secret used to sign the provers authentication tokens.
+    prover_secret_auth: Option<String>,
     connection_pool: storage::ConnectionPool,
+    ticker_request_sender: mpsc::Sender<TickerRequest>,
 }
 
 impl AppState {
@@ -57,6 +66,22 @@ struct AddTokenRequest {
     pub decimals: u8,
 }
 
+/// Request to issue the authentication token for a prover.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct IssueProverTokenRequest {
+    /// Name of the prover, as used in the prover server API requests.
+    pub worker: String,
+    /// Token lifetime in seconds.
+    pub lifetime: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct IssuedProverToken {
+    /// ID of the token, which can be used to revoke it.
+    pub id: i32,
+    pub token: String,
+}
+
 struct AuthTokenValidator<'a> {
     decoding_key: DecodingKey<'a>,
 }
@@ -125,6 +150,120 @@ async fn add_token(
     Ok(HttpResponse::Ok().json(token))
 }
 
+/// Stores the fee settings of the token (risk factor and whether it can be used to pay fees)
+/// and makes fee ticker reload them.
+async fn set_token_fee_settings(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+    settings: web::Json<TokenFeeSettings>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    let settings = settings.into_inner();
+
+    let mut storage = data.access_storage().await?;
+    let token = storage
+        .tokens_schema()
+        .get_token(token_id.into())
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load token in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if token.is_none() {
+        return Err(actix_web::error::ErrorNotFound("token not found"));
+    }
+
+    storage
+        .tokens_schema()
+        .store_token_fee_settings(token_id, settings.clone())
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed to store token fee settings in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    let (response, response_receiver) = oneshot::channel();
+    data.ticker_request_sender
+        .clone()
+        .send(TickerRequest::ReloadTokensFeeSettings { response })
+        .await
+        .expect("ticker receiver dropped");
+    response_receiver
+        .await
+        .expect("ticker answer sender dropped")
+        .map_err(|e| {
+            vlog::warn!("failed to reload tokens fee settings: {}", e);
+            actix_web::error::ErrorInternalServerError("fee ticker error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+/// Issues the authentication token for the prover to access the prover server API.
+async fn issue_prover_token(
+    data: web::Data<AppState>,
+    request: web::Json<IssueProverTokenRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let prover_secret_auth = data
+        .prover_secret_auth
+        .as_ref()
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("prover authentication is disabled"))?;
+    if request.worker == "" {
+        return Err(actix_web::error::ErrorBadRequest("empty name"));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let id = storage
+        .prover_schema()
+        .issue_prover_token(&request.worker)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to store prover token in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("current time is before the unix epoch")
+        .as_secs();
+    let token = encode_prover_token(
+        prover_secret_auth,
+        id,
+        &request.worker,
+        (now + request.lifetime) as usize,
+    )
+    .map_err(|e| {
+        vlog::warn!("failed to encode prover token: {}", e);
+        actix_web::error::ErrorInternalServerError("token encoding error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(IssuedProverToken { id, token }))
+}
+
+/// Revokes the prover authentication token, so it's no longer accepted by the prover server.
+async fn revoke_prover_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<i32>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let revoked = storage
+        .prover_schema()
+        .revoke_prover_token(token_id.into_inner())
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to revoke prover token in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !revoked {
+        return Err(actix_web::error::ErrorNotFound("active token not found"));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 async fn run_server(app_state: AppState, bind_to: SocketAddr) {
     HttpServer::new(move || {
         let auth = HttpAuthentication::bearer(move |req, credentials| async {
@@ -138,6 +277,15 @@ async fn run_server(app_state: AppState, bind_to: SocketAddr) {
             .wrap(auth)
             .data(app_state.clone())
             .route("/tokens", web::post().to(add_token))
+            .route(
+                "/tokens/{id}/fee_settings",
+                web::post().to(set_token_fee_settings),
+            )
+            .route("/provers/tokens", web::post().to(issue_prover_token))
+            .route(
+                "/provers/tokens/{id}",
+                web::delete().to(revoke_prover_token),
+            )
     })
     .workers(1)
     .bind(&bind_to)
@@ -150,7 +298,9 @@ async fn run_server(app_state: AppState, bind_to: SocketAddr) {
 pub fn start_admin_server(
     bind_to: SocketAddr,
     secret_auth: String,
+    prover_secret_auth: Option<String>,
     connection_pool: storage::ConnectionPool,
+    ticker_request_sender: mpsc::Sender<TickerRequest>,
     panic_notify: mpsc::Sender<bool>,
 ) {
     thread::Builder::new()
@@ -161,6 +311,8 @@ pub fn start_admin_server(
                 let app_state = AppState {
                     connection_pool,
                     secret_auth,
+                    prover_secret_auth,
+                    ticker_request_sender,
                 };
 
                 run_server(app_state, bind_to).await;
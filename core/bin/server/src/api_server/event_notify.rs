@@ -1,6 +1,7 @@
 use super::rpc_server::types::{
     BlockInfo, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
 };
+use crate::mempool::TxReplacedNotify;
 use crate::state_keeper::{ExecutedOpId, ExecutedOpsNotify, StateKeeperRequest};
 use crate::utils::token_db_cache::TokenDBCache;
 use failure::{bail, format_err};
@@ -561,6 +562,27 @@ impl OperationNotifier {
         )
     }
 
+    /// Notifies the subscribers of the replaced transaction, since it will never be executed.
+    fn handle_replaced_tx(&mut self, notify: TxReplacedNotify) -> Result<(), failure::Error> {
+        let rec = TransactionInfoResp {
+            executed: false,
+            success: Some(false),
+            fail_reason: Some(format!(
+                "Replaced by the transaction {}",
+                notify.new_tx.to_string()
+            )),
+            block: None,
+        };
+        for action in &[ActionType::COMMIT, ActionType::VERIFY] {
+            if let Some(subs) = self.tx_subs.remove(&(notify.replaced_tx.clone(), *action)) {
+                for sub in subs {
+                    self.send_once(&sub.sink, rec.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_new_block(&mut self, op: Operation) -> Result<(), failure::Error> {
         let action = op.action.get_type();
 
@@ -628,6 +650,7 @@ pub fn start_sub_notifier(
     mut new_block_stream: mpsc::Receiver<Operation>,
     mut subscription_stream: mpsc::Receiver<EventNotifierRequest>,
     mut executed_tx_stream: mpsc::Receiver<ExecutedOpsNotify>,
+    mut replaced_tx_stream: mpsc::Receiver<TxReplacedNotify>,
     state_keeper_requests: mpsc::Sender<StateKeeperRequest>,
     api_requests_caches_size: usize,
 ) -> tokio::task::JoinHandle<()> {
@@ -663,6 +686,13 @@ pub fn start_sub_notifier(
                             .unwrap_or_default();
                     }
                 },
+                replaced_tx = replaced_tx_stream.next() => {
+                    if let Some(replaced_tx) = replaced_tx {
+                        notifier.handle_replaced_tx(replaced_tx)
+                            .map_err(|e| warn!("Failed to handle replaced tx: {}",e))
+                            .unwrap_or_default();
+                    }
+                },
                 new_sub = subscription_stream.next() => {
                     if let Some(new_sub) = new_sub {
                         notifier.handle_notify_req(new_sub)
@@ -16,7 +16,7 @@ use storage::ConnectionPool;
 use crate::fee_ticker::TickerRequest;
 use crate::{
     eth_watch::EthWatchRequest,
-    mempool::MempoolRequest,
+    mempool::{MempoolRequest, TxReplacedNotify},
     signature_checker,
     state_keeper::{ExecutedOpsNotify, StateKeeperRequest},
     utils::current_zksync_info::CurrentZksyncInfo,
@@ -37,6 +37,7 @@ pub fn start_api_server(
     panic_notify: mpsc::Sender<bool>,
     mempool_request_sender: mpsc::Sender<MempoolRequest>,
     executed_tx_receiver: mpsc::Receiver<ExecutedOpsNotify>,
+    replaced_tx_receiver: mpsc::Receiver<TxReplacedNotify>,
     state_keeper_request_sender: mpsc::Sender<StateKeeperRequest>,
     eth_watcher_request_sender: mpsc::Sender<EthWatchRequest>,
     ticker_request_sender: mpsc::Sender<TickerRequest>,
@@ -67,6 +68,7 @@ pub fn start_api_server(
         connection_pool.clone(),
         mempool_request_sender.clone(),
         executed_tx_receiver,
+        replaced_tx_receiver,
         state_keeper_request_sender.clone(),
         sign_check_sender.clone(),
         eth_watcher_request_sender.clone(),
@@ -79,7 +81,9 @@ pub fn start_api_server(
     admin_server::start_admin_server(
         admin_server_opts.admin_http_server_address,
         admin_server_opts.secret_auth,
+        config_options.prover_server_secret_auth.clone(),
         connection_pool.clone(),
+        ticker_request_sender.clone(),
         panic_notify.clone(),
     );
 
@@ -9,6 +9,9 @@ pub enum RpcErrorCodes {
     NonceMismatch = 101,
     IncorrectTx = 103,
     FeeTooLow = 104,
+    TxExpired = 105,
+    ReplacementFeeTooLow = 106,
+    FeeTokenNotAllowed = 107,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
@@ -28,6 +31,8 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::IncorrectTx => Self::IncorrectTx,
             TxAddError::TxFeeTooLow => Self::FeeTooLow,
             TxAddError::TxBatchFeeTooLow => Self::FeeTooLow,
+            TxAddError::TxExpired => Self::TxExpired,
+            TxAddError::ReplacementFeeTooLow => Self::ReplacementFeeTooLow,
             TxAddError::MissingEthSignature => Self::MissingEthSignature,
             TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
             TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
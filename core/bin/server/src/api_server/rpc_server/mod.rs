@@ -6,11 +6,12 @@ use futures::{
 };
 use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
+use num::BigUint;
 // Workspace uses
 use models::{
     config_options::{ConfigurationOptions, ThreadPanicNotify},
     node::{
-        tx::{TxEthSignature, TxHash},
+        tx::{get_batch_ethereum_sign_message, TxEthSignature, TxHash},
         Address, FranklinTx, PriorityOp, Token, TokenId, TokenLike, TxFeeTypes,
     },
 };
@@ -25,7 +26,7 @@ use storage::{
 use crate::{
     api_server::ops_counter::ChangePubKeyOpsCounter,
     eth_watch::{EthBlockId, EthWatchRequest},
-    fee_ticker::{Fee, TickerRequest, TokenPriceRequestType},
+    fee_ticker::{fee_quote::FeeQuoteSigner, Fee, TickerRequest, TokenPriceRequestType},
     mempool::{MempoolRequest, TxAddError},
     signature_checker::{VerifiedTx, VerifyTxSignatureRequest},
     state_keeper::StateKeeperRequest,
@@ -100,6 +101,9 @@ pub struct RpcApp {
 
     /// Counter for ChangePubKey operations to filter the spam.
     ops_counter: Arc<RwLock<ChangePubKeyOpsCounter>>,
+
+    /// Signer of the fee quotes honoured by `tx_submit`.
+    fee_quote_signer: FeeQuoteSigner,
 }
 
 impl RpcApp {
@@ -119,6 +123,10 @@ impl RpcApp {
 
         let api_requests_caches_size = config_options.api_requests_caches_size;
         let confirmations_for_eth_event = config_options.confirmations_for_eth_event;
+        let fee_quote_signer = FeeQuoteSigner::new(
+            config_options.fee_quote_secret.clone(),
+            config_options.fee_quote_lifetime,
+        );
 
         RpcApp {
             cache_of_executed_priority_operations: SharedLruCache::new(api_requests_caches_size),
@@ -140,6 +148,8 @@ impl RpcApp {
             current_zksync_info,
 
             ops_counter: Arc::new(RwLock::new(ChangePubKeyOpsCounter::new())),
+
+            fee_quote_signer,
         }
     }
 
@@ -335,6 +345,8 @@ impl RpcApp {
         address: Address,
         token: TokenLike,
     ) -> Result<Fee> {
+        Self::check_fee_token_allowed(ticker_request_sender.clone(), token.clone()).await?;
+
         let req = oneshot::channel();
         ticker_request_sender
             .send(TickerRequest::GetTxFee {
@@ -360,6 +372,63 @@ impl RpcApp {
         })
     }
 
+    /// Checks whether the fee quote is valid and covers the fee of the transaction.
+    fn fee_quote_covers(
+        &self,
+        quote: &str,
+        tx_type: &TxFeeTypes,
+        address: Address,
+        token: &TokenLike,
+        provided_fee: &BigUint,
+    ) -> bool {
+        match self.fee_quote_signer.verify(quote) {
+            Ok(fee_quote) => fee_quote.covers(tx_type, address, token, provided_fee),
+            Err(err) => {
+                log::debug!("Fee quote is not accepted: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Checks that the token can be used to pay fees.
+    async fn check_fee_token_allowed(
+        mut ticker_request_sender: mpsc::Sender<TickerRequest>,
+        token: TokenLike,
+    ) -> Result<()> {
+        let req = oneshot::channel();
+        ticker_request_sender
+            .send(TickerRequest::IsTokenAllowedForFees {
+                token: token.clone(),
+                response: req.0,
+            })
+            .await
+            .expect("ticker receiver dropped");
+        let allowed = req
+            .1
+            .await
+            .expect("ticker answer sender dropped")
+            .map_err(|err| {
+                log::warn!(
+                    "[{}:{}:{}] Internal Server Error: '{}'; input: {:?}",
+                    file!(),
+                    line!(),
+                    column!(),
+                    err,
+                    token,
+                );
+                Error::internal_error()
+            })?;
+
+        if !allowed {
+            return Err(Error {
+                code: RpcErrorCodes::FeeTokenNotAllowed.into(),
+                message: "Token is not acceptable for paying fees".to_string(),
+                data: None,
+            });
+        }
+        Ok(())
+    }
+
     async fn ticker_price_request(
         mut ticker_request_sender: mpsc::Sender<TickerRequest>,
         token: TokenLike,
@@ -519,9 +588,48 @@ async fn verify_tx_info_message_signature(
         .map_err(rpc_message)
 }
 
+/// Verifies the signatures of the batch transactions.
+///
+/// Transactions without their own Ethereum signature may be authorized by the
+/// signature of the whole batch, which covers the messages of all the batch
+/// transactions requiring an Ethereum signature, in the order of the batch.
+async fn verify_txs_batch_signature(
+    txs: &[TxWithSignature],
+    messages_to_sign: Vec<Option<String>>,
+    batch_signature: Option<TxEthSignature>,
+    req_channel: mpsc::Sender<VerifyTxSignatureRequest>,
+) -> Result<Vec<VerifiedTx>> {
+    let batch_message =
+        get_batch_ethereum_sign_message(messages_to_sign.iter().filter_map(|msg| msg.as_deref()));
+
+    let mut verified_txs = Vec::with_capacity(txs.len());
+    for (tx, msg_to_sign) in txs.iter().zip(messages_to_sign) {
+        let (signature, msg_to_sign) = match (&tx.signature, &batch_signature, msg_to_sign) {
+            (None, Some(batch_signature), Some(_)) => {
+                (Some(batch_signature.clone()), Some(batch_message.clone()))
+            }
+            (signature, _, msg_to_sign) => (signature.clone(), msg_to_sign),
+        };
+
+        let verified_tx =
+            verify_tx_info_message_signature(&tx.tx, signature, msg_to_sign, req_channel.clone())
+                .await?;
+        verified_txs.push(verified_tx);
+    }
+
+    Ok(verified_txs)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crypto_exports::rand::{Rng, SeedableRng, XorShiftRng};
+    use futures::StreamExt;
+    use models::node::{
+        priv_key_from_fs,
+        tx::{PackedEthSignature, Transfer},
+        PrivateKey, H256,
+    };
 
     #[test]
     fn tx_fee_type_serialization() {
@@ -551,4 +659,162 @@ mod test {
             assert_eq!(query, de);
         }
     }
+    /// Ethereum and zkSync keys of the test account.
+    struct TestAccount {
+        id: u32,
+        address: Address,
+        eth_private_key: H256,
+        private_key: PrivateKey,
+    }
+
+    impl TestAccount {
+        fn new(id: u32, seed: u8) -> Self {
+            let eth_private_key = H256::repeat_byte(seed);
+            let address = PackedEthSignature::address_from_private_key(&eth_private_key)
+                .expect("address from private key");
+            let mut rng = XorShiftRng::from_seed([seed.into(), 2, 3, 4]);
+            let private_key = priv_key_from_fs(rng.gen());
+
+            Self {
+                id,
+                address,
+                eth_private_key,
+                private_key,
+            }
+        }
+
+        /// Creates a transfer and the message its Ethereum signature must be created for.
+        fn transfer(&self, to: Address, nonce: u32) -> (FranklinTx, String) {
+            let transfer = Transfer::new_signed(
+                self.id,
+                self.address,
+                to,
+                0,
+                BigUint::from(100u32),
+                BigUint::from(10u32),
+                nonce,
+                Default::default(),
+                &self.private_key,
+            )
+            .expect("failed to sign transfer");
+            let message = transfer.get_ethereum_sign_message("ETH", 18);
+
+            (FranklinTx::Transfer(Box::new(transfer)), message)
+        }
+
+        fn eth_sign(&self, message: &str) -> TxEthSignature {
+            let signature = PackedEthSignature::sign(&self.eth_private_key, message.as_bytes())
+                .expect("failed to sign message");
+            TxEthSignature::EthereumSignature(signature)
+        }
+    }
+
+    /// Runs `verify_txs_batch_signature` against the signature checker routine.
+    async fn verify_batch(
+        txs: &[TxWithSignature],
+        messages_to_sign: Vec<Option<String>>,
+        batch_signature: Option<TxEthSignature>,
+    ) -> Result<Vec<VerifiedTx>> {
+        let (sign_verify_sender, mut sign_verify_receiver) =
+            mpsc::channel::<VerifyTxSignatureRequest>(8);
+        // Eth watcher is only queried for `ChangePubKey` and EIP1271 signatures, which aren't used here.
+        let (eth_watch_sender, _eth_watch_receiver) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(request) = sign_verify_receiver.next().await {
+                let resp = VerifiedTx::verify(&request, eth_watch_sender.clone()).await;
+                request.response.send(resp).unwrap_or_default();
+            }
+        });
+
+        verify_txs_batch_signature(txs, messages_to_sign, batch_signature, sign_verify_sender).await
+    }
+
+    fn assert_rejected(result: Result<Vec<VerifiedTx>>, error: TxAddError) {
+        let err = result.expect_err("batch must be rejected");
+        assert_eq!(err.code, RpcErrorCodes::from(error).into());
+    }
+
+    #[tokio::test]
+    async fn txs_batch_signature() {
+        let account = TestAccount::new(1, 1);
+        let other_account = TestAccount::new(2, 2);
+
+        let (first_tx, first_message) = account.transfer(other_account.address, 0);
+        let (second_tx, second_message) = account.transfer(other_account.address, 1);
+        let messages_to_sign = vec![Some(first_message.clone()), Some(second_message.clone())];
+        let batch_message =
+            get_batch_ethereum_sign_message(vec![first_message.as_str(), second_message.as_str()]);
+        let unsigned_batch = vec![
+            TxWithSignature {
+                tx: first_tx.clone(),
+                signature: None,
+            },
+            TxWithSignature {
+                tx: second_tx.clone(),
+                signature: None,
+            },
+        ];
+
+        // Batch signed as a whole is accepted.
+        let verified_txs = verify_batch(
+            &unsigned_batch,
+            messages_to_sign.clone(),
+            Some(account.eth_sign(&batch_message)),
+        )
+        .await
+        .expect("batch signature must be accepted");
+        assert_eq!(verified_txs.len(), 2);
+        for verified_tx in verified_txs {
+            let eth_sign_data = verified_tx
+                .into_inner()
+                .eth_sign_data
+                .expect("eth sign data must be stored");
+            assert_eq!(eth_sign_data.message, batch_message);
+        }
+
+        // Transaction with its own signature doesn't require the batch signature.
+        let partially_signed_batch = vec![
+            TxWithSignature {
+                tx: first_tx.clone(),
+                signature: Some(account.eth_sign(&first_message)),
+            },
+            unsigned_batch[1].clone(),
+        ];
+        verify_batch(
+            &partially_signed_batch,
+            messages_to_sign.clone(),
+            Some(account.eth_sign(&batch_message)),
+        )
+        .await
+        .expect("batch with individually signed transaction must be accepted");
+
+        // Batch signed by another account is rejected.
+        assert_rejected(
+            verify_batch(
+                &unsigned_batch,
+                messages_to_sign.clone(),
+                Some(other_account.eth_sign(&batch_message)),
+            )
+            .await,
+            TxAddError::IncorrectEthSignature,
+        );
+
+        // Signature doesn't match the reordered batch.
+        let reordered_batch = vec![unsigned_batch[1].clone(), unsigned_batch[0].clone()];
+        assert_rejected(
+            verify_batch(
+                &reordered_batch,
+                vec![Some(second_message.clone()), Some(first_message.clone())],
+                Some(account.eth_sign(&batch_message)),
+            )
+            .await,
+            TxAddError::IncorrectEthSignature,
+        );
+
+        // Neither the transaction, nor the batch are signed.
+        assert_rejected(
+            verify_batch(&unsigned_batch, messages_to_sign, None).await,
+            TxAddError::MissingEthSignature,
+        );
+    }
 }
@@ -17,7 +17,9 @@ use crate::{
 };
 use bigdecimal::BigDecimal;
 
-use super::{error::*, types::*, verify_tx_info_message_signature, RpcApp};
+use super::{
+    error::*, types::*, verify_tx_info_message_signature, verify_txs_batch_signature, RpcApp,
+};
 
 impl RpcApp {
     pub async fn _impl_account_info(self, address: Address) -> Result<AccountInfoResp> {
@@ -139,6 +141,7 @@ impl RpcApp {
         mut tx: Box<FranklinTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
+        fee_quote: Option<String>,
     ) -> Result<TxHash> {
         if tx.is_close() {
             return Err(Error {
@@ -186,22 +189,31 @@ impl RpcApp {
         let ops_counter = self.ops_counter.clone();
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
-            let required_fee =
-                Self::ticker_request(ticker_request_sender, tx_type, address, token.clone())
-                    .await?;
-            // We allow fee to be 5% off the required fee
-            let scaled_provided_fee =
-                provided_fee.clone() * BigUint::from(105u32) / BigUint::from(100u32);
-            if required_fee.total_fee >= scaled_provided_fee {
-                vlog::warn!(
-                    "User provided fee is too low, required: {:?}, provided: {} (scaled: {}), token: {:?}",
-                    required_fee, provided_fee, scaled_provided_fee, token
-                );
-                return Err(Error {
-                    code: RpcErrorCodes::from(TxAddError::TxFeeTooLow).into(),
-                    message: TxAddError::TxFeeTooLow.to_string(),
-                    data: None,
-                });
+            // Fee that matches a valid quote is accepted even if the prices have changed since then.
+            let quote_accepted = fee_quote
+                .map(|quote| {
+                    self.fee_quote_covers(&quote, &tx_type, address, &token, &provided_fee)
+                })
+                .unwrap_or(false);
+
+            if !quote_accepted {
+                let required_fee =
+                    Self::ticker_request(ticker_request_sender, tx_type, address, token.clone())
+                        .await?;
+                // We allow fee to be 5% off the required fee
+                let scaled_provided_fee =
+                    provided_fee.clone() * BigUint::from(105u32) / BigUint::from(100u32);
+                if required_fee.total_fee >= scaled_provided_fee {
+                    vlog::warn!(
+                        "User provided fee is too low, required: {:?}, provided: {} (scaled: {}), token: {:?}",
+                        required_fee, provided_fee, scaled_provided_fee, token
+                    );
+                    return Err(Error {
+                        code: RpcErrorCodes::from(TxAddError::TxFeeTooLow).into(),
+                        message: TxAddError::TxFeeTooLow.to_string(),
+                        data: None,
+                    });
+                }
             }
         }
 
@@ -251,7 +263,11 @@ impl RpcApp {
         })
     }
 
-    pub async fn _impl_submit_txs_batch(self, txs: Vec<TxWithSignature>) -> Result<Vec<TxHash>> {
+    pub async fn _impl_submit_txs_batch(
+        self,
+        txs: Vec<TxWithSignature>,
+        eth_signature: Option<TxEthSignature>,
+    ) -> Result<Vec<TxHash>> {
         for tx in &txs {
             if tx.tx.is_close() {
                 return Err(Error {
@@ -316,18 +332,13 @@ impl RpcApp {
             });
         }
 
-        let mut verified_txs = Vec::new();
-        for (tx, msg_to_sign) in txs.iter().zip(messages_to_sign.iter()) {
-            let verified_tx = verify_tx_info_message_signature(
-                &tx.tx,
-                tx.signature.clone(),
-                msg_to_sign.clone(),
-                self.sign_verify_request_sender.clone(),
-            )
-            .await?;
-
-            verified_txs.push(verified_tx);
-        }
+        let verified_txs = verify_txs_batch_signature(
+            &txs,
+            messages_to_sign,
+            eth_signature,
+            self.sign_verify_request_sender.clone(),
+        )
+        .await?;
 
         let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.tx.hash()).collect();
 
@@ -417,8 +428,37 @@ impl RpcApp {
         tx_type: TxFeeTypes,
         address: Address,
         token: TokenLike,
+        with_quote: Option<bool>,
     ) -> Result<Fee> {
-        Self::ticker_request(self.ticker_request_sender.clone(), tx_type, address, token).await
+        let mut fee = Self::ticker_request(
+            self.ticker_request_sender.clone(),
+            tx_type.clone(),
+            address,
+            token.clone(),
+        )
+        .await?;
+
+        if with_quote.unwrap_or_default() {
+            // Token exists, otherwise the fee wouldn't be calculated.
+            let token_id = self
+                .token_cache
+                .get_token(token)
+                .await
+                .ok()
+                .flatten()
+                .ok_or_else(Error::internal_error)?
+                .id;
+            let quote = self
+                .fee_quote_signer
+                .sign(tx_type, address, token_id, fee.total_fee.clone())
+                .map_err(|err| {
+                    vlog::warn!("Failed to sign the fee quote: {}", err);
+                    Error::internal_error()
+                })?;
+            fee.quote = Some(quote);
+        }
+
+        Ok(fee)
     }
 
     pub async fn _impl_get_txs_batch_fee_in_wei(
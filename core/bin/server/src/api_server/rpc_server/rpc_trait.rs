@@ -42,10 +42,15 @@ pub trait Rpc {
         tx: Box<FranklinTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
+        fee_quote: Option<String>,
     ) -> FutureResp<TxHash>;
 
     #[rpc(name = "submit_txs_batch", returns = "Vec<TxHash>")]
-    fn submit_txs_batch(&self, txs: Vec<TxWithSignature>) -> FutureResp<Vec<TxHash>>;
+    fn submit_txs_batch(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signature: Option<TxEthSignature>,
+    ) -> FutureResp<Vec<TxHash>>;
 
     #[rpc(name = "contract_address", returns = "ContractAddressResp")]
     fn contract_address(&self) -> FutureResp<ContractAddressResp>;
@@ -60,6 +65,7 @@ pub trait Rpc {
         tx_type: TxFeeTypes,
         address: Address,
         token_like: TokenLike,
+        with_quote: Option<bool>,
     ) -> FutureResp<Fee>;
 
     #[rpc(name = "get_txs_batch_fee_in_wei", returns = "BatchFee")]
@@ -113,24 +119,29 @@ impl Rpc for RpcApp {
         tx: Box<FranklinTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
+        fee_quote: Option<String>,
     ) -> FutureResp<TxHash> {
         let self_ = self.clone();
         let resp = async move {
             let handle = self_.tokio_runtime.clone();
             handle
-                .spawn(self_._impl_tx_submit(tx, signature, fast_processing))
+                .spawn(self_._impl_tx_submit(tx, signature, fast_processing, fee_quote))
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn submit_txs_batch(&self, txs: Vec<TxWithSignature>) -> FutureResp<Vec<TxHash>> {
+    fn submit_txs_batch(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signature: Option<TxEthSignature>,
+    ) -> FutureResp<Vec<TxHash>> {
         let self_ = self.clone();
         let resp = async move {
             let handle = self_.tokio_runtime.clone();
             handle
-                .spawn(self_._impl_submit_txs_batch(txs))
+                .spawn(self_._impl_submit_txs_batch(txs, eth_signature))
                 .await
                 .unwrap()
         };
@@ -165,12 +176,13 @@ impl Rpc for RpcApp {
         tx_type: TxFeeTypes,
         address: Address,
         token_like: TokenLike,
+        with_quote: Option<bool>,
     ) -> FutureResp<Fee> {
         let self_ = self.clone();
         let resp = async move {
             let handle = self_.tokio_runtime.clone();
             handle
-                .spawn(self_._impl_get_tx_fee(tx_type, address, token_like))
+                .spawn(self_._impl_get_tx_fee(tx_type, address, token_like, with_quote))
                 .await
                 .unwrap()
         };
@@ -13,7 +13,7 @@ use models::{
 // Local uses
 use crate::utils::token_db_cache::TokenDBCache;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TxWithSignature {
     pub tx: FranklinTx,
@@ -22,7 +22,7 @@ use crate::fee_ticker::TickerRequest;
 use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
     api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
-    mempool::MempoolRequest,
+    mempool::{MempoolRequest, TxReplacedNotify},
     signature_checker::VerifyTxSignatureRequest,
     state_keeper::{ExecutedOpsNotify, StateKeeperRequest},
     utils::current_zksync_info::CurrentZksyncInfo,
@@ -187,6 +187,7 @@ pub fn start_ws_server(
     db_pool: ConnectionPool,
     mempool_request_sender: mpsc::Sender<MempoolRequest>,
     executed_tx_receiver: mpsc::Receiver<ExecutedOpsNotify>,
+    replaced_tx_receiver: mpsc::Receiver<TxReplacedNotify>,
     state_keeper_request_sender: mpsc::Sender<StateKeeperRequest>,
     sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
     eth_watcher_request_sender: mpsc::Sender<EthWatchRequest>,
@@ -205,6 +206,7 @@ pub fn start_ws_server(
         op_recv,
         event_sub_receiver,
         executed_tx_receiver,
+        replaced_tx_receiver,
         state_keeper_request_sender.clone(),
         each_cache_size,
     );
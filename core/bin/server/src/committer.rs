@@ -7,7 +7,10 @@ use tokio::{task::JoinHandle, time};
 // Workspace uses
 use crate::eth_sender::ETHSenderRequest;
 use crate::mempool::MempoolRequest;
-use models::{node::block::PendingBlock, Action, BlockCommitRequest, CommitRequest, Operation};
+use models::{
+    node::{block::PendingBlock, tx::TxHash},
+    Action, BlockCommitRequest, CommitRequest, Operation,
+};
 use storage::ConnectionPool;
 
 const PROOF_POLL_INTERVAL: Duration = Duration::from_secs(1);
@@ -70,6 +73,15 @@ async fn commit_block(
         accounts_updated,
     } = request;
 
+    // Transactions of the block are either executed or failed, in both cases
+    // the mempool doesn't have to track them anymore.
+    let committed_txs: Vec<TxHash> = block
+        .block_transactions
+        .iter()
+        .filter_map(|op| op.get_executed_tx())
+        .map(|tx| tx.signed_tx.hash())
+        .collect();
+
     let mut storage = pool
         .access_storage()
         .await
@@ -92,6 +104,12 @@ async fn commit_block(
             .save_block_transactions(block.block_number, block.block_transactions)
             .await
             .expect("committer failed tx save");
+
+        mempool_req_sender
+            .send(MempoolRequest::UpdateNonces(Vec::new(), committed_txs))
+            .await
+            .map_err(|e| warn!("Failed notify mempool about failed txs: {}", e))
+            .unwrap_or_default();
         return;
     }
 
@@ -122,7 +140,10 @@ async fn commit_block(
         .unwrap_or_default();
 
     mempool_req_sender
-        .send(MempoolRequest::UpdateNonces(op.accounts_updated))
+        .send(MempoolRequest::UpdateNonces(
+            op.accounts_updated,
+            committed_txs,
+        ))
         .await
         .map_err(|e| warn!("Failed notify mempool about account updates: {}", e))
         .unwrap_or_default();
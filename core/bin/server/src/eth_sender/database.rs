@@ -18,6 +18,90 @@ use storage::{ConnectionPool, StorageProcessor};
 // Local uses
 use super::transactions::ETHStats;
 
+/// Abstract database access trait, optimized for the needs of `ETHSender`.
+///
+/// Every method except `acquire_connection` expects a connection to be provided, so
+/// several calls can be grouped within one database transaction.
+#[async_trait::async_trait]
+pub(super) trait DatabaseInterface {
+    /// Returns connection to the database.
+    async fn acquire_connection(&self) -> Result<StorageProcessor<'_>, failure::Error>;
+
+    /// Loads the unconfirmed and unprocessed operations from the database.
+    /// Unconfirmed operations are Ethereum operations that were started, but not confirmed yet.
+    /// Unprocessed operations are zkSync operations that were not started at all.
+    async fn restore_state(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> Result<(VecDeque<ETHOperation>, Vec<Operation>), failure::Error>;
+
+    /// Saves a new unconfirmed operation to the database.
+    /// `ops` are the zkSync operations sent within the Ethereum operation (if any).
+    /// Priority fee is expected to be set for EIP-1559 transactions only.
+    async fn save_new_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_type: OperationType,
+        ops: &[Operation],
+        deadline_block: i64,
+        used_gas_price: U256,
+        used_priority_fee: Option<U256>,
+        raw_tx: Vec<u8>,
+    ) -> Result<InsertedOperationResponse, failure::Error>;
+
+    /// Adds a tx hash entry associated with some Ethereum operation to the database.
+    async fn add_hash_entry(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        hash: &H256,
+    ) -> Result<(), failure::Error>;
+
+    /// Adds a new tx info to the previously started Ethereum operation.
+    async fn update_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        new_deadline_block: i64,
+        new_gas_value: U256,
+        new_priority_fee: Option<U256>,
+    ) -> Result<(), failure::Error>;
+
+    /// Assigns a new nonce to the Ethereum operation, removing the hashes
+    /// of transactions sent with the old nonce.
+    async fn assign_new_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> Result<U256, failure::Error>;
+
+    /// Marks an operation as completed in the database.
+    async fn confirm_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+    ) -> Result<(), failure::Error>;
+
+    /// Loads the stored Ethereum operations stats.
+    async fn load_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> Result<ETHStats, failure::Error>;
+
+    /// Loads the stored gas price limit.
+    async fn load_gas_price_limit(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> Result<U256, failure::Error>;
+
+    /// Updates the stored gas price limit.
+    async fn update_gas_price_limit(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        value: U256,
+    ) -> Result<(), failure::Error>;
+}
+
 /// The actual database wrapper.
 /// This structure uses `StorageProcessor` to interact with an existing database.
 #[derive(Debug)]
@@ -32,14 +116,15 @@ impl Database {
     }
 }
 
-impl Database {
-    pub async fn acquire_connection(&self) -> Result<StorageProcessor<'_>, failure::Error> {
+#[async_trait::async_trait]
+impl DatabaseInterface for Database {
+    async fn acquire_connection(&self) -> Result<StorageProcessor<'_>, failure::Error> {
         let connection = self.db_pool.access_storage().await?;
 
         Ok(connection)
     }
 
-    pub async fn restore_state(
+    async fn restore_state(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> Result<(VecDeque<ETHOperation>, Vec<Operation>), failure::Error> {
@@ -54,22 +139,25 @@ impl Database {
         Ok((unconfirmed_ops, unprocessed_ops))
     }
 
-    pub async fn save_new_eth_tx(
+    async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
         op_type: OperationType,
-        op: Option<Operation>,
+        ops: &[Operation],
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
         raw_tx: Vec<u8>,
     ) -> Result<InsertedOperationResponse, failure::Error> {
+        let op_ids: Vec<i64> = ops.iter().map(|op| op.id.unwrap()).collect();
         let result = connection
             .ethereum_schema()
             .save_new_eth_tx(
                 op_type,
-                op.map(|op| op.id.unwrap()),
+                &op_ids,
                 deadline_block,
                 BigUint::from_str(&used_gas_price.to_string()).unwrap(),
+                used_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
                 raw_tx,
             )
             .await?;
@@ -77,7 +165,7 @@ impl Database {
         Ok(result)
     }
 
-    pub async fn add_hash_entry(
+    async fn add_hash_entry(
         &self,
         connection: &mut StorageProcessor<'_>,
         eth_op_id: i64,
@@ -89,12 +177,13 @@ impl Database {
             .await?)
     }
 
-    pub async fn update_eth_tx(
+    async fn update_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> Result<(), failure::Error> {
         Ok(connection
             .ethereum_schema()
@@ -102,11 +191,23 @@ impl Database {
                 eth_op_id,
                 new_deadline_block,
                 BigUint::from_str(&new_gas_value.to_string()).unwrap(),
+                new_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
             )
             .await?)
     }
 
-    pub async fn confirm_operation(
+    async fn assign_new_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> Result<U256, failure::Error> {
+        Ok(connection
+            .ethereum_schema()
+            .assign_new_nonce(eth_op_id)
+            .await?)
+    }
+
+    async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
@@ -114,7 +215,7 @@ impl Database {
         Ok(connection.ethereum_schema().confirm_eth_tx(hash).await?)
     }
 
-    pub async fn load_stats(
+    async fn load_stats(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> Result<ETHStats, failure::Error> {
@@ -122,7 +223,7 @@ impl Database {
         Ok(stats.into())
     }
 
-    pub async fn load_gas_price_limit(
+    async fn load_gas_price_limit(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> Result<U256, failure::Error> {
@@ -130,7 +231,7 @@ impl Database {
         Ok(limit)
     }
 
-    pub async fn update_gas_price_limit(
+    async fn update_gas_price_limit(
         &self,
         connection: &mut StorageProcessor<'_>,
         value: U256,
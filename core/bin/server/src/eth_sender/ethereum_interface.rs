@@ -8,7 +8,7 @@ use web3::transports::{EventLoopHandle, Http};
 use web3::types::{TransactionReceipt, H256, U256};
 // Workspace uses
 use super::ExecutedTxStatus;
-use eth_client::{ETHClient, SignedCallResult};
+use eth_client::{signer_from_source, ETHClient, Eip1559Fees, SignedCallResult};
 use models::abi::zksync_contract;
 use models::config_options::ConfigurationOptions;
 use std::time::Duration;
@@ -41,6 +41,28 @@ pub(super) trait EthereumInterface {
     /// Gets the current gas price.
     async fn gas_price(&self) -> Result<U256, failure::Error>;
 
+    /// Gets the base fees of the `block_count` latest blocks, followed by the base fee
+    /// of the next block.
+    async fn base_fee_history(&self, block_count: usize) -> Result<Vec<U256>, failure::Error>;
+
+    /// Gets the priority fee per gas suggested by the network.
+    async fn max_priority_fee(&self) -> Result<U256, failure::Error>;
+
+    /// Gets the nonce of the operator account based on the latest *mined* block.
+    async fn current_nonce(&self) -> Result<U256, failure::Error>;
+
+    /// Gets the amount of blocks committed in the zkSync contract.
+    async fn total_blocks_committed(&self) -> Result<u32, failure::Error>;
+
+    /// Gets the amount of blocks verified in the zkSync contract.
+    async fn total_blocks_verified(&self) -> Result<u32, failure::Error>;
+
+    /// Gets the amount of withdrawals awaiting for the completion in the zkSync contract.
+    async fn pending_withdrawals_count(&self) -> Result<u32, failure::Error>;
+
+    /// Estimates the gas required to execute the transaction with the encoded data.
+    async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, failure::Error>;
+
     /// Sends a signed transaction to the Ethereum blockchain.
     async fn send_tx(&self, signed_tx: &SignedCallResult) -> Result<(), failure::Error>;
 
@@ -55,6 +77,15 @@ pub(super) trait EthereumInterface {
         data: Vec<u8>,
         options: Options,
     ) -> Result<SignedCallResult, failure::Error>;
+
+    /// Signs the EIP-1559 transaction given the previously encoded data.
+    /// Fills in gas/nonce if not supplied inside options, `gas_price` of options is ignored.
+    async fn sign_prepared_eip1559_tx(
+        &self,
+        data: Vec<u8>,
+        fees: Eip1559Fees,
+        options: Options,
+    ) -> Result<SignedCallResult, failure::Error>;
 }
 
 /// Wrapper over `ETHClient` using `Http` transport.
@@ -70,13 +101,19 @@ impl EthereumHttpClient {
     pub fn new(options: &ConfigurationOptions) -> Result<Self, failure::Error> {
         let (_event_loop, transport) = Http::new(&options.web3_url)?;
 
+        let signer = signer_from_source(
+            options
+                .operator_eth_signer
+                .as_ref()
+                .expect("Operator Ethereum signer is required for eth_sender"),
+            options.operator_commit_eth_addr,
+        )?;
+
         let eth_client = ETHClient::new(
             transport,
             zksync_contract(),
             options.operator_commit_eth_addr,
-            options
-                .operator_private_key
-                .expect("Operator private key is required for eth_sender"),
+            signer,
             options.contract_eth_addr,
             options.chain_id,
             options.gas_price_factor,
@@ -159,6 +196,53 @@ impl EthereumInterface for EthereumHttpClient {
         self.eth_client.get_gas_price().await
     }
 
+    async fn base_fee_history(&self, block_count: usize) -> Result<Vec<U256>, failure::Error> {
+        self.sleep();
+        self.eth_client.get_base_fee_history(block_count).await
+    }
+
+    async fn max_priority_fee(&self) -> Result<U256, failure::Error> {
+        self.sleep();
+        self.eth_client.get_max_priority_fee().await
+    }
+
+    async fn current_nonce(&self) -> Result<U256, failure::Error> {
+        self.sleep();
+        Ok(self.eth_client.current_nonce().await?)
+    }
+
+    async fn total_blocks_committed(&self) -> Result<u32, failure::Error> {
+        self.sleep();
+        let total_blocks: U256 = self
+            .eth_client
+            .call_contract_function("totalBlocksCommitted", ())
+            .await?;
+        Ok(total_blocks.as_u32())
+    }
+
+    async fn total_blocks_verified(&self) -> Result<u32, failure::Error> {
+        self.sleep();
+        let total_blocks: U256 = self
+            .eth_client
+            .call_contract_function("totalBlocksVerified", ())
+            .await?;
+        Ok(total_blocks.as_u32())
+    }
+
+    async fn pending_withdrawals_count(&self) -> Result<u32, failure::Error> {
+        self.sleep();
+        let pending_withdrawals: U256 = self
+            .eth_client
+            .call_contract_function("numberOfPendingWithdrawals", ())
+            .await?;
+        Ok(pending_withdrawals.as_u32())
+    }
+
+    async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, failure::Error> {
+        self.sleep();
+        self.eth_client.estimate_gas(data).await
+    }
+
     fn encode_tx_data<P: Tokenize>(&self, func: &str, params: P) -> Vec<u8> {
         self.eth_client.encode_tx_data(func, params)
     }
@@ -171,4 +255,16 @@ impl EthereumInterface for EthereumHttpClient {
         self.sleep();
         self.eth_client.sign_prepared_tx(data, options).await
     }
+
+    async fn sign_prepared_eip1559_tx(
+        &self,
+        data: Vec<u8>,
+        fees: Eip1559Fees,
+        options: Options,
+    ) -> Result<SignedCallResult, failure::Error> {
+        self.sleep();
+        self.eth_client
+            .sign_prepared_eip1559_tx(data, fees, options)
+            .await
+    }
 }
@@ -2,14 +2,15 @@
 use std::{collections::VecDeque, marker::PhantomData, time::Instant};
 // External deps
 use web3::types::U256;
+// Workspace deps
+use eth_client::Eip1559Fees;
 // Local deps
-use crate::eth_sender::{database::Database, ethereum_interface::EthereumInterface};
+use crate::eth_sender::{database::DatabaseInterface, ethereum_interface::EthereumInterface};
 
 mod parameters;
 
-// TODO: Tests are commented for now as DB traits aren't implemented yet.
-// #[cfg(test)]
-// mod tests;
+#[cfg(test)]
+mod tests;
 
 /// Gas adjuster is an entity capable of scaling the gas price for
 /// all the Ethereum transactions.
@@ -20,20 +21,31 @@ mod parameters;
 /// transactions only), which guarantees that we will increase the
 /// gas price for transactions that were not mined by the network
 /// within a reasonable time.
+///
+/// For EIP-1559 transactions the same limits are applied to the max fee per gas,
+/// and the statistics are gathered for the effective gas price (base fee of the
+/// next block plus the priority fee suggested by the network).
 #[derive(Debug)]
-pub(super) struct GasAdjuster<ETH: EthereumInterface> {
+pub(super) struct GasAdjuster<ETH: EthereumInterface, DB: DatabaseInterface> {
     /// Collected statistics about recently used gas prices.
     statistics: GasStatistics,
     /// Timestamp of the last maximum gas price update.
     last_price_renewal: Instant,
     /// Timestamp of the last sample added to the `statistics`.
     last_sample_added: Instant,
+    /// Whether the network prices are obtained for EIP-1559 transactions.
+    eip1559_enabled: bool,
 
     _etherum_client: PhantomData<ETH>,
+    _db: PhantomData<DB>,
 }
 
-impl<ETH: EthereumInterface> GasAdjuster<ETH> {
-    pub async fn new(db: &Database) -> Self {
+impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
+    /// Amount of the recent blocks which base fee is taken into account
+    /// for the EIP-1559 transactions.
+    const BASE_FEE_HISTORY_BLOCKS: usize = 10;
+
+    pub async fn new(db: &DB, eip1559_enabled: bool) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
@@ -46,8 +58,10 @@ impl<ETH: EthereumInterface> GasAdjuster<ETH> {
             statistics: GasStatistics::new(gas_price_limit),
             last_price_renewal: Instant::now(),
             last_sample_added: Instant::now(),
+            eip1559_enabled,
 
             _etherum_client: PhantomData,
+            _db: PhantomData,
         }
     }
 
@@ -85,13 +99,87 @@ impl<ETH: EthereumInterface> GasAdjuster<ETH> {
         Ok(price)
     }
 
+    /// Calculates the fees for the EIP-1559 transaction.
+    ///
+    /// For a new transaction the priority fee is the one suggested by the network, and the max fee
+    /// is twice the highest base fee of the recent blocks plus the priority fee, so the transaction
+    /// remains valid even if the base fee grows for several blocks in a row.
+    ///
+    /// For the replacement of the stuck tx both fees must be increased by at least 10%,
+    /// we make them 15% higher (or use the new network values if they are higher).
+    pub async fn get_eip1559_fees(
+        &mut self,
+        ethereum: &ETH,
+        old_tx_fees: Option<Eip1559Fees>,
+    ) -> Result<Eip1559Fees, failure::Error> {
+        let base_fee = self.recent_base_fee(ethereum).await?;
+        let network_priority_fee = ethereum.max_priority_fee().await?;
+        let network_max_fee = base_fee * U256::from(2) + network_priority_fee;
+
+        let (max_fee, priority_fee) = if let Some(old_fees) = old_tx_fees {
+            // Stuck transaction, scale both fees up.
+            (
+                self.scale_up(old_fees.max_fee_per_gas, network_max_fee),
+                self.scale_up(old_fees.max_priority_fee_per_gas, network_priority_fee),
+            )
+        } else {
+            // New transaction, use the network values as the base.
+            (network_max_fee, network_priority_fee)
+        };
+
+        // Now, cut the max fee if it's too big. Priority fee can't exceed the max fee.
+        let max_fee = self.limit_max(max_fee);
+        let priority_fee = std::cmp::min(priority_fee, max_fee);
+
+        if max_fee == self.get_current_max_price() {
+            log::warn!(
+                "Maximum possible max fee per gas will be used: <{}>",
+                max_fee
+            );
+        }
+
+        Ok(Eip1559Fees {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: priority_fee,
+        })
+    }
+
+    /// Returns the highest base fee among the recent blocks (including the next one).
+    async fn recent_base_fee(&self, ethereum: &ETH) -> Result<U256, failure::Error> {
+        let base_fees = ethereum
+            .base_fee_history(Self::BASE_FEE_HISTORY_BLOCKS)
+            .await?;
+
+        base_fees
+            .into_iter()
+            .max()
+            .ok_or_else(|| failure::format_err!("Base fee history is empty"))
+    }
+
+    /// Returns the price per gas which is currently paid for the transactions in the network.
+    /// For EIP-1559 it's the base fee of the next block plus the suggested priority fee.
+    async fn network_gas_price(&self, ethereum: &ETH) -> Result<U256, failure::Error> {
+        if !self.eip1559_enabled {
+            return ethereum.gas_price().await;
+        }
+
+        let base_fees = ethereum.base_fee_history(1).await?;
+        let next_base_fee = base_fees
+            .last()
+            .copied()
+            .ok_or_else(|| failure::format_err!("Base fee history is empty"))?;
+        let priority_fee = ethereum.max_priority_fee().await?;
+
+        Ok(next_base_fee + priority_fee)
+    }
+
     /// Performs an actualization routine for `GasAdjuster`:
     /// This method is intended to be invoked periodically, and it updates the
     /// current max gas price limit according to the configurable update interval.
-    pub async fn keep_updated(&mut self, ethereum: &ETH, db: &Database) {
+    pub async fn keep_updated(&mut self, ethereum: &ETH, db: &DB) {
         if self.last_sample_added.elapsed() >= parameters::sample_adding_interval() {
             // Report the current price to be gathered by the statistics module.
-            match ethereum.gas_price().await {
+            match self.network_gas_price(ethereum).await {
                 Ok(network_price) => {
                     self.statistics.add_sample(network_price);
 
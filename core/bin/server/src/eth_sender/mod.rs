@@ -4,9 +4,10 @@
 //! every transaction is executed successfully and confirmed.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 // External uses
+use failure::{bail, ensure};
 use futures::{
     channel::{mpsc, oneshot},
     StreamExt,
@@ -17,17 +18,17 @@ use web3::{
     types::{TransactionReceipt, H256, U256},
 };
 // Workspace uses
-use eth_client::SignedCallResult;
+use eth_client::{Eip1559Fees, SignedCallResult};
 use models::{
     config_options::{ConfigurationOptions, EthSenderOptions},
-    ethereum::{ETHOperation, OperationType},
+    ethereum::{ETHOperation, EthOpId, OperationType},
     node::config,
     Action, Operation,
 };
 use storage::ConnectionPool;
 // Local uses
 use self::{
-    database::Database,
+    database::{Database, DatabaseInterface},
     ethereum_interface::{EthereumHttpClient, EthereumInterface},
     gas_adjuster::GasAdjuster,
     transactions::*,
@@ -41,9 +42,8 @@ mod gas_adjuster;
 mod transactions;
 mod tx_queue;
 
-// TODO: Restore tests
-// #[cfg(test)]
-// mod tests;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug)]
 pub enum ETHSenderRequest {
@@ -101,6 +101,12 @@ enum TxCheckMode {
 /// Note: make sure to save signed tx to db before sending it to ETH, this way we can be sure
 /// that state is always recoverable.
 ///
+/// If EIP-1559 support is enabled in the options, new operations are sent as type 2 transactions
+/// with the max fee and priority fee provided by `GasAdjuster`. Both fees are increased for the
+/// replacement of the stuck transaction. Type of the transaction is stored with the operation
+/// (operation has a priority fee only if it's sent via EIP-1559 transactions), so the replacement
+/// transactions have the same type as the original one.
+///
 /// # Concurrent transaction sending
 ///
 /// `ETHSender` supports sending multiple transaction to the Ethereum at the same time.
@@ -115,17 +121,42 @@ enum TxCheckMode {
 /// 2. Withdraw operations (only if both commit/verify for the same block operations were sent).
 /// 3. Commit operations.
 ///
+/// # Blocks aggregation
+///
+/// If configured, several consecutive blocks are committed (or verified) within one transaction
+/// via `commitMultipleBlocks` (or `verifyMultipleBlocks`) contract call. The base cost of the
+/// call is paid once per transaction, which makes it cheaper than sending a transaction per block.
+/// Blocks are aggregated by the `TxQueue`: the batch is sent once it reaches the maximum amount of
+/// blocks (or the gas limit), or once the oldest block in it has waited for the configured time.
+/// Aggregated blocks share the same `ETHOperation` and are confirmed together.
+///
 /// # Failure policy
 ///
-/// By default, `ETHSender` expects no transactions to fail, and thus upon a failure it will
-/// report the incident to the log and then panic to prevent continue working in a probably
-/// erroneous conditions. Failure handling policy is determined by a corresponding callback,
-/// which can be changed if needed.
-struct ETHSender<ETH: EthereumInterface> {
+/// `ETHSender` expects no transactions to fail, but some failures can be handled without the
+/// operator's involvement. Upon a failure, the reason is determined (see `TxFailure`):
+///
+/// - If the operation is already executed according to the contract state (e.g. block was
+///   committed by another transaction), the operation is marked as confirmed.
+/// - If the transaction ran out of gas, it is resent with a new nonce and an increased gas limit.
+/// - If the nonce of the operation was consumed by another transaction, the transaction is resent
+///   with a new nonce.
+///
+/// Any other failure is considered unrecoverable: `ETHSender` reports the incident to the log and
+/// stops sending transactions to prevent working in a probably erroneous conditions. The rest of
+/// the node keeps working, and the sender is expected to be restarted once the issue is resolved.
+///
+/// To avoid the out of gas failures, the gas limit of every transaction (including the replacements
+/// of the stuck ones) is checked against the gas estimation before sending. If the limit is not
+/// enough, the transaction is sent with the same nonce and the increased gas limit.
+///
+/// Nonce of the failed transaction is consumed, so the resent transaction gets a new one. Transactions
+/// sent for the later operations would be executed before it and fail, so these operations are resent
+/// with the new nonces as well, keeping the order of the operations.
+struct ETHSender<ETH: EthereumInterface, DB: DatabaseInterface> {
     /// Ongoing operations queue.
     ongoing_ops: VecDeque<ETHOperation>,
     /// Connection to the database.
-    db: Database,
+    db: DB,
     /// Ethereum intermediator.
     ethereum: ETH,
     /// Channel for receiving operations to commit.
@@ -135,17 +166,21 @@ struct ETHSender<ETH: EthereumInterface> {
     /// Queue for ordered transaction processing.
     tx_queue: TxQueue,
     /// Utility for managing the gas price for transactions.
-    gas_adjuster: GasAdjuster<ETH>,
+    gas_adjuster: GasAdjuster<ETH, DB>,
+    /// Gas limits increased for the operations which transactions ran out of gas.
+    gas_limit_overrides: HashMap<EthOpId, U256>,
+    /// Flag denoting that an unrecoverable failure occurred and no transactions should be sent.
+    is_stopped: bool,
     /// Settings for the `ETHSender`.
     options: EthSenderOptions,
     /// struct to communicate current verified block number to api server
     current_zksync_info: CurrentZksyncInfo,
 }
 
-impl<ETH: EthereumInterface> ETHSender<ETH> {
+impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
     pub async fn new(
         options: EthSenderOptions,
-        db: Database,
+        db: DB,
         ethereum: ETH,
         rx_for_eth: mpsc::Receiver<ETHSenderRequest>,
         op_notify: mpsc::Sender<Operation>,
@@ -167,13 +202,17 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
             .expect("Failed loading ETH operations stats");
 
         let tx_queue = TxQueueBuilder::new(options.max_txs_in_flight as usize)
+            .with_aggregation(
+                options.max_blocks_per_tx as usize,
+                options.aggregation_max_wait,
+            )
             .with_sent_pending_txs(ongoing_ops.len())
             .with_commit_operations_count(stats.commit_ops)
             .with_verify_operations_count(stats.verify_ops)
             .with_withdraw_operations_count(stats.withdraw_ops)
             .build();
 
-        let gas_adjuster = GasAdjuster::new(&db).await;
+        let gas_adjuster = GasAdjuster::new(&db, options.eip1559_enabled).await;
 
         drop(connection);
         let mut sender = Self {
@@ -184,6 +223,8 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
             op_notify,
             tx_queue,
             gas_adjuster,
+            gas_limit_overrides: HashMap::new(),
+            is_stopped: false,
             options,
             current_zksync_info,
         };
@@ -209,7 +250,7 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
                 .await
                 .unwrap_or_default();
 
-            if self.options.is_enabled {
+            if self.options.is_enabled && !self.is_stopped {
                 // ...and proceed them.
                 self.proceed_next_operations().await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
@@ -250,8 +291,13 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
         // Queue for storing all the operations that were not finished at this iteration.
         let mut new_ongoing_ops = VecDeque::new();
 
-        while let Some(tx) = self.tx_queue.pop_front() {
-            if let Err(e) = self.initialize_operation(tx.clone()).await {
+        while let Some(txs) = self.tx_queue.pop_front() {
+            if self.is_stopped {
+                self.tx_queue.return_popped(txs);
+                break;
+            }
+
+            if let Err(e) = self.initialize_operation(txs.clone()).await {
                 warn!(
                     "[{}:{}:{}] Error while trying to complete uncommitted op: {}",
                     file!(),
@@ -269,12 +315,21 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
 
                 // Return the unperformed operation to the queue, since failing the
                 // operation initialization means that it was not stored in the database.
-                self.tx_queue.return_popped(tx);
+                // The next operations can't be sent before it, so we retry on the next iteration.
+                self.tx_queue.return_popped(txs);
+                break;
             }
         }
 
         // Commit the next operations (if any).
         while let Some(mut current_op) = self.ongoing_ops.pop_front() {
+            if self.is_stopped {
+                // Sender was stopped while processing one of the previous operations,
+                // keep the rest of operations untouched.
+                new_ongoing_ops.push_back(current_op);
+                continue;
+            }
+
             // We perform a commitment step here. In case of error, we suppose that this is some
             // network issue which won't appear the next time, so we report the situation to the
             // log and consider the operation pending (meaning that we won't process it on this
@@ -304,23 +359,29 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
                 OperationCommitment::Committed => {
                     // Free a slot for the next tx in the queue.
                     self.tx_queue.report_commitment();
+                    self.gas_limit_overrides.remove(&current_op.id);
 
                     if current_op.is_verify() {
-                        let sync_op = current_op.op.expect("Should be verify operation");
-                        self.current_zksync_info
-                            .set_new_verified_block(sync_op.block.block_number);
-
-                        let contains_withdrawals = !sync_op.block.get_withdrawals_data().is_empty();
-
-                        // We notify about verify only when it's confirmed on the Ethereum.
-                        self.op_notify
-                            .try_send(sync_op)
-                            .map_err(|e| warn!("Failed notify about verify op confirmation: {}", e))
-                            .unwrap_or_default();
-
-                        if contains_withdrawals {
-                            // Complete pending withdrawals after each verify.
-                            self.add_complete_withdrawals_to_queue();
+                        // Aggregated blocks are verified together, so we handle each of them.
+                        for sync_op in current_op.ops {
+                            self.current_zksync_info
+                                .set_new_verified_block(sync_op.block.block_number);
+
+                            let contains_withdrawals =
+                                !sync_op.block.get_withdrawals_data().is_empty();
+
+                            // We notify about verify only when it's confirmed on the Ethereum.
+                            self.op_notify
+                                .try_send(sync_op)
+                                .map_err(|e| {
+                                    warn!("Failed notify about verify op confirmation: {}", e)
+                                })
+                                .unwrap_or_default();
+
+                            if contains_withdrawals {
+                                // Complete pending withdrawals after each verify.
+                                self.add_complete_withdrawals_to_queue();
+                            }
                         }
                     }
                 }
@@ -341,48 +402,69 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
     }
 
     /// Stores the new operation in the database and sends the corresponding transaction.
-    async fn initialize_operation(&mut self, tx: TxData) -> Result<(), failure::Error> {
+    /// If several operations are provided, they're sent within one transaction.
+    async fn initialize_operation(&mut self, txs: Vec<TxData>) -> Result<(), failure::Error> {
+        let op_type = match txs.first() {
+            Some(tx) => tx.op_type,
+            None => bail!("No operations to initialize"),
+        };
+        ensure!(
+            txs.iter().all(|tx| tx.op_type == op_type),
+            "Operations of different types can't be sent within one transaction"
+        );
+        let ops: Vec<Operation> = txs.iter().filter_map(|tx| tx.operation.clone()).collect();
+        let raw_tx = match txs.as_slice() {
+            [tx] => tx.raw.clone(),
+            _ => {
+                ensure!(
+                    ops.len() == txs.len(),
+                    "Only zkSync operations can be sent within one transaction"
+                );
+                self.operations_to_raw_tx(&ops)?
+            }
+        };
+
         let current_block = self.ethereum.block_number().await?;
         let deadline_block = self.get_deadline_block(current_block);
-        let gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, None)
-            .await?;
+        let (gas_price, priority_fee) = self.fees_for_new_tx().await?;
 
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
         // let (new_op, signed_tx) = self.db.transaction(|| {
-        let (new_op, signed_tx) = {
+        let (new_op, signed_tx, gas_limit) = {
             // First, we should store the operation in the database and obtain the assigned
             // operation ID and nonce. Without them we won't be able to sign the transaction.
             let assigned_data = self
                 .db
                 .save_new_eth_tx(
                     &mut transaction,
-                    tx.op_type,
-                    tx.operation.clone(),
+                    op_type,
+                    &ops,
                     deadline_block as i64,
                     gas_price,
-                    tx.raw.clone(),
+                    priority_fee,
+                    raw_tx.clone(),
                 )
                 .await?;
 
             let mut new_op = ETHOperation {
                 id: assigned_data.id,
-                op_type: tx.op_type,
-                op: tx.operation,
+                op_type,
+                ops,
                 nonce: assigned_data.nonce,
                 last_deadline_block: deadline_block,
                 last_used_gas_price: gas_price,
+                last_used_priority_fee: priority_fee,
                 used_tx_hashes: vec![], // No hash yet, will be added below.
-                encoded_tx_data: tx.raw,
+                encoded_tx_data: raw_tx,
                 confirmed: false,
                 final_hash: None,
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let gas_limit = self.estimated_gas_limit(&new_op).await;
+            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op, gas_limit).await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -390,7 +472,7 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
                 .add_hash_entry(&mut transaction, new_op.id, &signed_tx.hash)
                 .await?;
 
-            (new_op, signed_tx)
+            (new_op, signed_tx, gas_limit)
         };
 
         // We should store the operation as `ongoing` **before** sending it as well,
@@ -411,6 +493,7 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
         });
 
         transaction.commit().await?;
+        self.set_gas_limit(&new_op, gas_limit);
 
         Ok(())
     }
@@ -420,24 +503,40 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
     fn eth_tx_description(&self, tx: &SignedCallResult) -> String {
         // Gas price in gwei (wei / 10^9).
         let gas_price = tx.gas_price / (1_000_000_000);
-        format!(
-            "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
-            tx.hash, gas_price, tx.nonce
-        )
+        match tx.max_priority_fee_per_gas {
+            Some(priority_fee) => format!(
+                "<hash: {:#x}; max fee: {} gwei; priority fee: {} gwei; nonce: {}>",
+                tx.hash,
+                gas_price,
+                priority_fee / (1_000_000_000),
+                tx.nonce
+            ),
+            None => format!(
+                "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
+                tx.hash, gas_price, tx.nonce
+            ),
+        }
     }
 
     /// Helper method to obtain the string representation of the zkSync operation.
     /// Intended to be used for log entries.
     fn zksync_operation_description(&self, operation: &ETHOperation) -> String {
-        if let Some(op) = &operation.op {
-            format!(
+        match (operation.ops.first(), operation.ops.last()) {
+            (Some(op), Some(last_op)) if operation.ops.len() > 1 => format!(
+                "<ids {}..{}; action: {}; blocks: {}..{}>",
+                op.id.expect("ID must be set"),
+                last_op.id.expect("ID must be set"),
+                op.action.to_string(),
+                op.block.block_number,
+                last_op.block.block_number
+            ),
+            (Some(op), _) => format!(
                 "<id {}; action: {}; block: {}>",
                 op.id.expect("ID must be set"),
                 op.action.to_string(),
                 op.block.block_number
-            )
-        } else {
-            "<not applicable>".into()
+            ),
+            _ => "<not applicable>".into(),
         }
     }
 
@@ -491,20 +590,46 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
                 }
                 TxCheckOutcome::Failed(receipt) => {
                     warn!(
-                        "ETH transaction failed: tx: {:#x}, op_type: {:?}, op: {:?}; tx_receipt: {:#?} ",
+                        "ETH transaction failed: tx: {:#x}, op_type: {:?}, ops: {:?}; tx_receipt: {:#?} ",
                         tx_hash,
                         op.op_type,
-                        op.op,
+                        op.ops,
                         receipt,
                     );
                     // Process the failure according to the chosen policy.
-                    self.failure_handler(&receipt);
+                    let failure = self.classify_failure(op, &receipt);
+                    return self.failure_handler(op, failure, current_block).await;
                 }
             }
         }
 
         // Reaching this point will mean that the latest transaction got stuck.
-        // We should create another tx based on it, and send it.
+        // If the operation nonce is already used, none of the sent transactions can be mined
+        // anymore, since the nonce was consumed by some other transaction.
+        let mined_nonce = self.ethereum.current_nonce().await?;
+        if mined_nonce > op.nonce {
+            warn!(
+                "Nonce {} of ETH Operation <id: {}> was consumed by another transaction (account nonce: {})",
+                op.nonce, op.id, mined_nonce,
+            );
+            return self
+                .failure_handler(op, TxFailure::NonceConflict, current_block)
+                .await;
+        }
+
+        // Otherwise we should create another tx based on the stuck one, and send it.
+        self.send_supplement_tx(op, current_block).await?;
+
+        Ok(OperationCommitment::Pending)
+    }
+
+    /// Creates a new transaction for the operation with increased gas price, stores it in the database
+    /// and sends it to the Ethereum.
+    async fn send_supplement_tx(
+        &mut self,
+        op: &mut ETHOperation,
+        current_block: u64,
+    ) -> Result<(), failure::Error> {
         let deadline_block = self.get_deadline_block(current_block);
         // Raw tx contents are the same for every transaction, so we just
         // create a new one from the old one with updated parameters.
@@ -519,6 +644,7 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
                 op.id,
                 deadline_block as i64,
                 new_tx.gas_price,
+                new_tx.max_priority_fee_per_gas,
             )
             .await?;
         self.db
@@ -533,17 +659,238 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
         self.ethereum.send_tx(&new_tx).await?;
         transaction.commit().await?;
 
-        Ok(OperationCommitment::Pending)
+        Ok(())
+    }
+
+    /// Determines the reason of the transaction failure given its receipt.
+    fn classify_failure(&self, op: &ETHOperation, receipt: &TransactionReceipt) -> TxFailure {
+        match receipt.gas_used {
+            Some(gas_used) if gas_used >= self.gas_limit(op) => TxFailure::OutOfGas,
+            _ => TxFailure::Reverted,
+        }
+    }
+
+    /// Chooses the way to handle the failure of the operation.
+    async fn resolve_failure(
+        &self,
+        op: &ETHOperation,
+        tx_failure: TxFailure,
+    ) -> Result<FailureResolution, failure::Error> {
+        // Regardless of the failure reason, there is nothing to do if the operation
+        // is already executed (e.g. by a transaction sent before the node restart).
+        match self.operation_execution(op).await? {
+            OperationExecution::Executed => return Ok(FailureResolution::AlreadyExecuted),
+            OperationExecution::PartiallyExecuted => {
+                // Resending the same blocks would fail again, since some of them are processed.
+                return Ok(FailureResolution::Unrecoverable(
+                    "aggregated blocks are partially executed by another transaction".to_string(),
+                ));
+            }
+            OperationExecution::NotExecuted => {}
+        }
+
+        let gas_limit = self.gas_limit(op);
+        let resolution = match tx_failure {
+            TxFailure::OutOfGas => match self.increased_gas_limit(gas_limit) {
+                Some(new_gas_limit) => FailureResolution::Resend {
+                    gas_limit: new_gas_limit,
+                },
+                None => FailureResolution::Unrecoverable(format!(
+                    "transaction ran out of gas, and the gas limit can't be increased beyond {}",
+                    self.options.max_gas_limit
+                )),
+            },
+            TxFailure::NonceConflict => FailureResolution::Resend { gas_limit },
+            TxFailure::Reverted => {
+                FailureResolution::Unrecoverable("transaction was reverted".to_string())
+            }
+        };
+
+        Ok(resolution)
+    }
+
+    /// Checks whether the operation is already executed according to the contract state.
+    /// Every block of the aggregated operation is checked, since some of them may be executed
+    /// by another transaction.
+    async fn operation_execution(
+        &self,
+        op: &ETHOperation,
+    ) -> Result<OperationExecution, failure::Error> {
+        let processed_blocks = match op.op_type {
+            OperationType::Commit => self.ethereum.total_blocks_committed().await?,
+            OperationType::Verify => self.ethereum.total_blocks_verified().await?,
+            OperationType::Withdraw => {
+                // There is no way to check whether the particular `completeWithdrawals` call
+                // was executed, but there is nothing to do once the queue of pending withdrawals
+                // is empty.
+                let execution = if self.ethereum.pending_withdrawals_count().await? == 0 {
+                    OperationExecution::Executed
+                } else {
+                    OperationExecution::NotExecuted
+                };
+                return Ok(execution);
+            }
+        };
+
+        ensure!(
+            !op.ops.is_empty(),
+            "No zkSync operations for ETH Operation <id: {}, type: {:?}>",
+            op.id,
+            op.op_type
+        );
+        let executed_count = op
+            .ops
+            .iter()
+            .filter(|sync_op| sync_op.block.block_number <= processed_blocks)
+            .count();
+        let execution = if executed_count == op.ops.len() {
+            OperationExecution::Executed
+        } else if executed_count > 0 {
+            OperationExecution::PartiallyExecuted
+        } else {
+            OperationExecution::NotExecuted
+        };
+
+        Ok(execution)
+    }
+
+    /// Handles a transaction execution failure according to the failure policy:
+    /// operation is either marked as confirmed, or resent, or the `ETHSender` is stopped.
+    async fn failure_handler(
+        &mut self,
+        op: &mut ETHOperation,
+        tx_failure: TxFailure,
+        current_block: u64,
+    ) -> Result<OperationCommitment, failure::Error> {
+        match self.resolve_failure(op, tx_failure).await? {
+            FailureResolution::AlreadyExecuted => {
+                // We don't know the hash of the transaction which actually executed the operation,
+                // so the latest sent one is considered final.
+                let final_hash = *op
+                    .used_tx_hashes
+                    .last()
+                    .expect("OperationETHState should have at least one transaction");
+                info!(
+                    "ETH Operation <id: {}, type: {:?}> is already executed, marking it as confirmed. ZKSync operation: {}",
+                    op.id, op.op_type, self.zksync_operation_description(op),
+                );
+                let mut connection = self.db.acquire_connection().await?;
+                self.db
+                    .confirm_operation(&mut connection, &final_hash)
+                    .await?;
+
+                Ok(OperationCommitment::Committed)
+            }
+            FailureResolution::Resend { gas_limit } => {
+                info!(
+                    "Resending ETH Operation <id: {}, type: {:?}> after {:?} with a new nonce and gas limit {}",
+                    op.id, op.op_type, tx_failure, gas_limit,
+                );
+                let failed_nonce = op.nonce;
+                self.gas_limit_overrides.insert(op.id, gas_limit);
+                self.resend_with_new_nonce(op, current_block).await?;
+                self.resend_later_operations(failed_nonce, current_block)
+                    .await?;
+
+                Ok(OperationCommitment::Pending)
+            }
+            FailureResolution::Unrecoverable(reason) => {
+                error!(
+                    "ETH sender is stopped after unrecoverable failure of ETH Operation <id: {}, type: {:?}>: {}. \
+                     ZKSync operation: {}. Manual intervention is required.",
+                    op.id, op.op_type, reason, self.zksync_operation_description(op),
+                );
+                self.is_stopped = true;
+
+                Ok(OperationCommitment::Pending)
+            }
+        }
+    }
+
+    /// Resends the ongoing operations sent after the nonce `failed_nonce` with the new nonces,
+    /// so they're executed after the operation resent instead of the failed one.
+    async fn resend_later_operations(
+        &mut self,
+        failed_nonce: U256,
+        current_block: u64,
+    ) -> Result<(), failure::Error> {
+        let mut ongoing_ops = std::mem::take(&mut self.ongoing_ops);
+        let mut result = Ok(());
+        for later_op in ongoing_ops.iter_mut() {
+            if later_op.nonce > failed_nonce {
+                info!(
+                    "Resending ETH Operation <id: {}, type: {:?}> after the resent operation with a new nonce",
+                    later_op.id, later_op.op_type,
+                );
+                result = self.resend_with_new_nonce(later_op, current_block).await;
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+        // Operations are returned back even if one of them was not resent,
+        // so it will be resent once it's considered stuck.
+        self.ongoing_ops = ongoing_ops;
+
+        result
     }
 
-    /// Handles a transaction execution failure by reporting the issue to the log
-    /// and terminating the node.
-    fn failure_handler(&self, receipt: &TransactionReceipt) -> ! {
-        error!(
-            "Ethereum transaction unexpectedly failed. Receipt: {:#?}",
-            receipt
+    /// Assigns a new nonce to the operation and sends a new transaction for it.
+    async fn resend_with_new_nonce(
+        &mut self,
+        op: &mut ETHOperation,
+        current_block: u64,
+    ) -> Result<(), failure::Error> {
+        let deadline_block = self.get_deadline_block(current_block);
+        let (gas_price, priority_fee) = self.fees_for_new_tx().await?;
+
+        let mut connection = self.db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+
+        // The operation is updated only after the changes are stored in the database,
+        // so its state won't diverge from the stored one in case of error.
+        let mut updated_op = op.clone();
+        updated_op.nonce = self.db.assign_new_nonce(&mut transaction, op.id).await?;
+        updated_op.last_deadline_block = deadline_block;
+        updated_op.last_used_gas_price = gas_price;
+        updated_op.last_used_priority_fee = priority_fee;
+        // Transactions sent with the old nonce can't be mined anymore, so they're forgotten.
+        updated_op.used_tx_hashes.clear();
+
+        let gas_limit = self.estimated_gas_limit(&updated_op).await;
+        let signed_tx = Self::sign_new_tx(&self.ethereum, &updated_op, gas_limit).await?;
+        updated_op.used_tx_hashes.push(signed_tx.hash);
+
+        self.db
+            .update_eth_tx(
+                &mut transaction,
+                op.id,
+                deadline_block as i64,
+                gas_price,
+                priority_fee,
+            )
+            .await?;
+        self.db
+            .add_hash_entry(&mut transaction, op.id, &signed_tx.hash)
+            .await?;
+
+        info!(
+            "Resending tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}]",
+            op.id,
+            op.op_type,
+            self.eth_tx_description(&signed_tx),
         );
-        panic!("Cannot operate after unexpected TX failure");
+        self.ethereum.send_tx(&signed_tx).await.unwrap_or_else(|e| {
+            // Same as for the new transactions, sending error is not critical: transaction
+            // will be considered stuck and resent.
+            warn!("Error while sending the operation: {}", e);
+        });
+        transaction.commit().await?;
+
+        self.set_gas_limit(&updated_op, gas_limit);
+        *op = updated_op;
+
+        Ok(())
     }
 
     /// Helper method encapsulating the logic of determining the next deadline block.
@@ -600,16 +947,13 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
     async fn sign_new_tx(
         ethereum: &ETH,
         op: &ETHOperation,
+        gas_limit: U256,
     ) -> Result<SignedCallResult, failure::Error> {
         let tx_options = {
             let mut options = Options::default();
             options.nonce = Some(op.nonce);
             options.gas_price = Some(op.last_used_gas_price);
 
-            // We set the gas limit for commit / verify operations as pre-calculated estimation.
-            // This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
-            let gas_limit = Self::gas_limit_for_op(op);
-
             assert!(
                 gas_limit > 0.into(),
                 "Proposed gas limit for operation is 0; operation: {:?}",
@@ -627,29 +971,118 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
             options
         };
 
-        let signed_tx = ethereum
-            .sign_prepared_tx(op.encoded_tx_data.clone(), tx_options)
-            .await?;
+        let signed_tx = match op.last_used_priority_fee {
+            Some(priority_fee) => {
+                let fees = Eip1559Fees {
+                    max_fee_per_gas: op.last_used_gas_price,
+                    max_priority_fee_per_gas: priority_fee,
+                };
+                ethereum
+                    .sign_prepared_eip1559_tx(op.encoded_tx_data.clone(), fees, tx_options)
+                    .await?
+            }
+            None => {
+                ethereum
+                    .sign_prepared_tx(op.encoded_tx_data.clone(), tx_options)
+                    .await?
+            }
+        };
 
         Ok(signed_tx)
     }
 
+    /// Obtains the fees for the transaction sent with a new nonce: the gas price and, if EIP-1559
+    /// is enabled, the priority fee (in this case the gas price is the max fee per gas).
+    async fn fees_for_new_tx(&mut self) -> Result<(U256, Option<U256>), failure::Error> {
+        if self.options.eip1559_enabled {
+            let fees = self
+                .gas_adjuster
+                .get_eip1559_fees(&self.ethereum, None)
+                .await?;
+            Ok((fees.max_fee_per_gas, Some(fees.max_priority_fee_per_gas)))
+        } else {
+            let gas_price = self
+                .gas_adjuster
+                .get_gas_price(&self.ethereum, None)
+                .await?;
+            Ok((gas_price, None))
+        }
+    }
+
+    /// Returns the gas limit for the next transaction of the operation: the increased one
+    /// if the previous transaction ran out of gas, and the default one otherwise.
+    fn gas_limit(&self, op: &ETHOperation) -> U256 {
+        self.gas_limit_overrides
+            .get(&op.id)
+            .copied()
+            .unwrap_or_else(|| Self::gas_limit_for_op(op))
+    }
+
+    /// Remembers the gas limit used for the transaction of the operation,
+    /// so the failure of this transaction is classified correctly.
+    fn set_gas_limit(&mut self, op: &ETHOperation, gas_limit: U256) {
+        if gas_limit != Self::gas_limit_for_op(op) {
+            self.gas_limit_overrides.insert(op.id, gas_limit);
+        }
+    }
+
+    /// Increases the gas limit by the configured percentage.
+    /// Returns `None` if the increased limit exceeds the configured maximum.
+    fn increased_gas_limit(&self, gas_limit: U256) -> Option<U256> {
+        let new_gas_limit =
+            gas_limit * U256::from(100 + self.options.gas_limit_increase_percent) / U256::from(100);
+        if new_gas_limit > U256::from(self.options.max_gas_limit) {
+            None
+        } else {
+            Some(new_gas_limit)
+        }
+    }
+
+    /// Returns the gas limit for the next transaction of the operation (see `gas_limit`),
+    /// increased in advance if the gas estimation shows that it's not enough.
+    async fn estimated_gas_limit(&self, op: &ETHOperation) -> U256 {
+        let gas_limit = self.gas_limit(op);
+        let estimated_gas = match self.ethereum.estimate_gas(op.encoded_tx_data.clone()).await {
+            Ok(estimated_gas) => estimated_gas,
+            Err(e) => {
+                // Estimation fails if the call can't be executed on top of the current state
+                // (e.g. the previous block is not committed yet), so the limit is kept as is.
+                debug!(
+                    "Failed to estimate gas for <ETH Operation id: {}>: {}",
+                    op.id, e
+                );
+                return gas_limit;
+            }
+        };
+
+        if estimated_gas <= gas_limit {
+            return gas_limit;
+        }
+        let new_gas_limit = self
+            .increased_gas_limit(estimated_gas)
+            .unwrap_or_else(|| U256::from(self.options.max_gas_limit));
+        warn!(
+            "Gas limit {} for <ETH Operation id: {}> is below the estimation {}, using {}",
+            gas_limit, op.id, estimated_gas, new_gas_limit
+        );
+        new_gas_limit
+    }
+
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
+    /// We set the gas limit for commit / verify operations as pre-calculated estimation.
+    /// This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
+    /// For the aggregated blocks the base cost of the contract call is taken into account only once.
     fn gas_limit_for_op(op: &ETHOperation) -> U256 {
         match op.op_type {
             OperationType::Commit => {
-                op.op
-                    .as_ref()
-                    .expect("No zkSync operation for Commit")
-                    .block
-                    .commit_gas_limit
+                assert!(!op.ops.is_empty(), "No zkSync operation for Commit");
+                let limits: Vec<U256> = op.ops.iter().map(|op| op.block.commit_gas_limit).collect();
+                GasCounter::aggregated_commit_gas_limit(&limits)
             }
             OperationType::Verify => {
-                op.op
-                    .as_ref()
-                    .expect("No zkSync operation for Verify")
-                    .block
-                    .verify_gas_limit
+                assert!(!op.ops.is_empty(), "No zkSync operation for Verify");
+                let limits: Vec<U256> = op.ops.iter().map(|op| op.block.verify_gas_limit).collect();
+                GasCounter::aggregated_verify_gas_limit(&limits)
             }
             OperationType::Withdraw => GasCounter::complete_withdrawals_gas_limit(),
         }
@@ -662,32 +1095,58 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
         deadline_block: u64,
         stuck_tx: &mut ETHOperation,
     ) -> Result<SignedCallResult, failure::Error> {
-        let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
+        let (tx_options, eip1559_fees) = self.tx_options_from_stuck_tx(stuck_tx).await?;
 
         let raw_tx = stuck_tx.encoded_tx_data.clone();
-        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
+        let signed_tx = match eip1559_fees {
+            Some(fees) => {
+                self.ethereum
+                    .sign_prepared_eip1559_tx(raw_tx, fees, tx_options)
+                    .await?
+            }
+            None => self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?,
+        };
 
         stuck_tx.last_deadline_block = deadline_block;
         stuck_tx.last_used_gas_price = signed_tx.gas_price;
+        stuck_tx.last_used_priority_fee = signed_tx.max_priority_fee_per_gas;
         stuck_tx.used_tx_hashes.push(signed_tx.hash);
 
         Ok(signed_tx)
     }
 
     /// Creates a new tx options from a stuck transaction, with updated gas amount
-    /// and nonce.
+    /// and nonce. If the stuck transaction is an EIP-1559 one, the updated fees are
+    /// returned as well.
     async fn tx_options_from_stuck_tx(
         &mut self,
         stuck_tx: &ETHOperation,
-    ) -> Result<Options, failure::Error> {
+    ) -> Result<(Options, Option<Eip1559Fees>), failure::Error> {
         let old_tx_gas_price = stuck_tx.last_used_gas_price;
 
-        let new_gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
-            .await?;
+        let (new_gas_price, new_eip1559_fees) = match stuck_tx.last_used_priority_fee {
+            Some(old_priority_fee) => {
+                let old_fees = Eip1559Fees {
+                    max_fee_per_gas: old_tx_gas_price,
+                    max_priority_fee_per_gas: old_priority_fee,
+                };
+                let new_fees = self
+                    .gas_adjuster
+                    .get_eip1559_fees(&self.ethereum, Some(old_fees))
+                    .await?;
+                (new_fees.max_fee_per_gas, Some(new_fees))
+            }
+            None => {
+                let new_gas_price = self
+                    .gas_adjuster
+                    .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
+                    .await?;
+                (new_gas_price, None)
+            }
+        };
         let nonce = stuck_tx.nonce;
-        let gas_limit = Self::gas_limit_for_op(stuck_tx);
+        let gas_limit = self.estimated_gas_limit(stuck_tx).await;
+        self.set_gas_limit(stuck_tx, gas_limit);
 
         assert!(
             gas_limit > 0.into(),
@@ -704,11 +1163,13 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
             gas_limit,
         );
 
-        Ok(Options::with(move |opt| {
+        let options = Options::with(move |opt| {
             opt.gas_price = Some(new_gas_price);
             opt.nonce = Some(nonce);
             opt.gas = Some(gas_limit);
-        }))
+        });
+
+        Ok((options, new_eip1559_fees))
     }
 
     /// Encodes the operation data to the Ethereum tx payload (not signs it!).
@@ -759,6 +1220,97 @@ impl<ETH: EthereumInterface> ETHSender<ETH> {
         }
     }
 
+    /// Encodes several zkSync operations of the same type to the payload of the Ethereum tx
+    /// which processes all of them at once (not signs it!).
+    /// Operations must belong to the consecutive blocks.
+    fn operations_to_raw_tx(&self, ops: &[Operation]) -> Result<Vec<u8>, failure::Error> {
+        let first_op = match ops.first() {
+            Some(op) => op,
+            None => bail!("No operations to encode"),
+        };
+        let first_block_number = u64::from(first_op.block.block_number);
+        for (expected_block_number, op) in (first_op.block.block_number..).zip(ops) {
+            ensure!(
+                op.block.block_number == expected_block_number,
+                "Aggregated blocks must be consecutive: expected block {}, got {}",
+                expected_block_number,
+                op.block.block_number
+            );
+        }
+
+        match &first_op.action {
+            Action::Commit => {
+                let mut fee_accounts = Vec::with_capacity(ops.len());
+                let mut roots = Vec::with_capacity(ops.len());
+                let mut public_data = Vec::new();
+                let mut public_data_sizes = Vec::with_capacity(ops.len());
+                let mut eth_witness = Vec::new();
+                let mut eth_witness_sizes = Vec::new();
+                let mut eth_witness_sizes_counts = Vec::with_capacity(ops.len());
+
+                for op in ops {
+                    if let Action::Verify { .. } = op.action {
+                        bail!("Verify operation can't be committed");
+                    }
+                    let block_public_data = op.block.get_eth_public_data();
+                    let (mut block_witness, block_witness_sizes) = op.block.get_eth_witness_data();
+
+                    fee_accounts.push(u64::from(op.block.fee_account));
+                    roots.push(op.block.get_eth_encoded_root());
+                    public_data_sizes.push(block_public_data.len() as u64);
+                    public_data.extend(block_public_data);
+                    eth_witness.append(&mut block_witness);
+                    eth_witness_sizes_counts.push(block_witness_sizes.len() as u64);
+                    eth_witness_sizes.extend(block_witness_sizes);
+                }
+
+                Ok(self.ethereum.encode_tx_data(
+                    "commitMultipleBlocks",
+                    (
+                        first_block_number,
+                        fee_accounts,
+                        roots,
+                        public_data,
+                        public_data_sizes,
+                        eth_witness,
+                        eth_witness_sizes,
+                        eth_witness_sizes_counts,
+                    ),
+                ))
+            }
+            Action::Verify { .. } => {
+                let mut proofs = Vec::new();
+                let mut proof_sizes = Vec::with_capacity(ops.len());
+                let mut withdrawals_data = Vec::new();
+                let mut withdrawals_data_sizes = Vec::with_capacity(ops.len());
+
+                for op in ops {
+                    let proof = match &op.action {
+                        Action::Verify { proof } => &proof.proof,
+                        Action::Commit => bail!("Commit operation can't be verified"),
+                    };
+                    let block_withdrawals_data = op.block.get_withdrawals_data();
+
+                    proof_sizes.push(proof.len() as u64);
+                    proofs.extend_from_slice(proof);
+                    withdrawals_data_sizes.push(block_withdrawals_data.len() as u64);
+                    withdrawals_data.extend(block_withdrawals_data);
+                }
+
+                Ok(self.ethereum.encode_tx_data(
+                    "verifyMultipleBlocks",
+                    (
+                        first_block_number,
+                        proofs,
+                        proof_sizes,
+                        withdrawals_data,
+                        withdrawals_data_sizes,
+                    ),
+                ))
+            }
+        }
+    }
+
     /// Encodes the zkSync operation to the tx payload and adds it to the queue.
     fn add_operation_to_queue(&mut self, op: Operation) {
         let raw_tx = self.operation_to_raw_tx(&op);
@@ -1,22 +1,23 @@
 //! Mocking utilities for tests.
 
 // Built-in deps
-use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
 // External uses
 use futures::channel::mpsc;
 use web3::contract::{tokens::Tokenize, Options};
 use web3::types::{H256, U256};
 // Workspace uses
-use eth_client::SignedCallResult;
+use eth_client::{Eip1559Fees, SignedCallResult};
 use models::{
     config_options::EthSenderOptions,
     ethereum::{ETHOperation, EthOpId, InsertedOperationResponse, OperationType},
     Action, Operation,
 };
+use storage::StorageProcessor;
 // Local uses
 use super::ETHSender;
-// use crate::eth_sender::database::DatabaseAccess;
+use crate::eth_sender::database::DatabaseInterface;
 use crate::eth_sender::ethereum_interface::EthereumInterface;
 use crate::eth_sender::transactions::{ETHStats, ExecutedTxStatus};
 use crate::eth_sender::ETHSenderRequest;
@@ -25,15 +26,18 @@ use crate::utils::current_zksync_info::CurrentZksyncInfo;
 const CHANNEL_CAPACITY: usize = 16;
 
 /// Mock database is capable of recording all the incoming requests for the further analysis.
+///
+/// Note that the connection provided by `acquire_connection` is a real one (so the methods
+/// using database transactions can be tested), but it is not used to store any data.
 #[derive(Debug, Default)]
 pub(in crate::eth_sender) struct MockDatabase {
     restore_state: VecDeque<ETHOperation>,
-    unconfirmed_operations: RefCell<HashMap<i64, ETHOperation>>,
-    confirmed_operations: RefCell<HashMap<i64, ETHOperation>>,
-    nonce: Cell<i64>,
-    gas_price_limit: Cell<U256>,
-    pending_op_id: Cell<EthOpId>,
-    stats: RefCell<ETHStats>,
+    unconfirmed_operations: RwLock<HashMap<i64, ETHOperation>>,
+    confirmed_operations: RwLock<HashMap<i64, ETHOperation>>,
+    nonce: RwLock<i64>,
+    gas_price_limit: RwLock<U256>,
+    pending_op_id: RwLock<EthOpId>,
+    stats: RwLock<ETHStats>,
 }
 
 impl MockDatabase {
@@ -55,72 +59,105 @@ impl MockDatabase {
 
         Self {
             restore_state,
-            nonce: Cell::new(nonce as i64),
-            gas_price_limit: Cell::new(gas_price_limit.into()),
-            pending_op_id: Cell::new(pending_op_id as EthOpId),
-            stats: RefCell::new(stats),
-            unconfirmed_operations: RefCell::new(unconfirmed_operations),
+            nonce: RwLock::new(nonce as i64),
+            gas_price_limit: RwLock::new(gas_price_limit.into()),
+            pending_op_id: RwLock::new(pending_op_id as EthOpId),
+            stats: RwLock::new(stats),
+            unconfirmed_operations: RwLock::new(unconfirmed_operations),
             ..Default::default()
         }
     }
 
     /// Ensures that the provided transaction is stored in the database and not confirmed yet.
     pub fn assert_stored(&self, tx: &ETHOperation) {
-        assert_eq!(self.unconfirmed_operations.borrow().get(&tx.id), Some(tx));
+        assert_eq!(
+            self.unconfirmed_operations.read().unwrap().get(&tx.id),
+            Some(tx)
+        );
 
-        assert!(self.confirmed_operations.borrow().get(&tx.id).is_none());
+        assert!(self
+            .confirmed_operations
+            .read()
+            .unwrap()
+            .get(&tx.id)
+            .is_none());
     }
 
     /// Ensures that the provided transaction is stored as confirmed.
     pub fn assert_confirmed(&self, tx: &ETHOperation) {
-        assert_eq!(self.confirmed_operations.borrow().get(&tx.id), Some(tx));
+        assert_eq!(
+            self.confirmed_operations.read().unwrap().get(&tx.id),
+            Some(tx)
+        );
 
-        assert!(self.unconfirmed_operations.borrow().get(&tx.id).is_none());
+        assert!(self
+            .unconfirmed_operations
+            .read()
+            .unwrap()
+            .get(&tx.id)
+            .is_none());
     }
 
-    fn next_nonce(&self) -> Result<i64, failure::Error> {
-        let old_value = self.nonce.get();
-        let new_value = old_value + 1;
-        self.nonce.set(new_value);
+    fn next_nonce(&self) -> i64 {
+        let mut nonce = self.nonce.write().unwrap();
+        let old_value = *nonce;
+        *nonce += 1;
 
-        Ok(old_value)
+        old_value
     }
 }
 
-impl DatabaseAccess for MockDatabase {
-    fn restore_state(&self) -> Result<(VecDeque<ETHOperation>, Vec<Operation>), failure::Error> {
+#[async_trait::async_trait]
+impl DatabaseInterface for MockDatabase {
+    async fn acquire_connection(&self) -> Result<StorageProcessor<'_>, failure::Error> {
+        StorageProcessor::establish_connection().await
+    }
+
+    async fn restore_state(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> Result<(VecDeque<ETHOperation>, Vec<Operation>), failure::Error> {
         Ok((self.restore_state.clone(), Vec::new()))
     }
 
-    fn save_new_eth_tx(
+    async fn save_new_eth_tx(
         &self,
+        _connection: &mut StorageProcessor<'_>,
         op_type: OperationType,
-        op: Option<Operation>,
+        ops: &[Operation],
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
         encoded_tx_data: Vec<u8>,
     ) -> Result<InsertedOperationResponse, failure::Error> {
-        let id = self.pending_op_id.get();
-        let new_id = id + 1;
-        self.pending_op_id.set(new_id);
+        let id = {
+            let mut pending_op_id = self.pending_op_id.write().unwrap();
+            let id = *pending_op_id;
+            *pending_op_id += 1;
+            id
+        };
 
-        let nonce = self.next_nonce()?;
+        let nonce = self.next_nonce();
 
         // Store with the assigned ID.
         let state = ETHOperation {
             id,
             op_type,
-            op,
+            ops: ops.to_vec(),
             nonce: nonce.into(),
             last_deadline_block: deadline_block as u64,
             last_used_gas_price: used_gas_price,
+            last_used_priority_fee: used_priority_fee,
             used_tx_hashes: vec![],
             encoded_tx_data,
             confirmed: false,
             final_hash: None,
         };
 
-        self.unconfirmed_operations.borrow_mut().insert(id, state);
+        self.unconfirmed_operations
+            .write()
+            .unwrap()
+            .insert(id, state);
 
         let response = InsertedOperationResponse {
             id,
@@ -131,89 +168,102 @@ impl DatabaseAccess for MockDatabase {
     }
 
     /// Adds a tx hash entry associated with some Ethereum operation to the database.
-    fn add_hash_entry(&self, eth_op_id: i64, hash: &H256) -> Result<(), failure::Error> {
-        assert!(
-            self.unconfirmed_operations
-                .borrow()
-                .contains_key(&eth_op_id),
-            "Attempt to update tx that is not unconfirmed"
-        );
-
-        let mut ops = self.unconfirmed_operations.borrow_mut();
-        let mut op = ops[&eth_op_id].clone();
+    async fn add_hash_entry(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        hash: &H256,
+    ) -> Result<(), failure::Error> {
+        let mut ops = self.unconfirmed_operations.write().unwrap();
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
         op.used_tx_hashes.push(*hash);
-        ops.insert(eth_op_id, op);
 
         Ok(())
     }
 
-    fn update_eth_tx(
+    async fn update_eth_tx(
         &self,
+        _connection: &mut StorageProcessor<'_>,
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> Result<(), failure::Error> {
-        assert!(
-            self.unconfirmed_operations
-                .borrow()
-                .contains_key(&eth_op_id),
-            "Attempt to update tx that is not unconfirmed"
-        );
-
-        let mut ops = self.unconfirmed_operations.borrow_mut();
-        let mut op = ops[&eth_op_id].clone();
+        let mut ops = self.unconfirmed_operations.write().unwrap();
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
         op.last_deadline_block = new_deadline_block as u64;
         op.last_used_gas_price = new_gas_value;
-        ops.insert(eth_op_id, op);
+        op.last_used_priority_fee = new_priority_fee;
 
         Ok(())
     }
 
-    fn confirm_operation(&self, hash: &H256) -> Result<(), failure::Error> {
-        let mut unconfirmed_operations = self.unconfirmed_operations.borrow_mut();
-        let mut op_idx: Option<i64> = None;
-        for operation in unconfirmed_operations.values_mut() {
-            if operation.used_tx_hashes.contains(hash) {
-                operation.confirmed = true;
-                operation.final_hash = Some(*hash);
-                op_idx = Some(operation.id);
-                break;
-            }
-        }
+    async fn assign_new_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> Result<U256, failure::Error> {
+        let nonce = self.next_nonce();
 
-        assert!(
-            op_idx.is_some(),
-            "Request to confirm operation that was not stored"
-        );
-        let op_idx = op_idx.unwrap();
+        let mut ops = self.unconfirmed_operations.write().unwrap();
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
+        op.nonce = nonce.into();
+        op.used_tx_hashes.clear();
+
+        Ok(nonce.into())
+    }
 
-        let operation = unconfirmed_operations.remove(&op_idx).unwrap();
+    async fn confirm_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+    ) -> Result<(), failure::Error> {
+        let mut unconfirmed_operations = self.unconfirmed_operations.write().unwrap();
+        let op_idx = unconfirmed_operations
+            .values()
+            .find(|operation| operation.used_tx_hashes.contains(hash))
+            .map(|operation| operation.id)
+            .expect("Request to confirm operation that was not stored");
+
+        let mut operation = unconfirmed_operations.remove(&op_idx).unwrap();
+        operation.confirmed = true;
+        operation.final_hash = Some(*hash);
         self.confirmed_operations
-            .borrow_mut()
+            .write()
+            .unwrap()
             .insert(op_idx, operation);
 
         Ok(())
     }
 
-    fn load_gas_price_limit(&self) -> Result<U256, failure::Error> {
-        Ok(self.gas_price_limit.get())
+    async fn load_stats(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> Result<ETHStats, failure::Error> {
+        Ok(self.stats.read().unwrap().clone())
     }
 
-    fn update_gas_price_limit(&self, value: U256) -> Result<(), failure::Error> {
-        self.gas_price_limit.set(value);
-
-        Ok(())
+    async fn load_gas_price_limit(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> Result<U256, failure::Error> {
+        Ok(*self.gas_price_limit.read().unwrap())
     }
 
-    fn load_stats(&self) -> Result<ETHStats, failure::Error> {
-        Ok(self.stats.borrow().clone())
-    }
+    async fn update_gas_price_limit(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        value: U256,
+    ) -> Result<(), failure::Error> {
+        *self.gas_price_limit.write().unwrap() = value;
 
-    fn transaction<F, T>(&self, f: F) -> Result<T, failure::Error>
-    where
-        F: FnOnce() -> Result<T, failure::Error>,
-    {
-        f()
+        Ok(())
     }
 }
 
@@ -222,8 +272,18 @@ impl DatabaseAccess for MockDatabase {
 pub(in crate::eth_sender) struct MockEthereum {
     pub block_number: u64,
     pub gas_price: U256,
-    pub tx_statuses: RefCell<HashMap<H256, ExecutedTxStatus>>,
-    pub sent_txs: RefCell<HashMap<H256, SignedCallResult>>,
+    /// Base fee of the recent blocks (the same for every block).
+    pub base_fee: U256,
+    pub max_priority_fee: U256,
+    /// Nonce of the operator account based on the latest mined block.
+    pub current_nonce: U256,
+    pub total_blocks_committed: u32,
+    pub total_blocks_verified: u32,
+    pub pending_withdrawals_count: u32,
+    /// Gas required for any transaction according to the estimation.
+    pub estimated_gas: U256,
+    pub tx_statuses: RwLock<HashMap<H256, ExecutedTxStatus>>,
+    pub sent_txs: RwLock<HashMap<H256, SignedCallResult>>,
 }
 
 impl Default for MockEthereum {
@@ -231,6 +291,13 @@ impl Default for MockEthereum {
         Self {
             block_number: 1,
             gas_price: 100.into(),
+            base_fee: 40.into(),
+            max_priority_fee: 10.into(),
+            current_nonce: 0.into(),
+            total_blocks_committed: 0,
+            total_blocks_verified: 0,
+            pending_withdrawals_count: 0,
+            estimated_gas: 0.into(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
         }
@@ -255,14 +322,27 @@ impl MockEthereum {
     /// Checks that there was a request to send the provided transaction.
     pub fn assert_sent(&self, hash: &H256) {
         assert!(
-            self.sent_txs.borrow().get(hash).is_some(),
+            self.sent_txs.read().unwrap().get(hash).is_some(),
             format!("Transaction with hash {:?} was not sent", hash),
         );
     }
 
+    /// Returns the sent transaction with the provided hash.
+    pub fn sent_tx(&self, hash: &H256) -> SignedCallResult {
+        self.sent_txs
+            .read()
+            .unwrap()
+            .get(hash)
+            .cloned()
+            .unwrap_or_else(|| panic!("Transaction with hash {:?} was not sent", hash))
+    }
+
     /// Adds an response for the sent transaction for `ETHSender` to receive.
     pub fn add_execution(&mut self, hash: &H256, status: &ExecutedTxStatus) {
-        self.tx_statuses.borrow_mut().insert(*hash, status.clone());
+        self.tx_statuses
+            .write()
+            .unwrap()
+            .insert(*hash, status.clone());
     }
 
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
@@ -275,7 +355,7 @@ impl MockEthereum {
             success: true,
             receipt: None,
         };
-        self.tx_statuses.borrow_mut().insert(tx_hash, status);
+        self.tx_statuses.write().unwrap().insert(tx_hash, status);
     }
 
     /// Same as `add_successfull_execution`, but marks the transaction as a failure.
@@ -287,26 +367,71 @@ impl MockEthereum {
             success: false,
             receipt: Some(Default::default()),
         };
-        self.tx_statuses.borrow_mut().insert(*hash, status);
+        self.tx_statuses.write().unwrap().insert(*hash, status);
+    }
+
+    /// Same as `add_failed_execution`, but the transaction is marked as the one that
+    /// used all the provided gas.
+    pub fn add_out_of_gas_execution(&mut self, hash: &H256, gas_limit: U256, confirmations: u64) {
+        self.block_number += confirmations;
+
+        let mut receipt: web3::types::TransactionReceipt = Default::default();
+        receipt.gas_used = Some(gas_limit);
+        let status = ExecutedTxStatus {
+            confirmations,
+            success: false,
+            receipt: Some(receipt),
+        };
+        self.tx_statuses.write().unwrap().insert(*hash, status);
     }
 }
 
+#[async_trait::async_trait]
 impl EthereumInterface for MockEthereum {
-    fn get_tx_status(&self, hash: &H256) -> Result<Option<ExecutedTxStatus>, failure::Error> {
-        Ok(self.tx_statuses.borrow().get(hash).cloned())
+    async fn get_tx_status(&self, hash: &H256) -> Result<Option<ExecutedTxStatus>, failure::Error> {
+        Ok(self.tx_statuses.read().unwrap().get(hash).cloned())
     }
 
-    fn block_number(&self) -> Result<u64, failure::Error> {
+    async fn block_number(&self) -> Result<u64, failure::Error> {
         Ok(self.block_number)
     }
 
-    fn gas_price(&self) -> Result<U256, failure::Error> {
+    async fn gas_price(&self) -> Result<U256, failure::Error> {
         Ok(self.gas_price)
     }
 
-    fn send_tx(&self, signed_tx: &SignedCallResult) -> Result<(), failure::Error> {
+    async fn base_fee_history(&self, block_count: usize) -> Result<Vec<U256>, failure::Error> {
+        Ok(vec![self.base_fee; block_count + 1])
+    }
+
+    async fn max_priority_fee(&self) -> Result<U256, failure::Error> {
+        Ok(self.max_priority_fee)
+    }
+
+    async fn current_nonce(&self) -> Result<U256, failure::Error> {
+        Ok(self.current_nonce)
+    }
+
+    async fn total_blocks_committed(&self) -> Result<u32, failure::Error> {
+        Ok(self.total_blocks_committed)
+    }
+
+    async fn total_blocks_verified(&self) -> Result<u32, failure::Error> {
+        Ok(self.total_blocks_verified)
+    }
+
+    async fn pending_withdrawals_count(&self) -> Result<u32, failure::Error> {
+        Ok(self.pending_withdrawals_count)
+    }
+
+    async fn estimate_gas(&self, _data: Vec<u8>) -> Result<U256, failure::Error> {
+        Ok(self.estimated_gas)
+    }
+
+    async fn send_tx(&self, signed_tx: &SignedCallResult) -> Result<(), failure::Error> {
         self.sent_txs
-            .borrow_mut()
+            .write()
+            .unwrap()
             .insert(signed_tx.hash, signed_tx.clone());
 
         Ok(())
@@ -316,7 +441,7 @@ impl EthereumInterface for MockEthereum {
         ethabi::encode(params.into_tokens().as_ref())
     }
 
-    fn sign_prepared_tx(
+    async fn sign_prepared_tx(
         &self,
         raw_tx: Vec<u8>,
         options: Options,
@@ -334,6 +459,36 @@ impl EthereumInterface for MockEthereum {
         Ok(SignedCallResult {
             raw_tx,
             gas_price,
+            max_priority_fee_per_gas: None,
+            nonce,
+            hash,
+        })
+    }
+
+    async fn sign_prepared_eip1559_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        fees: Eip1559Fees,
+        options: Options,
+    ) -> Result<SignedCallResult, failure::Error> {
+        let nonce = options.nonce.expect("Nonce must be set for every tx");
+
+        // Same as for the legacy transactions, fees and nonce are used to distinguish
+        // transactions by their hash.
+        let mut data_for_hash = raw_tx.clone();
+        data_for_hash.append(&mut ethabi::encode(
+            fees.max_fee_per_gas.into_tokens().as_ref(),
+        ));
+        data_for_hash.append(&mut ethabi::encode(
+            fees.max_priority_fee_per_gas.into_tokens().as_ref(),
+        ));
+        data_for_hash.append(&mut ethabi::encode(nonce.into_tokens().as_ref()));
+        let hash = Self::fake_sha256(data_for_hash.as_ref());
+
+        Ok(SignedCallResult {
+            raw_tx,
+            gas_price: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: Some(fees.max_priority_fee_per_gas),
             nonce,
             hash,
         })
@@ -342,30 +497,54 @@ impl EthereumInterface for MockEthereum {
 
 /// Creates a default `ETHSender` with mock Ethereum connection/database and no operations in DB.
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
-pub(in crate::eth_sender) fn default_eth_sender() -> (
+pub(in crate::eth_sender) async fn default_eth_sender() -> (
+    ETHSender<MockEthereum, MockDatabase>,
+    mpsc::Sender<ETHSenderRequest>,
+    mpsc::Receiver<Operation>,
+) {
+    build_eth_sender(1, Vec::new(), Default::default(), false, 1).await
+}
+
+/// Creates a default `ETHSender` with mock Ethereum connection/database and no operations in DB,
+/// which sends EIP-1559 transactions.
+/// Returns the `ETHSender` itself along with communication channels to interact with it.
+pub(in crate::eth_sender) async fn eip1559_eth_sender() -> (
     ETHSender<MockEthereum, MockDatabase>,
     mpsc::Sender<ETHSenderRequest>,
     mpsc::Receiver<Operation>,
 ) {
-    build_eth_sender(1, Vec::new(), Default::default())
+    build_eth_sender(1, Vec::new(), Default::default(), true, 1).await
 }
 
 /// Creates an `ETHSender` with mock Ethereum connection/database and no operations in DB
 /// which supports multiple transactions in flight.
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
-pub(in crate::eth_sender) fn concurrent_eth_sender(
+pub(in crate::eth_sender) async fn concurrent_eth_sender(
     max_txs_in_flight: u64,
 ) -> (
     ETHSender<MockEthereum, MockDatabase>,
     mpsc::Sender<ETHSenderRequest>,
     mpsc::Receiver<Operation>,
 ) {
-    build_eth_sender(max_txs_in_flight, Vec::new(), Default::default())
+    build_eth_sender(max_txs_in_flight, Vec::new(), Default::default(), false, 1).await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database and no operations in DB
+/// which sends up to `max_blocks_per_tx` blocks within one transaction.
+/// Returns the `ETHSender` itself along with communication channels to interact with it.
+pub(in crate::eth_sender) async fn aggregating_eth_sender(
+    max_blocks_per_tx: u64,
+) -> (
+    ETHSender<MockEthereum, MockDatabase>,
+    mpsc::Sender<ETHSenderRequest>,
+    mpsc::Receiver<Operation>,
+) {
+    build_eth_sender(1, Vec::new(), Default::default(), false, max_blocks_per_tx).await
 }
 
 /// Creates an `ETHSender` with mock Ethereum connection/database and restores its state "from DB".
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
-pub(in crate::eth_sender) fn restored_eth_sender(
+pub(in crate::eth_sender) async fn restored_eth_sender(
     restore_state: impl IntoIterator<Item = ETHOperation>,
     stats: ETHStats,
 ) -> (
@@ -375,14 +554,16 @@ pub(in crate::eth_sender) fn restored_eth_sender(
 ) {
     const MAX_TXS_IN_FLIGHT: u64 = 1;
 
-    build_eth_sender(MAX_TXS_IN_FLIGHT, restore_state, stats)
+    build_eth_sender(MAX_TXS_IN_FLIGHT, restore_state, stats, false, 1).await
 }
 
 /// Helper method for configurable creation of `ETHSender`.
-fn build_eth_sender(
+async fn build_eth_sender(
     max_txs_in_flight: u64,
     restore_state: impl IntoIterator<Item = ETHOperation>,
     stats: ETHStats,
+    eip1559_enabled: bool,
+    max_blocks_per_tx: u64,
 ) -> (
     ETHSender<MockEthereum, MockDatabase>,
     mpsc::Sender<ETHSenderRequest>,
@@ -400,6 +581,11 @@ fn build_eth_sender(
         wait_confirmations: super::WAIT_CONFIRMATIONS,
         tx_poll_period: Default::default(),
         is_enabled: true,
+        eip1559_enabled,
+        max_blocks_per_tx,
+        aggregation_max_wait: Default::default(),
+        gas_limit_increase_percent: super::GAS_LIMIT_INCREASE_PERCENT,
+        max_gas_limit: super::MAX_GAS_LIMIT,
     };
 
     let current_zksync_info = CurrentZksyncInfo::with_block_number(0);
@@ -410,7 +596,8 @@ fn build_eth_sender(
         operation_receiver,
         notify_sender,
         current_zksync_info,
-    );
+    )
+    .await;
 
     (eth_sender, operation_sender, notify_receiver)
 }
@@ -418,7 +605,7 @@ fn build_eth_sender(
 /// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
 /// This method should be used to create expected tx copies which won't affect
 /// the internal `ETHSender` state.
-pub(in crate::eth_sender) fn create_signed_tx(
+pub(in crate::eth_sender) async fn create_signed_tx(
     id: i64,
     eth_sender: &ETHSender<MockEthereum, MockDatabase>,
     operation: &Operation,
@@ -432,6 +619,46 @@ pub(in crate::eth_sender) fn create_signed_tx(
     let signed_tx = eth_sender
         .ethereum
         .sign_prepared_tx(raw_tx.clone(), options)
+        .await
+        .unwrap();
+
+    let op_type = match operation.action {
+        Action::Commit => OperationType::Commit,
+        Action::Verify { .. } => OperationType::Verify,
+    };
+
+    ETHOperation {
+        id,
+        op_type,
+        ops: vec![operation.clone()],
+        nonce: signed_tx.nonce,
+        last_deadline_block: deadline_block,
+        last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
+        used_tx_hashes: vec![signed_tx.hash],
+        encoded_tx_data: raw_tx,
+        confirmed: false,
+        final_hash: None,
+    }
+}
+
+/// Same as `create_signed_tx`, but creates an EIP-1559 transaction with the provided fees.
+pub(in crate::eth_sender) async fn create_signed_eip1559_tx(
+    id: i64,
+    eth_sender: &ETHSender<MockEthereum, MockDatabase>,
+    operation: &Operation,
+    deadline_block: u64,
+    nonce: i64,
+    fees: Eip1559Fees,
+) -> ETHOperation {
+    let mut options = Options::default();
+    options.nonce = Some(nonce.into());
+
+    let raw_tx = eth_sender.operation_to_raw_tx(&operation);
+    let signed_tx = eth_sender
+        .ethereum
+        .sign_prepared_eip1559_tx(raw_tx.clone(), fees, options)
+        .await
         .unwrap();
 
     let op_type = match operation.action {
@@ -442,10 +669,49 @@ pub(in crate::eth_sender) fn create_signed_tx(
     ETHOperation {
         id,
         op_type,
-        op: Some(operation.clone()),
+        ops: vec![operation.clone()],
+        nonce: signed_tx.nonce,
+        last_deadline_block: deadline_block,
+        last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
+        used_tx_hashes: vec![signed_tx.hash],
+        encoded_tx_data: raw_tx,
+        confirmed: false,
+        final_hash: None,
+    }
+}
+
+/// Same as `create_signed_tx`, but creates a transaction for several aggregated operations.
+pub(in crate::eth_sender) async fn create_signed_aggregated_tx(
+    id: i64,
+    eth_sender: &ETHSender<MockEthereum, MockDatabase>,
+    operations: &[Operation],
+    deadline_block: u64,
+    nonce: i64,
+) -> ETHOperation {
+    let mut options = Options::default();
+    options.nonce = Some(nonce.into());
+
+    let raw_tx = eth_sender.operations_to_raw_tx(operations).unwrap();
+    let signed_tx = eth_sender
+        .ethereum
+        .sign_prepared_tx(raw_tx.clone(), options)
+        .await
+        .unwrap();
+
+    let op_type = match operations[0].action {
+        Action::Commit => OperationType::Commit,
+        Action::Verify { .. } => OperationType::Verify,
+    };
+
+    ETHOperation {
+        id,
+        op_type,
+        ops: operations.to_vec(),
         nonce: signed_tx.nonce,
         last_deadline_block: deadline_block,
         last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
         used_tx_hashes: vec![signed_tx.hash],
         encoded_tx_data: raw_tx,
         confirmed: false,
@@ -454,7 +720,7 @@ pub(in crate::eth_sender) fn create_signed_tx(
 }
 
 /// Creates an `ETHOperation` object for a withdraw operation.
-pub(in crate::eth_sender) fn create_signed_withdraw_tx(
+pub(in crate::eth_sender) async fn create_signed_withdraw_tx(
     id: i64,
     eth_sender: &ETHSender<MockEthereum, MockDatabase>,
     deadline_block: u64,
@@ -470,6 +736,7 @@ pub(in crate::eth_sender) fn create_signed_withdraw_tx(
     let signed_tx = eth_sender
         .ethereum
         .sign_prepared_tx(raw_tx.clone(), options)
+        .await
         .unwrap();
 
     let op_type = OperationType::Withdraw;
@@ -477,10 +744,11 @@ pub(in crate::eth_sender) fn create_signed_withdraw_tx(
     ETHOperation {
         id,
         op_type,
-        op: None,
+        ops: vec![],
         nonce: signed_tx.nonce,
         last_deadline_block: deadline_block,
         last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
         used_tx_hashes: vec![signed_tx.hash],
         encoded_tx_data: raw_tx,
         confirmed: false,
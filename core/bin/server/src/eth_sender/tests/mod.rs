@@ -1,60 +1,53 @@
 // External uses
-use tokio::runtime::Builder;
 use tokio::time::timeout;
+use web3::types::U256;
 // Workspace uses
-use models::ethereum::ETHOperation;
+use eth_client::Eip1559Fees;
+use models::{ethereum::ETHOperation, Action};
 // Local uses
 use self::mock::{
-    concurrent_eth_sender, create_signed_tx, create_signed_withdraw_tx, default_eth_sender,
-    restored_eth_sender,
+    aggregating_eth_sender, concurrent_eth_sender, create_signed_aggregated_tx,
+    create_signed_eip1559_tx, create_signed_tx, create_signed_withdraw_tx, default_eth_sender,
+    eip1559_eth_sender, restored_eth_sender,
 };
 use super::{
+    database::DatabaseInterface,
+    ethereum_interface::EthereumInterface,
     transactions::{ETHStats, ExecutedTxStatus, TxCheckOutcome},
     ETHSender, TxCheckMode,
 };
-use crate::eth_sender::ethereum_interface::EthereumInterface;
 use crate::eth_sender::ETHSenderRequest;
-use futures::executor::block_on;
 use std::time::Duration;
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 1;
+const GAS_LIMIT_INCREASE_PERCENT: u64 = 50;
+const MAX_GAS_LIMIT: u64 = 10_000_000;
 
 pub mod mock;
 mod test_data;
 
-fn retrieve_all_operations<ETH: EthereumInterface, DB: DatabaseAccess>(
+async fn retrieve_all_operations<ETH: EthereumInterface, DB: DatabaseInterface>(
     eth_sender: &mut ETHSender<ETH, DB>,
 ) {
-    async fn process_with_timeout<ETH2: EthereumInterface, DB2: DatabaseAccess>(
-        eth_sender: &mut ETHSender<ETH2, DB2>,
-    ) {
-        timeout(Duration::from_secs(1), eth_sender.process_requests())
-            .await
-            .unwrap_or_default()
-    }
-
-    let mut runtime = Builder::new()
-        .basic_scheduler()
-        .enable_time()
-        .build()
-        .expect("Tokio runtime build");
-    runtime.block_on(process_with_timeout(eth_sender));
+    timeout(Duration::from_secs(1), eth_sender.process_requests())
+        .await
+        .unwrap_or_default()
 }
 
 /// Basic test that `ETHSender` creation does not panic and initializes correctly.
-#[test]
-fn basic_test() {
-    let (eth_sender, _, _) = default_eth_sender();
+#[tokio::test]
+async fn basic_test() {
+    let (eth_sender, _, _) = default_eth_sender().await;
 
     // Check that there are no unconfirmed operations by default.
     assert!(eth_sender.ongoing_ops.is_empty());
 }
 
 /// Checks that deadline block is chosen according to the expected policy.
-#[test]
-fn deadline_block() {
-    let (eth_sender, _, _) = default_eth_sender();
+#[tokio::test]
+async fn deadline_block() {
+    let (eth_sender, _, _) = default_eth_sender().await;
 
     assert_eq!(eth_sender.get_deadline_block(0), EXPECTED_WAIT_TIME_BLOCKS);
     assert_eq!(
@@ -67,25 +60,24 @@ fn deadline_block() {
 /// `TxCheckOutcome` correctly.
 ///
 /// Here we check every possible output of the `check_transaction_state` method.
-#[test]
-fn transaction_state() {
-    let (mut eth_sender, _, _) = default_eth_sender();
+#[tokio::test]
+async fn transaction_state() {
+    let (mut eth_sender, _, _) = default_eth_sender().await;
     let current_block = eth_sender.ethereum.block_number;
     let deadline_block = eth_sender.get_deadline_block(current_block);
-    let operations: Vec<ETHOperation> = vec![
+    let zksync_operations = vec![
         test_data::commit_operation(0), // Will be committed.
         test_data::commit_operation(1), // Will be pending because of not enough confirmations.
         test_data::commit_operation(2), // Will be failed.
         test_data::commit_operation(3), // Will be stuck.
         test_data::commit_operation(4), // Will be pending due no response.
-    ]
-    .iter()
-    .enumerate()
-    .map(|(eth_op_id, op)| {
+    ];
+    let mut operations: Vec<ETHOperation> = Vec::new();
+    for (eth_op_id, op) in zksync_operations.iter().enumerate() {
         let nonce = eth_op_id as i64;
-        create_signed_tx(eth_op_id as i64, &eth_sender, op, deadline_block, nonce)
-    })
-    .collect();
+        operations
+            .push(create_signed_tx(eth_op_id as i64, &eth_sender, op, deadline_block, nonce).await);
+    }
 
     // Committed operation.
     let committed_response = ExecutedTxStatus {
@@ -128,6 +120,7 @@ fn transaction_state() {
                 &operations[0].used_tx_hashes[0],
                 current_block + committed_response.confirmations,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Committed
     );
@@ -141,6 +134,7 @@ fn transaction_state() {
                 &operations[1].used_tx_hashes[0],
                 current_block + pending_response.confirmations,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Pending
     );
@@ -154,6 +148,7 @@ fn transaction_state() {
                 &operations[2].used_tx_hashes[0],
                 current_block + failed_response.confirmations,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Failed(Default::default())
     );
@@ -167,6 +162,7 @@ fn transaction_state() {
                 &operations[3].used_tx_hashes[0],
                 current_block + EXPECTED_WAIT_TIME_BLOCKS,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Stuck
     );
@@ -180,6 +176,7 @@ fn transaction_state() {
                 &operations[4].used_tx_hashes[0],
                 current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Pending
     );
@@ -193,6 +190,7 @@ fn transaction_state() {
                 &operations[4].used_tx_hashes[0],
                 current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
             )
+            .await
             .unwrap(),
         TxCheckOutcome::Stuck
     );
@@ -203,9 +201,9 @@ fn transaction_state() {
 /// - they are successfully committed to the Ethereum;
 /// - `completeWithdrawals` tx is sent to the Ethereum;
 /// - notification is sent after `verify` operation is committed.
-#[test]
-fn operation_commitment_workflow() {
-    let (mut eth_sender, mut sender, mut receiver) = default_eth_sender();
+#[tokio::test]
+async fn operation_commitment_workflow() {
+    let (mut eth_sender, mut sender, mut receiver) = default_eth_sender().await;
 
     // In this test we will run one commit and one verify operation and should
     // obtain a notification about the operation being completed in the end.
@@ -225,8 +223,8 @@ fn operation_commitment_workflow() {
             .unwrap();
 
         // Retrieve it there and then process.
-        retrieve_all_operations(&mut eth_sender);
-        block_on(eth_sender.proceed_next_operations());
+        retrieve_all_operations(&mut eth_sender).await;
+        eth_sender.proceed_next_operations().await;
 
         // Now we should see that transaction is stored in the database and sent to the Ethereum.
         let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
@@ -236,7 +234,8 @@ fn operation_commitment_workflow() {
             operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
         expected_tx.id = eth_op_id as i64; // We have to set the ID manually.
 
         eth_sender.db.assert_stored(&expected_tx);
@@ -252,7 +251,7 @@ fn operation_commitment_workflow() {
         eth_sender
             .ethereum
             .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         // Check that operation is confirmed.
         expected_tx.confirmed = true;
@@ -261,13 +260,13 @@ fn operation_commitment_workflow() {
     }
 
     // Process the next operation and check that `completeWithdrawals` transaction is stored and sent.
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     let eth_op_idx = operations.len() as i64;
     let nonce = eth_op_idx;
     let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
     let mut withdraw_op_tx =
-        create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce);
+        create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
 
     eth_sender.db.assert_stored(&withdraw_op_tx);
     eth_sender
@@ -278,7 +277,7 @@ fn operation_commitment_workflow() {
     eth_sender
         .ethereum
         .add_successfull_execution(withdraw_op_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     // Check that `completeWithdrawals` is completed in the DB.
     withdraw_op_tx.confirmed = true;
@@ -292,14 +291,141 @@ fn operation_commitment_workflow() {
     );
 }
 
+/// Checks that with the aggregation enabled several blocks are committed and verified
+/// within one transaction, and every aggregated verify operation is reported once
+/// the transaction is confirmed.
+#[tokio::test]
+async fn aggregated_operations() {
+    const MAX_BLOCKS_PER_TX: u64 = 3;
+    let (mut eth_sender, mut sender, mut receiver) =
+        aggregating_eth_sender(MAX_BLOCKS_PER_TX).await;
+
+    let commit_operations: Vec<_> = (0..3).map(test_data::commit_operation).collect();
+    let verify_operations: Vec<_> = (0..3).map(test_data::verify_operation).collect();
+
+    for (eth_op_id, operations) in [&commit_operations, &verify_operations].iter().enumerate() {
+        let nonce = eth_op_id as i64;
+
+        // Send all the operations to `ETHSender` before processing them.
+        for operation in operations.iter() {
+            sender
+                .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+                .unwrap();
+        }
+        retrieve_all_operations(&mut eth_sender).await;
+        eth_sender.proceed_next_operations().await;
+
+        // All the operations should be sent within one transaction.
+        let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+        let mut expected_tx = create_signed_aggregated_tx(
+            eth_op_id as i64,
+            &eth_sender,
+            operations,
+            deadline_block,
+            nonce,
+        )
+        .await;
+
+        eth_sender.db.assert_stored(&expected_tx);
+        eth_sender
+            .ethereum
+            .assert_sent(&expected_tx.used_tx_hashes[0]);
+
+        // Base cost of the contract call should be paid once, so the aggregated transaction
+        // should be cheaper than separate transactions for every block.
+        let separate_txs_limit = operations
+            .iter()
+            .map(|op| match op.action {
+                Action::Commit => op.block.commit_gas_limit,
+                Action::Verify { .. } => op.block.verify_gas_limit,
+            })
+            .fold(U256::zero(), |total, limit| total + limit);
+        assert!(eth_sender.gas_limit(&expected_tx) < separate_txs_limit);
+
+        eth_sender
+            .ethereum
+            .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
+        eth_sender.proceed_next_operations().await;
+
+        expected_tx.confirmed = true;
+        expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
+        eth_sender.db.assert_confirmed(&expected_tx);
+    }
+
+    // We should be notified about every verified block.
+    for operation in &verify_operations {
+        assert_eq!(receiver.try_next().unwrap().unwrap().id, operation.id);
+    }
+}
+
+/// Check that the aggregated operation partially executed by another transaction
+/// is not considered confirmed, and `ETHSender` is stopped.
+#[tokio::test]
+async fn aggregated_operation_partially_executed() {
+    const MAX_BLOCKS_PER_TX: u64 = 3;
+    let (mut eth_sender, mut sender, _) = aggregating_eth_sender(MAX_BLOCKS_PER_TX).await;
+
+    let operations: Vec<_> = (0..3).map(test_data::commit_operation).collect();
+    for operation in &operations {
+        sender
+            .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+            .unwrap();
+    }
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let failing_tx =
+        create_signed_aggregated_tx(0, &eth_sender, &operations, deadline_block, 0).await;
+    eth_sender.db.assert_stored(&failing_tx);
+
+    // Only the first block of the aggregated ones is committed.
+    eth_sender.ethereum.total_blocks_committed = operations[0].block.block_number;
+    eth_sender
+        .ethereum
+        .add_failed_execution(&failing_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
+    eth_sender.proceed_next_operations().await;
+
+    assert!(eth_sender.is_stopped);
+    eth_sender.db.assert_stored(&failing_tx);
+}
+
+/// Check that the operations which can't be processed within one transaction
+/// are not encoded.
+#[tokio::test]
+async fn invalid_aggregated_operations() {
+    let (eth_sender, _, _) = aggregating_eth_sender(3).await;
+
+    // Commit operation in the verify batch.
+    let operations = vec![
+        test_data::verify_operation(0),
+        test_data::commit_operation(1),
+    ];
+    assert!(eth_sender.operations_to_raw_tx(&operations).is_err());
+    // Verify operation in the commit batch.
+    let operations = vec![
+        test_data::commit_operation(0),
+        test_data::verify_operation(1),
+    ];
+    assert!(eth_sender.operations_to_raw_tx(&operations).is_err());
+    // Non-consecutive blocks.
+    let operations = vec![
+        test_data::commit_operation(0),
+        test_data::commit_operation(2),
+    ];
+    assert!(eth_sender.operations_to_raw_tx(&operations).is_err());
+    // No operations at all.
+    assert!(eth_sender.operations_to_raw_tx(&[]).is_err());
+}
+
 /// A simple scenario for a stuck transaction:
 /// - A transaction is sent to the Ethereum.
 /// - It is not processed after some blocks.
 /// - `ETHSender` creates a new transaction with increased gas.
 /// - This transaction is completed successfully.
-#[test]
-fn stuck_transaction() {
-    let (mut eth_sender, mut sender, _) = default_eth_sender();
+#[tokio::test]
+async fn stuck_transaction() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
 
     // Workflow for the test is similar to `operation_commitment_workflow`.
     let operation = test_data::commit_operation(0);
@@ -307,17 +433,18 @@ fn stuck_transaction() {
         .try_send(ETHSenderRequest::SendOperation(operation.clone()))
         .unwrap();
 
-    retrieve_all_operations(&mut eth_sender);
-    block_on(eth_sender.proceed_next_operations());
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
 
     let eth_op_id = 0;
     let nonce = 0;
     let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
-    let mut stuck_tx = create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce);
+    let mut stuck_tx =
+        create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce).await;
 
     // Skip some blocks and expect sender to send a new tx.
     eth_sender.ethereum.block_number += EXPECTED_WAIT_TIME_BLOCKS;
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     // Check that new transaction is sent (and created based on the previous stuck tx).
     let expected_sent_tx = eth_sender
@@ -325,6 +452,7 @@ fn stuck_transaction() {
             eth_sender.get_deadline_block(eth_sender.ethereum.block_number),
             &mut stuck_tx,
         )
+        .await
         .unwrap();
     eth_sender.db.assert_stored(&stuck_tx);
     eth_sender.ethereum.assert_sent(&expected_sent_tx.hash);
@@ -334,7 +462,7 @@ fn stuck_transaction() {
     eth_sender
         .ethereum
         .add_successfull_execution(stuck_tx.used_tx_hashes[1], WAIT_CONFIRMATIONS);
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     // Check that operation is confirmed (we set the final hash to the second sent tx).
     stuck_tx.confirmed = true;
@@ -342,14 +470,73 @@ fn stuck_transaction() {
     eth_sender.db.assert_confirmed(&stuck_tx);
 }
 
+/// Checks the stuck transaction workflow for the EIP-1559 transactions:
+/// - Operation is sent with fees based on the recent base fee and suggested priority fee.
+/// - After the transaction got stuck, a new one is sent with both fees increased.
+/// - The new transaction is completed successfully.
+#[tokio::test]
+async fn eip1559_stuck_transaction() {
+    let (mut eth_sender, mut sender, _) = eip1559_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    sender
+        .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+        .unwrap();
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    // Max fee is (2 * base fee + priority fee).
+    let fees = Eip1559Fees {
+        max_fee_per_gas: eth_sender.ethereum.base_fee * U256::from(2)
+            + eth_sender.ethereum.max_priority_fee,
+        max_priority_fee_per_gas: eth_sender.ethereum.max_priority_fee,
+    };
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let mut stuck_tx =
+        create_signed_eip1559_tx(0, &eth_sender, &operation, deadline_block, 0, fees).await;
+    eth_sender.db.assert_stored(&stuck_tx);
+    eth_sender.ethereum.assert_sent(&stuck_tx.used_tx_hashes[0]);
+
+    // Skip some blocks and expect sender to send a new tx.
+    eth_sender.ethereum.block_number += EXPECTED_WAIT_TIME_BLOCKS;
+    eth_sender.proceed_next_operations().await;
+
+    // Both fees must be increased by 15%.
+    let new_fees = Eip1559Fees {
+        max_fee_per_gas: fees.max_fee_per_gas * U256::from(115) / U256::from(100),
+        max_priority_fee_per_gas: fees.max_priority_fee_per_gas * U256::from(115) / U256::from(100),
+    };
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let replacement_tx =
+        create_signed_eip1559_tx(0, &eth_sender, &operation, deadline_block, 0, new_fees).await;
+    stuck_tx.last_deadline_block = deadline_block;
+    stuck_tx.last_used_gas_price = new_fees.max_fee_per_gas;
+    stuck_tx.last_used_priority_fee = Some(new_fees.max_priority_fee_per_gas);
+    stuck_tx
+        .used_tx_hashes
+        .push(replacement_tx.used_tx_hashes[0]);
+    eth_sender.db.assert_stored(&stuck_tx);
+    eth_sender.ethereum.assert_sent(&stuck_tx.used_tx_hashes[1]);
+
+    eth_sender
+        .ethereum
+        .add_successfull_execution(stuck_tx.used_tx_hashes[1], WAIT_CONFIRMATIONS);
+    eth_sender.proceed_next_operations().await;
+
+    stuck_tx.confirmed = true;
+    stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[1]);
+    eth_sender.db.assert_confirmed(&stuck_tx);
+}
+
 /// This test verifies that with multiple operations received all-together,
 /// their order is respected and no processing of the next operation is started until
 /// the previous one is committed.
 ///
 /// This test includes all three operation types (commit, verify and withdraw).
-#[test]
-fn operations_order() {
-    let (mut eth_sender, mut sender, mut receiver) = default_eth_sender();
+#[tokio::test]
+async fn operations_order() {
+    let (mut eth_sender, mut sender, mut receiver) = default_eth_sender().await;
 
     // We send multiple the operations at once to the channel.
     let operations_count = 3;
@@ -382,7 +569,8 @@ fn operations_order() {
             commit_operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
 
         expected_txs.push(commit_op_tx);
 
@@ -398,7 +586,8 @@ fn operations_order() {
             verify_operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
 
         expected_txs.push(verify_op_tx);
 
@@ -409,7 +598,7 @@ fn operations_order() {
         let nonce = eth_op_idx;
 
         let withdraw_op_tx =
-            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce);
+            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
 
         expected_txs.push(withdraw_op_tx);
     }
@@ -419,13 +608,13 @@ fn operations_order() {
             .try_send(ETHSenderRequest::SendOperation(operation.clone()))
             .unwrap();
     }
-    retrieve_all_operations(&mut eth_sender);
+    retrieve_all_operations(&mut eth_sender).await;
 
     // Then we go through the operations and check that the order of operations is preserved.
     for mut tx in expected_txs.into_iter() {
         let current_tx_hash = tx.used_tx_hashes[0];
 
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         // Check that current expected tx is stored.
         eth_sender.db.assert_stored(&tx);
@@ -435,7 +624,7 @@ fn operations_order() {
         eth_sender
             .ethereum
             .add_successfull_execution(current_tx_hash, WAIT_CONFIRMATIONS);
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         // Update the fields in the tx and check if it's confirmed.
         tx.confirmed = true;
@@ -449,11 +638,11 @@ fn operations_order() {
     }
 }
 
-/// Check that upon a transaction failure the incident causes a panic by default.
-#[test]
-#[should_panic(expected = "Cannot operate after unexpected TX failure")]
-fn transaction_failure() {
-    let (mut eth_sender, mut sender, _) = default_eth_sender();
+/// Check that upon a transaction failure which can't be handled automatically,
+/// `ETHSender` stops sending transactions (but doesn't panic).
+#[tokio::test]
+async fn transaction_failure() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
 
     // Workflow for the test is similar to `operation_commitment_workflow`.
     let operation = test_data::commit_operation(0);
@@ -464,33 +653,255 @@ fn transaction_failure() {
     let eth_op_id = 0;
     let nonce = 0;
     let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
-    let failing_tx = create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce);
+    let failing_tx =
+        create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce).await;
 
-    retrieve_all_operations(&mut eth_sender);
-    block_on(eth_sender.proceed_next_operations());
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
 
     eth_sender
         .ethereum
         .add_failed_execution(&failing_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
+
+    // Operation is not confirmed, and the sender is stopped.
+    assert!(eth_sender.is_stopped);
+    eth_sender.db.assert_stored(&failing_tx);
+
+    // No more transactions are sent after the stop.
+    sender
+        .try_send(ETHSenderRequest::SendOperation(
+            test_data::commit_operation(1),
+        ))
+        .unwrap();
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    assert_eq!(eth_sender.ethereum.sent_txs.read().unwrap().len(), 1);
+}
+
+/// Check that the operation which transaction ran out of gas is resent
+/// with a new nonce and an increased gas limit.
+#[tokio::test]
+async fn transaction_failure_out_of_gas() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    sender
+        .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+        .unwrap();
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let failing_tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 0).await;
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    // Make the transaction use all the provided gas.
+    let gas_limit = operation.block.commit_gas_limit;
+    eth_sender.ethereum.add_out_of_gas_execution(
+        &failing_tx.used_tx_hashes[0],
+        gas_limit,
+        WAIT_CONFIRMATIONS,
+    );
+    eth_sender.proceed_next_operations().await;
+
+    // Operation should be resent with the next nonce and the increased gas limit.
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let mut resent_tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 1).await;
+
+    assert!(!eth_sender.is_stopped);
+    eth_sender.db.assert_stored(&resent_tx);
+    eth_sender
+        .ethereum
+        .assert_sent(&resent_tx.used_tx_hashes[0]);
+    assert_eq!(
+        eth_sender.gas_limit(&resent_tx),
+        gas_limit * U256::from(100 + GAS_LIMIT_INCREASE_PERCENT) / U256::from(100)
+    );
+
+    // Resent transaction is executed successfully.
+    eth_sender
+        .ethereum
+        .add_successfull_execution(resent_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
+    eth_sender.proceed_next_operations().await;
+
+    resent_tx.confirmed = true;
+    resent_tx.final_hash = Some(resent_tx.used_tx_hashes[0]);
+    eth_sender.db.assert_confirmed(&resent_tx);
+}
+
+/// Check that the failed operation is considered confirmed if the contract state
+/// shows that it's already executed.
+#[tokio::test]
+async fn transaction_failure_already_executed() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    sender
+        .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+        .unwrap();
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let mut failing_tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 0).await;
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    // Transaction is reverted, since the block is already committed.
+    eth_sender.ethereum.total_blocks_committed = operation.block.block_number;
+    eth_sender
+        .ethereum
+        .add_failed_execution(&failing_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
+    eth_sender.proceed_next_operations().await;
+
+    assert!(!eth_sender.is_stopped);
+    failing_tx.confirmed = true;
+    failing_tx.final_hash = Some(failing_tx.used_tx_hashes[0]);
+    eth_sender.db.assert_confirmed(&failing_tx);
+}
+
+/// Check that the operations sent after the one which ran out of gas are resent after it,
+/// so the order of the operations is preserved.
+#[tokio::test]
+async fn transaction_failure_out_of_gas_keeps_order() {
+    let (mut eth_sender, mut sender, _) = concurrent_eth_sender(2).await;
+
+    let operations = &test_data::COMMIT_OPERATIONS[..2];
+    for operation in operations {
+        sender
+            .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+            .unwrap();
+    }
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let failing_tx = create_signed_tx(0, &eth_sender, &operations[0], deadline_block, 0).await;
+    let next_tx = create_signed_tx(1, &eth_sender, &operations[1], deadline_block, 1).await;
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+    eth_sender.db.assert_stored(&failing_tx);
+    eth_sender.db.assert_stored(&next_tx);
+
+    eth_sender.ethereum.add_out_of_gas_execution(
+        &failing_tx.used_tx_hashes[0],
+        operations[0].block.commit_gas_limit,
+        WAIT_CONFIRMATIONS,
+    );
+    eth_sender.proceed_next_operations().await;
+
+    // Both operations are resent with the new nonces in the same order.
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let resent_tx = create_signed_tx(0, &eth_sender, &operations[0], deadline_block, 2).await;
+    let resent_next_tx = create_signed_tx(1, &eth_sender, &operations[1], deadline_block, 3).await;
+
+    assert!(!eth_sender.is_stopped);
+    for tx in &[resent_tx, resent_next_tx] {
+        eth_sender.db.assert_stored(tx);
+        eth_sender.ethereum.assert_sent(&tx.used_tx_hashes[0]);
+    }
+}
+
+/// Check that if the gas estimation exceeds the gas limit of the operation,
+/// the transaction is sent with the same nonce and an increased gas limit.
+#[tokio::test]
+async fn gas_limit_increased_by_estimation() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    sender
+        .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+        .unwrap();
+
+    let estimated_gas = operation.block.commit_gas_limit + U256::from(1);
+    eth_sender.ethereum.estimated_gas = estimated_gas;
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 0).await;
+
+    eth_sender.db.assert_stored(&tx);
+    eth_sender.ethereum.assert_sent(&tx.used_tx_hashes[0]);
+    assert_eq!(
+        eth_sender.gas_limit(&tx),
+        estimated_gas * U256::from(100 + GAS_LIMIT_INCREASE_PERCENT) / U256::from(100)
+    );
+}
+
+/// Check that the failed withdraw operation is considered confirmed
+/// if there are no pending withdrawals left.
+#[tokio::test]
+async fn withdraw_failure_without_pending_withdrawals() {
+    let (mut eth_sender, _, _) = default_eth_sender().await;
+
+    eth_sender.add_complete_withdrawals_to_queue();
+    eth_sender.proceed_next_operations().await;
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let mut failing_tx = create_signed_withdraw_tx(0, &eth_sender, deadline_block, 0).await;
+    eth_sender.db.assert_stored(&failing_tx);
+
+    // Withdrawals were completed by another transaction.
+    eth_sender.ethereum.pending_withdrawals_count = 0;
+    eth_sender
+        .ethereum
+        .add_failed_execution(&failing_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
+    eth_sender.proceed_next_operations().await;
+
+    assert!(!eth_sender.is_stopped);
+    failing_tx.confirmed = true;
+    failing_tx.final_hash = Some(failing_tx.used_tx_hashes[0]);
+    eth_sender.db.assert_confirmed(&failing_tx);
+}
+
+/// Check that if the nonce of the stuck operation was consumed by another transaction,
+/// the operation is resent with a new nonce.
+#[tokio::test]
+async fn nonce_conflict() {
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    sender
+        .try_send(ETHSenderRequest::SendOperation(operation.clone()))
+        .unwrap();
+
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
+
+    // Transaction is stuck, and its nonce is already used.
+    eth_sender.ethereum.block_number += EXPECTED_WAIT_TIME_BLOCKS;
+    eth_sender.ethereum.current_nonce = 1.into();
+    eth_sender.proceed_next_operations().await;
+
+    // Operation should be resent with the next nonce.
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let resent_tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 1).await;
+
+    assert!(!eth_sender.is_stopped);
+    eth_sender.db.assert_stored(&resent_tx);
+    eth_sender
+        .ethereum
+        .assert_sent(&resent_tx.used_tx_hashes[0]);
 }
 
 /// Check that after recovering state with several non-processed operations
 /// they will be processed normally.
-#[test]
-fn restore_state() {
+#[tokio::test]
+async fn restore_state() {
     let (operations, stored_operations) = {
         // This `eth_sender` is required to generate the input only.
-        let (eth_sender, _, _) = default_eth_sender();
+        let (eth_sender, _, _) = default_eth_sender().await;
 
         let commit_op = test_data::commit_operation(0);
         let verify_op = test_data::verify_operation(0);
 
         let deadline_block = eth_sender.get_deadline_block(1);
-        let commit_op_tx = create_signed_tx(0, &eth_sender, &commit_op, deadline_block, 0);
+        let commit_op_tx = create_signed_tx(0, &eth_sender, &commit_op, deadline_block, 0).await;
 
         let deadline_block = eth_sender.get_deadline_block(2);
-        let verify_op_tx = create_signed_tx(1, &eth_sender, &verify_op, deadline_block, 1);
+        let verify_op_tx = create_signed_tx(1, &eth_sender, &verify_op, deadline_block, 1).await;
 
         let operations = vec![commit_op, verify_op];
         let stored_operations = vec![commit_op_tx, verify_op_tx];
@@ -503,14 +914,14 @@ fn restore_state() {
         verify_ops: 1,
         withdraw_ops: 0,
     };
-    let (mut eth_sender, _, mut receiver) = restored_eth_sender(stored_operations, stats);
+    let (mut eth_sender, _, mut receiver) = restored_eth_sender(stored_operations, stats).await;
 
     for (eth_op_id, operation) in operations.iter().enumerate() {
         // Note that we DO NOT send an operation to `ETHSender` and neither receive it.
 
         // We do process operations restored from the DB though.
         // The rest of this test is the same as in `operation_commitment_workflow`.
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
         let nonce = eth_op_id as i64;
@@ -520,7 +931,8 @@ fn restore_state() {
             operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
         expected_tx.id = eth_op_id as i64;
 
         eth_sender.db.assert_stored(&expected_tx);
@@ -528,7 +940,7 @@ fn restore_state() {
         eth_sender
             .ethereum
             .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         expected_tx.confirmed = true;
         expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
@@ -541,34 +953,36 @@ fn restore_state() {
 /// Checks that even after getting the first transaction stuck and sending the next
 /// one, confirmation for the first (stuck) transaction is processed and leads
 /// to the operation commitment.
-#[test]
-fn confirmations_independence() {
+#[tokio::test]
+async fn confirmations_independence() {
     // Workflow in the test is the same as in `stuck_transaction`, except for the fact
     // that confirmation is obtained for the stuck transaction instead of the latter one.
 
-    let (mut eth_sender, mut sender, _) = default_eth_sender();
+    let (mut eth_sender, mut sender, _) = default_eth_sender().await;
 
     let operation = test_data::commit_operation(0);
     sender
         .try_send(ETHSenderRequest::SendOperation(operation.clone()))
         .unwrap();
 
-    retrieve_all_operations(&mut eth_sender);
-    block_on(eth_sender.proceed_next_operations());
+    retrieve_all_operations(&mut eth_sender).await;
+    eth_sender.proceed_next_operations().await;
 
     let eth_op_id = 0;
     let nonce = 0;
     let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
-    let mut stuck_tx = create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce);
+    let mut stuck_tx =
+        create_signed_tx(eth_op_id, &eth_sender, &operation, deadline_block, nonce).await;
 
     eth_sender.ethereum.block_number += EXPECTED_WAIT_TIME_BLOCKS;
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     let next_tx = eth_sender
         .create_supplement_tx(
             eth_sender.get_deadline_block(eth_sender.ethereum.block_number),
             &mut stuck_tx,
         )
+        .await
         .unwrap();
     eth_sender.db.assert_stored(&stuck_tx);
     eth_sender.ethereum.assert_sent(&next_tx.hash);
@@ -577,7 +991,7 @@ fn confirmations_independence() {
     eth_sender
         .ethereum
         .add_successfull_execution(stuck_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS);
-    block_on(eth_sender.proceed_next_operations());
+    eth_sender.proceed_next_operations().await;
 
     // Check that operation is confirmed (we set the final hash to the *first* sent tx).
     stuck_tx.confirmed = true;
@@ -587,10 +1001,10 @@ fn confirmations_independence() {
 
 /// This test is the same as `operations_order`, but configures ETH sender
 /// to use 3 transactions in flight, and checks that they are being sent concurrently.
-#[test]
-fn concurrent_operations_order() {
+#[tokio::test]
+async fn concurrent_operations_order() {
     const MAX_TXS_IN_FLIGHT: u64 = 3;
-    let (mut eth_sender, mut sender, mut receiver) = concurrent_eth_sender(MAX_TXS_IN_FLIGHT);
+    let (mut eth_sender, mut sender, mut receiver) = concurrent_eth_sender(MAX_TXS_IN_FLIGHT).await;
 
     // We send multiple the operations at once to the channel.
     let operations_count = 3;
@@ -625,7 +1039,8 @@ fn concurrent_operations_order() {
             commit_operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
 
         expected_txs.push(commit_op_tx);
 
@@ -639,7 +1054,8 @@ fn concurrent_operations_order() {
             verify_operation,
             deadline_block,
             nonce,
-        );
+        )
+        .await;
 
         expected_txs.push(verify_op_tx);
 
@@ -650,7 +1066,7 @@ fn concurrent_operations_order() {
         let nonce = eth_op_idx;
 
         let withdraw_op_tx =
-            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce);
+            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
 
         expected_txs.push(withdraw_op_tx);
     }
@@ -671,10 +1087,10 @@ fn concurrent_operations_order() {
         sender
             .try_send(ETHSenderRequest::SendOperation(verify_op.clone()))
             .unwrap();
-        retrieve_all_operations(&mut eth_sender);
+        retrieve_all_operations(&mut eth_sender).await;
 
         // Call `proceed_next_operations`. Several txs should be sent.
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         let commit_tx = &txs[0];
         let verify_tx = &txs[1];
@@ -695,7 +1111,7 @@ fn concurrent_operations_order() {
         }
 
         // Call `proceed_next_operations` again. Both txs should become confirmed.
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         for &tx in &[commit_tx, verify_tx] {
             let mut tx = tx.clone();
@@ -709,7 +1125,7 @@ fn concurrent_operations_order() {
 
         // Now, the withdraw operation should be taken from the queue, and
         // sent to the Ethereum.
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
 
         let withdraw_tx_hash = withdraw_tx.used_tx_hashes[0];
         eth_sender.db.assert_stored(&withdraw_tx);
@@ -721,7 +1137,7 @@ fn concurrent_operations_order() {
             .add_successfull_execution(withdraw_tx_hash, WAIT_CONFIRMATIONS);
 
         // Call `proceed_next_operations` again. Withdraw tx should become confirmed.
-        block_on(eth_sender.proceed_next_operations());
+        eth_sender.proceed_next_operations().await;
         // Update the fields in the tx and check if it's confirmed.
         withdraw_tx.confirmed = true;
         withdraw_tx.final_hash = Some(withdraw_tx_hash);
@@ -5,7 +5,7 @@
 
 // Built-in deps
 // External uses
-use web3::types::TransactionReceipt;
+use web3::types::{TransactionReceipt, U256};
 // Workspace uses
 use storage::ethereum::records::ETHStats as StorageETHStats;
 
@@ -58,6 +58,40 @@ pub enum TxCheckOutcome {
     Failed(Box<TransactionReceipt>),
 }
 
+/// Reason of the operation failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFailure {
+    /// Transaction was executed, but ran out of gas.
+    OutOfGas,
+    /// Transaction was executed, but reverted by the contract.
+    Reverted,
+    /// Nonce of the operation was consumed by another transaction, so none of
+    /// the transactions sent for the operation can be mined.
+    NonceConflict,
+}
+
+/// State of the operation according to the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationExecution {
+    /// None of the blocks of the operation are processed by the contract.
+    NotExecuted,
+    /// Some of the aggregated blocks are processed by the contract, and some are not.
+    PartiallyExecuted,
+    /// All the blocks of the operation are processed by the contract.
+    Executed,
+}
+
+/// The way to handle the failed operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureResolution {
+    /// Operation is already executed according to the contract state.
+    AlreadyExecuted,
+    /// Operation should be sent again with a new nonce and the provided gas limit.
+    Resend { gas_limit: U256 },
+    /// Failure can't be handled automatically. Contains a human-readable reason.
+    Unrecoverable(String),
+}
+
 /// Enumeration denoting if the operation was successfully committed, or not yet.
 #[derive(Debug, PartialEq, Eq)]
 pub enum OperationCommitment {
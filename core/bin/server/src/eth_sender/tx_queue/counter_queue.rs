@@ -52,6 +52,12 @@ impl<T: fmt::Debug> CounterQueue<T> {
         }
     }
 
+    /// Returns the references to the elements (no more than `max_count` of them)
+    /// which will be yielded by the subsequent `pop_front` calls.
+    pub fn peek_front(&self, max_count: usize) -> Vec<&T> {
+        self.elements.iter().take(max_count).collect()
+    }
+
     /// Returns the value of the counter.
     pub fn get_count(&self) -> usize {
         self.counter
@@ -98,5 +104,13 @@ mod tests {
         assert_eq!(queue.get_count(), 1);
         assert_eq!(queue.pop_front().unwrap(), "two");
         assert_eq!(queue.get_count(), 2);
+
+        // Peeking the elements should not affect the queue.
+        queue.push_back("three".into());
+        queue.push_back("four".into());
+        assert_eq!(queue.peek_front(1), vec!["three"]);
+        assert_eq!(queue.peek_front(5), vec!["three", "four"]);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.get_count(), 2);
     }
 }
@@ -1,7 +1,12 @@
+// Built-in imports
+use std::time::{Duration, Instant};
+// External uses
+use web3::types::U256;
 // Workspace imports
 use models::{ethereum::OperationType, Operation};
 // Local imports
 use self::{counter_queue::CounterQueue, sparse_queue::SparseQueue};
+use crate::gas_counter::{GasCounter, AGGREGATED_TX_GAS_LIMIT};
 
 mod counter_queue;
 mod sparse_queue;
@@ -19,6 +24,8 @@ pub struct TxData {
     pub raw: RawTxData,
     /// Optional zkSync operation.
     pub operation: Option<Operation>,
+    /// Moment when the data was created, used to limit the time of waiting for aggregation.
+    pub created_at: Instant,
 }
 
 impl PartialEq for TxData {
@@ -34,6 +41,7 @@ impl TxData {
             op_type,
             raw,
             operation: Some(operation),
+            created_at: Instant::now(),
         }
     }
 
@@ -43,6 +51,7 @@ impl TxData {
             op_type,
             raw,
             operation: None,
+            created_at: Instant::now(),
         }
     }
 }
@@ -55,6 +64,8 @@ impl TxData {
 pub struct TxQueueBuilder {
     max_pending_txs: usize,
     sent_pending_txs: usize,
+    max_aggregated_ops: usize,
+    aggregation_max_wait: Duration,
 
     commit_operations_count: usize,
     verify_operations_count: usize,
@@ -67,6 +78,8 @@ impl TxQueueBuilder {
         Self {
             max_pending_txs,
             sent_pending_txs: 0,
+            max_aggregated_ops: 1,
+            aggregation_max_wait: Duration::default(),
             commit_operations_count: 0,
             verify_operations_count: 0,
             withdraw_operations_count: 0,
@@ -81,6 +94,22 @@ impl TxQueueBuilder {
         }
     }
 
+    /// Enables the aggregation of `commit` and `verify` operations: up to `max_aggregated_ops`
+    /// operations of the same type are yielded to be sent within one transaction. Operations
+    /// are yielded without reaching the maximum only after waiting for `max_wait`.
+    pub fn with_aggregation(self, max_aggregated_ops: usize, max_wait: Duration) -> Self {
+        assert!(
+            max_aggregated_ops > 0,
+            "At least one operation should be sent within one transaction"
+        );
+
+        Self {
+            max_aggregated_ops,
+            aggregation_max_wait: max_wait,
+            ..self
+        }
+    }
+
     /// Sets the amount of operations sent for the `commit` queue.
     pub fn with_commit_operations_count(self, commit_operations_count: usize) -> Self {
         Self {
@@ -113,6 +142,8 @@ impl TxQueueBuilder {
         TxQueue {
             max_pending_txs: self.max_pending_txs,
             sent_pending_txs: self.sent_pending_txs,
+            max_aggregated_ops: self.max_aggregated_ops,
+            aggregation_max_wait: self.aggregation_max_wait,
 
             commit_operations: CounterQueue::new(self.commit_operations_count),
             verify_operations: SparseQueue::new(verify_operations_next_block),
@@ -135,10 +166,18 @@ impl TxQueueBuilder {
 ///   - Otherwise, if `withdraw` queue contains elements, a `withdraw` operation is yielded.
 ///   - Otherwise, if `commit` queue is not empty, a `commit` operation is yielded.
 /// 3. If all the queues are empty, no operation is returned.
+///
+/// If the aggregation is enabled, several consecutive `commit` (or `verify`) operations
+/// are yielded at once to be sent within one transaction. The amount of these operations
+/// is limited by the configured maximum and by the gas limit of the aggregated transaction.
+/// Operations are not yielded until the maximum is reached or the oldest of them has waited
+/// for the configured time.
 #[derive(Debug)]
 pub struct TxQueue {
     max_pending_txs: usize,
     sent_pending_txs: usize,
+    max_aggregated_ops: usize,
+    aggregation_max_wait: Duration,
 
     commit_operations: CounterQueue<TxData>,
     verify_operations: SparseQueue<TxData>,
@@ -191,22 +230,26 @@ impl TxQueue {
         );
     }
 
-    /// Returns a previously popped element to the front of the queue.
-    pub fn return_popped(&mut self, element: TxData) {
+    /// Returns previously popped elements to the front of the queue.
+    pub fn return_popped(&mut self, elements: Vec<TxData>) {
         assert!(
             self.sent_pending_txs > 0,
             "No transactions are expected to be returned"
         );
 
-        match &element.op_type {
-            OperationType::Commit => {
-                self.commit_operations.return_popped(element);
-            }
-            OperationType::Verify => {
-                self.verify_operations.return_popped(element);
-            }
-            OperationType::Withdraw => {
-                self.withdraw_operations.return_popped(element);
+        // Elements are returned in the reversed order, so the first popped
+        // element will be the first one in the queue.
+        for element in elements.into_iter().rev() {
+            match &element.op_type {
+                OperationType::Commit => {
+                    self.commit_operations.return_popped(element);
+                }
+                OperationType::Verify => {
+                    self.verify_operations.return_popped(element);
+                }
+                OperationType::Withdraw => {
+                    self.withdraw_operations.return_popped(element);
+                }
             }
         }
 
@@ -215,26 +258,28 @@ impl TxQueue {
         self.sent_pending_txs -= 1;
     }
 
-    /// Gets the next transaction to send, according to the transaction sending policy.
-    /// For details, see the structure doc-comment.
-    pub fn pop_front(&mut self) -> Option<TxData> {
+    /// Gets the operations for the next transaction to send, according to the transaction
+    /// sending policy. For details, see the structure doc-comment.
+    ///
+    /// Returned operations have the same type and are meant to be sent within one transaction.
+    pub fn pop_front(&mut self) -> Option<Vec<TxData>> {
         if self.sent_pending_txs >= self.max_pending_txs {
             return None;
         }
 
-        // Get the next operation and increment the sent counter if needed.
-        match self.get_next_operation() {
-            Some(op) => {
+        // Get the next operations and increment the sent counter if needed.
+        match self.get_next_operations() {
+            Some(ops) => {
                 self.sent_pending_txs += 1;
-                Some(op)
+                Some(ops)
             }
             None => None,
         }
     }
 
-    /// Obtains the next operation from the underlying queues.
+    /// Obtains the next operations from the underlying queues.
     /// This method does not use/affect `sent_pending_tx` counter.
-    fn get_next_operation(&mut self) -> Option<TxData> {
+    fn get_next_operations(&mut self) -> Option<Vec<TxData>> {
         // 1. Highest priority: verify operations.
 
         // If we've committed a corresponding `Commit` operation, and
@@ -242,7 +287,18 @@ impl TxQueue {
         let next_verify_op_id = self.verify_operations.next_id();
         let next_commit_op_id = self.commit_operations.get_count() + 1;
         if next_verify_op_id < next_commit_op_id && self.verify_operations.has_next() {
-            return Some(self.verify_operations.pop_front().unwrap());
+            // Only the blocks with already sent commit operations can be verified.
+            let max_count = std::cmp::min(
+                self.max_aggregated_ops,
+                next_commit_op_id - next_verify_op_id,
+            );
+            let candidates = self.verify_operations.peek_front(max_count);
+            if let Some(count) = self.aggregated_ops_count(OperationType::Verify, &candidates) {
+                let verify_operations = (0..count)
+                    .map(|_| self.verify_operations.pop_front().unwrap())
+                    .collect();
+                return Some(verify_operations);
+            }
         }
 
         // 2. After verify operations we should process withdraw operation.
@@ -251,14 +307,18 @@ impl TxQueue {
         let next_withdraw_op_id = self.withdraw_operations.get_count() + 1;
         if next_withdraw_op_id < next_verify_op_id {
             if let Some(withdraw_operation) = self.withdraw_operations.pop_front() {
-                return Some(withdraw_operation);
+                return Some(vec![withdraw_operation]);
             }
         }
 
         // 3. Finally, check the commit queue.
 
-        if let Some(commit_operation) = self.commit_operations.pop_front() {
-            return Some(commit_operation);
+        let candidates = self.commit_operations.peek_front(self.max_aggregated_ops);
+        if let Some(count) = self.aggregated_ops_count(OperationType::Commit, &candidates) {
+            let commit_operations = (0..count)
+                .map(|_| self.commit_operations.pop_front().unwrap())
+                .collect();
+            return Some(commit_operations);
         }
 
         // 4. There are no operations to process, return `None`.
@@ -266,6 +326,49 @@ impl TxQueue {
         None
     }
 
+    /// Determines how many of the candidate operations should be sent within one transaction.
+    /// Returns `None` if there are no candidates, or if they should wait for more operations
+    /// to be aggregated with.
+    fn aggregated_ops_count(
+        &self,
+        op_type: OperationType,
+        candidates: &[&TxData],
+    ) -> Option<usize> {
+        let oldest_candidate = candidates.first()?;
+
+        // Take as many operations as we can fit into the gas limit (but at least one).
+        let mut count = 1;
+        while count < candidates.len()
+            && Self::aggregated_gas_limit(op_type, &candidates[..=count])
+                <= U256::from(AGGREGATED_TX_GAS_LIMIT)
+        {
+            count += 1;
+        }
+
+        let limit_reached = count == self.max_aggregated_ops || count < candidates.len();
+        if limit_reached || oldest_candidate.created_at.elapsed() >= self.aggregation_max_wait {
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    /// Calculates the gas limit of the transaction processing the provided operations.
+    fn aggregated_gas_limit(op_type: OperationType, txs: &[&TxData]) -> U256 {
+        let operations = txs.iter().filter_map(|tx| tx.operation.as_ref());
+        match op_type {
+            OperationType::Commit => {
+                let limits: Vec<U256> = operations.map(|op| op.block.commit_gas_limit).collect();
+                GasCounter::aggregated_commit_gas_limit(&limits)
+            }
+            OperationType::Verify => {
+                let limits: Vec<U256> = operations.map(|op| op.block.verify_gas_limit).collect();
+                GasCounter::aggregated_verify_gas_limit(&limits)
+            }
+            OperationType::Withdraw => unreachable!("Withdraw operations are not aggregated"),
+        }
+    }
+
     /// Notifies the queue about the transaction being confirmed on the Ethereum blockchain.
     /// Decrements the amount of transactions "in the fly".
     pub fn report_commitment(&mut self) {
@@ -282,6 +385,14 @@ impl TxQueue {
 mod tests {
     use super::*;
 
+    /// Pops the next operation, expecting it to be the only one in the transaction.
+    fn pop_single(queue: &mut TxQueue) -> Option<TxData> {
+        queue.pop_front().map(|mut ops| {
+            assert_eq!(ops.len(), 1, "Operations should not be aggregated");
+            ops.remove(0)
+        })
+    }
+
     /// Checks the basic workflow of the queue including adding several operations
     /// and retrieving them later.
     #[test]
@@ -322,16 +433,16 @@ mod tests {
         // Retrieve the next {MAX_IN_FLY} operations.
 
         // The first operation should be `commit`, since we can't send `verify` before the commitment.
-        let op_1 = queue.pop_front().unwrap();
+        let op_1 = pop_single(&mut queue).unwrap();
         assert_eq!(op_1.raw, vec![COMMIT_MARK, 0]);
 
         // The second operation should be `verify`, since it has the highest priority.
-        let op_2 = queue.pop_front().unwrap();
+        let op_2 = pop_single(&mut queue).unwrap();
         assert_eq!(op_2.raw, vec![VERIFY_MARK, 0]);
 
         // The third operation should be `withdraw`, since it has higher priority than `commit`, and we can't
         // send the `verify` before the corresponding `commit` operation.
-        let op_3 = queue.pop_front().unwrap();
+        let op_3 = pop_single(&mut queue).unwrap();
         assert_eq!(op_3.raw, vec![WITHDRAW_MARK, 0]);
 
         // After that we have {MAX_IN_FLY} operations, and `pop_front` should yield nothing.
@@ -341,7 +452,7 @@ mod tests {
         queue.report_commitment();
 
         // Now we should obtain the next commit operation.
-        let op_4 = queue.pop_front().unwrap();
+        let op_4 = pop_single(&mut queue).unwrap();
         assert_eq!(op_4.raw, vec![COMMIT_MARK, 1]);
 
         // The limit should be met again, and nothing more should be yielded.
@@ -355,10 +466,10 @@ mod tests {
         assert_eq!(queue.sent_pending_txs, 0);
 
         // Pop remaining operations.
-        let op_5 = queue.pop_front().unwrap();
+        let op_5 = pop_single(&mut queue).unwrap();
         assert_eq!(op_5.raw, vec![VERIFY_MARK, 1]);
 
-        let op_6 = queue.pop_front().unwrap();
+        let op_6 = pop_single(&mut queue).unwrap();
         assert_eq!(op_6.raw, vec![WITHDRAW_MARK, 1]);
 
         // Though the limit is not met (2 txs in fly, and limit is 3), there should be no txs in the queue.
@@ -367,12 +478,12 @@ mod tests {
         let pending_count = queue.sent_pending_txs;
 
         // Return the operation to the queue.
-        queue.return_popped(op_6);
+        queue.return_popped(vec![op_6]);
 
         // Now, as we've returned tx to queue, pending count should be decremented.
         assert_eq!(queue.sent_pending_txs, pending_count - 1);
 
-        let op_6 = queue.pop_front().unwrap();
+        let op_6 = pop_single(&mut queue).unwrap();
         assert_eq!(op_6.raw, vec![WITHDRAW_MARK, 1]);
 
         // We've popped the tx once again, now pending count should be increased.
@@ -387,9 +498,64 @@ mod tests {
 
         let mut queue = TxQueueBuilder::new(MAX_IN_FLY).build();
 
-        queue.return_popped(TxData::from_raw(
+        queue.return_popped(vec![TxData::from_raw(
             OperationType::Commit,
             vec![COMMIT_MARK, 0],
-        ));
+        )]);
+    }
+
+    /// Checks that commit and verify operations are aggregated up to the configured limit,
+    /// and that the incomplete batch is yielded only after the wait time has passed.
+    #[test]
+    fn aggregated_operations() {
+        const MAX_IN_FLY: usize = 3;
+        const MAX_AGGREGATED_OPS: usize = 2;
+        const MAX_WAIT: Duration = Duration::from_secs(60);
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY)
+            .with_aggregation(MAX_AGGREGATED_OPS, MAX_WAIT)
+            .build();
+
+        // Add 3 commit operations and 3 verify operations.
+        for idx in 0..3u8 {
+            queue.add_commit_operation(TxData::from_raw(
+                OperationType::Commit,
+                vec![COMMIT_MARK, idx],
+            ));
+            queue.add_verify_operation(
+                idx as usize + 1,
+                TxData::from_raw(OperationType::Verify, vec![VERIFY_MARK, idx]),
+            );
+        }
+
+        // The first two commit operations should be sent at once.
+        let ops = queue.pop_front().unwrap();
+        let raw: Vec<_> = ops.iter().map(|op| op.raw.clone()).collect();
+        assert_eq!(raw, vec![vec![COMMIT_MARK, 0], vec![COMMIT_MARK, 1]]);
+
+        // Now the verify operations for the committed blocks should be sent at once.
+        let ops = queue.pop_front().unwrap();
+        let raw: Vec<_> = ops.iter().map(|op| op.raw.clone()).collect();
+        assert_eq!(raw, vec![vec![VERIFY_MARK, 0], vec![VERIFY_MARK, 1]]);
+
+        // The last commit operation should wait for more operations to be aggregated with.
+        assert_eq!(queue.pop_front(), None);
+
+        // Once the operation waited long enough, it should be sent alone.
+        let mut stale_op = queue.commit_operations.pop_front().unwrap();
+        stale_op.created_at = Instant::now() - MAX_WAIT;
+        queue.commit_operations.return_popped(stale_op);
+
+        let ops = queue.pop_front().unwrap();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].raw, vec![COMMIT_MARK, 2]);
+
+        // Return the operations to the queue, and check that they're yielded again.
+        queue.return_popped(ops);
+        let ops = queue.pop_front().unwrap();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].raw, vec![COMMIT_MARK, 2]);
     }
 }
@@ -67,6 +67,18 @@ impl<T: fmt::Debug> SparseQueue<T> {
         self.elements.contains_key(&self.next_expected_idx)
     }
 
+    /// Returns the references to the elements (no more than `max_count` of them)
+    /// which will be yielded by the subsequent `pop_front` calls, i.e. the elements
+    /// with consecutive indices starting from the next expected one.
+    pub fn peek_front(&self, max_count: usize) -> Vec<&T> {
+        (self.next_expected_idx..)
+            .map(|idx| self.elements.get(&idx))
+            .take(max_count)
+            .take_while(Option::is_some)
+            .flatten()
+            .collect()
+    }
+
     /// Returns the next expected element ID.
     pub fn next_id(&self) -> usize {
         self.next_expected_idx
@@ -114,6 +126,15 @@ mod tests {
         assert_eq!(queue.next_id(), 2);
         assert_eq!(queue.pop_front().unwrap(), "two");
         assert_eq!(queue.next_id(), 3);
+
+        // Only the elements without gaps should be peeked.
+        queue.insert(3, "three".into());
+        queue.insert(4, "four".into());
+        queue.insert(6, "six".into());
+        assert_eq!(queue.peek_front(1), vec!["three"]);
+        assert_eq!(queue.peek_front(5), vec!["three", "four"]);
+        assert_eq!(queue.next_id(), 3);
+        assert_eq!(queue.len(), 3);
     }
 
     /// Checks that we can use the difference `next_expected_idx` as the custom
@@ -61,6 +61,7 @@ enum WatcherMode {
     Backoff(Instant),
 }
 
+#[derive(Debug)]
 pub enum EthWatchRequest {
     PollETHNode,
     IsPubkeyChangeAuthorized {
@@ -88,6 +89,10 @@ pub enum EthWatchRequest {
         signature: EIP1271Signature,
         resp: oneshot::Sender<Result<bool, failure::Error>>,
     },
+    /// Returns the number of the last Ethereum block processed by the watcher.
+    GetLastEthBlock {
+        resp: oneshot::Sender<u64>,
+    },
 }
 
 pub struct EthWatch<T: Transport> {
@@ -497,6 +502,10 @@ impl<T: Transport> EthWatch<T> {
                     let unconfirmed_op = self.find_ongoing_op_by_hash(&eth_hash);
                     resp.send(unconfirmed_op).unwrap_or_default();
                 }
+                EthWatchRequest::GetLastEthBlock { resp } => {
+                    resp.send(self.eth_state.last_ethereum_block())
+                        .unwrap_or_default();
+                }
                 EthWatchRequest::IsPubkeyChangeAuthorized {
                     address,
                     nonce,
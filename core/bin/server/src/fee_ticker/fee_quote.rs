@@ -0,0 +1,161 @@
+//! Signed fee quotes.
+//!
+//! Fee returned by the `get_tx_fee` method may become too low a few seconds later if the
+//! token prices change. To avoid rejecting such transactions, server can provide a quote,
+//! which is a JSON Web Token signed by the server that contains the fee and the transaction
+//! parameters it was calculated for. Until the quote is expired, transaction with the same
+//! parameters and the fee not lower than the quoted one is accepted regardless of the current prices.
+
+// Built-in deps
+use std::time::Duration;
+// External deps
+use chrono::Utc;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use models::{
+    node::{Address, TokenId, TokenLike, TxFeeTypes},
+    primitives::BigUintSerdeAsRadix10Str,
+};
+
+/// Claims of the fee quote token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeQuote {
+    pub tx_type: TxFeeTypes,
+    pub address: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub total_fee: BigUint,
+    /// Expiration time (as UTC timestamp).
+    pub exp: usize,
+}
+
+impl FeeQuote {
+    /// Checks whether the quote was issued for the transaction with the given parameters
+    /// and the provided fee is not lower than the quoted one.
+    pub fn covers(
+        &self,
+        tx_type: &TxFeeTypes,
+        address: Address,
+        token: &TokenLike,
+        provided_fee: &BigUint,
+    ) -> bool {
+        &self.tx_type == tx_type
+            && self.address == address
+            && token == &TokenLike::Id(self.token)
+            && provided_fee >= &self.total_fee
+    }
+}
+
+/// Issues and verifies the fee quotes.
+#[derive(Clone)]
+pub struct FeeQuoteSigner {
+    secret: String,
+    lifetime: Duration,
+}
+
+impl FeeQuoteSigner {
+    pub fn new(secret: String, lifetime: Duration) -> Self {
+        Self { secret, lifetime }
+    }
+
+    /// Creates a signed quote of the fee for the transaction with the given parameters.
+    pub fn sign(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: TokenId,
+        total_fee: BigUint,
+    ) -> Result<String, failure::Error> {
+        let quote = FeeQuote {
+            tx_type,
+            address,
+            token,
+            total_fee,
+            exp: Utc::now().timestamp() as usize + self.lifetime.as_secs() as usize,
+        };
+
+        Ok(encode(
+            &Header::default(),
+            &quote,
+            &EncodingKey::from_secret(self.secret.as_ref()),
+        )?)
+    }
+
+    /// Checks the quote signature and expiration time, returning its content.
+    pub fn verify(&self, quote: &str) -> Result<FeeQuote, failure::Error> {
+        let token = decode::<FeeQuote>(
+            quote,
+            &DecodingKey::from_secret(self.secret.as_ref()),
+            &Validation::default(),
+        )?;
+
+        Ok(token.claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote_signer(secret: &str, lifetime_secs: u64) -> FeeQuoteSigner {
+        FeeQuoteSigner::new(secret.into(), Duration::from_secs(lifetime_secs))
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let signer = quote_signer("secret", 60);
+        let address = Address::repeat_byte(0x11);
+        let quote = signer
+            .sign(TxFeeTypes::Transfer, address, 1, 1000u32.into())
+            .unwrap();
+
+        let fee_quote = signer.verify(&quote).expect("Quote must be valid");
+        let token = TokenLike::Id(1);
+        assert!(fee_quote.covers(&TxFeeTypes::Transfer, address, &token, &1000u32.into()));
+        assert!(fee_quote.covers(&TxFeeTypes::Transfer, address, &token, &1001u32.into()));
+        // Fee is lower than the quoted one.
+        assert!(!fee_quote.covers(&TxFeeTypes::Transfer, address, &token, &999u32.into()));
+        // Different transaction parameters.
+        assert!(!fee_quote.covers(&TxFeeTypes::Withdraw, address, &token, &1000u32.into()));
+        assert!(!fee_quote.covers(
+            &TxFeeTypes::Transfer,
+            Address::zero(),
+            &token,
+            &1000u32.into()
+        ));
+        assert!(!fee_quote.covers(
+            &TxFeeTypes::Transfer,
+            address,
+            &TokenLike::Id(0),
+            &1000u32.into()
+        ));
+
+        // Quote signed with another secret is not accepted.
+        quote_signer("another secret", 60)
+            .verify(&quote)
+            .expect_err("Quote with the wrong signature");
+    }
+
+    #[test]
+    fn expired_quote() {
+        let signer = quote_signer("secret", 60);
+        let expired_quote = FeeQuote {
+            tx_type: TxFeeTypes::Transfer,
+            address: Address::zero(),
+            token: 0,
+            total_fee: 1000u32.into(),
+            exp: Utc::now().timestamp() as usize - 10,
+        };
+        let quote = encode(
+            &Header::default(),
+            &expired_quote,
+            &EncodingKey::from_secret("secret".as_ref()),
+        )
+        .unwrap();
+
+        signer.verify(&quote).expect_err("Expired quote");
+    }
+}
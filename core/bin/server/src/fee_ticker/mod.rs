@@ -7,6 +7,7 @@
 use std::collections::HashMap;
 // External deps
 use bigdecimal::BigDecimal;
+use failure::format_err;
 use futures::{
     channel::{
         mpsc::{self, Receiver},
@@ -14,17 +15,13 @@ use futures::{
     },
     StreamExt,
 };
-use num::{
-    rational::Ratio,
-    traits::{Inv, Pow},
-    BigUint,
-};
+use num::{rational::Ratio, traits::Pow, BigUint};
 use tokio::task::JoinHandle;
 // Workspace deps
 use models::{
     node::{
-        pack_fee_amount, unpack_fee_amount, Address, TokenId, TokenLike, TransferOp,
-        TransferToNewOp, TxFeeTypes, WithdrawOp,
+        pack_fee_amount, unpack_fee_amount, Address, ForcedExitOp, SwapOp, TokenFeeSettings,
+        TokenId, TokenLike, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawOp,
     },
     primitives::{ratio_to_big_decimal, round_precision, BigUintSerdeAsRadix10Str},
 };
@@ -32,11 +29,12 @@ use storage::ConnectionPool;
 // Local deps
 use crate::fee_ticker::ticker_api::coingecko::CoinGeckoAPI;
 use crate::fee_ticker::ticker_api::coinmarkercap::CoinMarketCapAPI;
+use crate::fee_ticker::ticker_api::median::MedianPriceAPI;
 use crate::gas_counter::{CommitCost, GasCounter, VerifyCost};
 use crate::{
     eth_sender::ETHSenderRequest,
     fee_ticker::{
-        ticker_api::{FeeTickerAPI, TickerApi, CONNECTION_TIMEOUT},
+        ticker_api::{FeeTickerAPI, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT},
         ticker_info::{FeeTickerInfo, TickerInfo},
     },
     state_keeper::StateKeeperRequest,
@@ -44,9 +42,12 @@ use crate::{
 use models::config_options::TokenPriceSource;
 use models::node::config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL;
 
+pub mod fee_quote;
 mod ticker_api;
 mod ticker_info;
 
+pub use self::ticker_api::median::{PriceSourcesHealth, SourceHealth};
+
 // Base operation costs estimated via `gas_price` test.
 //
 // Factor of 1000 * CHUNKS accounts for constant overhead of the commit and verify for block of 680 chunks
@@ -61,6 +62,13 @@ const BASE_WITHDRAW_COST: u64 = VerifyCost::WITHDRAW_COST
     + GasCounter::COMPLETE_WITHDRAWALS_COST
     + 1000 * (WithdrawOp::CHUNKS as u64)
     + (GasCounter::COMPLETE_WITHDRAWALS_BASE_COST / MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL);
+const BASE_FORCED_EXIT_COST: u64 = VerifyCost::FORCED_EXIT_COST
+    + CommitCost::FORCED_EXIT_COST
+    + GasCounter::COMPLETE_WITHDRAWALS_COST
+    + 1000 * (ForcedExitOp::CHUNKS as u64)
+    + (GasCounter::COMPLETE_WITHDRAWALS_BASE_COST / MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL);
+const BASE_SWAP_COST: u64 =
+    VerifyCost::SWAP_COST + CommitCost::SWAP_COST + 1000 * (SwapOp::CHUNKS as u64);
 
 /// Type of the fee calculation pattern.
 /// Unlike the `TxFeeTypes`, this enum represents the fee
@@ -74,6 +82,8 @@ pub enum OutputFeeType {
     TransferToNew,
     Withdraw,
     FastWithdraw,
+    ForcedExit,
+    Swap,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -90,6 +100,10 @@ pub struct Fee {
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Signed quote of the fee which is honoured by the `tx_submit` method until it's expired.
+    /// See `fee_quote` module for details.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -121,6 +135,7 @@ impl Fee {
             gas_fee,
             zkp_fee,
             total_fee,
+            quote: None,
         }
     }
 }
@@ -129,7 +144,8 @@ impl Fee {
 pub struct TickerConfig {
     zkp_cost_chunk_usd: Ratio<BigUint>,
     gas_cost_tx: HashMap<OutputFeeType, BigUint>, //wei
-    tokens_risk_factors: HashMap<TokenId, Ratio<BigUint>>,
+    /// Fee settings of the tokens accepted for paying fees, loaded from the database.
+    tokens_fee_settings: HashMap<TokenId, TokenFeeSettings>,
 }
 
 pub enum TokenPriceRequestType {
@@ -149,6 +165,14 @@ pub enum TickerRequest {
         response: oneshot::Sender<Result<BigDecimal, failure::Error>>,
         req_type: TokenPriceRequestType,
     },
+    IsTokenAllowedForFees {
+        token: TokenLike,
+        response: oneshot::Sender<Result<bool, failure::Error>>,
+    },
+    /// Reloads the tokens fee settings from the database, e.g. after they were changed via admin server.
+    ReloadTokensFeeSettings {
+        response: oneshot::Sender<Result<(), failure::Error>>,
+    },
 }
 
 struct FeeTicker<API, INFO> {
@@ -158,21 +182,26 @@ struct FeeTicker<API, INFO> {
     config: TickerConfig,
 }
 
-#[must_use]
+/// Starts the fee ticker. Health of the price sources is reported to `price_sources_health`
+/// if the median of several sources is used.
+/// Returns an error if the token price source is not configured correctly.
+#[allow(clippy::too_many_arguments)]
 pub fn run_ticker_task(
     token_price_source: TokenPriceSource,
+    price_sources_health: PriceSourcesHealth,
     fast_processing_coeff: f64,
+    zkp_cost_chunk_usd: Ratio<BigUint>,
     db_pool: ConnectionPool,
     eth_sender_request_sender: mpsc::Sender<ETHSenderRequest>,
     state_keeper_request_sender: mpsc::Sender<StateKeeperRequest>,
     tricker_requests: Receiver<TickerRequest>,
-) -> JoinHandle<()> {
+) -> Result<JoinHandle<()>, failure::Error> {
     // We increase gas price for fast withdrawals, since it will induce generating a smaller block
     // size, resulting in us paying more gas than for bigger block.
     let fast_withdrawal_cost = (BASE_WITHDRAW_COST as f64 * fast_processing_coeff) as u32;
 
     let ticker_config = TickerConfig {
-        zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
+        zkp_cost_chunk_usd,
         gas_cost_tx: vec![
             (OutputFeeType::Transfer, BASE_TRANSFER_COST.into()),
             (
@@ -181,10 +210,13 @@ pub fn run_ticker_task(
             ),
             (OutputFeeType::Withdraw, BASE_WITHDRAW_COST.into()),
             (OutputFeeType::FastWithdraw, fast_withdrawal_cost.into()),
+            (OutputFeeType::ForcedExit, BASE_FORCED_EXIT_COST.into()),
+            (OutputFeeType::Swap, BASE_SWAP_COST.into()),
         ]
         .into_iter()
         .collect(),
-        tokens_risk_factors: HashMap::new(),
+        // Loaded from the database once the ticker is started.
+        tokens_fee_settings: HashMap::new(),
     };
 
     let client = reqwest::ClientBuilder::new()
@@ -192,7 +224,7 @@ pub fn run_ticker_task(
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
-    match token_price_source {
+    let task = match token_price_source {
         TokenPriceSource::CoinMarketCap { base_url } => {
             let token_price_api = CoinMarketCapAPI::new(client, base_url);
 
@@ -204,8 +236,8 @@ pub fn run_ticker_task(
             tokio::spawn(fee_ticker.run())
         }
         TokenPriceSource::CoinGecko { base_url } => {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url).expect("failed to init CoinGecko client");
+            let token_price_api = CoinGeckoAPI::new(client, base_url)
+                .map_err(|e| format_err!("failed to init CoinGecko client: {}", e))?;
 
             let ticker_api = TickerApi::new(db_pool, eth_sender_request_sender, token_price_api);
             let ticker_info = TickerInfo::new(state_keeper_request_sender);
@@ -214,7 +246,58 @@ pub fn run_ticker_task(
 
             tokio::spawn(fee_ticker.run())
         }
-    }
+        TokenPriceSource::Median {
+            sources,
+            min_quorum,
+        } => {
+            if min_quorum == 0 || min_quorum > sources.len() {
+                failure::bail!(
+                    "Median price quorum must be between 1 and the amount of sources ({}), got {}",
+                    sources.len(),
+                    min_quorum
+                );
+            }
+            let sources = sources
+                .into_iter()
+                .map(|source| token_price_api_for_source(client.clone(), source))
+                .collect::<Result<_, _>>()?;
+            let token_price_api = MedianPriceAPI::new(sources, min_quorum, price_sources_health);
+
+            let ticker_api = TickerApi::new(db_pool, eth_sender_request_sender, token_price_api);
+            let ticker_info = TickerInfo::new(state_keeper_request_sender);
+            let fee_ticker =
+                FeeTicker::new(ticker_api, ticker_info, tricker_requests, ticker_config);
+
+            tokio::spawn(fee_ticker.run())
+        }
+    };
+
+    Ok(task)
+}
+
+/// Creates a price API for the single source to be used by the `MedianPriceAPI`.
+fn token_price_api_for_source(
+    client: reqwest::Client,
+    source: TokenPriceSource,
+) -> Result<(String, Box<dyn TokenPriceAPI + Send + Sync>), failure::Error> {
+    let api: (String, Box<dyn TokenPriceAPI + Send + Sync>) = match source {
+        TokenPriceSource::CoinMarketCap { base_url } => (
+            "CoinMarketCap".to_string(),
+            Box::new(CoinMarketCapAPI::new(client, base_url)),
+        ),
+        TokenPriceSource::CoinGecko { base_url } => (
+            "CoinGecko".to_string(),
+            Box::new(
+                CoinGeckoAPI::new(client, base_url)
+                    .map_err(|e| format_err!("failed to init CoinGecko client: {}", e))?,
+            ),
+        ),
+        TokenPriceSource::Median { .. } => {
+            failure::bail!("Median price source can't be nested into another median source")
+        }
+    };
+
+    Ok(api)
 }
 
 impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
@@ -228,6 +311,10 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
     }
 
     async fn run(mut self) {
+        self.reload_tokens_fee_settings()
+            .await
+            .expect("Failed to load tokens fee settings");
+
         while let Some(request) = self.requests.next().await {
             match request {
                 TickerRequest::GetTxFee {
@@ -249,6 +336,14 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
                     let price = self.get_token_price(token, req_type).await;
                     response.send(price).unwrap_or_default();
                 }
+                TickerRequest::IsTokenAllowedForFees { token, response } => {
+                    let allowed = self.is_token_allowed_for_fees(token).await;
+                    response.send(allowed).unwrap_or_default();
+                }
+                TickerRequest::ReloadTokensFeeSettings { response } => {
+                    let result = self.reload_tokens_fee_settings().await;
+                    response.send(result).unwrap_or_default();
+                }
             }
         }
     }
@@ -272,6 +367,28 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
             .map(|price| ratio_to_big_decimal(&(price.usd_price / factor), 100))
     }
 
+    async fn reload_tokens_fee_settings(&mut self) -> Result<(), failure::Error> {
+        self.config.tokens_fee_settings = self.api.get_tokens_fee_settings().await?;
+        info!(
+            "Loaded fee settings for {} tokens",
+            self.config.tokens_fee_settings.len()
+        );
+        Ok(())
+    }
+
+    async fn is_token_allowed_for_fees(&self, token: TokenLike) -> Result<bool, failure::Error> {
+        let token = self.api.get_token(token).await?;
+        Ok(self.token_fee_settings(token.id).is_some())
+    }
+
+    /// Returns the fee settings of the token if it's accepted for paying fees.
+    fn token_fee_settings(&self, token_id: TokenId) -> Option<&TokenFeeSettings> {
+        self.config
+            .tokens_fee_settings
+            .get(&token_id)
+            .filter(|settings| settings.fee_allowed)
+    }
+
     /// Returns `true` if account does not yet exist in the zkSync network.
     async fn is_account_new(&mut self, address: Address) -> bool {
         self.info.is_account_new(address).await
@@ -286,15 +403,17 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.api.get_token(token).await?;
         let token_risk_factor = self
-            .config
-            .tokens_risk_factors
-            .get(&token.id)
-            .cloned()
-            .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
+            .token_fee_settings(token.id)
+            .map(|settings| settings.risk_factor.clone())
+            .ok_or_else(|| {
+                format_err!("Token {} is not acceptable for paying fees", token.symbol)
+            })?;
 
         let (fee_type, op_chunks) = match tx_type {
             TxFeeTypes::Withdraw => (OutputFeeType::Withdraw, WithdrawOp::CHUNKS),
             TxFeeTypes::FastWithdraw => (OutputFeeType::FastWithdraw, WithdrawOp::CHUNKS),
+            TxFeeTypes::ForcedExit => (OutputFeeType::ForcedExit, ForcedExitOp::CHUNKS),
+            TxFeeTypes::Swap => (OutputFeeType::Swap, SwapOp::CHUNKS),
             TxFeeTypes::Transfer => {
                 if self.is_account_new(recipient).await {
                     (OutputFeeType::TransferToNew, TransferToNewOp::CHUNKS)
@@ -376,6 +495,13 @@ mod test {
                 .unwrap_or_else(|| Ratio::from_integer(1u32.into()))
         }
 
+        fn fee_settings(&self) -> TokenFeeSettings {
+            TokenFeeSettings {
+                risk_factor: self.risk_factor(),
+                fee_allowed: true,
+            }
+        }
+
         fn eth() -> Self {
             Self::new(0, 182.0, None, 18)
         }
@@ -405,15 +531,17 @@ mod test {
                     BigUint::from(BASE_TRANSFER_TO_NEW_COST),
                 ),
                 (OutputFeeType::Withdraw, BigUint::from(BASE_WITHDRAW_COST)),
+                (
+                    OutputFeeType::ForcedExit,
+                    BigUint::from(BASE_FORCED_EXIT_COST),
+                ),
+                (OutputFeeType::Swap, BigUint::from(BASE_SWAP_COST)),
             ]
             .into_iter()
             .collect(),
-            tokens_risk_factors: TestToken::all_tokens()
+            tokens_fee_settings: TestToken::all_tokens()
                 .into_iter()
-                .filter_map(|t| {
-                    let id = t.id;
-                    t.risk_factor.map(|risk| (id, risk))
-                })
+                .map(|t| (t.id, t.fee_settings()))
                 .collect(),
         }
     }
@@ -452,6 +580,12 @@ mod test {
             }
             unreachable!("incorrect token input")
         }
+
+        async fn get_tokens_fee_settings(
+            &self,
+        ) -> Result<HashMap<TokenId, TokenFeeSettings>, failure::Error> {
+            Ok(get_test_ticker_config().tokens_fee_settings)
+        }
     }
 
     struct MockTickerInfo;
@@ -527,4 +661,46 @@ mod test {
             }
         }
     }
+
+    /// Checks that fee can't be paid in the token which is not in the allowlist.
+    #[test]
+    fn fee_token_allowlist() {
+        let mut config = get_test_ticker_config();
+        let cheap = TestToken::cheap();
+        config.tokens_fee_settings.insert(
+            cheap.id,
+            TokenFeeSettings {
+                fee_allowed: false,
+                ..cheap.fee_settings()
+            },
+        );
+        config
+            .tokens_fee_settings
+            .remove(&TestToken::expensive().id);
+        let mut ticker =
+            FeeTicker::new(MockApiProvider, MockTickerInfo, mpsc::channel(1).1, config);
+
+        for token in TestToken::all_tokens() {
+            let allowed = token.id == TestToken::eth().id;
+            assert_eq!(
+                block_on(ticker.is_token_allowed_for_fees(token.id.into())).unwrap(),
+                allowed
+            );
+            assert_eq!(
+                block_on(ticker.get_fee_from_ticker_in_wei(
+                    TxFeeTypes::Transfer,
+                    token.id.into(),
+                    Address::default()
+                ))
+                .is_ok(),
+                allowed
+            );
+        }
+
+        // Settings are restored after reload.
+        block_on(ticker.reload_tokens_fee_settings()).unwrap();
+        for token in TestToken::all_tokens() {
+            assert!(block_on(ticker.is_token_allowed_for_fees(token.id.into())).unwrap());
+        }
+    }
 }
@@ -0,0 +1,421 @@
+// Built-in deps
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+// External deps
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use num::{rational::Ratio, BigUint};
+// Workspace deps
+use super::{TokenPriceAPI, API_PRICE_EXPIRATION_TIME_SECS};
+use models::node::TokenPrice;
+
+/// Quotes deviating from the median price by more than this percentage are considered outliers.
+const MAX_PRICE_DEVIATION_PERCENT: u32 = 10;
+
+/// Health statistics of the price source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceHealth {
+    /// Amount of quotes used to calculate the price.
+    pub accepted_quotes: u64,
+    /// Amount of quotes discarded as stale or deviating too much from other sources.
+    pub discarded_quotes: u64,
+    /// Amount of failed requests to the source.
+    pub failed_requests: u64,
+    /// Time of the last accepted quote.
+    pub last_accepted: Option<DateTime<Utc>>,
+}
+
+/// Health statistics of the price sources shared between the `MedianPriceAPI`
+/// and the metrics exporter.
+#[derive(Debug, Clone, Default)]
+pub struct PriceSourcesHealth(Arc<Mutex<HashMap<String, SourceHealth>>>);
+
+impl PriceSourcesHealth {
+    /// Returns the health statistics of every source.
+    pub fn snapshot(&self) -> HashMap<String, SourceHealth> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn update(&self, source: &str, update: impl FnOnce(&mut SourceHealth)) {
+        let mut health = self.0.lock().unwrap();
+        let source_health = health.entry(source.to_string()).or_default();
+        update(source_health);
+    }
+}
+
+/// Token price API which queries several sources concurrently and reports the median
+/// of their prices. Stale quotes and quotes deviating too much from the median are
+/// discarded, so one flaky or manipulated source can't set the price on its own.
+///
+/// With only two fresh quotes there is no majority to tell which one is wrong, so their
+/// average is used only if they agree with each other, and none of them is blamed otherwise.
+///
+/// The price is provided only if at least `min_quorum` fresh quotes agree with each other,
+/// so the price can't be set by the single source that survived when the others are down.
+pub struct MedianPriceAPI {
+    sources: Vec<(String, Box<dyn TokenPriceAPI + Send + Sync>)>,
+    min_quorum: usize,
+    health: PriceSourcesHealth,
+}
+
+impl MedianPriceAPI {
+    /// Creates a new API given the list of sources along with their names and the minimum
+    /// amount of agreeing quotes. Health statistics of the sources are reported to the
+    /// provided `health`.
+    pub fn new(
+        sources: Vec<(String, Box<dyn TokenPriceAPI + Send + Sync>)>,
+        min_quorum: usize,
+        health: PriceSourcesHealth,
+    ) -> Self {
+        assert!(!sources.is_empty(), "At least one price source is required");
+        assert!(
+            min_quorum > 0 && min_quorum <= sources.len(),
+            "Quorum must be between 1 and the amount of price sources"
+        );
+
+        for (name, _) in &sources {
+            health.update(name, |_| {});
+        }
+
+        Self {
+            sources,
+            min_quorum,
+            health,
+        }
+    }
+
+    fn update_health(&self, source: &str, update: impl FnOnce(&mut SourceHealth)) {
+        self.health.update(source, update);
+    }
+
+    fn is_stale(price: &TokenPrice) -> bool {
+        Utc::now()
+            .signed_duration_since(price.last_updated)
+            .num_seconds()
+            > API_PRICE_EXPIRATION_TIME_SECS
+    }
+}
+
+/// Calculates the median of the prices. Prices are expected to be non-empty.
+fn median(prices: &[Ratio<BigUint>]) -> Ratio<BigUint> {
+    let mut prices = prices.to_vec();
+    prices.sort();
+
+    let middle = prices.len() / 2;
+    if prices.len() % 2 == 0 {
+        (prices[middle - 1].clone() + prices[middle].clone())
+            / Ratio::from_integer(BigUint::from(2u32))
+    } else {
+        prices[middle].clone()
+    }
+}
+
+/// Checks whether the price deviates from the median by more than `MAX_PRICE_DEVIATION_PERCENT`.
+fn is_outlier(price: &Ratio<BigUint>, median: &Ratio<BigUint>) -> bool {
+    let deviation = if price > median {
+        price - median
+    } else {
+        median - price
+    };
+
+    deviation * Ratio::from_integer(BigUint::from(100u32))
+        > median.clone() * Ratio::from_integer(BigUint::from(MAX_PRICE_DEVIATION_PERCENT))
+}
+
+#[async_trait]
+impl TokenPriceAPI for MedianPriceAPI {
+    async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, failure::Error> {
+        let responses = join_all(
+            self.sources
+                .iter()
+                .map(|(_, source)| source.get_price(token_symbol)),
+        )
+        .await;
+
+        // Collect the fresh quotes from the sources that responded.
+        let mut quotes = Vec::with_capacity(responses.len());
+        for ((name, _), response) in self.sources.iter().zip(responses) {
+            match response {
+                Ok(price) if Self::is_stale(&price) => {
+                    warn!(
+                        "Price source {} returned stale price of {} (last updated: {})",
+                        name, token_symbol, price.last_updated
+                    );
+                    self.update_health(name, |health| health.discarded_quotes += 1);
+                }
+                Ok(price) => quotes.push((name, price)),
+                Err(e) => {
+                    warn!(
+                        "Price source {} failed to provide price of {}: {}",
+                        name, token_symbol, e
+                    );
+                    self.update_health(name, |health| health.failed_requests += 1);
+                }
+            }
+        }
+
+        if quotes.len() < self.min_quorum {
+            failure::bail!(
+                "Only {} price sources provided a fresh price of {}, at least {} are required",
+                quotes.len(),
+                token_symbol,
+                self.min_quorum
+            );
+        }
+
+        if let [(first_name, first_price), (second_name, second_price)] = quotes.as_slice() {
+            // Deviation of the higher price is checked against the lower one, since the
+            // median of two prices is their average and tolerates twice the deviation.
+            let (low, high) = if first_price.usd_price <= second_price.usd_price {
+                (&first_price.usd_price, &second_price.usd_price)
+            } else {
+                (&second_price.usd_price, &first_price.usd_price)
+            };
+            if is_outlier(high, low) {
+                // There is no way to know which one is wrong, so the disagreement
+                // is not counted in the health of the sources.
+                failure::bail!(
+                    "Price sources {} and {} disagree on the price of {} by more than {}%",
+                    first_name,
+                    second_name,
+                    token_symbol,
+                    MAX_PRICE_DEVIATION_PERCENT
+                );
+            }
+        }
+
+        // Discard the quotes that deviate too much from the median of all the quotes.
+        let prices: Vec<_> = quotes
+            .iter()
+            .map(|(_, price)| price.usd_price.clone())
+            .collect();
+        let initial_median = median(&prices);
+
+        let mut accepted_quotes = Vec::with_capacity(quotes.len());
+        for (name, price) in quotes {
+            if is_outlier(&price.usd_price, &initial_median) {
+                warn!(
+                    "Price source {} returned price of {} deviating from the median by more than {}%",
+                    name, token_symbol, MAX_PRICE_DEVIATION_PERCENT
+                );
+                self.update_health(name, |health| health.discarded_quotes += 1);
+            } else {
+                accepted_quotes.push((name, price));
+            }
+        }
+
+        // All the quotes but a few may be discarded if the sources disagree with each other.
+        if accepted_quotes.len() < self.min_quorum {
+            failure::bail!(
+                "Only {} price sources agree on the price of {}, at least {} are required",
+                accepted_quotes.len(),
+                token_symbol,
+                self.min_quorum
+            );
+        }
+
+        for (name, _) in &accepted_quotes {
+            self.update_health(name, |health| {
+                health.accepted_quotes += 1;
+                health.last_accepted = Some(Utc::now());
+            });
+        }
+
+        let last_updated = accepted_quotes
+            .iter()
+            .map(|(_, price)| price.last_updated)
+            .min()
+            .expect("Quorum is not empty");
+        let prices: Vec<_> = accepted_quotes
+            .into_iter()
+            .map(|(_, price)| price.usd_price)
+            .collect();
+
+        Ok(TokenPrice {
+            usd_price: median(&prices),
+            last_updated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use failure::format_err;
+    use futures::executor::block_on;
+    use models::config_options::DEFAULT_TOKEN_PRICE_MIN_QUORUM;
+
+    /// Price source returning the predefined response.
+    struct FixedPriceAPI(Option<TokenPrice>);
+
+    #[async_trait]
+    impl TokenPriceAPI for FixedPriceAPI {
+        async fn get_price(&self, _token_symbol: &str) -> Result<TokenPrice, failure::Error> {
+            self.0
+                .clone()
+                .ok_or_else(|| format_err!("Source is not available"))
+        }
+    }
+
+    fn price(usd_price: u32, last_updated: DateTime<Utc>) -> Option<TokenPrice> {
+        Some(TokenPrice {
+            usd_price: Ratio::from_integer(usd_price.into()),
+            last_updated,
+        })
+    }
+
+    fn median_api(responses: Vec<(&str, Option<TokenPrice>)>) -> MedianPriceAPI {
+        median_api_with_quorum(responses, 1)
+    }
+
+    fn median_api_with_quorum(
+        responses: Vec<(&str, Option<TokenPrice>)>,
+        min_quorum: usize,
+    ) -> MedianPriceAPI {
+        let sources = responses
+            .into_iter()
+            .map(|(name, response)| {
+                let source: Box<dyn TokenPriceAPI + Send + Sync> =
+                    Box::new(FixedPriceAPI(response));
+                (name.to_string(), source)
+            })
+            .collect();
+        MedianPriceAPI::new(sources, min_quorum, PriceSourcesHealth::default())
+    }
+
+    #[test]
+    fn median_calculation() {
+        let prices = |values: &[u32]| -> Vec<Ratio<BigUint>> {
+            values
+                .iter()
+                .map(|&value| Ratio::from_integer(value.into()))
+                .collect()
+        };
+
+        assert_eq!(median(&prices(&[3])), Ratio::from_integer(3u32.into()));
+        assert_eq!(
+            median(&prices(&[5, 1, 3])),
+            Ratio::from_integer(3u32.into())
+        );
+        assert_eq!(
+            median(&prices(&[4, 1, 3, 2])),
+            Ratio::new(5u32.into(), 2u32.into())
+        );
+    }
+
+    /// Checks that failed sources, stale quotes and outliers are not used to calculate
+    /// the price, and that it's reflected in the sources health.
+    #[test]
+    fn discard_bad_quotes() {
+        let now = Utc::now();
+        let stale = now - Duration::seconds(API_PRICE_EXPIRATION_TIME_SECS + 1);
+        let api = median_api(vec![
+            ("first", price(100, now)),
+            ("second", price(104, now - Duration::seconds(10))),
+            ("third", price(102, now)),
+            ("outlier", price(1000, now)),
+            ("stale", price(50, stale)),
+            ("failed", None),
+        ]);
+
+        let token_price = block_on(api.get_price("ETH")).expect("Price should be provided");
+        assert_eq!(token_price.usd_price, Ratio::from_integer(102u32.into()));
+        assert_eq!(token_price.last_updated, now - Duration::seconds(10));
+
+        let health = api.health.snapshot();
+        for source in &["first", "second", "third"] {
+            assert_eq!(health[*source].accepted_quotes, 1);
+        }
+        assert_eq!(health["outlier"].discarded_quotes, 1);
+        assert_eq!(health["stale"].discarded_quotes, 1);
+        assert_eq!(health["failed"].failed_requests, 1);
+        assert_eq!(health["failed"].last_accepted, None);
+    }
+
+    /// Checks that no price is provided if sources are not available or disagree.
+    #[test]
+    fn no_reliable_price() {
+        let api = median_api(vec![("first", None), ("second", None)]);
+        block_on(api.get_price("ETH")).expect_err("Price without sources");
+
+        let now = Utc::now();
+        let api = median_api(vec![
+            ("first", price(100, now)),
+            ("second", price(200, now)),
+        ]);
+        block_on(api.get_price("ETH")).expect_err("Price from disagreeing sources");
+    }
+
+    /// Checks that two sources are used only if they agree with each other,
+    /// and are not considered outliers otherwise.
+    #[test]
+    fn two_sources() {
+        let now = Utc::now();
+        let api = median_api(vec![
+            ("first", price(100, now)),
+            ("second", price(110, now)),
+            ("failed", None),
+        ]);
+        let token_price = block_on(api.get_price("ETH")).expect("Price should be provided");
+        assert_eq!(token_price.usd_price, Ratio::from_integer(105u32.into()));
+
+        // Prices would be within 10% of their average, but not of each other.
+        let api = median_api(vec![
+            ("first", price(100, now)),
+            ("second", price(115, now)),
+        ]);
+        block_on(api.get_price("ETH")).expect_err("Price from disagreeing sources");
+
+        let health = api.health.snapshot();
+        for source in &["first", "second"] {
+            assert_eq!(health[*source], SourceHealth::default());
+        }
+    }
+
+    /// Checks that the price is not provided by the single source that survived
+    /// when the quorum of two sources is required.
+    #[test]
+    fn single_survivor_below_quorum() {
+        let now = Utc::now();
+        let stale = now - Duration::seconds(API_PRICE_EXPIRATION_TIME_SECS + 1);
+        let api = median_api_with_quorum(
+            vec![
+                ("survivor", price(100, now)),
+                ("stale", price(100, stale)),
+                ("failed", None),
+            ],
+            DEFAULT_TOKEN_PRICE_MIN_QUORUM,
+        );
+        block_on(api.get_price("ETH")).expect_err("Price from the single source");
+
+        let health = api.health.snapshot();
+        assert_eq!(health["survivor"].accepted_quotes, 0);
+
+        // The only quote agreeing with the median is not enough either.
+        let api = median_api_with_quorum(
+            vec![
+                ("first", price(100, now)),
+                ("second", price(300, now)),
+                ("third", price(1000, now)),
+            ],
+            DEFAULT_TOKEN_PRICE_MIN_QUORUM,
+        );
+        block_on(api.get_price("ETH")).expect_err("Price agreed by the single source");
+
+        // Two agreeing sources satisfy the quorum.
+        let api = median_api_with_quorum(
+            vec![
+                ("first", price(100, now)),
+                ("second", price(102, now)),
+                ("failed", None),
+            ],
+            DEFAULT_TOKEN_PRICE_MIN_QUORUM,
+        );
+        let token_price = block_on(api.get_price("ETH")).expect("Price should be provided");
+        assert_eq!(token_price.usd_price, Ratio::from_integer(101u32.into()));
+    }
+}
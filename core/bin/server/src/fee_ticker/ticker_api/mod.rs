@@ -7,7 +7,7 @@ use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
-use models::node::{Token, TokenId, TokenLike, TokenPrice};
+use models::node::{Token, TokenFeeSettings, TokenId, TokenLike, TokenPrice};
 use num::rational::Ratio;
 use num::BigUint;
 use std::collections::HashMap;
@@ -17,6 +17,7 @@ use tokio::sync::Mutex;
 
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod median;
 
 const API_PRICE_EXPIRATION_TIME_SECS: i64 = 300; // 5 mins
 const HISTORICAL_PRICE_EXPIRATION_TIME: Duration = Duration::from_secs(60);
@@ -37,10 +38,16 @@ pub trait FeeTickerAPI {
     /// Get last price from ticker
     async fn get_last_quote(&self, token: TokenLike) -> Result<TokenPrice, failure::Error>;
 
-    /// Get current gas price in ETH
+    /// Get current gas price in ETH.
+    /// For EIP-1559 transactions it's the effective price (base fee + priority fee).
     async fn get_gas_price_wei(&self) -> Result<BigUint, failure::Error>;
 
     async fn get_token(&self, token: TokenLike) -> Result<Token, failure::Error>;
+
+    /// Get fee settings of the tokens accepted for paying fees.
+    async fn get_tokens_fee_settings(
+        &self,
+    ) -> Result<HashMap<TokenId, TokenFeeSettings>, failure::Error>;
 }
 
 #[derive(Debug, Clone)]
@@ -259,4 +266,20 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
             .await?
             .ok_or_else(|| format_err!("Token not found: {:?}", token))
     }
+
+    async fn get_tokens_fee_settings(
+        &self,
+    ) -> Result<HashMap<TokenId, TokenFeeSettings>, failure::Error> {
+        let mut storage = self
+            .db_pool
+            .access_storage_fragile()
+            .await
+            .map_err(|e| format_err!("Can't access storage: {}", e))?;
+
+        storage
+            .tokens_schema()
+            .load_tokens_fee_settings()
+            .await
+            .map_err(|e| format_err!("Can't load tokens fee settings from storage: {}", e))
+    }
 }
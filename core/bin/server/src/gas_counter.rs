@@ -13,6 +13,11 @@ use models::node::{config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, FranklinOp};
 /// but at the same time it should not exceed the block gas limit.
 pub const TX_GAS_LIMIT: u64 = 4_000_000;
 
+/// Amount of gas that we can afford to spend in one transaction committing (or verifying)
+/// several blocks at once. Note that the limit of the transaction that ran out of gas
+/// is increased by `eth_sender`, and the increased value must still fit into the block.
+pub const AGGREGATED_TX_GAS_LIMIT: u64 = 6_000_000;
+
 #[derive(Debug)]
 pub struct CommitCost;
 
@@ -30,11 +35,20 @@ impl CommitCost {
     pub const TRANSFER_TO_NEW_COST: u64 = 862;
     pub const FULL_EXIT_COST: u64 = 10_165;
     pub const WITHDRAW_COST: u64 = 2_167;
+    pub const FORCED_EXIT_COST: u64 = 2_215;
+    pub const SWAP_COST: u64 = 730;
+    /// Cost of every block except the first one in the `commitMultipleBlocks` contract call
+    /// (used instead of the `BASE_COST`, which is paid once per call).
+    pub const ADDITIONAL_BLOCK_COST: u64 = 100_000;
 
     pub fn base_cost() -> U256 {
         U256::from(Self::BASE_COST)
     }
 
+    pub fn additional_block_cost() -> U256 {
+        U256::from(Self::ADDITIONAL_BLOCK_COST)
+    }
+
     pub fn op_cost(op: &FranklinOp) -> U256 {
         let cost = match op {
             FranklinOp::Noop(_) => 0,
@@ -44,6 +58,8 @@ impl CommitCost {
             FranklinOp::TransferToNew(_) => Self::TRANSFER_TO_NEW_COST,
             FranklinOp::FullExit(_) => Self::FULL_EXIT_COST,
             FranklinOp::Withdraw(_) => Self::WITHDRAW_COST,
+            FranklinOp::ForcedExit(_) => Self::FORCED_EXIT_COST,
+            FranklinOp::Swap(_) => Self::SWAP_COST,
             FranklinOp::Close(_) => unreachable!("Close operations are disabled"),
         };
 
@@ -68,11 +84,23 @@ impl VerifyCost {
     pub const TRANSFER_TO_NEW_COST: u64 = 0;
     pub const FULL_EXIT_COST: u64 = 2_499;
     pub const WITHDRAW_COST: u64 = 45_668;
+    pub const FORCED_EXIT_COST: u64 = 45_668;
+    pub const SWAP_COST: u64 = 0;
+    /// Cost of every block except the first one in the `verifyMultipleBlocks` contract call
+    /// (used instead of the `BASE_COST`, which is paid once per call).
+    /// Proof of every block is still verified separately, so most of the base cost remains.
+    /// Note that `gas_price_test` uses the dummy verifier, so the proof verification cost
+    /// is not included into its estimation and has to be added on top of it.
+    pub const ADDITIONAL_BLOCK_COST: u64 = 700_000;
 
     pub fn base_cost() -> U256 {
         U256::from(Self::BASE_COST)
     }
 
+    pub fn additional_block_cost() -> U256 {
+        U256::from(Self::ADDITIONAL_BLOCK_COST)
+    }
+
     pub fn op_cost(op: &FranklinOp) -> U256 {
         let cost = match op {
             FranklinOp::Noop(_) => 0,
@@ -82,6 +110,8 @@ impl VerifyCost {
             FranklinOp::TransferToNew(_) => Self::TRANSFER_TO_NEW_COST,
             FranklinOp::FullExit(_) => Self::FULL_EXIT_COST,
             FranklinOp::Withdraw(_) => Self::WITHDRAW_COST,
+            FranklinOp::ForcedExit(_) => Self::FORCED_EXIT_COST,
+            FranklinOp::Swap(_) => Self::SWAP_COST,
             FranklinOp::Close(_) => unreachable!("Close operations are disabled"),
         };
 
@@ -153,6 +183,42 @@ impl GasCounter {
         self.verify_cost * U256::from(130) / U256::from(100)
     }
 
+    /// Calculates the gas limit for the `commitMultipleBlocks` call given the commit
+    /// gas limits of the blocks (as calculated by `commit_gas_limit`).
+    pub fn aggregated_commit_gas_limit(block_gas_limits: &[U256]) -> U256 {
+        Self::aggregated_gas_limit(
+            block_gas_limits,
+            CommitCost::base_cost(),
+            CommitCost::additional_block_cost(),
+        )
+    }
+
+    /// Calculates the gas limit for the `verifyMultipleBlocks` call given the verify
+    /// gas limits of the blocks (as calculated by `verify_gas_limit`).
+    pub fn aggregated_verify_gas_limit(block_gas_limits: &[U256]) -> U256 {
+        Self::aggregated_gas_limit(
+            block_gas_limits,
+            VerifyCost::base_cost(),
+            VerifyCost::additional_block_cost(),
+        )
+    }
+
+    /// Base cost is paid only once per aggregated call, so for every block except the
+    /// first one it's replaced with the (lower) additional block cost.
+    /// For a single block the gas limit of the block is returned as is.
+    fn aggregated_gas_limit(
+        block_gas_limits: &[U256],
+        base_cost: U256,
+        additional_block_cost: U256,
+    ) -> U256 {
+        let mut limits = block_gas_limits.iter();
+        let first_block_limit = limits.next().copied().unwrap_or_default();
+
+        limits.fold(first_block_limit, |total, &block_limit| {
+            total + block_limit - Self::scale_up(base_cost) + Self::scale_up(additional_block_cost)
+        })
+    }
+
     pub fn complete_withdrawals_gas_limit() -> U256 {
         // Currently we always complete a constant amount of withdrawals in the contract call, so the upper limit
         // is predictable.
@@ -183,6 +249,7 @@ mod tests {
                 account: Default::default(),
                 new_pk_hash: Default::default(),
                 nonce: Default::default(),
+                time_range: Default::default(),
                 eth_signature: None,
             },
             account_id: 1,
@@ -208,6 +275,7 @@ mod tests {
                 account: Default::default(),
                 new_pk_hash: Default::default(),
                 nonce: Default::default(),
+                time_range: Default::default(),
                 eth_signature: None,
             },
             account_id: 1,
@@ -233,6 +301,7 @@ mod tests {
                 account: Default::default(),
                 new_pk_hash: Default::default(),
                 nonce: Default::default(),
+                time_range: Default::default(),
                 eth_signature: None,
             },
             account_id: 1,
@@ -276,4 +345,39 @@ mod tests {
         assert_eq!(gas_counter.commit_gas_limit(), expected_commit_limit);
         assert_eq!(gas_counter.verify_gas_limit(), expected_verify_limit);
     }
+
+    #[test]
+    fn aggregated_gas_limit() {
+        let empty_block = GasCounter::new();
+        let commit_limit = empty_block.commit_gas_limit();
+        let verify_limit = empty_block.verify_gas_limit();
+
+        // Single block limit should not be changed.
+        assert_eq!(
+            GasCounter::aggregated_commit_gas_limit(&[commit_limit]),
+            commit_limit
+        );
+        assert_eq!(
+            GasCounter::aggregated_verify_gas_limit(&[verify_limit]),
+            verify_limit
+        );
+
+        // Base cost should be paid only once for several blocks.
+        let expected_commit_limit = GasCounter::scale_up(
+            U256::from(CommitCost::BASE_COST)
+                + U256::from(CommitCost::ADDITIONAL_BLOCK_COST) * U256::from(2),
+        );
+        let expected_verify_limit = GasCounter::scale_up(
+            U256::from(VerifyCost::BASE_COST)
+                + U256::from(VerifyCost::ADDITIONAL_BLOCK_COST) * U256::from(2),
+        );
+        assert_eq!(
+            GasCounter::aggregated_commit_gas_limit(&[commit_limit; 3]),
+            expected_commit_limit
+        );
+        assert_eq!(
+            GasCounter::aggregated_verify_gas_limit(&[verify_limit; 3]),
+            expected_verify_limit
+        );
+    }
 }
@@ -11,7 +11,7 @@ use models::{
     config_options::{AdminServerOptions, ConfigurationOptions, ProverOptions},
     node::{
         config::OBSERVER_MODE_PULL_INTERVAL,
-        tokens::{get_genesis_token_list, Token},
+        tokens::{get_genesis_token_list, Token, TokenFeeSettings},
         TokenId,
     },
 };
@@ -24,7 +24,7 @@ use server::{
     committer::run_committer,
     eth_sender,
     eth_watch::start_eth_watch,
-    fee_ticker::run_ticker_task,
+    fee_ticker::{run_ticker_task, PriceSourcesHealth},
     leader_election,
     mempool::run_mempool_task,
     observer_mode,
@@ -75,9 +75,8 @@ fn main() {
                     token.address,
                     token.decimals
                 );
-                pool.access_storage()
-                    .await
-                    .expect("failed to access db")
+                let mut storage = pool.access_storage().await.expect("failed to access db");
+                storage
                     .tokens_schema()
                     .store_token(Token {
                         id: id as TokenId,
@@ -89,6 +88,12 @@ fn main() {
                     })
                     .await
                     .expect("failed to store token");
+                // Genesis tokens are accepted for paying fees.
+                storage
+                    .tokens_schema()
+                    .store_token_fee_settings(id as TokenId, TokenFeeSettings::risk_free())
+                    .await
+                    .expect("failed to store token fee settings");
             }
             return;
         }
@@ -167,6 +172,7 @@ fn main() {
         let (state_keeper_req_sender, state_keeper_req_receiver) = mpsc::channel(channel_size);
         let (executed_tx_notify_sender, executed_tx_notify_receiver) = mpsc::channel(channel_size);
         let (mempool_request_sender, mempool_request_receiver) = mpsc::channel(channel_size);
+        let (replaced_tx_notify_sender, replaced_tx_notify_receiver) = mpsc::channel(channel_size);
         let (ticker_request_sender, ticker_request_receiver) = mpsc::channel(channel_size);
 
         // Load the most recent pending block from the database.
@@ -210,9 +216,10 @@ fn main() {
             stop_signal_sender.clone(),
             mempool_request_sender.clone(),
             executed_tx_notify_receiver,
+            replaced_tx_notify_receiver,
             state_keeper_req_sender.clone(),
             eth_watch_req_sender.clone(),
-            ticker_request_sender,
+            ticker_request_sender.clone(),
             config_opts.clone(),
             admin_server_opts,
             current_zksync_info,
@@ -224,6 +231,7 @@ fn main() {
             prover_options.gone_timeout,
             prover_options.prepare_data_interval,
             stop_signal_sender,
+            eth_watch_req_sender.clone(),
             config_opts.clone(),
         );
 
@@ -231,6 +239,8 @@ fn main() {
             connection_pool.clone(),
             mempool_request_receiver,
             eth_watch_req_sender,
+            ticker_request_sender,
+            replaced_tx_notify_sender,
             &config_opts,
         );
         let proposer_task = run_block_proposer_task(
@@ -239,16 +249,21 @@ fn main() {
             state_keeper_req_sender.clone(),
         );
 
+        let price_sources_health = PriceSourcesHealth::default();
         let ticker_task = run_ticker_task(
             config_opts.token_price_source.clone(),
+            price_sources_health.clone(),
             config_opts.ticker_fast_processing_coeff,
+            config_opts.ticker_zkp_cost_chunk_usd.clone(),
             connection_pool.clone(),
             eth_send_request_sender,
             state_keeper_req_sender,
             ticker_request_receiver,
-        );
+        )
+        .expect("Failed to start the fee ticker");
 
-        let prometheus_exporter = start_prometheus_exporter(connection_pool.clone(), &config_opts);
+        let prometheus_exporter =
+            start_prometheus_exporter(connection_pool.clone(), price_sources_health, &config_opts);
 
         let task_futures = vec![
             eth_watch_task,
@@ -4,36 +4,57 @@
 //! 1) Accept transactions from api, check signatures and basic nonce correctness(nonce not too small).
 //! To do nonce correctness check mempool stores mapping `AccountAddress -> Nonce`, this mapping is updated
 //! when new block is committed.
-//! 2) When polled return vector of the transactions in the queue.
+//! 2) Keep transactions in the order they can be executed: a transaction is queued for execution only
+//! when its nonce immediately follows the nonce of the previously queued transaction of the same account
+//! (or the committed nonce of the account). Transactions with a nonce gap are held back in the per-account
+//! queues ordered by nonce until their predecessor is received or executed. Nonces of the transactions
+//! taken for the execution stay reserved until the block with these transactions is committed.
+//! 3) When polled return vector of the transactions in the queue.
 //!
-//! Mempool is not persisted on disc, all transactions will be lost on node shutdown.
+//! Mempool is persisted in the `mempool_txs` table, so transactions are not lost on node shutdown.
 //!
 //! Communication channel with other actors:
 //! Mempool does not push information to other actors, only accepts requests. (see `MempoolRequest`)
 //!
 //! Communication with db:
-//! on restart mempool restores nonces of the accounts that are stored in the account tree.
+//! on restart mempool restores nonces of the accounts that are stored in the account tree along with
+//! the transactions of the pending block, and then replays the stored transactions to restore the
+//! queues. Held back transactions that can not be executed anymore (because their nonce was already
+//! used or they are expired) are evicted from both the queues and the database when a new block
+//! is committed.
 
 // Built-in deps
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 // External uses
 use failure::Fail;
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt, StreamExt,
 };
+use num::{rational::Ratio, BigUint};
 use tokio::task::JoinHandle;
 // Workspace uses
 use models::node::{
     mempool::{SignedTxVariant, SignedTxsBatch},
+    tx::TxHash,
     AccountId, AccountUpdate, AccountUpdates, Address, FranklinTx, Nonce, PriorityOp,
-    SignedFranklinTx, TransferOp, TransferToNewOp,
+    SignedFranklinTx, TokenId, TokenLike, TransferOp, TransferToNewOp,
 };
+use models::primitives::big_decimal_to_ratio;
 use storage::ConnectionPool;
 // Local uses
-use crate::{eth_watch::EthWatchRequest, signature_checker::VerifiedTx};
+use crate::{
+    eth_watch::EthWatchRequest,
+    fee_ticker::{TickerRequest, TokenPriceRequestType},
+    signature_checker::VerifiedTx,
+};
 use models::config_options::ConfigurationOptions;
 
+/// Period during which the token price obtained from the ticker is used to rank the transactions.
+const TOKEN_PRICE_CACHE_LIFETIME: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Fail)]
 pub enum TxAddError {
     #[fail(display = "Tx nonce is too low.")]
@@ -48,6 +69,9 @@ pub enum TxAddError {
     #[fail(display = "Transactions batch summary fee is too low")]
     TxBatchFeeTooLow,
 
+    #[fail(display = "Transaction is expired")]
+    TxExpired,
+
     #[fail(display = "EIP1271 signature could not be verified")]
     EIP1271SignatureVerificationFail,
 
@@ -66,6 +90,9 @@ pub enum TxAddError {
     #[fail(display = "Database unavailable")]
     DbError,
 
+    #[fail(display = "Fee of the replacement transaction is too low")]
+    ReplacementFeeTooLow,
+
     #[fail(display = "Batch will not fit in any of supported block sizes")]
     BatchTooBig,
 
@@ -85,6 +112,13 @@ impl ProposedBlock {
     }
 }
 
+/// Notification about a queued transaction replaced by a new transaction with the same nonce.
+#[derive(Debug, Clone)]
+pub struct TxReplacedNotify {
+    pub replaced_tx: TxHash,
+    pub new_tx: TxHash,
+}
+
 pub struct GetBlockRequest {
     pub last_priority_op_number: u64,
     pub response_sender: oneshot::Sender<ProposedBlock>,
@@ -101,7 +135,9 @@ pub enum MempoolRequest {
     /// `NewTx` variant of this enum.
     NewTxsBatch(Vec<VerifiedTx>, oneshot::Sender<Result<(), TxAddError>>),
     /// When block is committed, nonces of the account tree should be updated too.
-    UpdateNonces(AccountUpdates),
+    /// Hashes of the transactions included into the block (either executed or failed)
+    /// are sent along with the updates.
+    UpdateNonces(AccountUpdates, Vec<TxHash>),
     /// Get transactions from the mempool.
     GetBlock(GetBlockRequest),
 }
@@ -110,10 +146,41 @@ struct MempoolState {
     // account and last committed nonce
     account_nonces: HashMap<Address, Nonce>,
     account_ids: HashMap<AccountId, Address>,
+    /// Transactions that can be executed in the order of the queue.
     ready_txs: VecDeque<SignedTxVariant>,
+    /// Transactions taken for the execution, which are not included into a committed block yet
+    /// (e.g. the transactions of the pending block).
+    executing_txs: Vec<SignedFranklinTx>,
+    /// Nonce expected for the next transaction of the account, taking the `executing_txs`
+    /// and the `ready_txs` queue into account.
+    /// Accounts without queued transactions are not stored here.
+    queued_nonces: HashMap<Address, Nonce>,
+    /// Transactions held back because of the nonce gap, grouped by the charged account and ordered by nonce.
+    pending_txs: HashMap<Address, BTreeMap<Nonce, SignedFranklinTx>>,
+    /// Batches held back because of the nonce gap in at least one of their transactions.
+    pending_batches: Vec<SignedTxsBatch>,
+    /// Minimal fee increase (in percent) required to replace a queued transaction.
+    replacement_fee_increase: u64,
 }
 
 impl MempoolState {
+    fn new(
+        account_nonces: HashMap<Address, Nonce>,
+        account_ids: HashMap<AccountId, Address>,
+        replacement_fee_increase: u64,
+    ) -> Self {
+        Self {
+            account_nonces,
+            account_ids,
+            ready_txs: VecDeque::new(),
+            executing_txs: Vec::new(),
+            queued_nonces: HashMap::new(),
+            pending_txs: HashMap::new(),
+            pending_batches: Vec::new(),
+            replacement_fee_increase,
+        }
+    }
+
     fn chunks_for_tx(&self, tx: &FranklinTx) -> usize {
         match tx {
             FranklinTx::Transfer(tx) => {
@@ -138,20 +205,41 @@ impl MempoolState {
         }
     }
 
-    async fn restore_from_db(db_pool: &ConnectionPool) -> Self {
+    async fn restore_from_db(db_pool: &ConnectionPool, replacement_fee_increase: u64) -> Self {
         let mut storage = db_pool.access_storage().await.expect("mempool db restore");
         let mut transaction = storage
             .start_transaction()
             .await
             .expect("mempool db transaction");
 
-        let (_, accounts) = transaction
+        let (last_committed_block, accounts) = transaction
             .chain()
             .state_schema()
             .load_committed_state(None)
             .await
             .expect("mempool account state load");
 
+        // Transactions of the pending block are not committed yet,
+        // but their nonces are already taken.
+        let pending_block = transaction
+            .chain()
+            .block_schema()
+            .load_pending_block()
+            .await
+            .expect("mempool pending block load")
+            .filter(|pending_block| pending_block.number > last_committed_block);
+        let executing_txs: Vec<_> = pending_block
+            .map(|pending_block| {
+                pending_block
+                    .success_operations
+                    .iter()
+                    .filter_map(|op| op.get_executed_tx())
+                    .chain(pending_block.failed_txs.iter())
+                    .map(|tx| tx.signed_tx.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let mut account_ids = HashMap::new();
         let mut account_nonces = HashMap::new();
 
@@ -171,12 +259,40 @@ impl MempoolState {
 
         // Load transactions that were not yet processed and are awaiting in the
         // mempool.
-        let ready_txs: VecDeque<_> = transaction
+        let stored_txs = transaction
             .chain()
             .mempool_schema()
             .load_txs()
             .await
             .expect("Attempt to restore mempool txs from DB failed");
+        let stored_txs_count = stored_txs.len();
+
+        // Transactions are loaded in the order they were received. Replaying them restores
+        // both the ready queue and the held back transactions regardless of the order,
+        // since the held back transactions are promoted once their nonce gap is filled.
+        let mut mempool_state = Self::new(account_nonces, account_ids, replacement_fee_increase);
+        mempool_state.executing_txs = executing_txs;
+        mempool_state.recalculate_queued_nonces();
+        let mut rejected_txs = Vec::new();
+        for tx in stored_txs {
+            let tx_hashes = tx.hashes();
+            let add_result = match tx {
+                SignedTxVariant::Tx(tx) => mempool_state.add_tx(tx),
+                SignedTxVariant::Batch(batch) => mempool_state.add_batch(batch).map(|_| None),
+            };
+            match add_result {
+                Ok(Some(replaced_tx)) => rejected_txs.push(replaced_tx),
+                Ok(None) => {}
+                Err(_) => rejected_txs.extend(tx_hashes),
+            }
+        }
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&rejected_txs)
+            .await
+            .expect("Removing rejected txs from the mempool schema failed");
 
         transaction
             .commit()
@@ -184,45 +300,420 @@ impl MempoolState {
             .expect("mempool db transaction commit");
 
         log::info!(
-            "{} transactions were restored from the persistent mempool storage",
-            ready_txs.len()
+            "{} transactions were restored from the persistent mempool storage, {} of them are held back",
+            stored_txs_count,
+            mempool_state.pending_txs_count()
         );
 
-        Self {
-            account_nonces,
-            account_ids,
-            ready_txs,
-        }
+        mempool_state
     }
 
     fn nonce(&self, address: &Address) -> Nonce {
         *self.account_nonces.get(address).unwrap_or(&0)
     }
 
-    fn add_tx(&mut self, tx: SignedFranklinTx) -> Result<(), TxAddError> {
+    /// Returns the address of the account that will be charged for the transaction.
+    fn charged_account(&self, tx: &FranklinTx) -> Address {
+        // `ForcedExit` is paid (and its nonce is incremented) by the initiator
+        // account rather than the target one.
+        match tx.initiator_account_id() {
+            Some(id) => self.account_ids.get(&id).copied().unwrap_or_default(),
+            None => tx.account(),
+        }
+    }
+
+    /// Returns the accounts whose nonces are consumed by the element: the charged accounts
+    /// and, for swaps, the owners of the orders.
+    fn element_accounts(&self, element: &SignedTxVariant) -> Vec<Address> {
+        let mut accounts: Vec<_> = element_txs(element)
+            .iter()
+            .flat_map(|tx| {
+                let owners = filled_orders_nonces(&tx.tx)
+                    .into_iter()
+                    .map(|(address, _)| address);
+                std::iter::once(self.charged_account(&tx.tx)).chain(owners)
+            })
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+        accounts
+    }
+
+    /// Returns the nonce that the next transaction of the account must have to be queued for execution.
+    fn queued_nonce(&self, address: &Address) -> Nonce {
+        self.queued_nonces
+            .get(address)
+            .copied()
+            .unwrap_or_else(|| self.nonce(address))
+    }
+
+    /// Returns the number of transactions held back because of the nonce gap.
+    fn pending_txs_count(&self) -> usize {
+        let batched_txs: usize = self
+            .pending_batches
+            .iter()
+            .map(|batch| batch.txs.len())
+            .sum();
+        self.pending_txs.values().map(BTreeMap::len).sum::<usize>() + batched_txs
+    }
+
+    /// Returns the position of the single transaction with the given nonce in the `ready_txs` queue.
+    fn ready_tx_position(&self, address: &Address, nonce: Nonce) -> Option<usize> {
+        self.ready_txs.iter().position(|element| match element {
+            SignedTxVariant::Tx(tx) => {
+                tx.nonce() == nonce && self.charged_account(&tx.tx) == *address
+            }
+            SignedTxVariant::Batch(_) => false,
+        })
+    }
+
+    /// Returns the queued transaction from the same account with the same nonce, if any.
+    fn queued_tx(&self, tx: &SignedFranklinTx) -> Option<&SignedFranklinTx> {
+        let address = self.charged_account(&tx.tx);
+        let nonce = tx.nonce();
+
+        if let Some(queued_tx) = self
+            .pending_txs
+            .get(&address)
+            .and_then(|account_queue| account_queue.get(&nonce))
+        {
+            return Some(queued_tx);
+        }
+        if nonce >= self.queued_nonce(&address) {
+            return None;
+        }
+
+        self.ready_tx_position(&address, nonce)
+            .and_then(|position| match &self.ready_txs[position] {
+                SignedTxVariant::Tx(queued_tx) => Some(queued_tx),
+                SignedTxVariant::Batch(_) => None,
+            })
+    }
+
+    /// Checks whether the fee of the new transaction exceeds the fee of the old one at least by
+    /// `replacement_fee_increase` percent. Fees paid in different tokens are not comparable.
+    fn is_fee_increased(&self, old_tx: &FranklinTx, new_tx: &FranklinTx) -> bool {
+        match (old_tx.get_fee_info(), new_tx.get_fee_info()) {
+            (Some((_, old_token, _, old_fee)), Some((_, new_token, _, new_fee))) => {
+                let is_same_token = matches!(
+                    (old_token, new_token),
+                    (TokenLike::Id(old_token), TokenLike::Id(new_token)) if old_token == new_token
+                );
+                let min_fee = old_fee.clone() * BigUint::from(100 + self.replacement_fee_increase);
+
+                is_same_token && new_fee > old_fee && new_fee * BigUint::from(100u32) >= min_fee
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks whether the transaction can be added to the mempool.
+    ///
+    /// Returns the hash of the queued transaction that will be replaced by the new one. A queued transaction
+    /// can be replaced by a transaction from the same account with the same nonce and the increased fee.
+    /// Transactions included into batches can not be replaced.
+    fn check_tx(&self, tx: &SignedFranklinTx) -> Result<Option<TxHash>, TxAddError> {
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
 
-        if tx.nonce() >= self.nonce(&tx.account()) {
-            self.ready_txs.push_back(tx.into());
-            Ok(())
+        if tx.time_range().is_expired(current_timestamp()) {
+            return Err(TxAddError::TxExpired);
+        }
+
+        match self.queued_tx(tx) {
+            Some(queued_tx) if self.is_fee_increased(&queued_tx.tx, &tx.tx) => {
+                Ok(Some(queued_tx.hash()))
+            }
+            Some(_) => Err(TxAddError::ReplacementFeeTooLow),
+            None if tx.nonce() < self.queued_nonce(&self.charged_account(&tx.tx)) => {
+                Err(TxAddError::NonceMismatch)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Adds the transaction to the mempool.
+    ///
+    /// Returns the hash of the replaced transaction (see `check_tx`).
+    fn add_tx(&mut self, tx: SignedFranklinTx) -> Result<Option<TxHash>, TxAddError> {
+        let replaced_tx = self.check_tx(&tx)?;
+
+        let address = self.charged_account(&tx.tx);
+        let nonce = tx.nonce();
+
+        if replaced_tx.is_some() {
+            match self.ready_tx_position(&address, nonce) {
+                Some(position) => {
+                    self.reserve_order_nonces(&tx.tx);
+                    self.ready_txs[position] = tx.into();
+                }
+                None => {
+                    self.pending_txs
+                        .entry(address)
+                        .or_default()
+                        .insert(nonce, tx);
+                }
+            }
+            return Ok(replaced_tx);
+        }
+
+        if nonce == self.queued_nonce(&address) {
+            self.queued_nonces.insert(address, nonce + 1);
+            self.push_ready(tx.into());
+            self.promote_pending_txs();
         } else {
-            Err(TxAddError::NonceMismatch)
+            self.pending_txs
+                .entry(address)
+                .or_default()
+                .insert(nonce, tx);
+        }
+
+        Ok(None)
+    }
+
+    /// Checks whether the batch can be added to the mempool.
+    fn check_batch(&self, batch: &SignedTxsBatch) -> Result<(), TxAddError> {
+        let now = current_timestamp();
+        for tx in batch.txs.iter() {
+            if tx.time_range().is_expired(now) {
+                return Err(TxAddError::TxExpired);
+            }
         }
+
+        self.batch_queued_nonces(batch).map(|_| ())
     }
 
     fn add_batch(&mut self, batch: SignedTxsBatch) -> Result<(), TxAddError> {
         assert_ne!(batch.batch_id, 0, "Batch ID was not set");
 
+        self.check_batch(&batch)?;
+        match self.batch_queued_nonces(&batch)? {
+            Some(queued_nonces) => {
+                self.queued_nonces.extend(queued_nonces);
+                self.push_ready(SignedTxVariant::Batch(batch));
+                self.promote_pending_txs();
+            }
+            None => self.pending_batches.push(batch),
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether the batch can be queued for execution.
+    ///
+    /// Returns the nonces expected for the accounts after the batch if it can be queued,
+    /// `None` if the batch has to be held back because of the nonce gap, and an error
+    /// if one of the batch nonces is already taken.
+    fn batch_queued_nonces(
+        &self,
+        batch: &SignedTxsBatch,
+    ) -> Result<Option<HashMap<Address, Nonce>>, TxAddError> {
+        let mut queued_nonces = HashMap::new();
         for tx in batch.txs.iter() {
-            if tx.nonce() < self.nonce(&tx.account()) {
+            let address = self.charged_account(&tx.tx);
+            let queued_nonce = queued_nonces
+                .get(&address)
+                .copied()
+                .unwrap_or_else(|| self.queued_nonce(&address));
+
+            if tx.nonce() < queued_nonce {
                 return Err(TxAddError::NonceMismatch);
             }
+            if tx.nonce() > queued_nonce {
+                return Ok(None);
+            }
+            queued_nonces.insert(address, queued_nonce + 1);
         }
 
-        self.ready_txs.push_back(SignedTxVariant::Batch(batch));
+        Ok(Some(queued_nonces))
+    }
 
-        Ok(())
+    /// Queues the element for execution.
+    fn push_ready(&mut self, element: SignedTxVariant) {
+        for tx in element_txs(&element) {
+            self.reserve_order_nonces(&tx.tx);
+        }
+        self.ready_txs.push_back(element);
+    }
+
+    /// Swap consumes the nonces of the filled orders owners along with the submitter nonce,
+    /// so these nonces can't be taken by the next transactions of the owners.
+    fn reserve_order_nonces(&mut self, tx: &FranklinTx) {
+        for (address, nonce) in filled_orders_nonces(tx) {
+            let queued_nonce = std::cmp::max(self.queued_nonce(&address), nonce + 1);
+            self.queued_nonces.insert(address, queued_nonce);
+        }
+    }
+
+    /// Moves the held back transactions and batches that have no nonce gap anymore
+    /// to the `ready_txs` queue.
+    fn promote_pending_txs(&mut self) {
+        loop {
+            let mut promoted = false;
+
+            let addresses: Vec<_> = self.pending_txs.keys().copied().collect();
+            for address in addresses {
+                let mut queued_nonce = self.queued_nonce(&address);
+                let account_queue = self
+                    .pending_txs
+                    .get_mut(&address)
+                    .expect("account queue must exist");
+                let mut promoted_txs = Vec::new();
+                while let Some(tx) = account_queue.remove(&queued_nonce) {
+                    promoted_txs.push(tx);
+                    queued_nonce += 1;
+                }
+                if account_queue.is_empty() {
+                    self.pending_txs.remove(&address);
+                }
+                self.queued_nonces.insert(address, queued_nonce);
+                for tx in promoted_txs {
+                    self.push_ready(tx.into());
+                    promoted = true;
+                }
+            }
+
+            let pending_batches = std::mem::take(&mut self.pending_batches);
+            for batch in pending_batches {
+                match self.batch_queued_nonces(&batch) {
+                    Ok(Some(queued_nonces)) => {
+                        self.queued_nonces.extend(queued_nonces);
+                        self.push_ready(SignedTxVariant::Batch(batch));
+                        promoted = true;
+                    }
+                    // Batches with the already taken nonces are kept until eviction,
+                    // so they are removed from the database along with other stale txs.
+                    _ => self.pending_batches.push(batch),
+                }
+            }
+
+            if !promoted {
+                break;
+            }
+        }
+    }
+
+    /// Applies the account updates of the committed block.
+    ///
+    /// Returns hashes of the evicted transactions, which should be removed from the database.
+    fn update_nonces(&mut self, updates: AccountUpdates, committed_txs: &[TxHash]) -> Vec<TxHash> {
+        for (id, update) in updates {
+            match update {
+                AccountUpdate::Create { address, nonce } => {
+                    self.account_ids.insert(id, address);
+                    self.account_nonces.insert(address, nonce);
+                }
+                AccountUpdate::Delete { address, .. } => {
+                    self.account_ids.remove(&id);
+                    self.account_nonces.remove(&address);
+                }
+                AccountUpdate::UpdateBalance { new_nonce, .. } => {
+                    if let Some(address) = self.account_ids.get(&id) {
+                        if let Some(nonce) = self.account_nonces.get_mut(address) {
+                            *nonce = new_nonce;
+                        }
+                    }
+                }
+                AccountUpdate::ChangePubKeyHash { new_nonce, .. } => {
+                    if let Some(address) = self.account_ids.get(&id) {
+                        if let Some(nonce) = self.account_nonces.get_mut(address) {
+                            *nonce = new_nonce;
+                        }
+                    }
+                }
+            }
+        }
+
+        let committed_txs: HashSet<_> = committed_txs.iter().collect();
+        self.executing_txs
+            .retain(|tx| !committed_txs.contains(&tx.hash()));
+        self.recalculate_queued_nonces();
+
+        let evicted_txs = self.evict_stale_txs(current_timestamp());
+        self.promote_pending_txs();
+
+        evicted_txs
+    }
+
+    /// Recalculates the expected nonces from the committed state, the transactions
+    /// which are being executed and the transactions remaining in the queue.
+    /// Nonces of the failed transactions are released once their block is committed.
+    fn recalculate_queued_nonces(&mut self) {
+        let mut queued_nonces = HashMap::new();
+        let queued_txs = self
+            .executing_txs
+            .iter()
+            .chain(self.ready_txs.iter().flat_map(element_txs));
+        for tx in queued_txs {
+            let address = self.charged_account(&tx.tx);
+            let queued_nonce = queued_nonces
+                .entry(address)
+                .or_insert_with(|| self.nonce(&address));
+            *queued_nonce = std::cmp::max(*queued_nonce, tx.nonce() + 1);
+
+            for (address, nonce) in filled_orders_nonces(&tx.tx) {
+                let queued_nonce = queued_nonces
+                    .entry(address)
+                    .or_insert_with(|| self.nonce(&address));
+                *queued_nonce = std::cmp::max(*queued_nonce, nonce + 1);
+            }
+        }
+        self.queued_nonces = queued_nonces;
+    }
+
+    /// Removes queued transactions that can not be executed anymore: either their nonce is
+    /// already used by the committed transactions (e.g. nonce of the order owner consumed by
+    /// a swap), or their time range has expired.
+    /// If one transaction of the batch is stale, the whole batch is evicted.
+    ///
+    /// Returns hashes of the evicted transactions.
+    fn evict_stale_txs(&mut self, now: u64) -> Vec<TxHash> {
+        let mut evicted_txs = Vec::new();
+
+        let ready_txs = std::mem::take(&mut self.ready_txs);
+        for element in ready_txs {
+            let is_stale = element_txs(&element)
+                .iter()
+                .any(|tx| tx.nonce() < self.nonce(&self.charged_account(&tx.tx)));
+            if is_stale {
+                evicted_txs.extend(element_txs(&element).iter().map(|tx| tx.hash()));
+            } else {
+                self.ready_txs.push_back(element);
+            }
+        }
+
+        let account_nonces = &self.account_nonces;
+        for (address, account_queue) in self.pending_txs.iter_mut() {
+            let committed_nonce = account_nonces.get(address).copied().unwrap_or(0);
+            let actual_txs = account_queue.split_off(&committed_nonce);
+            let stale_txs = std::mem::replace(account_queue, BTreeMap::new());
+            evicted_txs.extend(stale_txs.values().map(|tx| tx.hash()));
+
+            for (nonce, tx) in actual_txs {
+                if tx.time_range().is_expired(now) {
+                    evicted_txs.push(tx.hash());
+                } else {
+                    account_queue.insert(nonce, tx);
+                }
+            }
+        }
+        self.pending_txs
+            .retain(|_, account_queue| !account_queue.is_empty());
+
+        let pending_batches = std::mem::take(&mut self.pending_batches);
+        for batch in pending_batches {
+            let is_stale = batch.txs.iter().any(|tx| {
+                tx.time_range().is_expired(now)
+                    || tx.nonce() < self.nonce(&self.charged_account(&tx.tx))
+            });
+            if is_stale {
+                evicted_txs.extend(batch.txs.iter().map(|tx| tx.hash()));
+            } else {
+                self.pending_batches.push(batch);
+            }
+        }
+
+        evicted_txs
     }
 }
 
@@ -231,12 +722,19 @@ struct Mempool {
     mempool_state: MempoolState,
     requests: mpsc::Receiver<MempoolRequest>,
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    ticker_req: mpsc::Sender<TickerRequest>,
+    replaced_tx_notify: mpsc::Sender<TxReplacedNotify>,
     max_block_size_chunks: usize,
     max_number_of_withdrawals_per_block: usize,
+    /// Recently requested token prices along with the time of the request,
+    /// so the block proposal doesn't wait for the ticker every time.
+    token_prices: HashMap<TokenId, (Instant, Ratio<BigUint>)>,
 }
 
 impl Mempool {
     async fn add_tx(&mut self, tx: VerifiedTx) -> Result<(), TxAddError> {
+        let replaced_tx = self.mempool_state.check_tx(tx.inner())?;
+
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             log::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
@@ -255,13 +753,36 @@ impl Mempool {
                 log::warn!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
+        if let Some(replaced_tx) = &replaced_tx {
+            transaction
+                .chain()
+                .mempool_schema()
+                .remove_tx(replaced_tx.as_ref())
+                .await
+                .map_err(|err| {
+                    log::warn!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+        }
 
         transaction.commit().await.map_err(|err| {
             log::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
 
-        self.mempool_state.add_tx(tx.into_inner())
+        let new_tx = tx.inner().hash();
+        if let Some(replaced_tx) = self.mempool_state.add_tx(tx.into_inner())? {
+            self.replaced_tx_notify
+                .send(TxReplacedNotify {
+                    replaced_tx,
+                    new_tx,
+                })
+                .await
+                .map_err(|e| log::warn!("Failed notify about replaced tx: {}", e))
+                .unwrap_or_default();
+        }
+
+        Ok(())
     }
 
     async fn add_batch(&mut self, txs: Vec<VerifiedTx>) -> Result<(), TxAddError> {
@@ -281,13 +802,16 @@ impl Mempool {
 
         let mut number_of_withdrawals = 0;
         for tx in txs {
-            if tx.into_inner().tx.is_withdraw() {
+            let tx = tx.into_inner().tx;
+            if tx.is_withdraw() || matches!(tx, FranklinTx::ForcedExit(_)) {
                 number_of_withdrawals += 1;
             }
         }
         if number_of_withdrawals > self.max_number_of_withdrawals_per_block {
             return Err(TxAddError::BatchWithdrawalsOverload);
         }
+        // Invalid batches must be rejected before they are stored in the database.
+        self.mempool_state.check_batch(&batch)?;
 
         let mut transaction = storage.start_transaction().await.map_err(|err| {
             log::warn!("Mempool storage access error: {}", err);
@@ -334,47 +858,47 @@ impl Mempool {
                         .send(proposed_block)
                         .expect("mempool proposed block response send failed");
                 }
-                MempoolRequest::UpdateNonces(updates) => {
-                    for (id, update) in updates {
-                        match update {
-                            AccountUpdate::Create { address, nonce } => {
-                                self.mempool_state.account_ids.insert(id, address);
-                                self.mempool_state.account_nonces.insert(address, nonce);
-                            }
-                            AccountUpdate::Delete { address, .. } => {
-                                self.mempool_state.account_ids.remove(&id);
-                                self.mempool_state.account_nonces.remove(&address);
-                            }
-                            AccountUpdate::UpdateBalance { new_nonce, .. } => {
-                                if let Some(address) = self.mempool_state.account_ids.get(&id) {
-                                    if let Some(nonce) =
-                                        self.mempool_state.account_nonces.get_mut(address)
-                                    {
-                                        *nonce = new_nonce;
-                                    }
-                                }
-                            }
-                            AccountUpdate::ChangePubKeyHash { new_nonce, .. } => {
-                                if let Some(address) = self.mempool_state.account_ids.get(&id) {
-                                    if let Some(nonce) =
-                                        self.mempool_state.account_nonces.get_mut(address)
-                                    {
-                                        *nonce = new_nonce;
-                                    }
-                                }
-                            }
-                        }
-                    }
+                MempoolRequest::UpdateNonces(updates, committed_txs) => {
+                    let evicted_txs = self.mempool_state.update_nonces(updates, &committed_txs);
+                    self.remove_evicted_txs(&evicted_txs).await;
                 }
             }
         }
     }
 
+    /// Removes transactions evicted from the mempool queues from the database.
+    /// Failure is not critical: remaining transactions will be evicted again on restart.
+    async fn remove_evicted_txs(&mut self, evicted_txs: &[TxHash]) {
+        if evicted_txs.is_empty() {
+            return;
+        }
+
+        log::info!(
+            "{} stale transactions were evicted from mempool",
+            evicted_txs.len()
+        );
+
+        let mut storage = match self.db_pool.access_storage().await {
+            Ok(storage) => storage,
+            Err(err) => {
+                log::warn!("Mempool storage access error: {}", err);
+                return;
+            }
+        };
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(evicted_txs)
+            .await
+            .map_err(|err| log::warn!("Failed to remove evicted txs from mempool storage: {}", err))
+            .unwrap_or_default();
+    }
+
     async fn propose_new_block(&mut self, current_unprocessed_priority_op: u64) -> ProposedBlock {
         let (chunks_left, priority_ops) = self
             .select_priority_ops(current_unprocessed_priority_op)
             .await;
-        let (_chunks_left, txs) = self.prepare_tx_for_block(chunks_left);
+        let (_chunks_left, txs) = self.prepare_tx_for_block(chunks_left).await;
 
         trace!("Proposed priority ops for block: {:#?}", priority_ops);
         trace!("Proposed txs for block: {:#?}", txs);
@@ -409,23 +933,191 @@ impl Mempool {
         )
     }
 
-    fn prepare_tx_for_block(&mut self, mut chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
-        let mut txs_for_commit = Vec::new();
+    /// Selects transactions for the block with the highest fee per chunk first,
+    /// keeping the nonce order of every account (see `select_by_fee_priority`).
+    async fn prepare_tx_for_block(&mut self, chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
+        let elements: Vec<_> = self.mempool_state.ready_txs.drain(..).collect();
 
-        while let Some(tx) = self.mempool_state.ready_txs.pop_front() {
-            let chunks_for_tx = self.mempool_state.required_chunks(&tx);
-            if chunks_left >= chunks_for_tx {
-                txs_for_commit.push(tx);
-                chunks_left -= chunks_for_tx;
-            } else {
-                // Push the taken tx back, it does not fit.
-                self.mempool_state.ready_txs.push_front(tx);
-                break;
+        let mut candidates = Vec::with_capacity(elements.len());
+        for element in elements {
+            let mut fee_usd = Ratio::from_integer(BigUint::from(0u32));
+            for tx in element_txs(&element) {
+                if let Some((_, TokenLike::Id(token), _, fee)) = tx.get_fee_info() {
+                    fee_usd += Ratio::from_integer(fee) * self.token_price_usd(token).await;
+                }
             }
+
+            let accounts = self.mempool_state.element_accounts(&element);
+            let chunks = self.mempool_state.required_chunks(&element);
+            candidates.push(BlockCandidate {
+                fee_per_chunk: fee_usd / BigUint::from(chunks),
+                chunks,
+                accounts,
+                element,
+            });
         }
 
+        let (chunks_left, txs_for_commit, remaining_txs) =
+            select_by_fee_priority(candidates, chunks_left);
+        self.mempool_state.ready_txs = remaining_txs;
+        self.mempool_state
+            .executing_txs
+            .extend(txs_for_commit.iter().flat_map(element_txs).cloned());
+
         (chunks_left, txs_for_commit)
     }
+
+    /// Returns the price of the smallest token unit in USD.
+    /// If the price is unknown, the token is considered worthless, so the transactions
+    /// paying fee in it get the lowest priority.
+    ///
+    /// Prices are cached for `TOKEN_PRICE_CACHE_LIFETIME`, since the precise price
+    /// is not required to rank the transactions.
+    async fn token_price_usd(&mut self, token: TokenId) -> Ratio<BigUint> {
+        if let Some((requested_at, price)) = self.token_prices.get(&token) {
+            if requested_at.elapsed() < TOKEN_PRICE_CACHE_LIFETIME {
+                return price.clone();
+            }
+        }
+
+        let price = self.request_token_price_usd(token).await;
+        self.token_prices
+            .insert(token, (Instant::now(), price.clone()));
+        price
+    }
+
+    async fn request_token_price_usd(&self, token: TokenId) -> Ratio<BigUint> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.ticker_req
+            .clone()
+            .send(TickerRequest::GetTokenPrice {
+                token: TokenLike::Id(token),
+                response: response_sender,
+                req_type: TokenPriceRequestType::USDForOneWei,
+            })
+            .await
+            .expect("Ticker req receiver dropped");
+
+        let price = response_receiver
+            .await
+            .map_err(failure::Error::from)
+            .and_then(|price| price)
+            .and_then(|price| big_decimal_to_ratio(&price));
+        match price {
+            Ok(price) => price,
+            Err(err) => {
+                log::warn!("Failed to get price of the token {}: {}", token, err);
+                Ratio::from_integer(BigUint::from(0u32))
+            }
+        }
+    }
+}
+
+/// Element of the block proposal along with the data required to rank it.
+#[derive(Debug)]
+struct BlockCandidate {
+    element: SignedTxVariant,
+    /// Accounts whose nonces are consumed by the transactions of the element.
+    accounts: Vec<Address>,
+    chunks: usize,
+    /// Fee paid for the element in USD, divided by the number of its chunks.
+    fee_per_chunk: Ratio<BigUint>,
+}
+
+/// Selects the elements for the block, starting with the highest fee per chunk.
+///
+/// An element can be selected only after all the preceding elements of its accounts, so the per-account
+/// nonce order is kept. Elements that don't fit into the remaining chunks are skipped along with the
+/// following elements of the same accounts, and the smaller elements are selected instead.
+///
+/// Returns the number of chunks left, the selected elements and the elements that remain in the queue.
+fn select_by_fee_priority(
+    candidates: Vec<BlockCandidate>,
+    mut chunks_left: usize,
+) -> (usize, Vec<SignedTxVariant>, VecDeque<SignedTxVariant>) {
+    let mut account_queues: HashMap<Address, VecDeque<usize>> = HashMap::new();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        for address in &candidate.accounts {
+            account_queues.entry(*address).or_default().push_back(idx);
+        }
+    }
+
+    // Candidate is available when it is the first one in the queues of all its accounts.
+    let is_available = |idx: usize, account_queues: &HashMap<Address, VecDeque<usize>>| {
+        candidates[idx]
+            .accounts
+            .iter()
+            .all(|address| account_queues[address].front() == Some(&idx))
+    };
+
+    // Candidates with equal fee are ordered by their position in the queue.
+    let mut available = BinaryHeap::new();
+    for idx in 0..candidates.len() {
+        if is_available(idx, &account_queues) {
+            available.push((candidates[idx].fee_per_chunk.clone(), Reverse(idx)));
+        }
+    }
+
+    let mut selected = Vec::new();
+    while let Some((_, Reverse(idx))) = available.pop() {
+        if candidates[idx].chunks > chunks_left {
+            continue;
+        }
+        chunks_left -= candidates[idx].chunks;
+        selected.push(idx);
+
+        for address in &candidates[idx].accounts {
+            let account_queue = account_queues
+                .get_mut(address)
+                .expect("account queue must exist");
+            account_queue.pop_front();
+            if let Some(&next_idx) = account_queue.front() {
+                if is_available(next_idx, &account_queues) {
+                    available.push((
+                        candidates[next_idx].fee_per_chunk.clone(),
+                        Reverse(next_idx),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut elements: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| Some(candidate.element))
+        .collect();
+    let txs_for_commit = selected
+        .into_iter()
+        .map(|idx| elements[idx].take().expect("element is selected twice"))
+        .collect();
+    let remaining_txs = elements.into_iter().flatten().collect();
+
+    (chunks_left, txs_for_commit, remaining_txs)
+}
+
+/// Returns the transactions of the block element.
+fn element_txs(element: &SignedTxVariant) -> &[SignedFranklinTx] {
+    match element {
+        SignedTxVariant::Tx(tx) => std::slice::from_ref(tx),
+        SignedTxVariant::Batch(batch) => batch.txs.as_slice(),
+    }
+}
+
+/// Returns the owners and the nonces of the orders filled by the swap.
+fn filled_orders_nonces(tx: &FranklinTx) -> Vec<(Address, Nonce)> {
+    match tx {
+        FranklinTx::Swap(swap) => [&swap.orders.0, &swap.orders.1]
+            .iter()
+            .map(|order| (order.address, order.nonce))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the current UNIX timestamp (in seconds), which is used to check
+/// the time range of the transactions.
+fn current_timestamp() -> u64 {
+    chrono::Utc::now().timestamp() as u64
 }
 
 #[must_use]
@@ -433,25 +1125,461 @@ pub fn run_mempool_task(
     db_pool: ConnectionPool,
     requests: mpsc::Receiver<MempoolRequest>,
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    ticker_req: mpsc::Sender<TickerRequest>,
+    replaced_tx_notify: mpsc::Sender<TxReplacedNotify>,
     config: &ConfigurationOptions,
 ) -> JoinHandle<()> {
     let config = config.clone();
     tokio::spawn(async move {
-        let mempool_state = MempoolState::restore_from_db(&db_pool).await;
+        let mempool_state =
+            MempoolState::restore_from_db(&db_pool, config.mempool_replacement_fee_increase).await;
 
         let mempool = Mempool {
             db_pool,
             mempool_state,
             requests,
             eth_watch_req,
+            ticker_req,
+            replaced_tx_notify,
             max_block_size_chunks: *config
                 .available_block_chunk_sizes
                 .iter()
                 .max()
                 .expect("failed to find max block chunks size"),
             max_number_of_withdrawals_per_block: config.max_number_of_withdrawals_per_block,
+            token_prices: HashMap::new(),
         };
 
         mempool.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::node::{Order, Swap, TimeRange, Transfer};
+    use num::BigUint;
+
+    fn transfer(from: Address, nonce: Nonce, time_range: TimeRange) -> SignedFranklinTx {
+        transfer_with_fee(from, nonce, 100, time_range)
+    }
+
+    fn transfer_with_fee(
+        from: Address,
+        nonce: Nonce,
+        fee: u32,
+        time_range: TimeRange,
+    ) -> SignedFranklinTx {
+        let transfer = Transfer::new(
+            1,
+            from,
+            Address::repeat_byte(0xff),
+            0,
+            BigUint::from(1u32),
+            BigUint::from(fee),
+            nonce,
+            time_range,
+            None,
+        );
+        FranklinTx::Transfer(Box::new(transfer)).into()
+    }
+
+    /// Creates a swap of the orders of the accounts with ids 2 and 3.
+    fn swap(
+        submitter: Address,
+        nonce: Nonce,
+        first_order: (Address, Nonce),
+        second_order: (Address, Nonce),
+    ) -> SignedFranklinTx {
+        let order = |account_id, (address, nonce), tokens: (TokenId, TokenId), amount: u32| {
+            Order::new(
+                account_id,
+                address,
+                nonce,
+                tokens.0,
+                tokens.1,
+                (BigUint::from(1u32), BigUint::from(1u32)),
+                BigUint::from(amount),
+                Default::default(),
+                None,
+            )
+        };
+        let swap = Swap::new(
+            1,
+            submitter,
+            nonce,
+            (
+                order(2, first_order, (0, 1), 1),
+                order(3, second_order, (1, 0), 1),
+            ),
+            (BigUint::from(1u32), BigUint::from(1u32)),
+            BigUint::from(100u32),
+            0,
+            None,
+        );
+        FranklinTx::Swap(Box::new(swap)).into()
+    }
+
+    fn mempool_state(address: Address, nonce: Nonce) -> MempoolState {
+        let account_nonces = vec![(address, nonce)].into_iter().collect();
+        let account_ids = vec![(1, address)].into_iter().collect();
+        MempoolState::new(account_nonces, account_ids, 10)
+    }
+
+    fn queued_nonces(state: &MempoolState) -> Vec<Nonce> {
+        state
+            .ready_txs
+            .iter()
+            .map(|element| match element {
+                SignedTxVariant::Tx(tx) => tx.nonce(),
+                SignedTxVariant::Batch(_) => panic!("unexpected batch"),
+            })
+            .collect()
+    }
+
+    fn nonce_update(old_nonce: Nonce, new_nonce: Nonce) -> AccountUpdates {
+        vec![(
+            1,
+            AccountUpdate::UpdateBalance {
+                old_nonce,
+                new_nonce,
+                balance_update: (0, BigUint::from(0u32), BigUint::from(0u32)),
+            },
+        )]
+    }
+
+    /// Checks that transactions with a nonce gap are held back until the gap is filled.
+    #[test]
+    fn nonce_gap_is_held_back() {
+        let address = Address::repeat_byte(0x01);
+        let mut state = mempool_state(address, 0);
+
+        state
+            .add_tx(transfer(address, 2, Default::default()))
+            .unwrap();
+        state
+            .add_tx(transfer(address, 1, Default::default()))
+            .unwrap();
+        assert!(state.ready_txs.is_empty());
+        assert_eq!(state.pending_txs_count(), 2);
+
+        state
+            .add_tx(transfer(address, 0, Default::default()))
+            .unwrap();
+        assert_eq!(queued_nonces(&state), vec![0, 1, 2]);
+        assert_eq!(state.pending_txs_count(), 0);
+
+        // Nonces that are already queued or held back can't be reused without the fee increase.
+        assert!(matches!(
+            state.add_tx(transfer(address, 1, Default::default())),
+            Err(TxAddError::ReplacementFeeTooLow)
+        ));
+        state
+            .add_tx(transfer(address, 4, Default::default()))
+            .unwrap();
+        assert!(matches!(
+            state.add_tx(transfer(address, 4, Default::default())),
+            Err(TxAddError::ReplacementFeeTooLow)
+        ));
+    }
+
+    /// Checks that queued transactions are replaced only if the fee is increased enough.
+    #[test]
+    fn tx_replacement() {
+        let address = Address::repeat_byte(0x01);
+        let mut state = mempool_state(address, 0);
+
+        let ready_tx = transfer_with_fee(address, 0, 100, Default::default());
+        let pending_tx = transfer_with_fee(address, 2, 100, Default::default());
+        state.add_tx(ready_tx.clone()).unwrap();
+        state.add_tx(pending_tx.clone()).unwrap();
+
+        // Fee increase is below the configured 10%.
+        assert!(matches!(
+            state.add_tx(transfer_with_fee(address, 0, 109, Default::default())),
+            Err(TxAddError::ReplacementFeeTooLow)
+        ));
+
+        let replacement = transfer_with_fee(address, 0, 110, Default::default());
+        assert_eq!(state.add_tx(replacement).unwrap(), Some(ready_tx.hash()));
+        let replacement = transfer_with_fee(address, 2, 200, Default::default());
+        assert_eq!(
+            state.add_tx(replacement.clone()).unwrap(),
+            Some(pending_tx.hash())
+        );
+        assert_eq!(queued_nonces(&state), vec![0]);
+        assert_eq!(state.pending_txs[&address][&2].hash(), replacement.hash());
+
+        // Executed nonces can't be replaced.
+        state.ready_txs.pop_front();
+        state.update_nonces(nonce_update(0, 1), &[]);
+        assert!(matches!(
+            state.add_tx(transfer_with_fee(address, 0, 1000, Default::default())),
+            Err(TxAddError::NonceMismatch)
+        ));
+    }
+
+    /// Checks that transactions and batches with the already used nonces are rejected
+    /// by the checks performed before they are stored in the database.
+    #[test]
+    fn used_nonces_are_rejected_before_insertion() {
+        let address = Address::repeat_byte(0x01);
+        let state = mempool_state(address, 3);
+
+        assert!(matches!(
+            state.check_tx(&transfer(address, 2, Default::default())),
+            Err(TxAddError::NonceMismatch)
+        ));
+        assert!(matches!(
+            state.check_tx(&transfer(address, 3, Default::default())),
+            Ok(None)
+        ));
+
+        let batch = SignedTxsBatch {
+            txs: vec![
+                transfer(address, 3, Default::default()),
+                transfer(address, 2, Default::default()),
+            ],
+            batch_id: 1,
+        };
+        assert!(matches!(
+            state.check_batch(&batch),
+            Err(TxAddError::NonceMismatch)
+        ));
+    }
+
+    /// Checks that held back transactions are promoted once their predecessor is executed.
+    #[test]
+    fn held_back_txs_are_promoted_after_commit() {
+        let address = Address::repeat_byte(0x01);
+        let mut state = mempool_state(address, 0);
+
+        state
+            .add_tx(transfer(address, 0, Default::default()))
+            .unwrap();
+        // The first transaction is taken for the execution, so its successor is held back.
+        state.ready_txs.pop_front();
+        state.update_nonces(Vec::new(), &[]);
+        state
+            .add_tx(transfer(address, 1, Default::default()))
+            .unwrap();
+        assert!(state.ready_txs.is_empty());
+
+        let evicted_txs = state.update_nonces(nonce_update(0, 1), &[]);
+        assert!(evicted_txs.is_empty());
+        assert_eq!(queued_nonces(&state), vec![1]);
+    }
+
+    /// Checks that nonces of the transactions taken for the execution stay reserved
+    /// until the block with these transactions is committed.
+    #[test]
+    fn executing_txs_keep_nonces_reserved() {
+        let address = Address::repeat_byte(0x01);
+        let mut state = mempool_state(address, 0);
+
+        let executing_tx = transfer(address, 0, Default::default());
+        state.add_tx(executing_tx.clone()).unwrap();
+        state.ready_txs.pop_front();
+        state.executing_txs.push(executing_tx.clone());
+
+        // The previous block is committed while the transaction is in the pending block.
+        state.update_nonces(Vec::new(), &[]);
+        assert!(matches!(
+            state.add_tx(transfer(address, 0, Default::default())),
+            Err(TxAddError::NonceMismatch)
+        ));
+        state
+            .add_tx(transfer(address, 1, Default::default()))
+            .unwrap();
+        assert_eq!(queued_nonces(&state), vec![1]);
+
+        let evicted_txs = state.update_nonces(nonce_update(0, 1), &[executing_tx.hash()]);
+        assert!(evicted_txs.is_empty());
+        assert!(state.executing_txs.is_empty());
+        assert_eq!(queued_nonces(&state), vec![1]);
+    }
+
+    /// Checks that held back transactions which can't be executed anymore are evicted.
+    #[test]
+    fn stale_txs_are_evicted() {
+        let address = Address::repeat_byte(0x01);
+        let mut state = mempool_state(address, 0);
+
+        let stale_tx = transfer(address, 1, Default::default());
+        let expired_tx = transfer(address, 4, TimeRange::new(0, 1));
+        let actual_tx = transfer(address, 5, Default::default());
+        state.pending_txs.entry(address).or_default().extend(vec![
+            (1, stale_tx.clone()),
+            (4, expired_tx.clone()),
+            (5, actual_tx),
+        ]);
+
+        let evicted_txs = state.update_nonces(nonce_update(0, 3), &[]);
+        assert_eq!(evicted_txs, vec![stale_tx.hash(), expired_tx.hash()]);
+        assert!(state.ready_txs.is_empty());
+        assert_eq!(state.pending_txs_count(), 1);
+    }
+
+    /// Checks that a swap consumes the nonces of both orders owners.
+    #[test]
+    fn swap_reserves_order_owner_nonce() {
+        let (submitter, first_owner, second_owner) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+        let mut state = mempool_state(submitter, 0);
+
+        state
+            .add_tx(swap(submitter, 0, (first_owner, 0), (second_owner, 0)))
+            .unwrap();
+        for owner in &[first_owner, second_owner] {
+            assert!(matches!(
+                state.add_tx(transfer(*owner, 0, Default::default())),
+                Err(TxAddError::NonceMismatch)
+            ));
+            state
+                .add_tx(transfer(*owner, 1, Default::default()))
+                .unwrap();
+        }
+        assert_eq!(queued_nonces(&state), vec![0, 1, 1]);
+    }
+
+    /// Checks that the queued transactions of the order owner are evicted
+    /// once the swap consumes their nonce.
+    #[test]
+    fn swap_evicts_order_owner_txs() {
+        let (submitter, owner, second_owner) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+        let account_nonces = vec![(submitter, 0), (owner, 0), (second_owner, 0)]
+            .into_iter()
+            .collect();
+        let account_ids = vec![(1, submitter), (2, owner), (3, second_owner)]
+            .into_iter()
+            .collect();
+        let mut state = MempoolState::new(account_nonces, account_ids, 10);
+
+        let owner_tx = transfer(owner, 0, Default::default());
+        state.add_tx(owner_tx.clone()).unwrap();
+        state
+            .add_tx(swap(submitter, 0, (owner, 0), (second_owner, 0)))
+            .unwrap();
+
+        // The swap is executed before the owner transaction.
+        state.ready_txs.pop_back();
+        let owner_update = AccountUpdate::UpdateBalance {
+            old_nonce: 0,
+            new_nonce: 1,
+            balance_update: (0, BigUint::from(0u32), BigUint::from(0u32)),
+        };
+        let evicted_txs = state.update_nonces(vec![(2, owner_update)], &[]);
+        assert_eq!(evicted_txs, vec![owner_tx.hash()]);
+        assert!(state.ready_txs.is_empty());
+    }
+
+    fn candidate(from: Address, nonce: Nonce, chunks: usize, fee_per_chunk: u32) -> BlockCandidate {
+        BlockCandidate {
+            element: transfer(from, nonce, Default::default()).into(),
+            accounts: vec![from],
+            chunks,
+            fee_per_chunk: Ratio::from_integer(BigUint::from(fee_per_chunk)),
+        }
+    }
+
+    /// Checks that elements with a higher fee are selected first, the nonce order is kept,
+    /// and the elements that don't fit are replaced with the smaller ones.
+    #[test]
+    fn fee_priority_selection() {
+        let first = Address::repeat_byte(0x01);
+        let second = Address::repeat_byte(0x02);
+        let candidates = vec![
+            candidate(first, 0, 2, 1),
+            candidate(first, 1, 2, 10),
+            candidate(second, 0, 6, 5),
+            candidate(second, 1, 1, 100),
+            candidate(first, 2, 1, 3),
+        ];
+
+        let (chunks_left, selected, remaining) = select_by_fee_priority(candidates, 5);
+        let nonces = |txs: Vec<SignedTxVariant>| -> Vec<(Address, Nonce)> {
+            txs.into_iter()
+                .map(|element| match element {
+                    SignedTxVariant::Tx(tx) => (tx.account(), tx.nonce()),
+                    SignedTxVariant::Batch(_) => panic!("unexpected batch"),
+                })
+                .collect()
+        };
+
+        // The large tx of the second account doesn't fit into the block, so its successor
+        // is held back despite the higher fee, and the block is filled with smaller txs.
+        assert_eq!(chunks_left, 0);
+        assert_eq!(nonces(selected), vec![(first, 0), (first, 1), (first, 2)]);
+        assert_eq!(
+            nonces(remaining.into_iter().collect()),
+            vec![(second, 0), (second, 1)]
+        );
+    }
+
+    /// Checks that a transaction of the order owner paying more per chunk is not selected
+    /// ahead of the swap that consumes the preceding nonce of the owner.
+    #[test]
+    fn swap_keeps_order_owners_nonce_order() {
+        let (submitter, first_owner, second_owner) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+        let account_nonces = vec![(submitter, 0), (first_owner, 0), (second_owner, 0)]
+            .into_iter()
+            .collect();
+        let account_ids = vec![(1, submitter), (2, first_owner), (3, second_owner)]
+            .into_iter()
+            .collect();
+        let mut state = MempoolState::new(account_nonces, account_ids, 10);
+
+        state
+            .add_tx(swap(submitter, 0, (first_owner, 0), (second_owner, 0)))
+            .unwrap();
+        state
+            .add_tx(transfer(second_owner, 1, Default::default()))
+            .unwrap();
+        let candidates = |state: &MempoolState| -> Vec<BlockCandidate> {
+            state
+                .ready_txs
+                .iter()
+                .map(|element| {
+                    let (chunks, fee_per_chunk) = match element {
+                        SignedTxVariant::Tx(tx) if tx.nonce() == 1 => (1, 100u32),
+                        _ => (5, 1),
+                    };
+                    BlockCandidate {
+                        element: element.clone(),
+                        accounts: state.element_accounts(element),
+                        chunks,
+                        fee_per_chunk: Ratio::from_integer(BigUint::from(fee_per_chunk)),
+                    }
+                })
+                .collect()
+        };
+
+        let (chunks_left, selected, _) = select_by_fee_priority(candidates(&state), 10);
+        assert_eq!(chunks_left, 4);
+        let selected: Vec<_> = selected
+            .into_iter()
+            .map(|element| match element {
+                SignedTxVariant::Tx(tx) => matches!(tx.tx, FranklinTx::Swap(_)),
+                SignedTxVariant::Batch(_) => panic!("unexpected batch"),
+            })
+            .collect();
+        assert_eq!(selected, vec![true, false]);
+
+        // The swap doesn't fit, so the owner transaction has to wait for it.
+        let (chunks_left, selected, remaining) = select_by_fee_priority(candidates(&state), 3);
+        assert_eq!(chunks_left, 3);
+        assert!(selected.is_empty());
+        assert_eq!(remaining.len(), 2);
+    }
+}
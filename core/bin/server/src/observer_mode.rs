@@ -3,8 +3,8 @@
 
 use crate::state_keeper::PlasmaStateInitParams;
 use circuit::witness::{
-    ChangePubkeyOffChainWitness, CloseAccountWitness, DepositWitness, FullExitWitness,
-    TransferToNewWitness, TransferWitness, WithdrawWitness, Witness,
+    ChangePubkeyOffChainWitness, CloseAccountWitness, DepositWitness, ForcedExitWitness,
+    FullExitWitness, SwapWitness, TransferToNewWitness, TransferWitness, WithdrawWitness, Witness,
 };
 use log::info;
 use models::circuit::account::CircuitAccount;
@@ -129,6 +129,12 @@ impl ObservedState {
                 FranklinOp::Withdraw(withdraw) => {
                     WithdrawWitness::apply_tx(&mut self.circuit_acc_tree, &withdraw);
                 }
+                FranklinOp::ForcedExit(forced_exit) => {
+                    ForcedExitWitness::apply_tx(&mut self.circuit_acc_tree, &forced_exit);
+                }
+                FranklinOp::Swap(swap) => {
+                    SwapWitness::apply_tx(&mut self.circuit_acc_tree, &swap);
+                }
                 FranklinOp::Close(close) => {
                     CloseAccountWitness::apply_tx(&mut self.circuit_acc_tree, &close);
                 }
@@ -7,10 +7,13 @@ use models::config_options::ConfigurationOptions;
 use models::ActionType;
 use storage::ConnectionPool;
 use tokio::task::JoinHandle;
+// Local uses
+use crate::fee_ticker::{PriceSourcesHealth, SourceHealth};
 
 #[must_use]
 pub fn start_prometheus_exporter(
     connection_pool: ConnectionPool,
+    price_sources_health: PriceSourcesHealth,
     config: &ConfigurationOptions,
 ) -> JoinHandle<()> {
     let addr = ([0, 0, 0, 0], config.prometheus_export_port).into();
@@ -86,6 +89,47 @@ pub fn start_prometheus_exporter(
 
         transaction.commit().await?;
 
+        s.push_str(&render_price_sources_health(&price_sources_health));
+
         Ok(s)
     }))
 }
+
+/// Renders the health statistics of the token price sources, one sample per source.
+fn render_price_sources_health(price_sources_health: &PriceSourcesHealth) -> String {
+    let mut health: Vec<_> = price_sources_health.snapshot().into_iter().collect();
+    health.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let metrics: [(&str, &str, fn(&SourceHealth) -> u64); 3] = [
+        (
+            "price_source_accepted_quotes",
+            "Number of the source quotes used to calculate the token price",
+            |health| health.accepted_quotes,
+        ),
+        (
+            "price_source_discarded_quotes",
+            "Number of the source quotes discarded as stale or deviating from other sources",
+            |health| health.discarded_quotes,
+        ),
+        (
+            "price_source_failed_requests",
+            "Number of the failed requests to the price source",
+            |health| health.failed_requests,
+        ),
+    ];
+
+    let mut s = String::new();
+    for (name, help, value) in metrics.iter() {
+        let pc = PrometheusMetric::new(name, MetricType::Counter, help);
+        s.push_str(&pc.render_header());
+        for (source, source_health) in &health {
+            s.push_str(&pc.render_sample(
+                Some(&[("source", source.as_str())]),
+                value(source_health),
+                None,
+            ));
+        }
+    }
+
+    s
+}
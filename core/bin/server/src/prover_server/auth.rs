@@ -0,0 +1,96 @@
+//! Authentication of the provers.
+//!
+//! Every prover uses its own JSON Web Token, issued via the admin server. IDs of the issued
+//! tokens are stored in the database, so any token can be revoked before its expiration.
+
+// External uses
+use jsonwebtoken::errors::Error as JwtError;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProverAuthToken {
+    pub sub: String,   // Subject (name of the prover the token is issued to)
+    pub token_id: i32, // ID of the token stored in the database
+    pub exp: usize,    // Expiration time (as UTC timestamp)
+}
+
+/// Creates the signed authentication token for the prover.
+pub fn encode_prover_token(
+    secret: &str,
+    token_id: i32,
+    worker: &str,
+    exp: usize,
+) -> Result<String, JwtError> {
+    let payload = ProverAuthToken {
+        sub: worker.to_string(),
+        token_id,
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &payload,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+pub struct ProverTokenValidator<'a> {
+    decoding_key: DecodingKey<'a>,
+}
+
+impl<'a> ProverTokenValidator<'a> {
+    pub fn new(secret: &'a str) -> Self {
+        Self {
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Validates the token signature and expiration time and returns its payload.
+    /// Note that the token is not checked to be revoked.
+    pub fn validate_auth_token(&self, token: &str) -> Result<ProverAuthToken, JwtError> {
+        decode::<ProverAuthToken>(token, &self.decoding_key, &Validation::default())
+            .map(|token| token.claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn exp_after(secs: u64) -> usize {
+        (SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + secs) as usize
+    }
+
+    #[test]
+    fn prover_token_roundtrip() {
+        let token = encode_prover_token("secret", 1, "prover_1", exp_after(3600)).unwrap();
+
+        let payload = ProverTokenValidator::new("secret")
+            .validate_auth_token(&token)
+            .unwrap();
+        assert_eq!(payload.sub, "prover_1");
+        assert_eq!(payload.token_id, 1);
+
+        // Token signed with another secret must be rejected.
+        assert!(ProverTokenValidator::new("another_secret")
+            .validate_auth_token(&token)
+            .is_err());
+    }
+
+    #[test]
+    fn expired_prover_token() {
+        // Token has expired a couple of minutes ago.
+        let exp = exp_after(0) - 120;
+        let token = encode_prover_token("secret", 1, "prover_1", exp).unwrap();
+
+        assert!(ProverTokenValidator::new("secret")
+            .validate_auth_token(&token)
+            .is_err());
+    }
+}
@@ -1,47 +1,91 @@
 // Built-in
-use std::sync::{Arc, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use std::thread;
 use std::time::{self, Duration};
 // External
-use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::channel::mpsc;
+use actix_web::dev::ServiceRequest;
+use actix_web::{
+    error::BlockingError, http::header, middleware::Condition, web, App, HttpMessage, HttpRequest,
+    HttpResponse, HttpServer,
+};
+use actix_web_httpauth::extractors::{
+    bearer::{BearerAuth, Config},
+    AuthenticationError,
+};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use futures::{
+    channel::{mpsc, oneshot},
+    lock::Mutex,
+    SinkExt,
+};
 use log::{info, trace};
 // Workspace deps
 use models::config_options::ConfigurationOptions;
-use models::{config_options::ThreadPanicNotify, node::BlockNumber};
-use prover::client;
-use storage::ConnectionPool;
+use models::primitives::serialize_fe_for_ethereum;
+use models::prover_utils::{EncodedProofPlonk, PlonkVerificationKey};
+use models::{
+    config_options::ThreadPanicNotify,
+    node::{block::ProofPriority, BlockNumber},
+};
+use prover::{
+    client,
+    prover_data::{ProverData, BINARY_PROVER_DATA_CONTENT_TYPE},
+};
+use storage::{
+    prover::records::{BlockWitness, ProverJob, ProverRun},
+    ConnectionPool,
+};
 // Local deps
-use crate::prover_server::scaler::ScalerOracle;
+use crate::{
+    eth_watch::EthWatchRequest,
+    prover_server::{auth::ProverTokenValidator, scaler::ScalerOracle},
+};
 
+pub mod auth;
 mod scaler;
 mod witness_generator;
 
 #[derive(Debug)]
 struct AppState {
     connection_pool: storage::ConnectionPool,
-    scaler_oracle: Arc<RwLock<ScalerOracle>>,
+    /// Scaler oracle is shared between the server workers, since it keeps the history
+    /// of its recommendations.
+    scaler_oracle: Arc<Mutex<ScalerOracle>>,
     prover_timeout: Duration,
+    /// Verification keys for each supported block size (in chunks).
+    /// Empty if the published proofs are not verified.
+    verification_keys: Arc<HashMap<usize, Arc<PlonkVerificationKey>>>,
+    secret_auth: Option<String>,
+    /// Used to get the current Ethereum block the deadlines of the priority operations
+    /// are compared with.
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
 }
 
 impl AppState {
     pub fn new(
         connection_pool: ConnectionPool,
+        scaler_oracle: Arc<Mutex<ScalerOracle>>,
         prover_timeout: Duration,
-        idle_provers: u32,
+        verification_keys: Arc<HashMap<usize, Arc<PlonkVerificationKey>>>,
+        secret_auth: Option<String>,
+        eth_watch_req: mpsc::Sender<EthWatchRequest>,
     ) -> Self {
-        let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
-            connection_pool.clone(),
-            idle_provers,
-        )));
-
         Self {
             connection_pool,
             scaler_oracle,
             prover_timeout,
+            verification_keys,
+            secret_auth,
+            eth_watch_req,
         }
     }
 
+    /// Checks whether the published proofs are verified before being stored.
+    fn verify_proofs(&self) -> bool {
+        !self.verification_keys.is_empty()
+    }
+
     async fn access_storage(&self) -> actix_web::Result<storage::StorageProcessor<'_>> {
         self.connection_pool
             .access_storage_fragile()
@@ -53,11 +97,84 @@ impl AppState {
     }
 }
 
+/// Name of the prover authenticated by the token.
+/// Stored in the request extensions if the authentication is enabled.
+#[derive(Debug, Clone)]
+struct AuthenticatedProver(String);
+
+/// Checks the prover authentication token, including whether it was revoked.
+async fn validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> Result<ServiceRequest, actix_web::Error> {
+    let config = req.app_data::<Config>().cloned().unwrap_or_default();
+    let data = req
+        .app_data::<web::Data<AppState>>()
+        .expect("app state is not set")
+        .clone();
+    let secret_auth = data
+        .secret_auth
+        .as_ref()
+        .expect("prover authentication is disabled");
+
+    let token = ProverTokenValidator::new(secret_auth)
+        .validate_auth_token(credentials.token())
+        .map_err(|_| AuthenticationError::from(config.clone()))?;
+
+    let stored_token = data
+        .access_storage()
+        .await?
+        .prover_schema()
+        .prover_token(token.token_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!("Failed to load prover token: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    match stored_token {
+        Some(stored_token)
+            if stored_token.revoked_at.is_none() && stored_token.worker == token.sub => {}
+        _ => return Err(AuthenticationError::from(config).into()),
+    }
+
+    req.extensions_mut().insert(AuthenticatedProver(token.sub));
+    Ok(req)
+}
+
+/// Checks that the prover does not use a name other than the one its token was issued to.
+fn check_prover_name(req: &HttpRequest, name: &str) -> actix_web::Result<()> {
+    match req.extensions().get::<AuthenticatedProver>() {
+        Some(prover) if prover.0 != name => Err(actix_web::error::ErrorForbidden(
+            "prover name does not match the auth token",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks whether the request is sent by the authenticated prover,
+/// i.e. whether the authentication is enabled.
+fn is_authenticated(req: &HttpRequest) -> bool {
+    req.extensions().get::<AuthenticatedProver>().is_some()
+}
+
+/// Checks that the prover job is assigned to the prover its token was issued to.
+/// Non-existent jobs are not assigned to any prover.
+fn check_prover_run(req: &HttpRequest, run: Option<&ProverRun>) -> actix_web::Result<()> {
+    let worker = run.and_then(|run| run.worker.as_deref());
+    match req.extensions().get::<AuthenticatedProver>() {
+        Some(prover) if Some(prover.0.as_str()) != worker => Err(actix_web::error::ErrorForbidden(
+            "prover job is not assigned to the auth token owner",
+        )),
+        _ => Ok(()),
+    }
+}
+
 async fn status() -> actix_web::Result<String> {
     Ok("alive".into())
 }
 
 async fn register(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<client::ProverReq>,
 ) -> actix_web::Result<String> {
@@ -65,6 +182,7 @@ async fn register(
     if r.name == "" {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
+    check_prover_name(&req, &r.name)?;
     let mut storage = data.access_storage().await?;
     let id = storage
         .prover_schema()
@@ -77,18 +195,49 @@ async fn register(
     Ok(id.to_string())
 }
 
+/// Requests the number of the last Ethereum block known to the node, which the deadlines
+/// of the priority operations are compared with to find the latency-sensitive blocks.
+async fn last_eth_block(
+    eth_watch_req: &mpsc::Sender<EthWatchRequest>,
+) -> Result<u64, failure::Error> {
+    let (resp, receiver) = oneshot::channel();
+    eth_watch_req
+        .clone()
+        .send(EthWatchRequest::GetLastEthBlock { resp })
+        .await?;
+
+    Ok(receiver.await?)
+}
+
 async fn block_to_prove(
+    req: HttpRequest,
     data: web::Data<AppState>,
-    r: web::Json<client::ProverReq>,
+    r: web::Json<client::BlockToProveReq>,
 ) -> actix_web::Result<HttpResponse> {
     trace!("request block to prove from worker: {}", r.name);
     if r.name == "" {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
+    check_prover_name(&req, &r.name)?;
+    let current_eth_block = last_eth_block(&data.eth_watch_req).await.map_err(|e| {
+        vlog::warn!("could not get the last Ethereum block: {}", e);
+        actix_web::error::ErrorInternalServerError("eth watch error")
+    })?;
+    let min_priority = if r.high_priority_only {
+        ProofPriority::High
+    } else {
+        ProofPriority::Normal
+    };
     let mut storage = data.access_storage().await?;
     let ret = storage
         .prover_schema()
-        .prover_run_for_next_commit(&r.name, data.prover_timeout, r.block_size)
+        .prover_run_for_next_commit(
+            &r.name,
+            data.prover_timeout,
+            r.block_size,
+            min_priority,
+            current_eth_block,
+        )
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
@@ -111,7 +260,28 @@ async fn block_to_prove(
     }
 }
 
+/// Checks whether the prover accepts witnesses in the compact binary form.
+/// Provers which don't declare it explicitly get the JSON representation.
+fn accepts_binary_prover_data(req: &HttpRequest) -> bool {
+    req.headers()
+        .get_all(header::ACCEPT)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| {
+            media_type.split(';').next().map(str::trim) == Some(BINARY_PROVER_DATA_CONTENT_TYPE)
+        })
+}
+
+/// Decodes the stored witness regardless of the form it was stored in.
+fn decode_witness(witness: BlockWitness) -> Result<ProverData, failure::Error> {
+    match witness {
+        BlockWitness::Json(witness) => Ok(serde_json::from_value(witness)?),
+        BlockWitness::Binary(witness) => ProverData::decode_binary(&witness),
+    }
+}
+
 async fn prover_data(
+    req: HttpRequest,
     data: web::Data<AppState>,
     block: web::Json<BlockNumber>,
 ) -> actix_web::Result<HttpResponse> {
@@ -120,20 +290,63 @@ async fn prover_data(
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
+    if is_authenticated(&req) {
+        let run = storage
+            .prover_schema()
+            .get_existing_prover_run(block.0)
+            .await
+            .map_err(|e| {
+                vlog::warn!("Failed to load prover run for block {}: {}", *block, e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
+        check_prover_run(&req, run.as_ref())?;
+    }
     let witness = match storage.prover_schema().get_witness(block.0).await {
         Ok(witness) => witness,
         Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
     };
-    if witness.is_some() {
-        info!("Sent prover_data for block {}", *block);
-    } else {
-        // No witness, we should just wait
-        warn!("No witness for block {}", *block);
-    }
-    Ok(HttpResponse::Ok().json(witness))
+    let witness = match witness {
+        Some(witness) => witness,
+        None => {
+            // No witness, we should just wait
+            warn!("No witness for block {}", *block);
+            return Ok(HttpResponse::Ok().json(Option::<ProverData>::None));
+        }
+    };
+
+    // Witnesses are transcoded only if the form requested by the prover differs from the stored one.
+    // Note that the response body is not streamed: the witness is loaded from the database as
+    // a whole anyway, so the memory used per request is bounded by the size of the witness
+    // (and of its transcoded form, if any). The compact binary form keeps it acceptable.
+    let response = match (accepts_binary_prover_data(&req), witness) {
+        (true, BlockWitness::Binary(witness)) => HttpResponse::Ok()
+            .content_type(BINARY_PROVER_DATA_CONTENT_TYPE)
+            .body(witness),
+        (false, BlockWitness::Json(witness)) => HttpResponse::Ok().json(witness),
+        (binary, witness) => {
+            let prover_data = decode_witness(witness).map_err(|e| {
+                vlog::error!("Failed to decode witness for block {}: {}", *block, e);
+                actix_web::error::ErrorInternalServerError("incorrect witness")
+            })?;
+            if binary {
+                let encoded = prover_data.encode_binary().map_err(|e| {
+                    vlog::error!("Failed to encode witness for block {}: {}", *block, e);
+                    actix_web::error::ErrorInternalServerError("incorrect witness")
+                })?;
+                HttpResponse::Ok()
+                    .content_type(BINARY_PROVER_DATA_CONTENT_TYPE)
+                    .body(encoded)
+            } else {
+                HttpResponse::Ok().json(prover_data)
+            }
+        }
+    };
+    info!("Sent prover_data for block {}", *block);
+    Ok(response)
 }
 
 async fn working_on(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<client::WorkingOnReq>,
 ) -> actix_web::Result<HttpResponse> {
@@ -147,6 +360,17 @@ async fn working_on(
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
+    if is_authenticated(&req) {
+        let run = storage
+            .prover_schema()
+            .prover_run(r.prover_run_id)
+            .await
+            .map_err(|e| {
+                vlog::warn!("Failed to load prover run {}: {}", r.prover_run_id, e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
+        check_prover_run(&req, run.as_ref())?;
+    }
     storage
         .prover_schema()
         .record_prover_is_working(r.prover_run_id)
@@ -159,15 +383,126 @@ async fn working_on(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Checks that the proof is valid for the block with the given number.
+///
+/// Returns the reason of rejection as an inner error if the proof is invalid, and
+/// the outer error if the proof could not be checked.
+async fn verify_proof(
+    storage: &mut storage::StorageProcessor<'_>,
+    verification_keys: &HashMap<usize, Arc<PlonkVerificationKey>>,
+    block: BlockNumber,
+    proof: &EncodedProofPlonk,
+) -> actix_web::Result<Result<(), String>> {
+    let witness = storage
+        .prover_schema()
+        .get_witness(block)
+        .await
+        .map_err(|e| {
+            vlog::warn!("Failed to load witness for block {}: {}", block, e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("no witness for block"))?;
+    let prover_data = decode_witness(witness).map_err(|e| {
+        vlog::error!("Failed to decode witness for block {}: {}", block, e);
+        actix_web::error::ErrorInternalServerError("incorrect witness")
+    })?;
+
+    // The only public input of the circuit is the commitment to the block data.
+    let expected_inputs = vec![serialize_fe_for_ethereum(
+        &prover_data.public_data_commitment,
+    )];
+    if proof.inputs != expected_inputs {
+        return Ok(Err(
+            "public input does not match the block commitment".into()
+        ));
+    }
+
+    // There is one circuit operation per block chunk.
+    let block_size = prover_data.operations.len();
+    let vk = verification_keys.get(&block_size).cloned().ok_or_else(|| {
+        vlog::error!("No verification key for block size {}", block_size);
+        actix_web::error::ErrorInternalServerError("verification key is not available")
+    })?;
+
+    // Verification takes a while, so it's performed on the blocking thread pool
+    // in order not to stall the other requests handled by the worker.
+    let proof = proof.clone();
+    match web::block(move || vk.verify_encoded_proof(&proof)).await {
+        Ok(true) => Ok(Ok(())),
+        Ok(false) => Ok(Err("proof verification failed".into())),
+        Err(BlockingError::Error(e)) => Ok(Err(format!("malformed proof: {}", e))),
+        Err(BlockingError::Canceled) => Err(actix_web::error::ErrorInternalServerError(
+            "proof verification was canceled",
+        )),
+    }
+}
+
+/// Loads the verification keys for all the supported block sizes,
+/// so they aren't read from the disk for every published proof.
+fn load_verification_keys(block_sizes: &[usize]) -> HashMap<usize, Arc<PlonkVerificationKey>> {
+    block_sizes
+        .iter()
+        .map(|&block_size| {
+            let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to read verification key for block size {}: {}",
+                        block_size, e
+                    )
+                });
+            (block_size, Arc::new(vk))
+        })
+        .collect()
+}
+
 async fn publish(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<client::PublishReq>,
 ) -> actix_web::Result<HttpResponse> {
     info!("Received a proof for block: {}", r.block);
+    check_prover_name(&req, &r.name)?;
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
+    if is_authenticated(&req) {
+        let run = storage
+            .prover_schema()
+            .get_existing_prover_run(r.block)
+            .await
+            .map_err(|e| {
+                vlog::warn!("Failed to load prover run for block {}: {}", r.block, e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
+        check_prover_run(&req, run.as_ref())?;
+    }
+
+    if data.verify_proofs() {
+        if let Err(reason) =
+            verify_proof(&mut storage, &data.verification_keys, r.block, &r.proof).await?
+        {
+            vlog::warn!(
+                "Rejected proof for block {} from prover {}: {}",
+                r.block,
+                r.name,
+                reason
+            );
+            storage
+                .prover_schema()
+                .record_invalid_proof(r.block, &r.name, &reason)
+                .await
+                .map_err(|e| {
+                    vlog::warn!("Failed to record invalid proof: {}", e);
+                    actix_web::error::ErrorInternalServerError("storage layer error")
+                })?;
+            return Err(actix_web::error::ErrorBadRequest(format!(
+                "invalid proof: {}",
+                reason
+            )));
+        }
+    }
+
     if let Err(e) = storage.prover_schema().store_proof(r.block, &r.proof).await {
         vlog::error!("failed to store received proof: {}", e);
         let message = if e.to_string().contains("duplicate key") {
@@ -182,6 +517,7 @@ async fn publish(
 }
 
 async fn stopped(
+    req: HttpRequest,
     data: web::Data<AppState>,
     prover_id: web::Json<i32>,
 ) -> actix_web::Result<HttpResponse> {
@@ -203,6 +539,7 @@ async fn stopped(
             );
             actix_web::error::ErrorBadRequest("unknown prover ID")
         })?;
+    check_prover_name(&req, &prover_description.worker)?;
 
     info!(
         "Prover instance '{}' with ID {} send a stopping notification",
@@ -234,20 +571,33 @@ pub struct RequiredReplicasOutput {
     /// Amount of the prover entities required for server
     /// to run optimally.
     needed_count: u32,
+    /// Amount of the prover entities required for each block size (in chunks).
+    needed_count_by_block_size: BTreeMap<usize, u32>,
+    /// Oldest blocks awaiting for proof in the order they are handed out to the provers.
+    pending_jobs: Vec<ProverJob>,
 }
 
 async fn required_replicas(
     data: web::Data<AppState>,
     _input: web::Json<RequiredReplicasInput>,
 ) -> actix_web::Result<HttpResponse> {
-    let mut oracle = data.scaler_oracle.write().expect("Expected write lock");
+    let mut oracle = data.scaler_oracle.lock().await;
 
-    let needed_count = oracle
+    let needed_count_by_block_size = oracle
         .provers_required()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    let response = RequiredReplicasOutput { needed_count };
+    let pending_jobs = oracle
+        .pending_jobs()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = RequiredReplicasOutput {
+        needed_count: needed_count_by_block_size.values().sum(),
+        needed_count_by_block_size,
+        pending_jobs,
+    };
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -258,6 +608,7 @@ pub fn start_prover_server(
     prover_timeout: time::Duration,
     rounds_interval: time::Duration,
     panic_notify: mpsc::Sender<bool>,
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
     config_options: ConfigurationOptions,
 ) {
     thread::Builder::new()
@@ -300,10 +651,35 @@ pub fn start_prover_server(
                 }
 
                 // Start HTTP server.
-                let idle_provers = config_options.idle_provers;
+                let scaler_oracle = Arc::new(Mutex::new(ScalerOracle::new(
+                    connection_pool.clone(),
+                    config_options.idle_provers,
+                    config_options.available_block_chunk_sizes.clone(),
+                    eth_watch_req.clone(),
+                )));
+                let verification_keys = if config_options.prover_server_verify_proofs {
+                    load_verification_keys(&config_options.available_block_chunk_sizes)
+                } else {
+                    HashMap::new()
+                };
+                let verification_keys = Arc::new(verification_keys);
+                let secret_auth = config_options.prover_server_secret_auth.clone();
+                if secret_auth.is_none() {
+                    vlog::warn!(
+                        "Prover server authentication is disabled by PROVER_SERVER_AUTH_DISABLED, \
+                         anyone can fetch the witnesses and publish proofs"
+                    );
+                }
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(connection_pool.clone(), prover_timeout, idle_provers);
+                    let app_state = AppState::new(
+                        connection_pool.clone(),
+                        scaler_oracle.clone(),
+                        prover_timeout,
+                        verification_keys.clone(),
+                        secret_auth.clone(),
+                        eth_watch_req.clone(),
+                    );
+                    let auth = HttpAuthentication::bearer(validator);
 
                     // By calling `register_data` instead of `data` we're avoiding double
                     // `Arc` wrapping of the object.
@@ -311,16 +687,23 @@ pub fn start_prover_server(
                         .wrap(actix_web::middleware::Logger::default())
                         .app_data(web::Data::new(app_state))
                         .route("/status", web::get().to(status))
-                        .route("/register", web::post().to(register))
-                        .route("/block_to_prove", web::get().to(block_to_prove))
-                        .route("/working_on", web::post().to(working_on))
-                        .route("/prover_data", web::get().to(prover_data))
-                        .route("/publish", web::post().to(publish))
-                        .route("/stopped", web::post().to(stopped))
                         .route(
                             "/api/internal/prover/replicas",
                             web::post().to(required_replicas),
                         )
+                        // Endpoints used by provers require authentication (if enabled).
+                        // Scope with the empty prefix captures all the requests, so
+                        // it must be registered after the other routes.
+                        .service(
+                            web::scope("")
+                                .wrap(Condition::new(secret_auth.is_some(), auth))
+                                .route("/register", web::post().to(register))
+                                .route("/block_to_prove", web::get().to(block_to_prove))
+                                .route("/working_on", web::post().to(working_on))
+                                .route("/prover_data", web::get().to(prover_data))
+                                .route("/publish", web::post().to(publish))
+                                .route("/stopped", web::post().to(stopped)),
+                        )
                 })
                 .bind(&config_options.prover_server_address)
                 .expect("failed to bind")
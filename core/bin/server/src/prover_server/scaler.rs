@@ -1,7 +1,95 @@
 //! Module with utilities for prover scaler service.
 
+// Built-in deps
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+// External deps
+use chrono::Utc;
+use futures::channel::mpsc;
 // Workspace deps
-use storage::ConnectionPool;
+use storage::{prover::records::ProverJob, ConnectionPool};
+// Local deps
+use super::last_eth_block;
+use crate::eth_watch::EthWatchRequest;
+
+/// Period over which the proof generation durations are averaged.
+const PROOF_DURATION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+/// Period over which the rate of the incoming blocks is estimated.
+const COMMIT_RATE_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// Time in which the already accumulated jobs are expected to be proven.
+const QUEUE_DRAIN_TIME: Duration = Duration::from_secs(10 * 60);
+/// Lowered amount of replicas is reported only after it stays lower during this period,
+/// so short pauses between the blocks don't make the autoscaler stop and start provers.
+const SCALE_DOWN_DELAY: Duration = Duration::from_secs(15 * 60);
+/// Maximum amount of the oldest pending jobs reported to the prover scaler.
+const PENDING_JOBS_REPORT_LIMIT: u32 = 100;
+
+/// Demand for the provers of a certain block size.
+#[derive(Debug, Clone, Default)]
+struct BlockSizeDemand {
+    /// Amount of blocks awaiting for proof.
+    pending_jobs: u32,
+    /// Amount of blocks awaiting for proof which some high-priority block waits for.
+    high_priority_jobs: u32,
+    /// Average proof generation time, if there were proofs generated recently.
+    proof_duration: Option<Duration>,
+    /// Amount of blocks committed per second recently.
+    commit_rate: f64,
+}
+
+impl BlockSizeDemand {
+    /// Estimates the amount of provers required to handle the demand.
+    fn provers_required(&self) -> u32 {
+        let proof_duration = match self.proof_duration {
+            Some(duration) => duration.as_secs_f64(),
+            // Without the history we require a prover for each pending block.
+            None => return self.pending_jobs,
+        };
+
+        // Provers required to drain the accumulated jobs in time. There is no
+        // point in having more such provers than there are jobs. Blocks which
+        // a high-priority block waits for don't wait in the queue, each of them
+        // gets a prover right away.
+        let queue_provers = (f64::from(self.pending_jobs) * proof_duration
+            / QUEUE_DRAIN_TIME.as_secs_f64())
+        .ceil()
+        .min(f64::from(self.pending_jobs))
+        .max(f64::from(self.high_priority_jobs));
+        // Provers which will be busy with the incoming blocks on average.
+        // Rounded down, so rarely committed blocks don't keep an idle prover forever.
+        let incoming_provers = (self.commit_rate * proof_duration).floor();
+
+        (queue_provers + incoming_provers) as u32
+    }
+}
+
+/// Recently recommended amounts of replicas, used to delay the scaling down.
+#[derive(Debug, Default)]
+struct Hysteresis {
+    recent: VecDeque<(Instant, u32)>,
+}
+
+impl Hysteresis {
+    /// Records the newly calculated amount of replicas and returns the amount to report:
+    /// the maximum over the `SCALE_DOWN_DELAY` period. Thus, scaling up is immediate,
+    /// while scaling down happens only once the demand stays lower for long enough.
+    fn apply(&mut self, now: Instant, replicas: u32) -> u32 {
+        self.recent.push_back((now, replicas));
+        while let Some(&(recorded_at, _)) = self.recent.front() {
+            if now.duration_since(recorded_at) > SCALE_DOWN_DELAY {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        self.recent
+            .iter()
+            .map(|&(_, replicas)| replicas)
+            .max()
+            .unwrap_or(replicas)
+    }
+}
 
 /// Scaler oracle provides information for prover scaler
 /// service about required amount of provers for server
@@ -11,25 +99,210 @@ pub struct ScalerOracle {
     /// Database access to gather the information about amount of pending blocks.
     db: ConnectionPool,
 
-    /// Number of idle provers running for faster up-scaling
+    /// Number of idle provers running for faster up-scaling.
+    /// Idle provers are only kept for the smallest block size, since these are the cheapest ones.
     idle_provers: u32,
+
+    /// Block sizes (in chunks) supported by the server.
+    block_sizes: Vec<usize>,
+
+    /// Recent recommendations for each block size.
+    hysteresis: HashMap<usize, Hysteresis>,
+
+    /// Used to get the current Ethereum block the deadlines of the priority operations
+    /// are compared with.
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
 }
 
 impl ScalerOracle {
-    pub fn new(db: ConnectionPool, idle_provers: u32) -> Self {
-        Self { db, idle_provers }
+    pub fn new(
+        db: ConnectionPool,
+        idle_provers: u32,
+        block_sizes: Vec<usize>,
+        eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    ) -> Self {
+        Self {
+            db,
+            idle_provers,
+            block_sizes,
+            hysteresis: HashMap::new(),
+            eth_watch_req,
+        }
     }
 
-    /// Decides how many prover entities should be created depending on the amount of pending blocks.
-    pub async fn provers_required(&mut self) -> Result<u32, failure::Error> {
-        // Currently the logic of this method is very simple:
-        // We require a prover for each pending block or IDLE_RROVERS amount if there are not so many
-        // pending jobs.
+    /// Decides how many prover entities of each block size should be created.
+    ///
+    /// The amount is estimated from the pending jobs, the average proof generation time
+    /// and the recent rate of the committed blocks. Since blocks are verified in order,
+    /// a high-priority block makes all the blocks before it require a prover of their own.
+    pub async fn provers_required(&mut self) -> Result<BTreeMap<usize, u32>, failure::Error> {
+        let demand = self.load_demand().await?;
+        let smallest_block_size = self.block_sizes.iter().min().copied();
 
-        let mut storage = self.db.access_storage().await?;
-        let pending_jobs = storage.prover_schema().pending_jobs_count().await?;
-        let provers_required = std::cmp::max(pending_jobs, self.idle_provers);
+        let now = Instant::now();
+        let mut provers_required = BTreeMap::new();
+        for (block_size, demand) in demand {
+            let mut replicas = demand.provers_required();
+            if Some(block_size) == smallest_block_size {
+                replicas = std::cmp::max(replicas, self.idle_provers);
+            }
+
+            let replicas = self
+                .hysteresis
+                .entry(block_size)
+                .or_default()
+                .apply(now, replicas);
+            provers_required.insert(block_size, replicas);
+        }
 
         Ok(provers_required)
     }
+
+    /// Returns the oldest blocks awaiting for proof in the order they are handed out to the provers.
+    pub async fn pending_jobs(&mut self) -> Result<Vec<ProverJob>, failure::Error> {
+        let current_eth_block = last_eth_block(&self.eth_watch_req).await?;
+        let mut storage = self.db.access_storage().await?;
+        let pending_jobs = storage
+            .prover_schema()
+            .pending_jobs(Some(PENDING_JOBS_REPORT_LIMIT), current_eth_block)
+            .await?;
+
+        Ok(pending_jobs)
+    }
+
+    /// Gathers the demand for the provers of each supported block size.
+    async fn load_demand(&mut self) -> Result<BTreeMap<usize, BlockSizeDemand>, failure::Error> {
+        let mut demand: BTreeMap<_, _> = self
+            .block_sizes
+            .iter()
+            .map(|&block_size| (block_size, BlockSizeDemand::default()))
+            .collect();
+
+        let current_eth_block = last_eth_block(&self.eth_watch_req).await?;
+        let mut storage = self.db.access_storage().await?;
+        let now = Utc::now();
+
+        let pending_jobs = storage
+            .prover_schema()
+            .pending_jobs_count_by_block_size(current_eth_block)
+            .await?;
+        for (block_size, count) in pending_jobs {
+            let demand = demand.entry(block_size).or_default();
+            demand.pending_jobs = count.total as u32;
+            demand.high_priority_jobs = count.high_priority as u32;
+        }
+
+        let since = now - chrono::Duration::from_std(PROOF_DURATION_WINDOW)?;
+        let proof_durations = storage
+            .prover_schema()
+            .average_proof_durations(since)
+            .await?;
+        for (block_size, duration) in proof_durations {
+            demand.entry(block_size).or_default().proof_duration = Some(duration);
+        }
+
+        let since = now - chrono::Duration::from_std(COMMIT_RATE_WINDOW)?;
+        let commits = storage
+            .prover_schema()
+            .commits_count_by_block_size(since)
+            .await?;
+        for (block_size, count) in commits {
+            demand.entry(block_size).or_default().commit_rate =
+                count as f64 / COMMIT_RATE_WINDOW.as_secs_f64();
+        }
+
+        Ok(demand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provers_required_without_history() {
+        let demand = BlockSizeDemand {
+            pending_jobs: 3,
+            high_priority_jobs: 0,
+            proof_duration: None,
+            commit_rate: 1.0,
+        };
+        assert_eq!(demand.provers_required(), 3);
+    }
+
+    #[test]
+    fn provers_required_with_history() {
+        // Five fast proofs fit into the drain time of a single prover.
+        let demand = BlockSizeDemand {
+            pending_jobs: 5,
+            high_priority_jobs: 0,
+            proof_duration: Some(Duration::from_secs(60)),
+            commit_rate: 0.0,
+        };
+        assert_eq!(demand.provers_required(), 1);
+
+        // Slow proofs require a prover per job, but not more.
+        let demand = BlockSizeDemand {
+            pending_jobs: 2,
+            high_priority_jobs: 0,
+            proof_duration: Some(QUEUE_DRAIN_TIME * 3),
+            commit_rate: 0.0,
+        };
+        assert_eq!(demand.provers_required(), 2);
+
+        // Block committed once a day doesn't keep a prover running.
+        let demand = BlockSizeDemand {
+            pending_jobs: 0,
+            high_priority_jobs: 0,
+            proof_duration: Some(Duration::from_secs(30 * 60)),
+            commit_rate: 1.0 / (24.0 * 60.0 * 60.0),
+        };
+        assert_eq!(demand.provers_required(), 0);
+
+        // A block every two seconds with 6 second proofs keeps 3 provers busy.
+        let demand = BlockSizeDemand {
+            pending_jobs: 0,
+            high_priority_jobs: 0,
+            proof_duration: Some(Duration::from_secs(6)),
+            commit_rate: 0.5,
+        };
+        assert_eq!(demand.provers_required(), 3);
+    }
+
+    #[test]
+    fn provers_required_for_high_priority_jobs() {
+        // Fast proofs would be handled by a single prover, but the blocks
+        // required for a high-priority block are proven in parallel.
+        let demand = BlockSizeDemand {
+            pending_jobs: 5,
+            high_priority_jobs: 3,
+            proof_duration: Some(Duration::from_secs(60)),
+            commit_rate: 0.0,
+        };
+        assert_eq!(demand.provers_required(), 3);
+    }
+
+    #[test]
+    fn hysteresis_delays_scaling_down() {
+        let mut hysteresis = Hysteresis::default();
+        let start = Instant::now();
+
+        assert_eq!(hysteresis.apply(start, 2), 2);
+        // Scaling up is immediate.
+        assert_eq!(hysteresis.apply(start + Duration::from_secs(60), 5), 5);
+        // Scaling down is delayed.
+        let scale_down_at = start + Duration::from_secs(120);
+        assert_eq!(hysteresis.apply(scale_down_at, 1), 5);
+        assert_eq!(
+            hysteresis.apply(
+                scale_down_at + SCALE_DOWN_DELAY - Duration::from_secs(60),
+                1
+            ),
+            5
+        );
+        assert_eq!(
+            hysteresis.apply(scale_down_at + SCALE_DOWN_DELAY + Duration::from_secs(1), 1),
+            1
+        );
+    }
 }
@@ -8,8 +8,8 @@ use log::info;
 // Workspace deps
 use circuit::witness::{
     utils::{SigDataInput, WitnessBuilder},
-    ChangePubkeyOffChainWitness, CloseAccountWitness, DepositWitness, FullExitWitness,
-    TransferToNewWitness, TransferWitness, WithdrawWitness, Witness,
+    ChangePubkeyOffChainWitness, CloseAccountWitness, DepositWitness, ForcedExitWitness,
+    FullExitWitness, SwapWitness, TransferToNewWitness, TransferWitness, WithdrawWitness, Witness,
 };
 use models::node::block::Block;
 use models::params::{account_tree_depth, CHUNK_BIT_WIDTH};
@@ -218,9 +218,9 @@ impl WitnessGenerator {
 
         transaction
             .prover_schema()
-            .store_witness(
+            .store_binary_witness(
                 block.block_number,
-                serde_json::to_value(witness).expect("Witness serialize to json"),
+                &witness.encode_binary().expect("Witness binary encoding"),
             )
             .await?;
 
@@ -355,6 +355,34 @@ async fn build_prover_block_data(
                 });
                 pub_data.extend(withdraw_witness.get_pubdata());
             }
+            FranklinOp::ForcedExit(forced_exit) => {
+                let forced_exit_witness =
+                    ForcedExitWitness::apply_tx(&mut witness_accum.account_tree, &forced_exit);
+
+                let input = SigDataInput::from_forced_exit_op(&forced_exit)
+                    .map_err(|e| format_err!("{}", e))?;
+                let forced_exit_operations = forced_exit_witness.calculate_operations(input);
+
+                operations.extend(forced_exit_operations);
+                fees.push(CollectedFee {
+                    token: forced_exit.tx.token,
+                    amount: forced_exit.tx.fee,
+                });
+                pub_data.extend(forced_exit_witness.get_pubdata());
+            }
+            FranklinOp::Swap(swap) => {
+                let swap_witness = SwapWitness::apply_tx(&mut witness_accum.account_tree, &swap);
+
+                let input = SigDataInput::from_swap_op(&swap).map_err(|e| format_err!("{}", e))?;
+                let swap_operations = swap_witness.calculate_operations(input);
+
+                operations.extend(swap_operations);
+                fees.push(CollectedFee {
+                    token: swap.tx.fee_token,
+                    amount: swap.tx.fee,
+                });
+                pub_data.extend(swap_witness.get_pubdata());
+            }
             FranklinOp::Close(close) => {
                 let close_account_witness =
                     CloseAccountWitness::apply_tx(&mut witness_accum.account_tree, &close);
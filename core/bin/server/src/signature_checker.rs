@@ -88,13 +88,19 @@ async fn verify_eth_signature(
 
     // Check the signature.
     if let Some(sign_data) = &request.eth_sign_data {
+        // Transactions initiated on behalf of another account can't be signed
+        // by an Ethereum account, since the initiator address is not known here.
+        if request.tx.initiator_account_id().is_some() {
+            return Err(TxAddError::IncorrectEthSignature);
+        }
+        let account = request.tx.account();
         match &sign_data.signature {
             TxEthSignature::EthereumSignature(packed_signature) => {
                 let signer_account = packed_signature
                     .signature_recover_signer(sign_data.message.as_bytes())
                     .or(Err(TxAddError::IncorrectEthSignature))?;
 
-                if signer_account != request.tx.account() {
+                if signer_account != account {
                     return Err(TxAddError::IncorrectEthSignature);
                 }
             }
@@ -109,7 +115,7 @@ async fn verify_eth_signature(
                 eth_watch_req
                     .clone()
                     .send(EthWatchRequest::CheckEIP1271Signature {
-                        address: request.tx.account(),
+                        address: account,
                         message: message.into_bytes(),
                         signature: signature.clone(),
                         resp: eth_watch_resp.0,
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, VecDeque};
 // External uses
+use chrono::{DateTime, Utc};
 use futures::{
     channel::{mpsc, oneshot},
     stream::StreamExt,
@@ -27,6 +28,11 @@ use storage::ConnectionPool;
 use crate::{gas_counter::GasCounter, mempool::ProposedBlock};
 use models::node::SignedFranklinTx;
 
+/// Failure reason for the transactions which can't be executed at the moment of
+/// the block creation.
+const INVALID_TIME_RANGE_REASON: &str =
+    "Transaction can't be executed at the current moment due to its time range";
+
 pub enum ExecutedOpId {
     Transaction(TxHash),
     PriorityOp(u64),
@@ -397,7 +403,7 @@ impl PlasmaStateKeeper {
             for operation in pending_block.success_operations {
                 match operation {
                     ExecutedOperations::Tx(tx) => {
-                        self.apply_tx(&tx.signed_tx)
+                        self.apply_tx(&tx.signed_tx, tx.created_at)
                             .expect("Tx from the restored pending block was not executed");
                         txs_count += 1;
                     }
@@ -575,7 +581,7 @@ impl PlasmaStateKeeper {
         while let Some(variant) = tx_queue.pop_front() {
             match &variant {
                 SignedTxVariant::Tx(tx) => {
-                    match self.apply_tx(tx) {
+                    match self.apply_tx(tx, Utc::now()) {
                         Ok(exec_op) => {
                             executed_ops.push(exec_op);
                         }
@@ -686,6 +692,34 @@ impl PlasmaStateKeeper {
         txs: &[SignedFranklinTx],
         batch_id: i64,
     ) -> Result<Vec<ExecutedOperations>, ()> {
+        // Batch is atomic, so if any of its transactions can't be executed at
+        // the current moment, the whole batch is dropped.
+        let now = Utc::now();
+        if txs
+            .iter()
+            .any(|tx| !tx.time_range().is_valid(now.timestamp() as u64))
+        {
+            let mut executed_operations = Vec::new();
+            for tx in txs {
+                let failed_tx = ExecutedTx {
+                    signed_tx: tx.clone(),
+                    success: false,
+                    op: None,
+                    fail_reason: Some(INVALID_TIME_RANGE_REASON.to_string()),
+                    block_index: None,
+                    created_at: now,
+                    batch_id: Some(batch_id),
+                };
+                self.pending_block.failed_txs.push(failed_tx.clone());
+                executed_operations.push(ExecutedOperations::Tx(Box::new(failed_tx)));
+            }
+            warn!(
+                "Failed to execute transactions batch {}: {}",
+                batch_id, INVALID_TIME_RANGE_REASON
+            );
+            return Ok(executed_operations);
+        }
+
         let chunks_needed = self.state.chunks_for_batch(txs);
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
@@ -713,7 +747,10 @@ impl PlasmaStateKeeper {
                 }
             }
 
-            if matches!(&tx.tx, &FranklinTx::Withdraw(_)) {
+            if matches!(
+                &tx.tx,
+                &FranklinTx::Withdraw(_) | &FranklinTx::ForcedExit(_)
+            ) {
                 // Increase amount of the withdraw operations in this block.
                 self.pending_block.withdrawals_amount += 1;
             }
@@ -754,7 +791,7 @@ impl PlasmaStateKeeper {
                         op: Some(executed_op),
                         fail_reason: None,
                         block_index: Some(block_index),
-                        created_at: chrono::Utc::now(),
+                        created_at: now,
                         batch_id: Some(batch_id),
                     }));
                     self.pending_block
@@ -770,7 +807,7 @@ impl PlasmaStateKeeper {
                         op: None,
                         fail_reason: Some(e.to_string()),
                         block_index: None,
-                        created_at: chrono::Utc::now(),
+                        created_at: now,
                         batch_id: Some(batch_id),
                     };
                     self.pending_block.failed_txs.push(failed_tx.clone());
@@ -783,7 +820,36 @@ impl PlasmaStateKeeper {
         Ok(executed_operations)
     }
 
-    fn apply_tx(&mut self, tx: &SignedFranklinTx) -> Result<ExecutedOperations, ()> {
+    /// Executes the transaction as a part of the pending block.
+    ///
+    /// `executed_at` is the moment of the transaction execution, which is checked against
+    /// the transaction time range. For transactions of the restored pending block it must
+    /// be the moment of the original execution.
+    fn apply_tx(
+        &mut self,
+        tx: &SignedFranklinTx,
+        executed_at: DateTime<Utc>,
+    ) -> Result<ExecutedOperations, ()> {
+        // Transactions which can't be executed at the current moment are dropped
+        // without affecting the block limits.
+        if !tx.time_range().is_valid(executed_at.timestamp() as u64) {
+            warn!(
+                "Failed to execute transaction: {:?}, {}",
+                tx, INVALID_TIME_RANGE_REASON
+            );
+            let failed_tx = ExecutedTx {
+                signed_tx: tx.clone(),
+                success: false,
+                op: None,
+                fail_reason: Some(INVALID_TIME_RANGE_REASON.to_string()),
+                block_index: None,
+                created_at: executed_at,
+                batch_id: None,
+            };
+            self.pending_block.failed_txs.push(failed_tx.clone());
+            return Ok(ExecutedOperations::Tx(Box::new(failed_tx)));
+        }
+
         let chunks_needed = self.state.chunks_for_tx(&tx);
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
@@ -810,14 +876,21 @@ impl PlasmaStateKeeper {
             }
         }
 
-        if let FranklinTx::Withdraw(tx) = &tx.tx {
-            // Increase amount of the withdraw operations in this block.
-            self.pending_block.withdrawals_amount += 1;
+        match &tx.tx {
+            FranklinTx::Withdraw(tx) => {
+                // Increase amount of the withdraw operations in this block.
+                self.pending_block.withdrawals_amount += 1;
 
-            // Check if we should mark this block as requiring fast processing.
-            if tx.fast {
-                self.pending_block.fast_processing_required = true;
+                // Check if we should mark this block as requiring fast processing.
+                if tx.fast {
+                    self.pending_block.fast_processing_required = true;
+                }
+            }
+            FranklinTx::ForcedExit(_) => {
+                // Forced exit also results in a withdrawal on L1.
+                self.pending_block.withdrawals_amount += 1;
             }
+            _ => {}
         }
 
         // Check if we've reached the withdraw operations amount limit.
@@ -848,7 +921,7 @@ impl PlasmaStateKeeper {
                     op: Some(executed_op),
                     fail_reason: None,
                     block_index: Some(block_index),
-                    created_at: chrono::Utc::now(),
+                    created_at: executed_at,
                     batch_id: None,
                 }));
                 self.pending_block
@@ -864,7 +937,7 @@ impl PlasmaStateKeeper {
                     op: None,
                     fail_reason: Some(e.to_string()),
                     block_index: None,
-                    created_at: chrono::Utc::now(),
+                    created_at: executed_at,
                     batch_id: None,
                 };
                 self.pending_block.failed_txs.push(failed_tx.clone());
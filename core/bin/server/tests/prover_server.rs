@@ -2,7 +2,7 @@
 use std::{net, str::FromStr, thread, time, time::Duration};
 // External deps
 use crypto_exports::pairing::ff::{Field, PrimeField};
-use futures::channel::mpsc;
+use futures::{channel::mpsc, executor::block_on, StreamExt};
 // Workspace deps
 use circuit::witness::{deposit::DepositWitness, Witness};
 use models::{
@@ -15,7 +15,7 @@ use num::BigUint;
 use prover::{client, ApiClient};
 // Local deps
 use circuit::witness::utils::get_used_subtree_root_hash;
-use server::prover_server;
+use server::{eth_watch::EthWatchRequest, prover_server};
 
 async fn connect_to_db() -> storage::ConnectionPool {
     storage::ConnectionPool::new(Some(1)).await
@@ -23,19 +23,44 @@ async fn connect_to_db() -> storage::ConnectionPool {
 
 async fn spawn_server(prover_timeout: time::Duration, rounds_interval: time::Duration) -> String {
     // TODO: make single server spawn for all tests
-    let bind_to = "127.0.0.1:8088";
+    spawn_server_with_auth("127.0.0.1:8088", prover_timeout, rounds_interval, None).await
+}
+
+async fn spawn_server_with_auth(
+    bind_to: &str,
+    prover_timeout: time::Duration,
+    rounds_interval: time::Duration,
+    secret_auth: Option<String>,
+) -> String {
     let mut config_opt = ConfigurationOptions::from_env();
     config_opt.prover_server_address = net::SocketAddr::from_str(bind_to).unwrap();
+    // Tests use dummy proofs which can't pass the verification.
+    config_opt.prover_server_verify_proofs = false;
+    config_opt.prover_server_secret_auth = secret_auth;
 
     let conn_pool = connect_to_db().await;
     let (tx, _rx) = mpsc::channel(1);
 
+    // Tests call the server with the blocking client, so the eth watcher stub
+    // has to be run in a separate thread. Ethereum blocks don't advance in tests.
+    let (eth_watch_req, mut eth_watch_requests) = mpsc::channel(1);
+    thread::spawn(move || {
+        block_on(async move {
+            while let Some(request) = eth_watch_requests.next().await {
+                if let EthWatchRequest::GetLastEthBlock { resp } = request {
+                    resp.send(0).unwrap_or_default();
+                }
+            }
+        })
+    });
+
     thread::spawn(move || {
         prover_server::start_prover_server(
             conn_pool,
             prover_timeout,
             rounds_interval,
             tx,
+            eth_watch_req,
             config_opt,
         );
     });
@@ -49,6 +74,7 @@ fn client_with_empty_worker_name_panics() {
         &"http:://example.com".parse().unwrap(),
         "",
         Duration::from_secs(1),
+        None,
     );
 }
 
@@ -61,6 +87,7 @@ async fn api_client_register_start_and_stop_of_prover() {
         &format!("http://{}", &addr).parse().unwrap(),
         "foo",
         Duration::from_secs(1),
+        None,
     );
     let id = client
         .register_prover(block_size_chunks)
@@ -99,11 +126,12 @@ async fn api_client_simple_simulation() {
         &format!("http://{}", &addr).parse().unwrap(),
         "foo",
         time::Duration::from_secs(1),
+        None,
     );
 
     // call block_to_prove and check its none
     let to_prove = client
-        .block_to_prove(block_size_chunks)
+        .block_to_prove(block_size_chunks, false)
         .expect("failed to get block to prove");
     assert!(to_prove.is_none());
 
@@ -128,14 +156,14 @@ async fn api_client_simple_simulation() {
 
     // should return block
     let to_prove = client
-        .block_to_prove(block_size_chunks)
+        .block_to_prove(block_size_chunks, false)
         .expect("failed to bet block to prove");
     assert!(to_prove.is_some());
 
     // block is taken unless no heartbeat from prover within prover_timeout period
     // should return None at this moment
     let to_prove = client
-        .block_to_prove(block_size_chunks)
+        .block_to_prove(block_size_chunks, false)
         .expect("failed to get block to prove");
     assert!(to_prove.is_none());
 
@@ -143,7 +171,7 @@ async fn api_client_simple_simulation() {
     thread::sleep(prover_timeout * 10);
 
     let to_prove = client
-        .block_to_prove(block_size_chunks)
+        .block_to_prove(block_size_chunks, false)
         .expect("failed to get block to prove");
     assert!(to_prove.is_some());
 
@@ -153,7 +181,7 @@ async fn api_client_simple_simulation() {
     client.working_on(job).unwrap();
 
     let to_prove = client
-        .block_to_prove(block_size_chunks)
+        .block_to_prove(block_size_chunks, false)
         .expect("failed to get block to prove");
     assert!(to_prove.is_none());
 
@@ -165,6 +193,19 @@ async fn api_client_simple_simulation() {
         prover_data.pub_data_commitment,
         Some(wanted_prover_data.public_data_commitment),
     );
+
+    // Provers which don't support the binary form of witnesses still get JSON.
+    let res = reqwest::blocking::Client::new()
+        .get(&format!("http://{}/prover_data", &addr))
+        .json(&block)
+        .send()
+        .expect("failed to request prover data");
+    let prover_data: Option<prover::prover_data::ProverData> =
+        res.json().expect("failed to parse prover data as JSON");
+    assert_eq!(
+        prover_data.expect("no prover data").public_data_commitment,
+        wanted_prover_data.public_data_commitment,
+    );
 }
 
 pub async fn test_operation_and_wanted_prover_data(
@@ -352,6 +393,7 @@ async fn api_server_publish_dummy() {
     let res = client
         .post(&format!("http://{}/publish", &addr))
         .json(&client::PublishReq {
+            name: "foo".to_string(),
             block: 1,
             proof: EncodedProofPlonk::default(),
         })
@@ -361,3 +403,136 @@ async fn api_server_publish_dummy() {
 
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
+
+/// Waits until the server spawned in a separate thread starts accepting requests.
+async fn wait_for_server(addr: &str) {
+    let client = reqwest::Client::new();
+    for _ in 0..50 {
+        let status = client.get(&format!("http://{}/status", addr)).send().await;
+        if matches!(status, Ok(res) if res.status() == reqwest::StatusCode::OK) {
+            return;
+        }
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+    }
+    panic!("Prover server has not started");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "db_test"), ignore)]
+async fn api_server_auth() {
+    let secret = "prover_server_secret";
+    let addr = spawn_server_with_auth(
+        "127.0.0.1:8089",
+        time::Duration::from_secs(1),
+        time::Duration::from_secs(10),
+        Some(secret.to_string()),
+    )
+    .await;
+    wait_for_server(&addr).await;
+
+    let db_connection = connect_to_db().await;
+    let mut storage = db_connection
+        .access_storage()
+        .await
+        .expect("Failed to connect to db");
+    let token_id = storage
+        .prover_schema()
+        .issue_prover_token("foo")
+        .await
+        .expect("failed to issue prover token");
+    let exp = time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as usize
+        + 3600;
+    let token = prover_server::auth::encode_prover_token(secret, token_id, "foo", exp).unwrap();
+
+    let block_size = ConfigurationOptions::from_env().available_block_chunk_sizes[0];
+    let client = reqwest::Client::new();
+    let register = |name: &str, token: Option<&str>| {
+        let mut request =
+            client
+                .post(&format!("http://{}/register", &addr))
+                .json(&client::ProverReq {
+                    name: name.to_string(),
+                    block_size,
+                });
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send()
+    };
+
+    // Requests without the token are rejected.
+    let res = register("foo", None).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Token signed with another secret is rejected.
+    let forged_token =
+        prover_server::auth::encode_prover_token("another_secret", token_id, "foo", exp).unwrap();
+    let res = register("foo", Some(&forged_token)).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Prover can't act on behalf of the other one.
+    let res = register("bar", Some(&token)).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let res = register("foo", Some(&token)).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    let prover_id: i32 = res.text().await.unwrap().parse().unwrap();
+
+    // Proof can't be published on behalf of the other prover.
+    let res = client
+        .post(&format!("http://{}/publish", &addr))
+        .bearer_auth(&token)
+        .json(&client::PublishReq {
+            name: "bar".to_string(),
+            block: 1,
+            proof: EncodedProofPlonk::default(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Proof can't be published for the block the prover is not working on.
+    let res = client
+        .post(&format!("http://{}/publish", &addr))
+        .bearer_auth(&token)
+        .json(&client::PublishReq {
+            name: "foo".to_string(),
+            block: 1_000_000,
+            proof: EncodedProofPlonk::default(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Heartbeats are accepted only for the jobs assigned to the prover.
+    let res = client
+        .post(&format!("http://{}/working_on", &addr))
+        .bearer_auth(&token)
+        .json(&client::WorkingOnReq { prover_run_id: -1 })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Revoked token is rejected.
+    assert!(storage
+        .prover_schema()
+        .revoke_prover_token(token_id)
+        .await
+        .expect("failed to revoke prover token"));
+    let res = register("foo", Some(&token)).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let res = client
+        .post(&format!("http://{}/stopped", &addr))
+        .bearer_auth(&token)
+        .json(&prover_id)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
@@ -106,8 +106,19 @@ pub struct AllocatedOperationData<E: Engine> {
     pub new_pubkey_hash: CircuitElement<E>,
     pub eth_address: CircuitElement<E>,
     pub pub_nonce: CircuitElement<E>,
+    pub valid_from: CircuitElement<E>,
+    pub valid_until: CircuitElement<E>,
     pub a: CircuitElement<E>,
     pub b: CircuitElement<E>,
+    pub second_amount_packed: CircuitElement<E>,
+    pub second_amount_unpacked: CircuitElement<E>,
+    pub special_accounts: Vec<CircuitElement<E>>,
+    pub special_tokens: Vec<CircuitElement<E>>,
+    pub special_prices: Vec<CircuitElement<E>>,
+    pub special_amounts_packed: Vec<CircuitElement<E>>,
+    pub special_amounts_unpacked: Vec<CircuitElement<E>>,
+    pub second_valid_from: CircuitElement<E>,
+    pub second_valid_until: CircuitElement<E>,
 }
 
 impl<E: RescueEngine> AllocatedOperationData<E> {
@@ -168,16 +179,88 @@ impl<E: RescueEngine> AllocatedOperationData<E> {
             franklin_constants::NONCE_BIT_WIDTH,
         );
 
+        let valid_from = CircuitElement::unsafe_empty_of_some_length(
+            zero_element.clone(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        );
+
+        let valid_until = CircuitElement::unsafe_empty_of_some_length(
+            zero_element.clone(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        );
+
         let a = CircuitElement::unsafe_empty_of_some_length(
             zero_element.clone(),
             franklin_constants::BALANCE_BIT_WIDTH,
         );
 
         let b = CircuitElement::unsafe_empty_of_some_length(
-            zero_element,
+            zero_element.clone(),
+            franklin_constants::BALANCE_BIT_WIDTH,
+        );
+
+        let second_amount_packed = CircuitElement::unsafe_empty_of_some_length(
+            zero_element.clone(),
+            franklin_constants::AMOUNT_EXPONENT_BIT_WIDTH
+                + franklin_constants::AMOUNT_MANTISSA_BIT_WIDTH,
+        );
+
+        let second_amount_unpacked = CircuitElement::unsafe_empty_of_some_length(
+            zero_element.clone(),
             franklin_constants::BALANCE_BIT_WIDTH,
         );
 
+        let special_accounts = vec![
+            CircuitElement::unsafe_empty_of_some_length(
+                zero_element.clone(),
+                franklin_constants::ACCOUNT_ID_BIT_WIDTH,
+            );
+            3
+        ];
+
+        let special_tokens = vec![
+            CircuitElement::unsafe_empty_of_some_length(
+                zero_element.clone(),
+                franklin_constants::TOKEN_BIT_WIDTH,
+            );
+            3
+        ];
+
+        let special_prices = vec![
+            CircuitElement::unsafe_empty_of_some_length(
+                zero_element.clone(),
+                franklin_constants::PRICE_BIT_WIDTH,
+            );
+            4
+        ];
+
+        let special_amounts_packed = vec![
+            CircuitElement::unsafe_empty_of_some_length(
+                zero_element.clone(),
+                franklin_constants::AMOUNT_EXPONENT_BIT_WIDTH
+                    + franklin_constants::AMOUNT_MANTISSA_BIT_WIDTH,
+            );
+            2
+        ];
+
+        let special_amounts_unpacked = vec![
+            CircuitElement::unsafe_empty_of_some_length(
+                zero_element.clone(),
+                franklin_constants::BALANCE_BIT_WIDTH,
+            );
+            2
+        ];
+
+        let second_valid_from = CircuitElement::unsafe_empty_of_some_length(
+            zero_element.clone(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        );
+
+        let second_valid_until = CircuitElement::unsafe_empty_of_some_length(
+            zero_element,
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        );
+
         Ok(AllocatedOperationData {
             eth_address,
             pub_nonce,
@@ -190,8 +273,19 @@ impl<E: RescueEngine> AllocatedOperationData<E> {
             second_sig_msg,
             third_sig_msg,
             new_pubkey_hash,
+            valid_from,
+            valid_until,
             a,
             b,
+            second_amount_packed,
+            second_amount_unpacked,
+            special_accounts,
+            special_tokens,
+            special_prices,
+            special_amounts_packed,
+            special_amounts_unpacked,
+            second_valid_from,
+            second_valid_until,
         })
     }
 
@@ -277,6 +371,16 @@ impl<E: RescueEngine> AllocatedOperationData<E> {
             || op.args.pub_nonce.grab(),
             franklin_constants::NONCE_BIT_WIDTH,
         )?;
+        let valid_from = CircuitElement::from_fe_with_known_length(
+            cs.namespace(|| "valid_from"),
+            || op.args.valid_from.grab(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        )?;
+        let valid_until = CircuitElement::from_fe_with_known_length(
+            cs.namespace(|| "valid_until"),
+            || op.args.valid_until.grab(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        )?;
         let a = CircuitElement::from_fe_with_known_length(
             cs.namespace(|| "a"),
             || op.args.a.grab(),
@@ -288,6 +392,89 @@ impl<E: RescueEngine> AllocatedOperationData<E> {
             franklin_constants::BALANCE_BIT_WIDTH,
         )?;
 
+        let second_amount_packed = CircuitElement::from_fe_with_known_length(
+            cs.namespace(|| "second_amount_packed"),
+            || op.args.second_amount_packed.grab(),
+            franklin_constants::AMOUNT_EXPONENT_BIT_WIDTH
+                + franklin_constants::AMOUNT_MANTISSA_BIT_WIDTH,
+        )?;
+        let second_amount_unpacked =
+            unpack_amount(cs.namespace(|| "second_amount"), &second_amount_packed)?;
+
+        assert_eq!(op.args.special_accounts.len(), 3);
+        let special_accounts = op
+            .args
+            .special_accounts
+            .iter()
+            .enumerate()
+            .map(|(i, account)| {
+                CircuitElement::from_fe_with_known_length(
+                    cs.namespace(|| format!("special_account {}", i)),
+                    || account.grab(),
+                    franklin_constants::ACCOUNT_ID_BIT_WIDTH,
+                )
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        assert_eq!(op.args.special_tokens.len(), 3);
+        let special_tokens = op
+            .args
+            .special_tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                CircuitElement::from_fe_with_known_length(
+                    cs.namespace(|| format!("special_token {}", i)),
+                    || token.grab(),
+                    franklin_constants::TOKEN_BIT_WIDTH,
+                )
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        assert_eq!(op.args.special_prices.len(), 4);
+        let special_prices = op
+            .args
+            .special_prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                CircuitElement::from_fe_with_known_length(
+                    cs.namespace(|| format!("special_price {}", i)),
+                    || price.grab(),
+                    franklin_constants::PRICE_BIT_WIDTH,
+                )
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        assert_eq!(op.args.special_amounts.len(), 2);
+        let mut special_amounts_packed = vec![];
+        let mut special_amounts_unpacked = vec![];
+        for (i, amount) in op.args.special_amounts.iter().enumerate() {
+            let amount_packed = CircuitElement::from_fe_with_known_length(
+                cs.namespace(|| format!("special_amount_packed {}", i)),
+                || amount.grab(),
+                franklin_constants::AMOUNT_EXPONENT_BIT_WIDTH
+                    + franklin_constants::AMOUNT_MANTISSA_BIT_WIDTH,
+            )?;
+            let amount_unpacked = unpack_amount(
+                cs.namespace(|| format!("special_amount {}", i)),
+                &amount_packed,
+            )?;
+            special_amounts_packed.push(amount_packed);
+            special_amounts_unpacked.push(amount_unpacked);
+        }
+
+        let second_valid_from = CircuitElement::from_fe_with_known_length(
+            cs.namespace(|| "second_valid_from"),
+            || op.args.second_valid_from.grab(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        )?;
+        let second_valid_until = CircuitElement::from_fe_with_known_length(
+            cs.namespace(|| "second_valid_until"),
+            || op.args.second_valid_until.grab(),
+            franklin_constants::TIMESTAMP_BIT_WIDTH,
+        )?;
+
         Ok(AllocatedOperationData {
             eth_address,
             pub_nonce,
@@ -300,8 +487,38 @@ impl<E: RescueEngine> AllocatedOperationData<E> {
             second_sig_msg,
             third_sig_msg,
             new_pubkey_hash,
+            valid_from,
+            valid_until,
             a,
             b,
+            second_amount_packed,
+            second_amount_unpacked,
+            special_accounts,
+            special_tokens,
+            special_prices,
+            special_amounts_packed,
+            special_amounts_unpacked,
+            second_valid_from,
+            second_valid_until,
         })
     }
 }
+
+/// Unpacks the amount encoded with the token amount packing into the `BALANCE_BIT_WIDTH` element.
+fn unpack_amount<E: RescueEngine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    amount_packed: &CircuitElement<E>,
+) -> Result<CircuitElement<E>, SynthesisError> {
+    let amount_parsed = parse_with_exponent_le(
+        cs.namespace(|| "parse amount"),
+        &amount_packed.get_bits_le(),
+        franklin_constants::AMOUNT_EXPONENT_BIT_WIDTH,
+        franklin_constants::AMOUNT_MANTISSA_BIT_WIDTH,
+        10,
+    )?;
+    CircuitElement::from_number_with_known_length(
+        cs.namespace(|| "amount"),
+        amount_parsed,
+        franklin_constants::BALANCE_BIT_WIDTH,
+    )
+}
@@ -20,9 +20,13 @@ use crypto_exports::franklin_crypto::{
 use models::{
     node::{
         operations::{ChangePubKeyOp, NoopOp},
-        CloseOp, DepositOp, FullExitOp, TransferOp, TransferToNewOp, WithdrawOp,
+        CloseOp, DepositOp, ForcedExitOp, FullExitOp, Order, SwapOp, TransferOp, TransferToNewOp,
+        WithdrawOp,
+    },
+    params::{
+        self, FR_BIT_WIDTH_PADDED, SIGNED_FORCED_EXIT_BIT_WIDTH, SIGNED_ORDER_BIT_WIDTH,
+        SIGNED_SWAP_BIT_WIDTH, SIGNED_TRANSFER_BIT_WIDTH,
     },
-    params::{self, FR_BIT_WIDTH_PADDED, SIGNED_TRANSFER_BIT_WIDTH},
 };
 // Local deps
 use crate::{
@@ -41,7 +45,7 @@ use crate::{
     },
 };
 
-const DIFFERENT_TRANSACTIONS_TYPE_NUMBER: usize = 8;
+const DIFFERENT_TRANSACTIONS_TYPE_NUMBER: usize = 10;
 pub struct FranklinCircuit<'a, E: RescueEngine + JubjubEngine> {
     pub rescue_params: &'a <E as RescueEngine>::Params,
     pub jubjub_params: &'a <E as JubjubEngine>::Params,
@@ -168,6 +172,8 @@ impl<'a, E: RescueEngine + JubjubEngine> Circuit<E> for FranklinCircuit<'a, E> {
             data[WithdrawOp::OP_CODE as usize] = vec![zero.clone(); 2];
             data[FullExitOp::OP_CODE as usize] = vec![zero.clone(); 2];
             data[ChangePubKeyOp::OP_CODE as usize] = vec![zero.clone(); 2];
+            data[ForcedExitOp::OP_CODE as usize] = vec![zero.clone(); 2];
+            data[SwapOp::OP_CODE as usize] = vec![zero.clone(); 2];
 
             // this operation is disabled for now
             // data[CloseOp::OP_CODE as usize] = vec![];
@@ -730,6 +736,62 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
                 &op_data.full_amount,
                 &prev.op_data.full_amount,
             )?);
+            is_op_data_correct_flags.push(CircuitElement::equals(
+                cs.namespace(|| "is valid_from equal to previous"),
+                &op_data.valid_from,
+                &prev.op_data.valid_from,
+            )?);
+            is_op_data_correct_flags.push(CircuitElement::equals(
+                cs.namespace(|| "is valid_until equal to previous"),
+                &op_data.valid_until,
+                &prev.op_data.valid_until,
+            )?);
+            is_op_data_correct_flags.push(CircuitElement::equals(
+                cs.namespace(|| "is second_amount_packed equal to previous"),
+                &op_data.second_amount_packed,
+                &prev.op_data.second_amount_packed,
+            )?);
+            is_op_data_correct_flags.push(CircuitElement::equals(
+                cs.namespace(|| "is second_valid_from equal to previous"),
+                &op_data.second_valid_from,
+                &prev.op_data.second_valid_from,
+            )?);
+            is_op_data_correct_flags.push(CircuitElement::equals(
+                cs.namespace(|| "is second_valid_until equal to previous"),
+                &op_data.second_valid_until,
+                &prev.op_data.second_valid_until,
+            )?);
+            let special_values = [
+                (
+                    "special_accounts",
+                    &op_data.special_accounts,
+                    &prev.op_data.special_accounts,
+                ),
+                (
+                    "special_tokens",
+                    &op_data.special_tokens,
+                    &prev.op_data.special_tokens,
+                ),
+                (
+                    "special_prices",
+                    &op_data.special_prices,
+                    &prev.op_data.special_prices,
+                ),
+                (
+                    "special_amounts",
+                    &op_data.special_amounts_packed,
+                    &prev.op_data.special_amounts_packed,
+                ),
+            ];
+            for (name, values, prev_values) in special_values.iter() {
+                for (i, (value, prev_value)) in values.iter().zip(prev_values.iter()).enumerate() {
+                    is_op_data_correct_flags.push(CircuitElement::equals(
+                        cs.namespace(|| format!("is {} {} equal to previous", name, i)),
+                        value,
+                        prev_value,
+                    )?);
+                }
+            }
 
             let is_op_data_equal_to_previous = multi_and(
                 cs.namespace(|| "is_op_data_equal_to_previous"),
@@ -862,6 +924,32 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
             &mut previous_pubdatas[ChangePubKeyOp::OP_CODE as usize],
             explicit_zero,
         )?);
+        op_flags.push(self.forced_exit(
+            cs.namespace(|| "forced_exit"),
+            &mut cur,
+            &lhs,
+            &rhs,
+            &chunk_data,
+            &is_a_geq_b,
+            &is_account_empty,
+            &op_data,
+            &signer_key,
+            &ext_pubdata_chunk,
+            &signature_data.is_verified,
+            &mut previous_pubdatas[ForcedExitOp::OP_CODE as usize],
+            &explicit_zero,
+        )?);
+        op_flags.push(self.swap(
+            cs.namespace(|| "swap"),
+            &mut cur,
+            &chunk_data,
+            &is_a_geq_b,
+            &op_data,
+            &signer_key,
+            &ext_pubdata_chunk,
+            &signature_data.is_verified,
+            &mut previous_pubdatas[SwapOp::OP_CODE as usize],
+        )?);
         op_flags.push(self.noop(
             cs.namespace(|| "noop"),
             &chunk_data,
@@ -981,6 +1069,8 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
         serialized_tx_bits.extend(op_data.full_amount.get_bits_be());
         serialized_tx_bits.extend(op_data.fee_packed.get_bits_be());
         serialized_tx_bits.extend(cur.account.nonce.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_from.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_until.get_bits_be());
         assert_eq!(serialized_tx_bits.len(), params::SIGNED_WITHDRAW_BIT_WIDTH);
 
         let pubdata_chunk = select_pubdata_chunk(
@@ -1111,6 +1201,224 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
         Ok(tx_valid)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn forced_exit<CS: ConstraintSystem<E>>(
+        &self,
+        mut cs: CS,
+        cur: &mut AllocatedOperationBranch<E>,
+        lhs: &AllocatedOperationBranch<E>,
+        rhs: &AllocatedOperationBranch<E>,
+        chunk_data: &AllocatedChunkData<E>,
+        is_a_geq_b: &Boolean,
+        is_account_empty: &Boolean,
+        op_data: &AllocatedOperationData<E>,
+        signer_key: &AllocatedSignerPubkey<E>,
+        ext_pubdata_chunk: &AllocatedNum<E>,
+        is_sig_verified: &Boolean,
+        pubdata_holder: &mut Vec<AllocatedNum<E>>,
+        explicit_zero: &AllocatedNum<E>,
+    ) -> Result<Boolean, SynthesisError> {
+        assert!(
+            !pubdata_holder.is_empty(),
+            "pubdata holder has to be preallocated"
+        );
+
+        // construct pubdata
+        let mut pubdata_bits = vec![];
+        pubdata_bits.extend(chunk_data.tx_type.get_bits_be()); //TX_TYPE_BIT_WIDTH=8
+        pubdata_bits.extend(lhs.account_id.get_bits_be()); //ACCOUNT_ID_BIT_WIDTH=32
+        pubdata_bits.extend(rhs.account_id.get_bits_be()); //ACCOUNT_ID_BIT_WIDTH=32
+        pubdata_bits.extend(cur.token.get_bits_be()); //TOKEN_BIT_WIDTH=16
+        pubdata_bits.extend(op_data.full_amount.get_bits_be()); //BALANCE_BIT_WIDTH=128
+        pubdata_bits.extend(op_data.fee_packed.get_bits_be()); //FEE_PACKED=16
+        pubdata_bits.extend(op_data.eth_address.get_bits_be()); //ETH_ADDRESS=160
+
+        resize_grow_only(
+            &mut pubdata_bits,
+            ForcedExitOp::CHUNKS * params::CHUNK_BIT_WIDTH,
+            Boolean::constant(false),
+        );
+
+        let (is_equal_pubdata, packed_pubdata) = vectorized_compare(
+            cs.namespace(|| "compare pubdata"),
+            &*pubdata_holder,
+            &pubdata_bits,
+        )?;
+
+        *pubdata_holder = packed_pubdata;
+
+        // construct signature message preimage (serialized_tx)
+
+        let mut serialized_tx_bits = vec![];
+
+        serialized_tx_bits.extend(chunk_data.tx_type.get_bits_be());
+        serialized_tx_bits.extend(lhs.account_id.get_bits_be());
+        serialized_tx_bits.extend(op_data.eth_address.get_bits_be());
+        serialized_tx_bits.extend(cur.token.get_bits_be());
+        serialized_tx_bits.extend(op_data.fee_packed.get_bits_be());
+        serialized_tx_bits.extend(cur.account.nonce.get_bits_be());
+        assert_eq!(serialized_tx_bits.len(), SIGNED_FORCED_EXIT_BIT_WIDTH);
+
+        let pubdata_chunk = select_pubdata_chunk(
+            cs.namespace(|| "select_pubdata_chunk"),
+            &pubdata_bits,
+            &chunk_data.chunk_number,
+            ForcedExitOp::CHUNKS,
+        )?;
+        let is_pubdata_chunk_correct = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_pubdata_correct"),
+            &pubdata_chunk,
+            ext_pubdata_chunk,
+        )?);
+
+        // verify correct tx_code
+        let is_forced_exit = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_forced_exit"),
+            &chunk_data.tx_type.get_number(),
+            Expression::u64::<CS>(u64::from(ForcedExitOp::OP_CODE)),
+        )?);
+
+        let is_first_chunk = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_first_chunk"),
+            &chunk_data.chunk_number,
+            Expression::constant::<CS>(E::Fr::zero()),
+        )?);
+
+        let is_second_chunk = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_second_chunk"),
+            &chunk_data.chunk_number,
+            Expression::u64::<CS>(1),
+        )?);
+
+        let pubdata_properly_copied = boolean_or(
+            cs.namespace(|| "first chunk or pubdata is copied properly"),
+            &is_first_chunk,
+            &is_equal_pubdata,
+        )?;
+
+        let is_base_valid = multi_and(
+            cs.namespace(|| "valid base forced_exit"),
+            &[
+                is_pubdata_chunk_correct,
+                is_forced_exit,
+                pubdata_properly_copied,
+            ],
+        )?;
+
+        // lhs: initiator of the forced exit pays the fee
+        let mut lhs_valid_flags = vec![];
+        lhs_valid_flags.push(is_base_valid.clone());
+        lhs_valid_flags.push(is_first_chunk.clone());
+
+        // check operation arguments
+        let is_a_correct =
+            CircuitElement::equals(cs.namespace(|| "is_a_correct"), &op_data.a, &cur.balance)?;
+        lhs_valid_flags.push(is_a_correct);
+
+        let is_b_correct = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_b_correct"),
+            &op_data.b.get_number(),
+            &op_data.fee.get_number(),
+        )?);
+        lhs_valid_flags.push(is_b_correct);
+        lhs_valid_flags.push(is_a_geq_b.clone());
+        lhs_valid_flags.push(is_sig_verified.clone());
+        lhs_valid_flags.push(no_nonce_overflow(
+            cs.namespace(|| "no nonce overflow"),
+            &cur.account.nonce.get_number(),
+        )?);
+
+        let is_serialized_tx_correct = verify_signature_message_construction(
+            cs.namespace(|| "is_serialized_tx_correct"),
+            serialized_tx_bits,
+            &op_data,
+        )?;
+        lhs_valid_flags.push(is_serialized_tx_correct);
+
+        let is_signer_valid = CircuitElement::equals(
+            cs.namespace(|| "signer_key_correct"),
+            &signer_key.pubkey.get_hash(),
+            &lhs.account.pub_key_hash,
+        )?;
+        lhs_valid_flags.push(is_signer_valid);
+
+        let lhs_valid = multi_and(cs.namespace(|| "lhs_valid"), &lhs_valid_flags)?;
+
+        let updated_balance = Expression::from(&cur.balance.get_number())
+            - Expression::from(&op_data.fee.get_number());
+
+        let updated_nonce =
+            Expression::from(&cur.account.nonce.get_number()) + Expression::u64::<CS>(1);
+
+        //update cur values if lhs is valid
+        //update nonce
+        cur.account.nonce = CircuitElement::conditionally_select_with_number_strict(
+            cs.namespace(|| "update cur nonce"),
+            updated_nonce,
+            &cur.account.nonce,
+            &lhs_valid,
+        )?;
+
+        //update balance
+        cur.balance = CircuitElement::conditionally_select_with_number_strict(
+            cs.namespace(|| "updated cur balance"),
+            updated_balance,
+            &cur.balance,
+            &lhs_valid,
+        )?;
+
+        // rhs: target account has no signing key set and is withdrawn completely
+        let mut rhs_valid_flags = vec![];
+        rhs_valid_flags.push(is_base_valid.clone());
+        rhs_valid_flags.push(is_second_chunk.clone());
+        rhs_valid_flags.push(is_account_empty.not());
+
+        let is_pubkey_hash_zero = Boolean::from(AllocatedNum::equals(
+            cs.namespace(|| "is_target_pubkey_hash_zero"),
+            &cur.account.pub_key_hash.get_number(),
+            &explicit_zero,
+        )?);
+        rhs_valid_flags.push(is_pubkey_hash_zero);
+
+        let is_address_correct = CircuitElement::equals(
+            cs.namespace(|| "is_target_address_correct"),
+            &cur.account.address,
+            &op_data.eth_address,
+        )?;
+        rhs_valid_flags.push(is_address_correct);
+
+        let is_withdrawn_amount_correct = CircuitElement::equals(
+            cs.namespace(|| "is_withdrawn_amount_correct"),
+            &cur.balance,
+            &op_data.full_amount,
+        )?;
+        rhs_valid_flags.push(is_withdrawn_amount_correct);
+
+        let is_rhs_valid = multi_and(cs.namespace(|| "is_rhs_valid"), &rhs_valid_flags)?;
+
+        //update balance
+        cur.balance = CircuitElement::conditionally_select_with_number_strict(
+            cs.namespace(|| "updated_balance rhs"),
+            Expression::constant::<CS>(E::Fr::zero()),
+            &cur.balance,
+            &is_rhs_valid,
+        )?;
+
+        // ohs: rest of the chunks only carry pubdata
+        let is_ohs_valid = multi_and(
+            cs.namespace(|| "is_ohs_valid"),
+            &[is_base_valid, is_first_chunk.not(), is_second_chunk.not()],
+        )?;
+
+        // Exactly one of LHS, RHS or OHS is correct (due to chunking)
+        let tx_valid = multi_or(
+            cs.namespace(|| "tx_valid"),
+            &[lhs_valid, is_rhs_valid, is_ohs_valid],
+        )?;
+
+        Ok(tx_valid)
+    }
+
     fn full_exit<CS: ConstraintSystem<E>>(
         &self,
         mut cs: CS,
@@ -1545,6 +1853,371 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
         Ok(tx_valid)
     }
 
+    /// Swap is processed in five chunks, each of them updates a single leaf:
+    /// - chunk 0: submitter pays the fee;
+    /// - chunk 1: account of the first order is charged with `amount` of the first token;
+    /// - chunk 2: account of the second order is charged with `second_amount` of the second token;
+    /// - chunk 3: account of the first order receives `second_amount` of the second token;
+    /// - chunk 4: account of the second order receives `amount` of the first token.
+    #[allow(clippy::too_many_arguments)]
+    fn swap<CS: ConstraintSystem<E>>(
+        &self,
+        mut cs: CS,
+        cur: &mut AllocatedOperationBranch<E>,
+        chunk_data: &AllocatedChunkData<E>,
+        is_a_geq_b: &Boolean,
+        op_data: &AllocatedOperationData<E>,
+        signer_key: &AllocatedSignerPubkey<E>,
+        ext_pubdata_chunk: &AllocatedNum<E>,
+        is_sig_verified: &Boolean,
+        pubdata_holder: &mut Vec<AllocatedNum<E>>,
+    ) -> Result<Boolean, SynthesisError> {
+        assert!(
+            !pubdata_holder.is_empty(),
+            "pubdata holder has to be preallocated"
+        );
+
+        let submitter = &op_data.special_accounts[0];
+        let (first_account, second_account) =
+            (&op_data.special_accounts[1], &op_data.special_accounts[2]);
+        let (first_token, second_token, fee_token) = (
+            &op_data.special_tokens[0],
+            &op_data.special_tokens[1],
+            &op_data.special_tokens[2],
+        );
+        let first_amount = &op_data.amount_unpacked;
+        let second_amount = &op_data.second_amount_unpacked;
+
+        // construct pubdata
+        let mut pubdata_bits = vec![];
+        pubdata_bits.extend(chunk_data.tx_type.get_bits_be()); //TX_TYPE_BIT_WIDTH=8
+        pubdata_bits.extend(submitter.get_bits_be()); //ACCOUNT_ID_BIT_WIDTH=32
+        pubdata_bits.extend(first_account.get_bits_be()); //ACCOUNT_ID_BIT_WIDTH=32
+        pubdata_bits.extend(second_account.get_bits_be()); //ACCOUNT_ID_BIT_WIDTH=32
+        pubdata_bits.extend(first_token.get_bits_be()); //TOKEN_BIT_WIDTH=16
+        pubdata_bits.extend(second_token.get_bits_be()); //TOKEN_BIT_WIDTH=16
+        pubdata_bits.extend(fee_token.get_bits_be()); //TOKEN_BIT_WIDTH=16
+        pubdata_bits.extend(op_data.amount_packed.get_bits_be()); //AMOUNT_PACKED=40
+        pubdata_bits.extend(op_data.second_amount_packed.get_bits_be()); //AMOUNT_PACKED=40
+        pubdata_bits.extend(op_data.fee_packed.get_bits_be()); //FEE_PACKED=16
+
+        resize_grow_only(
+            &mut pubdata_bits,
+            SwapOp::CHUNKS * params::CHUNK_BIT_WIDTH,
+            Boolean::constant(false),
+        );
+
+        let (is_equal_pubdata, packed_pubdata) = vectorized_compare(
+            cs.namespace(|| "compare pubdata"),
+            &*pubdata_holder,
+            &pubdata_bits,
+        )?;
+
+        *pubdata_holder = packed_pubdata;
+
+        let pubdata_chunk = select_pubdata_chunk(
+            cs.namespace(|| "select_pubdata_chunk"),
+            &pubdata_bits,
+            &chunk_data.chunk_number,
+            SwapOp::CHUNKS,
+        )?;
+        let is_pubdata_chunk_correct = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_pubdata_correct"),
+            &pubdata_chunk,
+            ext_pubdata_chunk,
+        )?);
+
+        // verify correct tx_code
+        let is_swap = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_swap"),
+            &chunk_data.tx_type.get_number(),
+            Expression::u64::<CS>(u64::from(SwapOp::OP_CODE)),
+        )?);
+
+        let mut is_chunk = vec![];
+        for i in 0..SwapOp::CHUNKS {
+            is_chunk.push(Boolean::from(Expression::equals(
+                cs.namespace(|| format!("is_chunk_{}", i)),
+                &chunk_data.chunk_number,
+                Expression::u64::<CS>(i as u64),
+            )?));
+        }
+
+        let pubdata_properly_copied = boolean_or(
+            cs.namespace(|| "first chunk or pubdata is copied properly"),
+            &is_chunk[0],
+            &is_equal_pubdata,
+        )?;
+
+        let is_base_valid = multi_and(
+            cs.namespace(|| "valid base swap"),
+            &[is_pubdata_chunk_correct, is_swap, pubdata_properly_copied],
+        )?;
+
+        // (account, token) pair that has to be updated by every chunk
+        let expected_leaves = [
+            (submitter, fee_token),
+            (first_account, first_token),
+            (second_account, second_token),
+            (first_account, second_token),
+            (second_account, first_token),
+        ];
+        let mut chunk_base_valid = vec![];
+        for (i, (account, token)) in expected_leaves.iter().enumerate() {
+            let is_account_correct = CircuitElement::equals(
+                cs.namespace(|| format!("is account correct in chunk {}", i)),
+                &cur.account_id,
+                account,
+            )?;
+            let is_token_correct = CircuitElement::equals(
+                cs.namespace(|| format!("is token correct in chunk {}", i)),
+                &cur.token,
+                token,
+            )?;
+            chunk_base_valid.push(multi_and(
+                cs.namespace(|| format!("is chunk {} base valid", i)),
+                &[
+                    is_base_valid.clone(),
+                    is_chunk[i].clone(),
+                    is_account_correct,
+                    is_token_correct,
+                ],
+            )?);
+        }
+
+        let is_signer_valid = CircuitElement::equals(
+            cs.namespace(|| "signer_key_correct"),
+            &signer_key.pubkey.get_hash(),
+            &cur.account.pub_key_hash,
+        )?;
+        let is_nonce_valid = no_nonce_overflow(
+            cs.namespace(|| "no nonce overflow"),
+            &cur.account.nonce.get_number(),
+        )?;
+
+        // construct signature message preimage (serialized_tx) for the current chunk:
+        // swap itself is signed by the submitter, and the orders by their owners
+        let mut swap_tx_bits = vec![];
+        swap_tx_bits.extend(chunk_data.tx_type.get_bits_be());
+        swap_tx_bits.extend(cur.account_id.get_bits_be());
+        swap_tx_bits.extend(cur.account.address.get_bits_be());
+        swap_tx_bits.extend(cur.account.nonce.get_bits_be());
+        swap_tx_bits.extend(first_account.get_bits_be());
+        swap_tx_bits.extend(second_account.get_bits_be());
+        swap_tx_bits.extend(first_token.get_bits_be());
+        swap_tx_bits.extend(second_token.get_bits_be());
+        swap_tx_bits.extend(op_data.amount_packed.get_bits_be());
+        swap_tx_bits.extend(op_data.second_amount_packed.get_bits_be());
+        swap_tx_bits.extend(fee_token.get_bits_be());
+        swap_tx_bits.extend(op_data.fee_packed.get_bits_be());
+        assert_eq!(swap_tx_bits.len(), SIGNED_SWAP_BIT_WIDTH);
+
+        let order_msg_type_bits = (0..params::TX_TYPE_BIT_WIDTH)
+            .rev()
+            .map(|i| Boolean::constant((Order::MSG_TYPE >> i) & 1 == 1))
+            .collect::<Vec<_>>();
+        let order_tx_bits = |token_sell: &CircuitElement<E>,
+                             token_buy: &CircuitElement<E>,
+                             prices: &[CircuitElement<E>],
+                             amount_packed: &CircuitElement<E>,
+                             valid_from: &CircuitElement<E>,
+                             valid_until: &CircuitElement<E>| {
+            let mut order_bits = order_msg_type_bits.clone();
+            order_bits.extend(cur.account_id.get_bits_be());
+            order_bits.extend(cur.account.address.get_bits_be());
+            order_bits.extend(cur.account.nonce.get_bits_be());
+            order_bits.extend(token_sell.get_bits_be());
+            order_bits.extend(token_buy.get_bits_be());
+            order_bits.extend(prices[0].get_bits_be());
+            order_bits.extend(prices[1].get_bits_be());
+            order_bits.extend(amount_packed.get_bits_be());
+            order_bits.extend(valid_from.get_bits_be());
+            order_bits.extend(valid_until.get_bits_be());
+            assert_eq!(order_bits.len(), SIGNED_ORDER_BIT_WIDTH);
+            order_bits
+        };
+        let first_order_bits = order_tx_bits(
+            first_token,
+            second_token,
+            &op_data.special_prices[0..2],
+            &op_data.special_amounts_packed[0],
+            &op_data.valid_from,
+            &op_data.valid_until,
+        );
+        let second_order_bits = order_tx_bits(
+            second_token,
+            first_token,
+            &op_data.special_prices[2..4],
+            &op_data.special_amounts_packed[1],
+            &op_data.second_valid_from,
+            &op_data.second_valid_until,
+        );
+
+        // chunk 0: submitter pays the fee
+        let is_swap_msg_correct = verify_signature_message_construction(
+            cs.namespace(|| "is swap serialized tx correct"),
+            swap_tx_bits,
+            &op_data,
+        )?;
+        let is_a_correct =
+            CircuitElement::equals(cs.namespace(|| "is_a_correct"), &op_data.a, &cur.balance)?;
+        let is_b_correct = Boolean::from(Expression::equals(
+            cs.namespace(|| "is_b_correct"),
+            &op_data.b.get_number(),
+            &op_data.fee.get_number(),
+        )?);
+        let is_fee_chunk_valid = multi_and(
+            cs.namespace(|| "is fee chunk valid"),
+            &[
+                chunk_base_valid[0].clone(),
+                is_swap_msg_correct,
+                is_sig_verified.clone(),
+                is_signer_valid.clone(),
+                is_nonce_valid.clone(),
+                is_a_correct,
+                is_b_correct,
+                is_a_geq_b.clone(),
+            ],
+        )?;
+
+        // chunk 1: account of the first order sells `amount` of the first token
+        let is_first_order_msg_correct = verify_signature_message_construction(
+            cs.namespace(|| "is first order serialized tx correct"),
+            first_order_bits,
+            &op_data,
+        )?;
+        // Orders are filled completely by a single swap.
+        let is_first_amount_valid = CircuitElement::equals(
+            cs.namespace(|| "first order amount is filled"),
+            &op_data.special_amounts_unpacked[0],
+            first_amount,
+        )?;
+        let is_first_amount_nonzero = Boolean::from(Expression::equals(
+            cs.namespace(|| "is first amount zero"),
+            &first_amount.get_number(),
+            Expression::constant::<CS>(E::Fr::zero()),
+        )?)
+        .not();
+        let is_first_balance_enough = is_geq(
+            cs.namespace(|| "first account balance is enough"),
+            &cur.balance.get_number(),
+            &first_amount.get_number(),
+            params::BALANCE_BIT_WIDTH,
+        )?;
+        let is_first_price_valid = is_price_satisfied(
+            cs.namespace(|| "first order price is satisfied"),
+            first_amount,
+            second_amount,
+            &op_data.special_prices[0..2],
+        )?;
+        let is_first_sell_chunk_valid = multi_and(
+            cs.namespace(|| "is first sell chunk valid"),
+            &[
+                chunk_base_valid[1].clone(),
+                is_first_order_msg_correct,
+                is_sig_verified.clone(),
+                is_signer_valid.clone(),
+                is_nonce_valid.clone(),
+                is_first_amount_valid,
+                is_first_amount_nonzero,
+                is_first_balance_enough,
+                is_first_price_valid,
+            ],
+        )?;
+
+        // chunk 2: account of the second order sells `second_amount` of the second token
+        let is_second_order_msg_correct = verify_signature_message_construction(
+            cs.namespace(|| "is second order serialized tx correct"),
+            second_order_bits,
+            &op_data,
+        )?;
+        let is_second_amount_valid = CircuitElement::equals(
+            cs.namespace(|| "second order amount is filled"),
+            &op_data.special_amounts_unpacked[1],
+            second_amount,
+        )?;
+        let is_second_amount_nonzero = Boolean::from(Expression::equals(
+            cs.namespace(|| "is second amount zero"),
+            &second_amount.get_number(),
+            Expression::constant::<CS>(E::Fr::zero()),
+        )?)
+        .not();
+        let is_second_balance_enough = is_geq(
+            cs.namespace(|| "second account balance is enough"),
+            &cur.balance.get_number(),
+            &second_amount.get_number(),
+            params::BALANCE_BIT_WIDTH,
+        )?;
+        let is_second_price_valid = is_price_satisfied(
+            cs.namespace(|| "second order price is satisfied"),
+            second_amount,
+            first_amount,
+            &op_data.special_prices[2..4],
+        )?;
+        let is_second_sell_chunk_valid = multi_and(
+            cs.namespace(|| "is second sell chunk valid"),
+            &[
+                chunk_base_valid[2].clone(),
+                is_second_order_msg_correct,
+                is_sig_verified.clone(),
+                is_signer_valid,
+                is_nonce_valid,
+                is_second_amount_valid,
+                is_second_amount_nonzero,
+                is_second_balance_enough,
+                is_second_price_valid,
+            ],
+        )?;
+
+        // chunks 3 and 4: accounts receive the bought tokens
+        let is_first_buy_chunk_valid = chunk_base_valid[3].clone();
+        let is_second_buy_chunk_valid = chunk_base_valid[4].clone();
+
+        // Only one of the flags can be set due to chunking, so at most one update is applied.
+        let updates = [
+            (&is_fee_chunk_valid, &op_data.fee, false),
+            (&is_first_sell_chunk_valid, first_amount, false),
+            (&is_second_sell_chunk_valid, second_amount, false),
+            (&is_first_buy_chunk_valid, second_amount, true),
+            (&is_second_buy_chunk_valid, first_amount, true),
+        ];
+        for (i, (is_valid, amount, is_credit)) in updates.iter().enumerate() {
+            let updated_balance = if *is_credit {
+                Expression::from(&cur.balance.get_number()) + Expression::from(&amount.get_number())
+            } else {
+                Expression::from(&cur.balance.get_number()) - Expression::from(&amount.get_number())
+            };
+            cur.balance = CircuitElement::conditionally_select_with_number_strict(
+                cs.namespace(|| format!("updated balance in chunk {}", i)),
+                updated_balance,
+                &cur.balance,
+                is_valid,
+            )?;
+
+            if !*is_credit {
+                let updated_nonce =
+                    Expression::from(&cur.account.nonce.get_number()) + Expression::u64::<CS>(1);
+                cur.account.nonce = CircuitElement::conditionally_select_with_number_strict(
+                    cs.namespace(|| format!("updated nonce in chunk {}", i)),
+                    updated_nonce,
+                    &cur.account.nonce,
+                    is_valid,
+                )?;
+            }
+        }
+
+        multi_or(
+            cs.namespace(|| "tx_valid"),
+            &[
+                is_fee_chunk_valid,
+                is_first_sell_chunk_valid,
+                is_second_sell_chunk_valid,
+                is_first_buy_chunk_valid,
+                is_second_buy_chunk_valid,
+            ],
+        )
+    }
+
     fn noop<CS: ConstraintSystem<E>>(
         &self,
         mut cs: CS,
@@ -1672,6 +2345,8 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
         serialized_tx_bits.extend(op_data.amount_packed.get_bits_be());
         serialized_tx_bits.extend(op_data.fee_packed.get_bits_be());
         serialized_tx_bits.extend(cur.account.nonce.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_from.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_until.get_bits_be());
         assert_eq!(serialized_tx_bits.len(), SIGNED_TRANSFER_BIT_WIDTH);
 
         let pubdata_chunk = select_pubdata_chunk(
@@ -1905,6 +2580,8 @@ impl<'a, E: RescueEngine + JubjubEngine> FranklinCircuit<'a, E> {
         serialized_tx_bits.extend(op_data.amount_packed.get_bits_be());
         serialized_tx_bits.extend(op_data.fee_packed.get_bits_be());
         serialized_tx_bits.extend(cur.account.nonce.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_from.get_bits_be());
+        serialized_tx_bits.extend(op_data.valid_until.get_bits_be());
         assert_eq!(serialized_tx_bits.len(), SIGNED_TRANSFER_BIT_WIDTH);
 
         let pubdata_chunk = select_pubdata_chunk(
@@ -2462,6 +3139,8 @@ fn generate_maxchunk_polynomial<E: JubjubEngine>() -> Vec<E::Fr> {
     points.push(get_xy(TransferToNewOp::OP_CODE, TransferToNewOp::CHUNKS));
     points.push(get_xy(FullExitOp::OP_CODE, FullExitOp::CHUNKS));
     points.push(get_xy(ChangePubKeyOp::OP_CODE, ChangePubKeyOp::CHUNKS));
+    points.push(get_xy(ForcedExitOp::OP_CODE, ForcedExitOp::CHUNKS));
+    points.push(get_xy(SwapOp::OP_CODE, SwapOp::CHUNKS));
 
     let interpolation = interpolate::<E>(&points[..]).expect("must interpolate");
     assert_eq!(interpolation.len(), DIFFERENT_TRANSACTIONS_TYPE_NUMBER);
@@ -2469,6 +3148,50 @@ fn generate_maxchunk_polynomial<E: JubjubEngine>() -> Vec<E::Fr> {
     interpolation
 }
 
+/// Checks that `a >= b`, given that both values fit into `bit_length` bits.
+fn is_geq<E: JubjubEngine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    a: &AllocatedNum<E>,
+    b: &AllocatedNum<E>,
+    bit_length: usize,
+) -> Result<Boolean, SynthesisError> {
+    let diff = Expression::from(a) - Expression::from(b);
+
+    let diff_bits = diff.into_bits_le_fixed(cs.namespace(|| "diff bits"), bit_length)?;
+
+    let diff_bits_repacked = Expression::from_le_bits::<CS>(&diff_bits);
+
+    Ok(Boolean::from(Expression::equals(
+        cs.namespace(|| "diff equal to repacked"),
+        diff,
+        diff_bits_repacked,
+    )?))
+}
+
+/// Checks that selling `sell_amount` for `buy_amount` satisfies the order price:
+/// `buy_amount * prices[0] >= sell_amount * prices[1]`.
+fn is_price_satisfied<E: JubjubEngine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    sell_amount: &CircuitElement<E>,
+    buy_amount: &CircuitElement<E>,
+    prices: &[CircuitElement<E>],
+) -> Result<Boolean, SynthesisError> {
+    assert_eq!(prices.len(), 2);
+    let buy_value = buy_amount
+        .get_number()
+        .mul(cs.namespace(|| "buy value"), &prices[0].get_number())?;
+    let sell_value = sell_amount
+        .get_number()
+        .mul(cs.namespace(|| "sell value"), &prices[1].get_number())?;
+
+    is_geq(
+        cs.namespace(|| "buy value is not less than sell value"),
+        &buy_value,
+        &sell_value,
+        params::BALANCE_BIT_WIDTH + params::PRICE_BIT_WIDTH,
+    )
+}
+
 fn no_nonce_overflow<E: JubjubEngine, CS: ConstraintSystem<E>>(
     mut cs: CS,
     nonce: &AllocatedNum<E>,
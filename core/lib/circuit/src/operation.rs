@@ -51,6 +51,20 @@ pub struct OperationArguments<E: RescueEngine> {
     pub new_pub_key_hash: Option<E::Fr>,
     pub eth_address: Option<E::Fr>,
     pub pub_nonce: Option<E::Fr>,
+    pub valid_from: Option<E::Fr>,
+    pub valid_until: Option<E::Fr>,
+    // Swap-specific arguments.
+    pub second_amount_packed: Option<E::Fr>,
+    /// Submitter account and accounts of the first and the second order.
+    pub special_accounts: Vec<Option<E::Fr>>,
+    /// Tokens sold by the first and the second order, and the fee token.
+    pub special_tokens: Vec<Option<E::Fr>>,
+    /// Price ratios of the first and the second order.
+    pub special_prices: Vec<Option<E::Fr>>,
+    /// Packed amounts of the first and the second order.
+    pub special_amounts: Vec<Option<E::Fr>>,
+    pub second_valid_from: Option<E::Fr>,
+    pub second_valid_until: Option<E::Fr>,
 }
 
 #[derive(Clone)]
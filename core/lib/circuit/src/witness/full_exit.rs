@@ -226,6 +226,15 @@ impl FullExitWitness<Bn256> {
                 a: Some(a),
                 b: Some(b),
                 new_pub_key_hash: Some(Fr::zero()),
+                valid_from: Some(Fr::zero()),
+                valid_until: Some(Fr::zero()),
+                second_amount_packed: Some(Fr::zero()),
+                special_accounts: vec![Some(Fr::zero()); 3],
+                special_tokens: vec![Some(Fr::zero()); 3],
+                special_prices: vec![Some(Fr::zero()); 4],
+                special_amounts: vec![Some(Fr::zero()); 2],
+                second_valid_from: Some(Fr::zero()),
+                second_valid_until: Some(Fr::zero()),
             },
             before_root: Some(before_root),
             after_root: Some(after_root),
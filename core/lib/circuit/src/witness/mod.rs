@@ -9,7 +9,9 @@ pub use self::{
     change_pubkey_offchain::ChangePubkeyOffChainWitness,
     close_account::CloseAccountWitness,
     deposit::DepositWitness,
+    forced_exit::ForcedExitWitness,
     full_exit::FullExitWitness,
+    swap::SwapWitness,
     transfer::TransferWitness,
     transfer_to_new::TransferToNewWitness,
     utils::{SigDataInput, WitnessBuilder},
@@ -19,8 +21,10 @@ pub use self::{
 pub mod change_pubkey_offchain;
 pub mod close_account;
 pub mod deposit;
+pub mod forced_exit;
 pub mod full_exit;
 pub mod noop;
+pub mod swap;
 pub mod transfer;
 pub mod transfer_to_new;
 pub mod withdraw;
@@ -56,6 +56,15 @@ pub fn noop_operation(tree: &CircuitAccountTree, acc_id: u32) -> Operation<Bn256
             b: Some(Fr::zero()),
             pub_nonce: Some(Fr::zero()),
             new_pub_key_hash: Some(Fr::zero()),
+            valid_from: Some(Fr::zero()),
+            valid_until: Some(Fr::zero()),
+            second_amount_packed: Some(Fr::zero()),
+            special_accounts: vec![Some(Fr::zero()); 3],
+            special_tokens: vec![Some(Fr::zero()); 3],
+            special_prices: vec![Some(Fr::zero()); 4],
+            special_amounts: vec![Some(Fr::zero()); 2],
+            second_valid_from: Some(Fr::zero()),
+            second_valid_until: Some(Fr::zero()),
         },
         lhs: OperationBranch {
             address: Some(account_address_fe),
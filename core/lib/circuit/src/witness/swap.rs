@@ -0,0 +1,275 @@
+// External deps
+use crypto_exports::franklin_crypto::{
+    bellman::pairing::{
+        bn256::{Bn256, Fr},
+        ff::{Field, PrimeField},
+    },
+    rescue::RescueEngine,
+};
+use num::ToPrimitive;
+// Workspace deps
+use models::{
+    circuit::{
+        account::CircuitAccountTree,
+        utils::{append_be_fixed_width, le_bit_vector_into_field_element},
+    },
+    node::operations::SwapOp,
+    params::{
+        ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH,
+        CHUNK_BIT_WIDTH, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+        TX_TYPE_BIT_WIDTH,
+    },
+    primitives::convert_to_float,
+};
+// Local deps
+use crate::{
+    operation::{Operation, OperationArguments, OperationBranch, OperationBranchWitness},
+    utils::resize_grow_only,
+    witness::{
+        utils::{apply_leaf_operation, get_audits, SigDataInput},
+        Witness,
+    },
+};
+
+#[derive(Debug)]
+pub struct SwapData {
+    pub submitter_address: u32,
+    pub accounts: (u32, u32),
+    /// Tokens sold by the first and the second order respectively.
+    pub tokens: (u32, u32),
+    pub amounts: (u128, u128),
+    pub fee: u128,
+    pub fee_token: u32,
+    /// Price ratios of the first and the second order.
+    pub prices: [u128; 4],
+    pub order_amounts: (u128, u128),
+    pub valid_from: (u64, u64),
+    pub valid_until: (u64, u64),
+}
+
+pub struct SwapWitness<E: RescueEngine> {
+    /// Leaves updated by the operation chunks, taken before the corresponding update.
+    pub branches: Vec<OperationBranch<E>>,
+    /// Roots of the tree after each operation chunk.
+    pub roots: Vec<Option<E::Fr>>,
+    pub args: OperationArguments<E>,
+    pub before_root: Option<E::Fr>,
+    pub tx_type: Option<E::Fr>,
+}
+
+impl Witness for SwapWitness<Bn256> {
+    type OperationType = SwapOp;
+    /// Signature data of the swap itself, the first and the second order.
+    type CalculateOpsInput = Vec<SigDataInput>;
+
+    fn apply_tx(tree: &mut CircuitAccountTree, swap: &SwapOp) -> Self {
+        let (first_order, second_order) = &swap.tx.orders;
+        let swap_data = SwapData {
+            submitter_address: swap.tx.submitter_id,
+            accounts: (first_order.account_id, second_order.account_id),
+            tokens: (
+                u32::from(first_order.token_sell),
+                u32::from(second_order.token_sell),
+            ),
+            amounts: (
+                swap.tx.amounts.0.to_u128().unwrap(),
+                swap.tx.amounts.1.to_u128().unwrap(),
+            ),
+            fee: swap.tx.fee.to_u128().unwrap(),
+            fee_token: u32::from(swap.tx.fee_token),
+            prices: [
+                first_order.ratio.0.to_u128().unwrap(),
+                first_order.ratio.1.to_u128().unwrap(),
+                second_order.ratio.0.to_u128().unwrap(),
+                second_order.ratio.1.to_u128().unwrap(),
+            ],
+            order_amounts: (
+                first_order.amount.to_u128().unwrap(),
+                second_order.amount.to_u128().unwrap(),
+            ),
+            valid_from: (
+                first_order.time_range.valid_from,
+                second_order.time_range.valid_from,
+            ),
+            valid_until: (
+                first_order.time_range.valid_until,
+                second_order.time_range.valid_until,
+            ),
+        };
+        Self::apply_data(tree, &swap_data)
+    }
+
+    fn get_pubdata(&self) -> Vec<bool> {
+        let mut pubdata_bits = vec![];
+        append_be_fixed_width(&mut pubdata_bits, &self.tx_type.unwrap(), TX_TYPE_BIT_WIDTH);
+
+        for account in &self.args.special_accounts {
+            append_be_fixed_width(&mut pubdata_bits, &account.unwrap(), ACCOUNT_ID_BIT_WIDTH);
+        }
+        for token in &self.args.special_tokens {
+            append_be_fixed_width(&mut pubdata_bits, &token.unwrap(), TOKEN_BIT_WIDTH);
+        }
+        append_be_fixed_width(
+            &mut pubdata_bits,
+            &self.args.amount_packed.unwrap(),
+            AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH,
+        );
+        append_be_fixed_width(
+            &mut pubdata_bits,
+            &self.args.second_amount_packed.unwrap(),
+            AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH,
+        );
+        append_be_fixed_width(
+            &mut pubdata_bits,
+            &self.args.fee.unwrap(),
+            FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH,
+        );
+        resize_grow_only(&mut pubdata_bits, SwapOp::CHUNKS * CHUNK_BIT_WIDTH, false);
+        pubdata_bits
+    }
+
+    fn calculate_operations(&self, input: Vec<SigDataInput>) -> Vec<Operation<Bn256>> {
+        assert_eq!(input.len(), 3, "swap requires three signatures");
+        let pubdata_chunks: Vec<_> = self
+            .get_pubdata()
+            .chunks(CHUNK_BIT_WIDTH)
+            .map(|x| le_bit_vector_into_field_element(&x.to_vec()))
+            .collect();
+
+        (0..SwapOp::CHUNKS)
+            .map(|chunk| {
+                // Only the first three chunks are signed, signatures of the rest are not checked.
+                let sig_data = input.get(chunk).unwrap_or(&input[0]);
+                Operation {
+                    new_root: self.roots[chunk],
+                    tx_type: self.tx_type,
+                    chunk: Some(Fr::from_str(&chunk.to_string()).unwrap()),
+                    pubdata_chunk: Some(pubdata_chunks[chunk]),
+                    first_sig_msg: Some(sig_data.first_sig_msg),
+                    second_sig_msg: Some(sig_data.second_sig_msg),
+                    third_sig_msg: Some(sig_data.third_sig_msg),
+                    signature_data: sig_data.signature.clone(),
+                    signer_pub_key_packed: sig_data.signer_pub_key_packed.to_vec(),
+                    args: self.args.clone(),
+                    lhs: self.branches[chunk].clone(),
+                    rhs: self.branches[chunk].clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl SwapWitness<Bn256> {
+    fn apply_data(tree: &mut CircuitAccountTree, swap: &SwapData) -> Self {
+        let before_root = tree.root_hash();
+        debug!("Initial root = {}", before_root);
+
+        let fe = |value: u128| Fr::from_str(&value.to_string()).unwrap();
+        let amount_encoded = |amount: u128| -> Fr {
+            let amount_bits = convert_to_float(
+                amount,
+                AMOUNT_EXPONENT_BIT_WIDTH,
+                AMOUNT_MANTISSA_BIT_WIDTH,
+                10,
+            )
+            .unwrap();
+            le_bit_vector_into_field_element(&amount_bits)
+        };
+
+        let fee_bits =
+            convert_to_float(swap.fee, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, 10).unwrap();
+        let fee_encoded: Fr = le_bit_vector_into_field_element(&fee_bits);
+
+        // (account, token, amount, whether the amount is charged or credited) for every chunk
+        let leaf_updates = [
+            (swap.submitter_address, swap.fee_token, swap.fee, true),
+            (swap.accounts.0, swap.tokens.0, swap.amounts.0, true),
+            (swap.accounts.1, swap.tokens.1, swap.amounts.1, true),
+            (swap.accounts.0, swap.tokens.1, swap.amounts.1, false),
+            (swap.accounts.1, swap.tokens.0, swap.amounts.0, false),
+        ];
+
+        let mut branches = Vec::with_capacity(SwapOp::CHUNKS);
+        let mut roots = Vec::with_capacity(SwapOp::CHUNKS);
+        let mut balances_before = Vec::with_capacity(SwapOp::CHUNKS);
+        for &(account, token, amount, is_charged) in leaf_updates.iter() {
+            let amount = fe(amount);
+            let (audit_path, audit_balance_path) = get_audits(tree, account, token);
+
+            let (account_witness_before, _, balance_before, _) = apply_leaf_operation(
+                tree,
+                account,
+                token,
+                |acc| {
+                    if is_charged {
+                        acc.nonce.add_assign(&Fr::from_str("1").unwrap());
+                    }
+                },
+                |bal| {
+                    if is_charged {
+                        bal.value.sub_assign(&amount)
+                    } else {
+                        bal.value.add_assign(&amount)
+                    }
+                },
+            );
+
+            branches.push(OperationBranch {
+                address: Some(fe(u128::from(account))),
+                token: Some(fe(u128::from(token))),
+                witness: OperationBranchWitness {
+                    account_witness: account_witness_before,
+                    account_path: audit_path,
+                    balance_value: Some(balance_before),
+                    balance_subtree_path: audit_balance_path,
+                },
+            });
+            balances_before.push(balance_before);
+
+            let root = tree.root_hash();
+            debug!("Root after chunk {} = {}", roots.len(), root);
+            roots.push(Some(root));
+        }
+
+        //calculate a and b
+        let a = balances_before[0];
+        let b = fe(swap.fee);
+
+        SwapWitness {
+            branches,
+            roots,
+            args: OperationArguments {
+                eth_address: Some(Fr::zero()),
+                amount_packed: Some(amount_encoded(swap.amounts.0)),
+                full_amount: Some(Fr::zero()),
+                fee: Some(fee_encoded),
+                pub_nonce: Some(Fr::zero()),
+                a: Some(a),
+                b: Some(b),
+                new_pub_key_hash: Some(Fr::zero()),
+                valid_from: Some(fe(u128::from(swap.valid_from.0))),
+                valid_until: Some(fe(u128::from(swap.valid_until.0))),
+                second_amount_packed: Some(amount_encoded(swap.amounts.1)),
+                special_accounts: vec![
+                    Some(fe(u128::from(swap.submitter_address))),
+                    Some(fe(u128::from(swap.accounts.0))),
+                    Some(fe(u128::from(swap.accounts.1))),
+                ],
+                special_tokens: vec![
+                    Some(fe(u128::from(swap.tokens.0))),
+                    Some(fe(u128::from(swap.tokens.1))),
+                    Some(fe(u128::from(swap.fee_token))),
+                ],
+                special_prices: swap.prices.iter().map(|&price| Some(fe(price))).collect(),
+                special_amounts: vec![
+                    Some(amount_encoded(swap.order_amounts.0)),
+                    Some(amount_encoded(swap.order_amounts.1)),
+                ],
+                second_valid_from: Some(fe(u128::from(swap.valid_from.1))),
+                second_valid_until: Some(fe(u128::from(swap.valid_until.1))),
+            },
+            before_root: Some(before_root),
+            tx_type: Some(Fr::from_str("9").unwrap()),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// External deps
+use crypto_exports::franklin_crypto::bellman::pairing::bn256::Bn256;
+use num::BigUint;
+// Workspace deps
+use models::node::operations::ForcedExitOp;
+// Local deps
+use crate::witness::{
+    forced_exit::ForcedExitWitness,
+    tests::test_utils::{corrupted_input_test_scenario, generic_test_scenario, WitnessTestAccount},
+    utils::SigDataInput,
+};
+
+#[test]
+#[ignore]
+fn test_forced_exit() {
+    // Test vector of (initiator_balance, target_balance, fee_amount).
+    let test_vector = vec![
+        (10u64, 7u64, 3u64),       // Basic forced exit
+        (0, 0, 0),                 // Zero forced exit
+        (std::u64::MAX, 1, 1),     // Small balance withdrawn, rich initiator
+        (1, std::u64::MAX, 1),     // Big balance withdrawn
+        (std::u64::MAX, 1, 10000), // Very big fee
+    ];
+
+    for (initiator_balance, target_balance, fee_amount) in test_vector {
+        // Input data.
+        let accounts = vec![
+            WitnessTestAccount::new(1, initiator_balance),
+            WitnessTestAccount::new_without_pk_hash(2, target_balance),
+        ];
+        let (account_from, account_to) = (&accounts[0], &accounts[1]);
+        let forced_exit_op = ForcedExitOp {
+            tx: account_from.zksync_account.sign_forced_exit(
+                0,
+                BigUint::from(fee_amount),
+                &account_to.account.address,
+                None,
+                true,
+            ),
+            target_account_id: account_to.id,
+            withdraw_amount: Some(BigUint::from(target_balance).into()),
+        };
+
+        // Additional data required for performing the operation.
+        let input = SigDataInput::from_forced_exit_op(&forced_exit_op)
+            .expect("SigDataInput creation failed");
+
+        generic_test_scenario::<ForcedExitWitness<Bn256>, _>(
+            &accounts,
+            forced_exit_op,
+            input,
+            |plasma_state, op| {
+                let (fee, _) = plasma_state
+                    .apply_forced_exit_op(&op)
+                    .expect("operation should be success");
+                vec![fee]
+            },
+        );
+    }
+}
+
+/// Checks that corrupted signature data leads to unsatisfied constraints in circuit.
+#[test]
+#[ignore]
+fn corrupted_ops_input() {
+    // Incorrect signature data will lead to `op_valid` constraint failure.
+    // See `circuit.rs` for details.
+    const EXPECTED_PANIC_MSG: &str = "op_valid is true";
+
+    // Legit input data.
+    let accounts = vec![
+        WitnessTestAccount::new(1, 10),
+        WitnessTestAccount::new_without_pk_hash(2, 7),
+    ];
+    let (account_from, account_to) = (&accounts[0], &accounts[1]);
+    let forced_exit_op = ForcedExitOp {
+        tx: account_from.zksync_account.sign_forced_exit(
+            0,
+            BigUint::from(3u64),
+            &account_to.account.address,
+            None,
+            true,
+        ),
+        target_account_id: account_to.id,
+        withdraw_amount: Some(BigUint::from(7u64).into()),
+    };
+
+    // Additional data required for performing the operation.
+    let input =
+        SigDataInput::from_forced_exit_op(&forced_exit_op).expect("SigDataInput creation failed");
+
+    // Test vector with values corrupted one by one.
+    let test_vector = input.corrupted_variations();
+
+    for input in test_vector {
+        corrupted_input_test_scenario::<ForcedExitWitness<Bn256>, _>(
+            &accounts,
+            forced_exit_op.clone(),
+            input,
+            EXPECTED_PANIC_MSG,
+            |plasma_state, op| {
+                let (fee, _) = plasma_state
+                    .apply_forced_exit_op(&op)
+                    .expect("operation should be success");
+                vec![fee]
+            },
+        );
+    }
+}
@@ -30,8 +30,10 @@ use crate::{
 
 mod change_pubkey_offchain;
 mod deposit;
+mod forced_exit;
 mod full_exit;
 mod noop;
+mod swap;
 pub(crate) mod test_utils;
 mod transfer;
 mod transfer_to_new;
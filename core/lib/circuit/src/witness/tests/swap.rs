@@ -0,0 +1,133 @@
+// External deps
+use crypto_exports::franklin_crypto::bellman::pairing::bn256::Bn256;
+use num::BigUint;
+// Workspace deps
+use models::node::operations::SwapOp;
+// Local deps
+use crate::witness::{
+    swap::SwapWitness,
+    tests::test_utils::{corrupted_input_test_scenario, generic_test_scenario, WitnessTestAccount},
+    utils::SigDataInput,
+};
+
+const FIRST_TOKEN: u16 = 0;
+const SECOND_TOKEN: u16 = 1;
+
+/// Creates a pair of accounts having `balance` of both tokens used in the swap.
+fn swap_accounts(balance: u64) -> Vec<WitnessTestAccount> {
+    let mut accounts = vec![
+        WitnessTestAccount::new(1, balance),
+        WitnessTestAccount::new(2, balance),
+    ];
+    for account in accounts.iter_mut() {
+        account
+            .account
+            .add_balance(SECOND_TOKEN, &BigUint::from(balance));
+    }
+    accounts
+}
+
+/// Creates a swap of `amounts.0` of the first token from the first account
+/// to `amounts.1` of the second token from the second account.
+/// The first account submits the swap and pays the fee in the first token.
+fn create_swap_op(accounts: &[WitnessTestAccount], amounts: (u64, u64), fee: u64) -> SwapOp {
+    let (first_account, second_account) = (&accounts[0], &accounts[1]);
+
+    let swap_nonce = first_account.account.nonce;
+    let first_order = first_account.zksync_account.sign_order(
+        FIRST_TOKEN,
+        SECOND_TOKEN,
+        (BigUint::from(amounts.0), BigUint::from(amounts.1)),
+        BigUint::from(amounts.0),
+        Some(swap_nonce + 1),
+        false,
+    );
+    let second_order = second_account.zksync_account.sign_order(
+        SECOND_TOKEN,
+        FIRST_TOKEN,
+        (BigUint::from(amounts.1), BigUint::from(amounts.0)),
+        BigUint::from(amounts.1),
+        None,
+        false,
+    );
+
+    SwapOp {
+        tx: first_account.zksync_account.sign_swap(
+            (first_order, second_order),
+            (BigUint::from(amounts.0), BigUint::from(amounts.1)),
+            BigUint::from(fee),
+            FIRST_TOKEN,
+            Some(swap_nonce),
+            false,
+        ),
+    }
+}
+
+#[test]
+#[ignore]
+fn test_swap() {
+    // Test vector of (balance, amounts, fee).
+    let test_vector = vec![
+        (10u64, (7u64, 3u64), 1u64), // Basic swap
+        (10, (7, 3), 0),             // Zero fee
+        (10, (9, 10), 1),            // Whole balances are swapped
+        (std::u64::MAX, (1, 1), 1),  // Small amounts, big balances
+    ];
+
+    for (balance, amounts, fee) in test_vector {
+        let accounts = swap_accounts(balance);
+        let swap_op = create_swap_op(&accounts, amounts, fee);
+
+        // Additional data required for performing the operation.
+        let input = SigDataInput::from_swap_op(&swap_op).expect("SigDataInput creation failed");
+
+        generic_test_scenario::<SwapWitness<Bn256>, _>(
+            &accounts,
+            swap_op,
+            input,
+            |plasma_state, op| {
+                let (fee, _) = plasma_state
+                    .apply_swap_op(&op)
+                    .expect("operation should be success");
+                vec![fee]
+            },
+        );
+    }
+}
+
+/// Checks that corrupted signature data of any party leads to unsatisfied constraints in circuit.
+#[test]
+#[ignore]
+fn corrupted_ops_input() {
+    // Incorrect signature data will lead to `op_valid` constraint failure.
+    // See `circuit.rs` for details.
+    const EXPECTED_PANIC_MSG: &str = "op_valid is true";
+
+    // Legit input data.
+    let accounts = swap_accounts(10);
+    let swap_op = create_swap_op(&accounts, (7, 3), 1);
+
+    // Additional data required for performing the operation.
+    let input = SigDataInput::from_swap_op(&swap_op).expect("SigDataInput creation failed");
+
+    // Test vector with values corrupted one by one, for every signature separately.
+    for signer in 0..input.len() {
+        for corrupted_input in input[signer].corrupted_variations() {
+            let mut input = input.clone();
+            input[signer] = corrupted_input;
+
+            corrupted_input_test_scenario::<SwapWitness<Bn256>, _>(
+                &accounts,
+                swap_op.clone(),
+                input,
+                EXPECTED_PANIC_MSG,
+                |plasma_state, op| {
+                    let (fee, _) = plasma_state
+                        .apply_swap_op(&op)
+                        .expect("operation should be success");
+                    vec![fee]
+                },
+            );
+        }
+    }
+}
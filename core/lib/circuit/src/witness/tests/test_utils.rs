@@ -7,7 +7,7 @@ use num::BigUint;
 // Workspace deps
 use models::{
     circuit::{account::CircuitAccount, CircuitAccountTree},
-    node::{Account, AccountId, AccountMap, Address, Engine, Fr},
+    node::{Account, AccountId, AccountMap, Address, Engine, Fr, PubKeyHash},
 };
 use plasma::state::{CollectedFee, PlasmaState};
 use testkit::zksync_account::ZksyncAccount;
@@ -116,6 +116,14 @@ impl WitnessTestAccount {
     pub fn new_empty(id: AccountId) -> Self {
         Self::new(id, 0)
     }
+
+    /// Creates an account which has no public key hash set, e.g. an account
+    /// that was created by a transfer but has never been unlocked.
+    pub fn new_without_pk_hash(id: AccountId, balance: u64) -> Self {
+        let mut account = Self::new(id, balance);
+        account.account.pub_key_hash = PubKeyHash::default();
+        account
+    }
 }
 
 /// Generic test scenario does the following:
@@ -38,6 +38,8 @@ pub struct TransferToNewData {
     pub from_account_address: u32,
     pub to_account_address: u32,
     pub new_address: Fr,
+    pub valid_from: u64,
+    pub valid_until: u64,
 }
 
 pub struct TransferToNewWitness<E: RescueEngine> {
@@ -66,6 +68,8 @@ impl Witness for TransferToNewWitness<Bn256> {
             from_account_address: transfer_to_new.from,
             to_account_address: transfer_to_new.to,
             new_address: eth_address_to_fr(&transfer_to_new.tx.to),
+            valid_from: transfer_to_new.tx.time_range.valid_from,
+            valid_until: transfer_to_new.tx.time_range.valid_until,
         };
         // le_bit_vector_into_field_element()
         Self::apply_data(tree, &transfer_data)
@@ -410,6 +414,15 @@ impl TransferToNewWitness<Bn256> {
                 b: Some(b),
                 pub_nonce: Some(Fr::zero()),
                 new_pub_key_hash: Some(Fr::zero()),
+                valid_from: Some(Fr::from_str(&transfer_to_new.valid_from.to_string()).unwrap()),
+                valid_until: Some(Fr::from_str(&transfer_to_new.valid_until.to_string()).unwrap()),
+                second_amount_packed: Some(Fr::zero()),
+                special_accounts: vec![Some(Fr::zero()); 3],
+                special_tokens: vec![Some(Fr::zero()); 3],
+                special_prices: vec![Some(Fr::zero()); 4],
+                special_amounts: vec![Some(Fr::zero()); 2],
+                second_valid_from: Some(Fr::zero()),
+                second_valid_until: Some(Fr::zero()),
             },
             before_root: Some(before_root),
             intermediate_root: Some(intermediate_root),
@@ -20,7 +20,7 @@ use models::{
     },
     merkle_tree::{hasher::Hasher, PedersenHasher, RescueHasher},
     node::{
-        operations::{CloseOp, TransferOp, TransferToNewOp, WithdrawOp},
+        operations::{CloseOp, ForcedExitOp, SwapOp, TransferOp, TransferToNewOp, WithdrawOp},
         tx::PackedPublicKey,
         AccountId, BlockNumber, Engine,
     },
@@ -580,6 +580,40 @@ impl SigDataInput {
         )
     }
 
+    pub fn from_forced_exit_op(forced_exit_op: &ForcedExitOp) -> Result<Self, String> {
+        let sign_packed = forced_exit_op
+            .tx
+            .signature
+            .signature
+            .serialize_packed()
+            .expect("signature serialize");
+        SigDataInput::new(
+            &sign_packed,
+            &forced_exit_op.tx.get_bytes(),
+            &forced_exit_op.tx.signature.pub_key,
+        )
+    }
+
+    /// Creates signature data inputs for the swap: the first one is for the swap tx signed by
+    /// the submitter, and the other two are for the orders signed by their owners.
+    pub fn from_swap_op(swap_op: &SwapOp) -> Result<Vec<Self>, String> {
+        let (first_order, second_order) = &swap_op.tx.orders;
+        vec![
+            (&swap_op.tx.signature, swap_op.tx.get_bytes()),
+            (&first_order.signature, first_order.get_bytes()),
+            (&second_order.signature, second_order.get_bytes()),
+        ]
+        .into_iter()
+        .map(|(signature, tx_bytes)| {
+            let sign_packed = signature
+                .signature
+                .serialize_packed()
+                .expect("signature serialize");
+            SigDataInput::new(&sign_packed, &tx_bytes, &signature.pub_key)
+        })
+        .collect()
+    }
+
     /// Provides a vector of copies of this `SigDataInput` object, all with one field
     /// set to incorrect value.
     /// Used for circuit tests.
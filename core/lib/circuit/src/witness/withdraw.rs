@@ -38,6 +38,8 @@ pub struct WithdrawData {
     pub token: u32,
     pub account_address: u32,
     pub eth_address: Fr,
+    pub valid_from: u64,
+    pub valid_until: u64,
 }
 
 pub struct WithdrawWitness<E: RescueEngine> {
@@ -60,6 +62,8 @@ impl Witness for WithdrawWitness<Bn256> {
             token: u32::from(withdraw.tx.token),
             account_address: withdraw.account_id,
             eth_address: eth_address_to_fr(&withdraw.tx.to),
+            valid_from: withdraw.tx.time_range.valid_from,
+            valid_until: withdraw.tx.time_range.valid_until,
         };
         // le_bit_vector_into_field_element()
         Self::apply_data(tree, &withdraw_data)
@@ -277,6 +281,15 @@ impl WithdrawWitness<Bn256> {
                 a: Some(a),
                 b: Some(b),
                 new_pub_key_hash: Some(Fr::zero()),
+                valid_from: Some(Fr::from_str(&withdraw.valid_from.to_string()).unwrap()),
+                valid_until: Some(Fr::from_str(&withdraw.valid_until.to_string()).unwrap()),
+                second_amount_packed: Some(Fr::zero()),
+                special_accounts: vec![Some(Fr::zero()); 3],
+                special_tokens: vec![Some(Fr::zero()); 3],
+                special_prices: vec![Some(Fr::zero()); 4],
+                special_amounts: vec![Some(Fr::zero()); 2],
+                second_valid_from: Some(Fr::zero()),
+                second_valid_until: Some(Fr::zero()),
             },
             before_root: Some(before_root),
             after_root: Some(after_root),
@@ -0,0 +1,108 @@
+// External uses
+use failure::ensure;
+use futures::compat::Future01CompatExt;
+use web3::transports::{EventLoopHandle, Http};
+use web3::types::{Address, Bytes, U256};
+use web3::Transport;
+// Local uses
+use super::EthereumSigner;
+use crate::signer::RawTransaction;
+
+/// Transaction in the format of the `eth_signTransaction` JSON-RPC method.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTransaction {
+    from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<Address>,
+    gas: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_price: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_priority_fee_per_gas: Option<U256>,
+    value: U256,
+    data: Bytes,
+    nonce: U256,
+    chain_id: U256,
+}
+
+/// Response of the `eth_signTransaction` JSON-RPC method.
+#[derive(Debug, Deserialize)]
+struct SignTransactionResponse {
+    /// RLP-encoded signed transaction.
+    raw: Bytes,
+}
+
+/// Signer delegating the signing to the external service via the `eth_signTransaction`
+/// JSON-RPC method (e.g. Clef or a node with the unlocked account).
+#[derive(Debug)]
+pub struct JsonRpcSigner {
+    address: Address,
+    transport: Http,
+    // We have to prevent handle from drop, since it will cause event loop termination.
+    _event_loop: EventLoopHandle,
+}
+
+impl JsonRpcSigner {
+    pub fn new(url: &str, address: Address) -> Result<Self, failure::Error> {
+        let (_event_loop, transport) = Http::new(url)?;
+
+        Ok(Self {
+            address,
+            transport,
+            _event_loop,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for JsonRpcSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx: RawTransaction) -> Result<Vec<u8>, failure::Error> {
+        // For the EIP-1559 transactions the gas price is the max fee per gas.
+        let (gas_price, max_fee_per_gas) = match tx.max_priority_fee_per_gas {
+            Some(_) => (None, Some(tx.gas_price)),
+            None => (Some(tx.gas_price), None),
+        };
+        let rpc_tx = RpcTransaction {
+            from: self.address,
+            to: tx.to,
+            gas: tx.gas,
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            value: tx.value,
+            data: Bytes(tx.data.clone()),
+            nonce: tx.nonce,
+            chain_id: U256::from(tx.chain_id),
+        };
+
+        let response = self
+            .transport
+            .execute("eth_signTransaction", vec![serde_json::to_value(rpc_tx)?])
+            .compat()
+            .await?;
+        let response: SignTransactionResponse = serde_json::from_value(response)?;
+
+        // External signer is not trusted to sign exactly the requested transaction.
+        let (signed_tx, signer) = RawTransaction::decode_signed(&response.raw.0)?;
+        ensure!(
+            signer == self.address,
+            "Transaction is signed by {:x} instead of {:x}",
+            signer,
+            self.address
+        );
+        ensure!(
+            signed_tx == tx,
+            "Signed transaction does not match the requested one: {:?}",
+            signed_tx
+        );
+
+        Ok(response.raw.0)
+    }
+}
@@ -0,0 +1,220 @@
+// Built-in deps
+use std::fs;
+use std::path::Path;
+// External uses
+use failure::{ensure, format_err};
+use parity_crypto::{aes, derive_key_iterations, derive_mac, is_equal, scrypt};
+use web3::types::{Address, H256};
+// Local uses
+use super::{EthereumSigner, PrivateKeySigner};
+use crate::signer::RawTransaction;
+
+/// Encrypted key file in the Web3 Secret Storage format (version 3).
+#[derive(Debug, Deserialize)]
+struct KeystoreFile {
+    #[serde(alias = "Crypto")]
+    crypto: KeystoreCrypto,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeystoreCrypto {
+    cipher: String,
+    ciphertext: String,
+    cipherparams: CipherParams,
+    kdf: String,
+    kdfparams: KdfParams,
+    mac: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum KdfParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        c: u32,
+        dklen: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+/// Signer using the private key decrypted from the keystore file.
+///
+/// Password is read from a separate file, so neither the key nor the password
+/// have to be passed through the environment variables.
+#[derive(Debug, Clone)]
+pub struct KeystoreSigner {
+    signer: PrivateKeySigner,
+}
+
+impl KeystoreSigner {
+    pub fn new(
+        keystore_path: impl AsRef<Path>,
+        password_path: impl AsRef<Path>,
+    ) -> Result<Self, failure::Error> {
+        let keystore = fs::read_to_string(keystore_path)?;
+        let password = fs::read_to_string(password_path)?;
+        let private_key = decrypt_keystore(&keystore, password.trim_end_matches('\n'))?;
+
+        Ok(Self {
+            signer: PrivateKeySigner::new(private_key)?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for KeystoreSigner {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    async fn sign_transaction(&self, tx: RawTransaction) -> Result<Vec<u8>, failure::Error> {
+        self.signer.sign_transaction(tx).await
+    }
+}
+
+/// Decrypts the private key stored in the keystore file.
+fn decrypt_keystore(keystore: &str, password: &str) -> Result<H256, failure::Error> {
+    let keystore: KeystoreFile = serde_json::from_str(keystore)?;
+    let crypto = keystore.crypto;
+    ensure!(
+        crypto.cipher == "aes-128-ctr",
+        "Unsupported keystore cipher: {}",
+        crypto.cipher
+    );
+
+    let (derived_right_bits, derived_left_bits) = match (crypto.kdf.as_str(), crypto.kdfparams) {
+        (
+            "scrypt",
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            },
+        ) => {
+            ensure!(dklen == 32, "Unsupported keystore key length: {}", dklen);
+            scrypt::derive_key(password.as_bytes(), &hex::decode(salt)?, n, p, r)
+                .map_err(|err| format_err!("Keystore key derivation failed: {:?}", err))?
+        }
+        (
+            "pbkdf2",
+            KdfParams::Pbkdf2 {
+                c,
+                dklen,
+                prf,
+                salt,
+            },
+        ) => {
+            ensure!(dklen == 32, "Unsupported keystore key length: {}", dklen);
+            ensure!(prf == "hmac-sha256", "Unsupported keystore prf: {}", prf);
+            derive_key_iterations(password.as_bytes(), &hex::decode(salt)?, c)
+        }
+        (kdf, _) => failure::bail!("Unsupported keystore kdf: {}", kdf),
+    };
+
+    let ciphertext = hex::decode(crypto.ciphertext)?;
+    let mac = derive_mac(&derived_left_bits, &ciphertext);
+    ensure!(
+        is_equal(&mac, &hex::decode(crypto.mac)?),
+        "Invalid keystore password"
+    );
+
+    let mut private_key = H256::zero();
+    ensure!(
+        ciphertext.len() == private_key.as_bytes().len(),
+        "Invalid keystore private key length"
+    );
+    aes::decrypt_128_ctr(
+        &derived_right_bits,
+        &hex::decode(crypto.cipherparams.iv)?,
+        &ciphertext,
+        private_key.as_bytes_mut(),
+    )
+    .map_err(|err| format_err!("Keystore decryption failed: {:?}", err))?;
+
+    Ok(private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vector from the Web3 Secret Storage definition.
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    /// Key from the test vector above encrypted with the scrypt parameters used by geth
+    /// for the light keystores (the scrypt test vector of the definition can't be used, since
+    /// its `n` is too big for `r = 1`). Cross-checked with the OpenSSL scrypt implementation.
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "3ca92af36ad7c2cd92454c59cea5ef00" },
+            "ciphertext": "7dfeaf7b7bd065621c7d27f3eff772e55fba4a65d5474a395db63b3f8b7f2e1b",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 4096,
+                "p": 6,
+                "r": 8,
+                "salt": "9a1bd8a1e2da73b1eb7a2c1b09a0ed6fe0bd6e2c8d49ec9a3a8ae3e65a1f4c21"
+            },
+            "mac": "b8d2cb68e1174d21b6b7e475b607fa3129e4f44011b6f165f54b75d50d3be68f"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    const PASSWORD: &str = "testpassword";
+    const PRIVATE_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    #[test]
+    fn decrypt_test_vector() {
+        let expected_key: H256 = PRIVATE_KEY.parse().unwrap();
+
+        assert_eq!(
+            decrypt_keystore(PBKDF2_KEYSTORE, PASSWORD).unwrap(),
+            expected_key
+        );
+        assert!(decrypt_keystore(PBKDF2_KEYSTORE, "wrongpassword").is_err());
+    }
+
+    #[test]
+    fn decrypt_scrypt_test_vector() {
+        let expected_key: H256 = PRIVATE_KEY.parse().unwrap();
+
+        assert_eq!(
+            decrypt_keystore(SCRYPT_KEYSTORE, PASSWORD).unwrap(),
+            expected_key
+        );
+        assert!(decrypt_keystore(SCRYPT_KEYSTORE, "wrongpassword").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Abstraction over the signing of the Ethereum transactions.
+//!
+//! Operator account may be controlled by the private key stored in the process memory,
+//! by the encrypted keystore file, or by the external signer (e.g. Clef), so the key itself
+//! never reaches the server process.
+
+// Built-in deps
+use std::sync::Arc;
+// External uses
+use web3::types::Address;
+// Workspace uses
+use models::config_options::EthSignerSource;
+// Local uses
+use crate::signer::RawTransaction;
+
+pub use self::{json_rpc::JsonRpcSigner, keystore::KeystoreSigner, private_key::PrivateKeySigner};
+
+mod json_rpc;
+mod keystore;
+mod private_key;
+
+/// Signer of the Ethereum transactions sent on behalf of a single account.
+#[async_trait::async_trait]
+pub trait EthereumSigner: Send + Sync {
+    /// Returns the address of the account which signs the transactions.
+    fn address(&self) -> Address;
+
+    /// Signs the transaction and returns its RLP-encoded representation.
+    async fn sign_transaction(&self, tx: RawTransaction) -> Result<Vec<u8>, failure::Error>;
+}
+
+/// Creates the signer according to the configured source.
+///
+/// `address` is the expected address of the signing account: it is used to identify the
+/// account in the external signer, and is checked to match the address derived from the key.
+pub fn signer_from_source(
+    source: &EthSignerSource,
+    address: Address,
+) -> Result<Arc<dyn EthereumSigner>, failure::Error> {
+    let signer: Arc<dyn EthereumSigner> = match source {
+        EthSignerSource::PrivateKey(private_key) => Arc::new(PrivateKeySigner::new(*private_key)?),
+        EthSignerSource::JsonRpc { url } => Arc::new(JsonRpcSigner::new(url.as_str(), address)?),
+        EthSignerSource::Keystore {
+            path,
+            password_path,
+        } => Arc::new(KeystoreSigner::new(path, password_path)?),
+    };
+
+    failure::ensure!(
+        signer.address() == address,
+        "Signer address {:x} does not match the expected address {:x}",
+        signer.address(),
+        address
+    );
+
+    Ok(signer)
+}
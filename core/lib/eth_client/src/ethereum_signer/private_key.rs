@@ -0,0 +1,45 @@
+// External uses
+use web3::types::{Address, H256};
+// Workspace uses
+use models::node::tx::PackedEthSignature;
+// Local uses
+use super::EthereumSigner;
+use crate::signer::RawTransaction;
+
+/// Signer holding the private key in the process memory.
+#[derive(Clone)]
+pub struct PrivateKeySigner {
+    private_key: H256,
+    address: Address,
+}
+
+impl std::fmt::Debug for PrivateKeySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // We do not want to have a private key in the debug representation.
+        f.debug_struct("PrivateKeySigner")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl PrivateKeySigner {
+    pub fn new(private_key: H256) -> Result<Self, failure::Error> {
+        let address = PackedEthSignature::address_from_private_key(&private_key)?;
+
+        Ok(Self {
+            private_key,
+            address,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for PrivateKeySigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx: RawTransaction) -> Result<Vec<u8>, failure::Error> {
+        Ok(tx.sign(&self.private_key))
+    }
+}
@@ -3,17 +3,21 @@ extern crate serde_derive;
 
 // Built-in deps
 use std::fmt;
+use std::sync::Arc;
 
 // External uses
 use futures::compat::Future01CompatExt;
-use web3::contract::tokens::Tokenize;
-use web3::contract::Options;
-use web3::types::{Address, BlockNumber, Bytes};
+use web3::contract::tokens::{Detokenize, Tokenize};
+use web3::contract::{Contract, Options};
+use web3::types::{Address, BlockNumber, Bytes, CallRequest};
 use web3::types::{H160, H256, U256, U64};
 use web3::{Error, Transport, Web3};
 
+pub mod ethereum_signer;
 pub mod signer;
 
+pub use ethereum_signer::{signer_from_source, EthereumSigner};
+
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
@@ -22,7 +26,7 @@ const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
 #[derive(Clone)]
 pub struct ETHClient<T: Transport> {
-    private_key: H256,
+    signer: Arc<dyn EthereumSigner>,
     pub sender_account: Address,
     pub contract_addr: H160,
     pub contract: ethabi::Contract,
@@ -47,24 +51,44 @@ impl<T: Transport> fmt::Debug for ETHClient<T> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignedCallResult {
     pub raw_tx: Vec<u8>,
+    /// Gas price of the transaction. For the EIP-1559 transactions it's the max fee per gas.
     pub gas_price: U256,
+    /// Max priority fee per gas (set for the EIP-1559 transactions only).
+    pub max_priority_fee_per_gas: Option<U256>,
     pub nonce: U256,
     pub hash: H256,
 }
 
+/// Fee parameters of the EIP-1559 transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eip1559Fees {
+    /// Maximum total price per gas the sender is willing to pay (base fee + priority fee).
+    pub max_fee_per_gas: U256,
+    /// Maximum tip per gas for the block producer.
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Response of the `eth_feeHistory` JSON-RPC method.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeeHistory {
+    /// Base fees of the requested blocks, followed by the base fee of the next block.
+    base_fee_per_gas: Vec<U256>,
+}
+
 impl<T: Transport> ETHClient<T> {
     pub fn new(
         transport: T,
         contract: ethabi::Contract,
         operator_eth_addr: H160,
-        operator_pk: H256,
+        signer: Arc<dyn EthereumSigner>,
         contract_eth_addr: H160,
         chain_id: u8,
         gas_price_factor: f64,
     ) -> Self {
         Self {
             sender_account: operator_eth_addr,
-            private_key: operator_pk,
+            signer,
             contract_addr: contract_eth_addr,
             chain_id,
             contract,
@@ -110,6 +134,40 @@ impl<T: Transport> ETHClient<T> {
         Ok(network_gas_price)
     }
 
+    /// Returns the base fees of the `block_count` latest blocks, followed by the base fee
+    /// of the next (pending) block. Requires the network to support EIP-1559.
+    pub async fn get_base_fee_history(
+        &self,
+        block_count: usize,
+    ) -> Result<Vec<U256>, failure::Error> {
+        let params = vec![
+            serde_json::to_value(U256::from(block_count))?,
+            serde_json::Value::from("latest"),
+            serde_json::Value::Array(Vec::new()),
+        ];
+        let response = self
+            .web3
+            .transport()
+            .execute("eth_feeHistory", params)
+            .compat()
+            .await?;
+        let fee_history: FeeHistory = serde_json::from_value(response)?;
+
+        Ok(fee_history.base_fee_per_gas)
+    }
+
+    /// Returns the priority fee per gas suggested by the Ethereum node.
+    pub async fn get_max_priority_fee(&self) -> Result<U256, failure::Error> {
+        let response = self
+            .web3
+            .transport()
+            .execute("eth_maxPriorityFeePerGas", Vec::new())
+            .compat()
+            .await?;
+
+        Ok(serde_json::from_value(response)?)
+    }
+
     /// Encodes the transaction data (smart contract method and its input) to the bytes
     /// without creating an actual transaction.
     pub fn encode_tx_data<P: Tokenize>(&self, func: &str, params: P) -> Vec<u8> {
@@ -146,6 +204,36 @@ impl<T: Transport> ETHClient<T> {
             None => self.get_gas_price().await?,
         };
 
+        self.sign_tx(data, contract_addr, gas_price, None, options)
+            .await
+    }
+
+    /// Signs the EIP-1559 transaction given the previously encoded data.
+    /// Fills in gas/nonce if not supplied inside options, `gas_price` of options is ignored.
+    pub async fn sign_prepared_eip1559_tx(
+        &self,
+        data: Vec<u8>,
+        fees: Eip1559Fees,
+        options: Options,
+    ) -> Result<SignedCallResult, failure::Error> {
+        self.sign_tx(
+            data,
+            self.contract_addr,
+            fees.max_fee_per_gas,
+            Some(fees.max_priority_fee_per_gas),
+            options,
+        )
+        .await
+    }
+
+    async fn sign_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas_price: U256,
+        max_priority_fee_per_gas: Option<U256>,
+        options: Options,
+    ) -> Result<SignedCallResult, failure::Error> {
         let nonce = match options.nonce {
             Some(nonce) => nonce,
             None => self.pending_nonce().await?,
@@ -173,11 +261,12 @@ impl<T: Transport> ETHClient<T> {
             to: Some(contract_addr),
             value: options.value.unwrap_or_default(),
             gas_price,
+            max_priority_fee_per_gas,
             gas,
             data,
         };
 
-        let signed_tx = tx.sign(&self.private_key);
+        let signed_tx = self.signer.sign_transaction(tx).await?;
         let hash = self
             .web3
             .web3()
@@ -188,6 +277,7 @@ impl<T: Transport> ETHClient<T> {
         Ok(SignedCallResult {
             raw_tx: signed_tx,
             gas_price,
+            max_priority_fee_per_gas,
             nonce,
             hash,
         })
@@ -212,6 +302,39 @@ impl<T: Transport> ETHClient<T> {
         self.sign_prepared_tx(data, options).await
     }
 
+    /// Calls the contract method in the read-only mode, without creating a transaction.
+    pub async fn call_contract_function<R: Detokenize, P: Tokenize>(
+        &self,
+        func: &str,
+        params: P,
+    ) -> Result<R, failure::Error> {
+        let contract = Contract::new(self.web3.eth(), self.contract_addr, self.contract.clone());
+        contract
+            .query(func, params, None, Options::default(), None)
+            .compat()
+            .await
+            .map_err(|e| failure::format_err!("Failed to query contract {}: {}", func, e))
+    }
+
+    /// Estimates the gas required to execute the contract call with the provided data.
+    /// Pending transactions of the sender are taken into account.
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, failure::Error> {
+        let request = CallRequest {
+            from: Some(self.sender_account),
+            to: self.contract_addr,
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(Bytes(data)),
+        };
+        Ok(self
+            .web3
+            .eth()
+            .estimate_gas(request, Some(BlockNumber::Pending))
+            .compat()
+            .await?)
+    }
+
     /// Sends the transaction to the Ethereum blockchain.
     /// Transaction is expected to be encoded as the byte sequence.
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, failure::Error> {
@@ -1,8 +1,14 @@
-use parity_crypto::publickey::sign;
-use rlp::RlpStream;
+use std::convert::TryFrom;
+
+use failure::ensure;
+use parity_crypto::publickey::{public_to_address, recover, sign, Signature};
+use rlp::{Rlp, RlpStream};
 use tiny_keccak::keccak256;
 use web3::types::{H160, H256, U256};
 
+/// Type byte of the EIP-1559 transaction envelope (see EIP-2718).
+const EIP1559_TX_TYPE: u8 = 0x02;
+
 /// Description of a Transaction, pending or in the chain.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RawTransaction {
@@ -14,9 +20,13 @@ pub struct RawTransaction {
     pub to: Option<H160>,
     /// Transfered value
     pub value: U256,
-    /// Gas Price
+    /// Gas Price. For the EIP-1559 transactions it's the max fee per gas.
     #[serde(rename = "gasPrice")]
     pub gas_price: U256,
+    /// Max priority fee per gas. If set, the transaction is encoded as the
+    /// EIP-1559 (type 2) transaction, otherwise as the legacy one.
+    #[serde(rename = "maxPriorityFeePerGas", default)]
+    pub max_priority_fee_per_gas: Option<U256>,
     /// Gas amount
     pub gas: U256,
     /// Input data
@@ -39,6 +49,10 @@ fn find_first_nonzero(vector: &[u8]) -> usize {
 impl RawTransaction {
     /// Signs and returns the RLP-encoded transaction
     pub fn sign(&self, private_key: &H256) -> Vec<u8> {
+        if self.max_priority_fee_per_gas.is_some() {
+            return self.sign_eip1559(private_key);
+        }
+
         let hash = self.hash();
         let sig = ecdsa_sign(hash, &private_key, self.chain_id);
         let mut tx = RlpStream::new();
@@ -55,6 +69,72 @@ impl RawTransaction {
         tx.out()
     }
 
+    /// Decodes the signed RLP-encoded transaction (either legacy or EIP-1559 one)
+    /// and returns it together with the address of its signer.
+    pub fn decode_signed(raw_tx: &[u8]) -> Result<(Self, H160), failure::Error> {
+        ensure!(!raw_tx.is_empty(), "Signed transaction is empty");
+        if raw_tx[0] == EIP1559_TX_TYPE {
+            return Self::decode_signed_eip1559(&raw_tx[1..]);
+        }
+
+        let rlp = Rlp::new(raw_tx);
+        ensure!(
+            rlp.is_list() && rlp.item_count()? == 9,
+            "Signed transaction is not a legacy transaction"
+        );
+        // Only the replay-protected transactions are signed, see `ecdsa_sign`.
+        let v: u64 = rlp.val_at(6)?;
+        ensure!(v >= 35, "Signed transaction has no chain id");
+        let chain_id = u8::try_from((v - 35) / 2)?;
+        let recovery_id = ((v - 35) % 2) as u8;
+
+        let tx = Self {
+            chain_id,
+            nonce: rlp.val_at(0)?,
+            to: decode_recipient(&rlp, 3)?,
+            value: rlp.val_at(4)?,
+            gas_price: rlp.val_at(1)?,
+            max_priority_fee_per_gas: None,
+            gas: rlp.val_at(2)?,
+            data: rlp.val_at(5)?,
+        };
+        let signer = recover_signer(tx.hash(), &rlp, 7, recovery_id)?;
+
+        Ok((tx, signer))
+    }
+
+    fn decode_signed_eip1559(payload: &[u8]) -> Result<(Self, H160), failure::Error> {
+        let rlp = Rlp::new(payload);
+        ensure!(
+            rlp.is_list() && rlp.item_count()? == 12,
+            "Signed transaction is not an EIP-1559 transaction"
+        );
+        let chain_id: U256 = rlp.val_at(0)?;
+        ensure!(
+            chain_id <= U256::from(u8::max_value()),
+            "Unsupported chain id: {}",
+            chain_id
+        );
+        ensure!(
+            rlp.at(8)?.item_count()? == 0,
+            "Signed transaction has a non-empty access list"
+        );
+
+        let tx = Self {
+            chain_id: chain_id.as_u32() as u8,
+            nonce: rlp.val_at(1)?,
+            to: decode_recipient(&rlp, 5)?,
+            value: rlp.val_at(6)?,
+            gas_price: rlp.val_at(3)?,
+            max_priority_fee_per_gas: Some(rlp.val_at(2)?),
+            gas: rlp.val_at(4)?,
+            data: rlp.val_at(7)?,
+        };
+        let signer = recover_signer(tx.eip1559_hash(), &rlp, 10, rlp.val_at(9)?)?;
+
+        Ok((tx, signer))
+    }
+
     fn hash(&self) -> [u8; 32] {
         let mut hash = RlpStream::new();
         hash.begin_unbounded_list();
@@ -78,6 +158,94 @@ impl RawTransaction {
         s.append(&self.value);
         s.append(&self.data);
     }
+
+    /// Signs and returns the typed envelope of the EIP-1559 transaction:
+    /// `0x02 || rlp([chain_id, nonce, max_priority_fee, max_fee, gas, to, value, data, access_list, y_parity, r, s])`.
+    fn sign_eip1559(&self, private_key: &H256) -> Vec<u8> {
+        let hash = self.eip1559_hash();
+        let sig = sign(&(*private_key).into(), &hash.into()).expect("failed to sign eth message");
+
+        let mut tx = RlpStream::new();
+        tx.begin_unbounded_list();
+        self.encode_eip1559(&mut tx);
+        tx.append(&sig.v());
+        let r_start = find_first_nonzero(sig.r());
+        let r = &sig.r()[r_start..];
+        tx.append(&r);
+        let s_start = find_first_nonzero(sig.s());
+        let s = &sig.s()[s_start..];
+        tx.append(&s);
+        tx.finalize_unbounded_list();
+
+        typed_envelope(EIP1559_TX_TYPE, tx.out())
+    }
+
+    fn eip1559_hash(&self) -> [u8; 32] {
+        let mut hash = RlpStream::new();
+        hash.begin_unbounded_list();
+        self.encode_eip1559(&mut hash);
+        hash.finalize_unbounded_list();
+        keccak256(&typed_envelope(EIP1559_TX_TYPE, hash.out()))
+    }
+
+    fn encode_eip1559(&self, s: &mut RlpStream) {
+        let max_priority_fee_per_gas = self
+            .max_priority_fee_per_gas
+            .expect("EIP-1559 transaction must have a max priority fee");
+
+        s.append(&U256::from(self.chain_id));
+        s.append(&self.nonce);
+        s.append(&max_priority_fee_per_gas);
+        s.append(&self.gas_price);
+        s.append(&self.gas);
+        if let Some(ref t) = self.to {
+            s.append(t);
+        } else {
+            s.append(&vec![]);
+        }
+        s.append(&self.value);
+        s.append(&self.data);
+        // Access list is not used.
+        s.begin_list(0);
+    }
+}
+
+fn typed_envelope(tx_type: u8, payload: Vec<u8>) -> Vec<u8> {
+    let mut envelope = Vec::with_capacity(payload.len() + 1);
+    envelope.push(tx_type);
+    envelope.extend(payload);
+    envelope
+}
+
+fn decode_recipient(rlp: &Rlp, index: usize) -> Result<Option<H160>, failure::Error> {
+    let to: Vec<u8> = rlp.val_at(index)?;
+    if to.is_empty() {
+        return Ok(None);
+    }
+
+    ensure!(to.len() == 20, "Invalid recipient length: {}", to.len());
+    Ok(Some(H160::from_slice(&to)))
+}
+
+/// Recovers the signer of the transaction hash, `r` and `s` are expected to be
+/// the items of the transaction at `index` and `index + 1`.
+fn recover_signer(
+    hash: [u8; 32],
+    rlp: &Rlp,
+    index: usize,
+    recovery_id: u8,
+) -> Result<H160, failure::Error> {
+    ensure!(recovery_id <= 1, "Invalid recovery id: {}", recovery_id);
+    let r: U256 = rlp.val_at(index)?;
+    let s: U256 = rlp.val_at(index + 1)?;
+
+    let mut signature = [0u8; 65];
+    r.to_big_endian(&mut signature[0..32]);
+    s.to_big_endian(&mut signature[32..64]);
+    signature[64] = recovery_id;
+
+    let public_key = recover(&Signature::from(signature), &hash.into())?;
+    Ok(public_to_address(&public_key))
 }
 
 fn ecdsa_sign(hash: [u8; 32], private_key: &H256, chain_id: u8) -> EcdsaSig {
@@ -97,3 +265,109 @@ pub struct EcdsaSig {
     r: Vec<u8>,
     s: Vec<u8>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::node::tx::PackedEthSignature;
+
+    fn test_tx() -> RawTransaction {
+        RawTransaction {
+            chain_id: 9,
+            nonce: 1.into(),
+            to: Some(H160::from_low_u64_be(0xDEAD)),
+            value: 0.into(),
+            gas_price: 2_000_000_000u64.into(),
+            gas: 21_000.into(),
+            data: vec![0xAB; 4],
+            max_priority_fee_per_gas: None,
+        }
+    }
+
+    /// Checks that the legacy transaction is encoded as a plain RLP list.
+    #[test]
+    fn legacy_tx_encoding() {
+        let private_key = H256::from_low_u64_be(1);
+        let signed_tx = test_tx().sign(&private_key);
+
+        let rlp = Rlp::new(&signed_tx);
+        assert!(rlp.is_list());
+        assert_eq!(rlp.item_count().unwrap(), 9);
+    }
+
+    /// Checks that the signed legacy transaction is decoded back with its signer.
+    #[test]
+    fn legacy_tx_decoding() {
+        let private_key = H256::from_low_u64_be(1);
+        let tx = test_tx();
+        let signed_tx = tx.sign(&private_key);
+
+        let (decoded_tx, signer) = RawTransaction::decode_signed(&signed_tx).unwrap();
+        assert_eq!(decoded_tx, tx);
+        assert_eq!(
+            signer,
+            PackedEthSignature::address_from_private_key(&private_key).unwrap()
+        );
+
+        // Altered transaction can't be attributed to the original signer.
+        let data_start = signed_tx
+            .windows(5)
+            .position(|window| window == [0x84, 0xAB, 0xAB, 0xAB, 0xAB])
+            .expect("transaction data not found");
+        let mut altered_tx = signed_tx;
+        altered_tx[data_start + 4] = 0xAC;
+        let (decoded_tx, altered_signer) = RawTransaction::decode_signed(&altered_tx).unwrap();
+        assert_eq!(decoded_tx.data, vec![0xAB, 0xAB, 0xAB, 0xAC]);
+        assert_ne!(altered_signer, signer);
+    }
+
+    /// Checks that the transaction with the priority fee is encoded as the EIP-1559 typed envelope.
+    #[test]
+    fn eip1559_tx_encoding() {
+        let private_key = H256::from_low_u64_be(1);
+        let tx = RawTransaction {
+            max_priority_fee_per_gas: Some(1_000_000_000u64.into()),
+            ..test_tx()
+        };
+        let signed_tx = tx.sign(&private_key);
+
+        assert_eq!(signed_tx[0], EIP1559_TX_TYPE);
+        let rlp = Rlp::new(&signed_tx[1..]);
+        assert!(rlp.is_list());
+        assert_eq!(rlp.item_count().unwrap(), 12);
+        assert_eq!(rlp.val_at::<U256>(0).unwrap(), U256::from(tx.chain_id));
+        assert_eq!(rlp.val_at::<U256>(2).unwrap(), 1_000_000_000u64.into());
+        assert_eq!(rlp.val_at::<U256>(3).unwrap(), tx.gas_price);
+        assert_eq!(rlp.at(8).unwrap().item_count().unwrap(), 0);
+    }
+
+    /// Checks that the EIP-1559 transaction is signed for the well-known address of the key `1`.
+    #[test]
+    fn eip1559_tx_signer() {
+        let private_key = H256::from_low_u64_be(1);
+        let expected_signer: H160 = "7e5f4552091a69125d5dfcb7b8c2659029395bdf".parse().unwrap();
+        let tx = RawTransaction {
+            max_priority_fee_per_gas: Some(1_000_000_000u64.into()),
+            ..test_tx()
+        };
+        let signed_tx = tx.sign(&private_key);
+
+        let (decoded_tx, signer) = RawTransaction::decode_signed(&signed_tx).unwrap();
+        assert_eq!(decoded_tx, tx);
+        assert_eq!(signer, expected_signer);
+
+        // The signature must cover the fee fields of the transaction.
+        let fee_start = signed_tx
+            .windows(5)
+            .position(|window| window == [0x84, 0x3B, 0x9A, 0xCA, 0x00])
+            .expect("priority fee not found");
+        let mut altered_tx = signed_tx;
+        altered_tx[fee_start + 4] = 0x01;
+        let (decoded_tx, altered_signer) = RawTransaction::decode_signed(&altered_tx).unwrap();
+        assert_eq!(
+            decoded_tx.max_priority_fee_per_gas,
+            Some(1_000_000_001u64.into())
+        );
+        assert_ne!(altered_signer, expected_signer);
+    }
+}
@@ -1,6 +1,7 @@
 // Built-in deps
 use std::env;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 // External uses
@@ -8,6 +9,8 @@ use futures::{channel::mpsc, executor::block_on, SinkExt};
 use web3::types::{H160, H256};
 // Local uses
 use crate::params::block_chunk_sizes;
+use crate::primitives::UnsignedRatioSerializeAsDecimal;
+use num::{rational::Ratio, BigUint};
 use url::Url;
 
 /// If its placed inside thread::spawn closure it will notify channel when this thread panics.
@@ -61,6 +64,16 @@ pub struct EthSenderOptions {
     pub wait_confirmations: u64,
     pub max_txs_in_flight: u64,
     pub is_enabled: bool,
+    /// Whether to send EIP-1559 (type 2) transactions instead of the legacy ones.
+    pub eip1559_enabled: bool,
+    /// The maximum amount of blocks committed (or verified) within one Ethereum transaction.
+    pub max_blocks_per_tx: u64,
+    /// The maximum time for the block to wait for other blocks to be aggregated with.
+    pub aggregation_max_wait: Duration,
+    /// Percentage by which the gas limit is increased when it's not enough for the transaction.
+    pub gas_limit_increase_percent: u64,
+    /// Upper bound for the gas limit of the transaction, chosen to fit into the Ethereum block.
+    pub max_gas_limit: u64,
 }
 
 impl EthSenderOptions {
@@ -68,6 +81,7 @@ impl EthSenderOptions {
     /// Panics if any of options is missing or has inappropriate value.
     pub fn from_env() -> Self {
         let tx_poll_period_secs: u64 = parse_env("ETH_TX_POLL_PERIOD");
+        let aggregation_max_wait_secs: u64 = parse_env("ETH_AGGREGATION_MAX_WAIT");
 
         Self {
             expected_wait_time_block: parse_env("ETH_EXPECTED_WAIT_TIME_BLOCK"),
@@ -75,6 +89,11 @@ impl EthSenderOptions {
             wait_confirmations: parse_env("ETH_WAIT_CONFIRMATIONS"),
             max_txs_in_flight: parse_env("ETH_MAX_TXS_IN_FLIGHT"),
             is_enabled: parse_env("ETH_IS_ENABLED"),
+            eip1559_enabled: parse_env("ETH_EIP1559_ENABLED"),
+            max_blocks_per_tx: parse_env("ETH_MAX_BLOCKS_PER_TX"),
+            aggregation_max_wait: Duration::new(aggregation_max_wait_secs, 0),
+            gas_limit_increase_percent: parse_env("ETH_GAS_LIMIT_INCREASE_PERCENT"),
+            max_gas_limit: parse_env("ETH_MAX_GAS_LIMIT"),
         }
     }
 }
@@ -128,13 +147,47 @@ impl AdminServerOptions {
 
 #[derive(Clone, Debug)]
 pub enum TokenPriceSource {
-    CoinMarketCap { base_url: Url },
-    CoinGecko { base_url: Url },
+    CoinMarketCap {
+        base_url: Url,
+    },
+    CoinGecko {
+        base_url: Url,
+    },
+    /// Median of the prices reported by several sources.
+    Median {
+        sources: Vec<TokenPriceSource>,
+        /// Minimum amount of fresh quotes agreeing with each other required to provide the price.
+        min_quorum: usize,
+    },
 }
 
+/// Default minimum amount of agreeing quotes for the median token price source.
+pub const DEFAULT_TOKEN_PRICE_MIN_QUORUM: usize = 2;
+
 impl TokenPriceSource {
     fn from_env() -> Self {
-        match get_env("TOKEN_PRICE_SOURCE").to_lowercase().as_str() {
+        let sources: Vec<_> = get_env("TOKEN_PRICE_SOURCE")
+            .split(',')
+            .map(|source| Self::single_source(source.trim()))
+            .collect();
+
+        if sources.len() == 1 {
+            sources.into_iter().next().unwrap()
+        } else {
+            let min_quorum = env::var("TOKEN_PRICE_MIN_QUORUM")
+                .ok()
+                .map(|_| parse_env("TOKEN_PRICE_MIN_QUORUM"))
+                .unwrap_or(DEFAULT_TOKEN_PRICE_MIN_QUORUM);
+
+            Self::Median {
+                sources,
+                min_quorum,
+            }
+        }
+    }
+
+    fn single_source(source: &str) -> Self {
+        match source.to_lowercase().as_str() {
             "coinmarketcap" => Self::CoinMarketCap {
                 base_url: parse_env("COINMARKETCAP_BASE_URL"),
             },
@@ -146,6 +199,60 @@ impl TokenPriceSource {
     }
 }
 
+/// Source of the signatures for the operator Ethereum account.
+#[derive(Clone)]
+pub enum EthSignerSource {
+    /// Private key stored in the process memory.
+    PrivateKey(H256),
+    /// External signer serving the `eth_signTransaction` JSON-RPC method (e.g. Clef).
+    JsonRpc { url: Url },
+    /// Encrypted key file in the Web3 Secret Storage format, along with the file containing its password.
+    Keystore {
+        path: PathBuf,
+        password_path: PathBuf,
+    },
+}
+
+impl std::fmt::Debug for EthSignerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // We do not want to have a private key in the debug representation.
+        match self {
+            Self::PrivateKey(_) => f.debug_tuple("PrivateKey").field(&"<hidden>").finish(),
+            Self::JsonRpc { url } => f.debug_struct("JsonRpc").field("url", url).finish(),
+            Self::Keystore {
+                path,
+                password_path,
+            } => f
+                .debug_struct("Keystore")
+                .field("path", path)
+                .field("password_path", password_path)
+                .finish(),
+        }
+    }
+}
+
+impl EthSignerSource {
+    /// Returns `None` if the operator signer is not configured.
+    /// For backward compatibility, private key signer is used if `OPERATOR_ETH_SIGNER` is not set.
+    fn from_env() -> Option<Self> {
+        let source = env::var("OPERATOR_ETH_SIGNER").unwrap_or_else(|_| "private_key".to_string());
+
+        match source.to_lowercase().as_str() {
+            "private_key" => env::var("OPERATOR_PRIVATE_KEY")
+                .ok()
+                .map(|_| Self::PrivateKey(parse_env("OPERATOR_PRIVATE_KEY"))),
+            "json_rpc" => Some(Self::JsonRpc {
+                url: parse_env("OPERATOR_ETH_SIGNER_URL"),
+            }),
+            "keystore" => Some(Self::Keystore {
+                path: parse_env("OPERATOR_KEYSTORE_PATH"),
+                password_path: parse_env("OPERATOR_KEYSTORE_PASSWORD_PATH"),
+            }),
+            source => panic!("Unknown operator Ethereum signer: {}", source),
+        }
+    }
+}
+
 /// Configuration options related to generating blocks by state keeper.
 /// Each block is generated after a certain amount of miniblock iterations.
 /// Miniblock iteration is a routine of processing transactions received so far.
@@ -188,7 +295,7 @@ pub struct ConfigurationOptions {
     pub governance_eth_addr: H160,
     pub operator_fee_eth_addr: H160,
     pub operator_commit_eth_addr: H160,
-    pub operator_private_key: Option<H256>,
+    pub operator_eth_signer: Option<EthSignerSource>,
     pub chain_id: u8,
     pub gas_price_factor: f64,
     pub prover_server_address: SocketAddr,
@@ -196,15 +303,45 @@ pub struct ConfigurationOptions {
     pub api_requests_caches_size: usize,
     pub available_block_chunk_sizes: Vec<usize>,
     pub max_number_of_withdrawals_per_block: usize,
+    /// Minimal fee increase (in percent) required to replace a transaction in the mempool.
+    pub mempool_replacement_fee_increase: u64,
     pub eth_watch_poll_interval: Duration,
     pub eth_network: String,
     pub idle_provers: u32,
+    /// Whether the prover server should verify the published proofs before storing them.
+    /// Has to be disabled if the dummy prover is used.
+    pub prover_server_verify_proofs: bool,
+    /// Secret used to sign the provers authentication tokens.
+    /// Required unless the authentication is explicitly disabled via `PROVER_SERVER_AUTH_DISABLED`,
+    /// in which case it's `None` and prover server API doesn't require authentication.
+    pub prover_server_secret_auth: Option<String>,
     pub miniblock_timings: MiniblockTimings,
     pub prometheus_export_port: u16,
     pub token_price_source: TokenPriceSource,
     pub witness_generators: usize,
     /// Fee increase coefficient for fast processing of withdrawal.
     pub ticker_fast_processing_coeff: f64,
+    /// Cost of the zero-knowledge proof generation for one chunk of the block, in USD.
+    pub ticker_zkp_cost_chunk_usd: Ratio<BigUint>,
+    /// Shared secret used to sign fee quotes returned by the `get_tx_fee` method.
+    pub fee_quote_secret: String,
+    /// Time for which the signed fee quote is honoured.
+    pub fee_quote_lifetime: Duration,
+}
+
+/// Loads the prover server authentication secret, unless the authentication is explicitly
+/// disabled by setting `PROVER_SERVER_AUTH_DISABLED` to `true`.
+fn prover_server_secret_auth_from_env() -> Option<String> {
+    let auth_disabled = env::var("PROVER_SERVER_AUTH_DISABLED")
+        .ok()
+        .map(|_| parse_env::<bool>("PROVER_SERVER_AUTH_DISABLED"))
+        .unwrap_or(false);
+
+    if auth_disabled {
+        None
+    } else {
+        Some(get_env("PROVER_SERVER_SECRET_AUTH"))
+    }
 }
 
 impl ConfigurationOptions {
@@ -224,11 +361,7 @@ impl ConfigurationOptions {
             governance_eth_addr: parse_env_with("GOVERNANCE_ADDR", |s| &s[2..]),
             operator_commit_eth_addr: parse_env_with("OPERATOR_COMMIT_ETH_ADDRESS", |s| &s[2..]),
             operator_fee_eth_addr: parse_env_with("OPERATOR_FEE_ETH_ADDRESS", |s| &s[2..]),
-            operator_private_key: if env::var("OPERATOR_PRIVATE_KEY").is_ok() {
-                Some(parse_env("OPERATOR_PRIVATE_KEY"))
-            } else {
-                None
-            },
+            operator_eth_signer: EthSignerSource::from_env(),
             chain_id: parse_env("CHAIN_ID"),
             gas_price_factor: parse_env("GAS_PRICE_FACTOR"),
             prover_server_address: parse_env("PROVER_SERVER_BIND"),
@@ -236,16 +369,31 @@ impl ConfigurationOptions {
             api_requests_caches_size: parse_env("API_REQUESTS_CACHES_SIZE"),
             available_block_chunk_sizes,
             max_number_of_withdrawals_per_block: parse_env("MAX_NUMBER_OF_WITHDRAWALS_PER_BLOCK"),
+            mempool_replacement_fee_increase: parse_env("MEMPOOL_REPLACEMENT_FEE_INCREASE"),
             eth_watch_poll_interval: Duration::from_millis(parse_env::<u64>(
                 "ETH_WATCH_POLL_INTERVAL",
             )),
             eth_network: parse_env("ETH_NETWORK"),
             idle_provers: parse_env("IDLE_PROVERS"),
+            prover_server_verify_proofs: parse_env("PROVER_SERVER_VERIFY_PROOFS"),
+            prover_server_secret_auth: prover_server_secret_auth_from_env(),
             miniblock_timings: MiniblockTimings::from_env(),
             prometheus_export_port: parse_env("PROMETHEUS_EXPORT_PORT"),
             token_price_source: TokenPriceSource::from_env(),
             witness_generators: parse_env("WITNESS_GENERATORS"),
             ticker_fast_processing_coeff: parse_env("TICKER_FAST_PROCESSING_COEFF"),
+            ticker_zkp_cost_chunk_usd:
+                UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot(&get_env(
+                    "TICKER_ZKP_COST_CHUNK_USD",
+                ))
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to parse environment variable TICKER_ZKP_COST_CHUNK_USD: {}",
+                        e
+                    )
+                }),
+            fee_quote_secret: parse_env("FEE_QUOTE_SECRET"),
+            fee_quote_lifetime: Duration::from_secs(parse_env("FEE_QUOTE_LIFETIME")),
         }
     }
 }
@@ -53,14 +53,20 @@ pub struct ETHOperation {
     pub id: i64,
     /// Type of the operation.
     pub op_type: OperationType,
-    /// Optional ZKSync operation associated with Ethereum operation.
-    pub op: Option<Operation>,
+    /// ZKSync operations associated with Ethereum operation, ordered by the block number.
+    /// Contains several operations if the blocks were aggregated into one transaction,
+    /// and is empty for the operations not related to blocks (e.g. `completeWithdrawals`).
+    pub ops: Vec<Operation>,
     /// Used nonce (fixed for all the sent transactions).
     pub nonce: U256,
     /// Deadline block of the last sent transaction.
     pub last_deadline_block: u64,
     /// Gas price used in the last sent transaction.
+    /// For the EIP-1559 transactions it's the max fee per gas.
     pub last_used_gas_price: U256,
+    /// Max priority fee per gas used in the last sent transaction.
+    /// Set only if the operation is sent via EIP-1559 transactions.
+    pub last_used_priority_fee: Option<U256>,
     /// Hashes of all the sent transactions.
     pub used_tx_hashes: Vec<H256>,
     /// Tx payload (not signed).
@@ -84,7 +90,7 @@ impl ETHOperation {
 
     /// Checks whether this object relates to the `Verify` zkSync operation.
     pub fn is_verify(&self) -> bool {
-        if let Some(op) = &self.op {
+        if let Some(op) = self.ops.first() {
             matches!(op.action, Action::Verify { .. })
         } else {
             false
@@ -107,6 +113,7 @@ impl PartialEq for ETHOperation {
         (self.id == other.id)
             && (self.last_deadline_block == other.last_deadline_block)
             && (self.last_used_gas_price == other.last_used_gas_price)
+            && (self.last_used_priority_fee == other.last_used_priority_fee)
             && (self.used_tx_hashes == other.used_tx_hashes)
             && (self.confirmed == other.confirmed)
             && (self.final_hash == other.final_hash)
@@ -2,8 +2,8 @@ use super::FranklinOp;
 use super::PriorityOp;
 use super::{AccountId, BlockNumber, Fr};
 use crate::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
-use crate::node::SignedFranklinTx;
-use crate::params::CHUNK_BIT_WIDTH;
+use crate::node::{FranklinTx, SignedFranklinTx};
+use crate::params::{CHUNK_BIT_WIDTH, PRIORITY_EXPIRATION};
 use crate::serialization::*;
 use chrono::DateTime;
 use chrono::Utc;
@@ -197,6 +197,37 @@ impl Block {
         smallest_block_size_for_chunks(chunks_used, available_block_sizes)
     }
 
+    /// Returns the priority of the proof generation for this block.
+    /// Blocks with fast withdrawals should be proven as soon as possible.
+    pub fn proof_priority(&self) -> ProofPriority {
+        let fast_processing_required = self
+            .block_transactions
+            .iter()
+            .filter_map(ExecutedOperations::get_executed_tx)
+            .any(|exec_tx| match &exec_tx.signed_tx.tx {
+                FranklinTx::Withdraw(tx) => tx.fast,
+                _ => false,
+            });
+
+        if fast_processing_required {
+            ProofPriority::High
+        } else {
+            ProofPriority::Normal
+        }
+    }
+
+    /// Returns the earliest Ethereum block by which the priority operations
+    /// included into this block must be processed, if there are any.
+    pub fn priority_ops_deadline(&self) -> Option<u64> {
+        self.block_transactions
+            .iter()
+            .filter_map(|block_tx| match block_tx {
+                ExecutedOperations::PriorityOp(exec_op) => Some(exec_op.priority_op.deadline_block),
+                ExecutedOperations::Tx(_) => None,
+            })
+            .min()
+    }
+
     pub fn get_withdrawals_data(&self) -> Vec<u8> {
         let mut withdrawals_data = Vec::new();
 
@@ -212,6 +243,37 @@ impl Block {
     }
 }
 
+/// Priority of the proof generation job for a block.
+/// Blocks are still proven in order, but the provers are allocated so that a block
+/// with a higher priority and all the blocks before it are proven without waiting in the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ProofPriority {
+    /// Regular blocks.
+    Normal,
+    /// Blocks with latency-sensitive operations, e.g. fast withdrawals
+    /// or priority operations close to their deadline.
+    High,
+}
+
+/// Priority operations are considered close to their deadline once less than this amount
+/// of Ethereum blocks is left until it, i.e. once three quarters of the expiration window passed.
+pub const PRIORITY_OPS_DEADLINE_THRESHOLD: u64 = PRIORITY_EXPIRATION / 4;
+
+impl ProofPriority {
+    /// Returns the priority of the proof generation job, taking into account the deadline
+    /// of its priority operations: jobs close to the deadline have the high priority.
+    pub fn with_deadline(self, deadline_block: Option<u64>, current_eth_block: u64) -> Self {
+        match deadline_block {
+            Some(deadline_block)
+                if deadline_block <= current_eth_block + PRIORITY_OPS_DEADLINE_THRESHOLD =>
+            {
+                ProofPriority::High
+            }
+            _ => self,
+        }
+    }
+}
+
 // Get smallest block size given
 pub fn smallest_block_size_for_chunks(
     chunks_used: usize,
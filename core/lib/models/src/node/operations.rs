@@ -4,7 +4,8 @@ use super::FranklinTx;
 use crate::node::tx::ChangePubKey;
 use crate::node::{
     pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount, Close, Deposit,
-    FranklinPriorityOp, FullExit, PubKeyHash, Transfer, Withdraw,
+    ForcedExit, FranklinPriorityOp, FullExit, Order, PubKeyHash, Swap, TimeRange, Transfer,
+    Withdraw,
 };
 use crate::params::{
     ACCOUNT_ID_BIT_WIDTH, ADDRESS_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH,
@@ -167,7 +168,17 @@ impl TransferToNewOp {
         let nonce = 0; // It is unknown from pubdata
 
         Ok(Self {
-            tx: Transfer::new(from_id, from, to, token, amount, fee, nonce, None),
+            tx: Transfer::new(
+                from_id,
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                nonce,
+                Default::default(),
+                None,
+            ),
             from: from_id,
             to: to_id,
         })
@@ -240,6 +251,7 @@ impl TransferOp {
                 amount,
                 fee,
                 nonce,
+                Default::default(),
                 None,
             ),
             from: from_id,
@@ -315,7 +327,17 @@ impl WithdrawOp {
         let nonce = 0; // From pubdata it is unknown
 
         Ok(Self {
-            tx: Withdraw::new(account_id, from, to, token, amount, fee, nonce, None),
+            tx: Withdraw::new(
+                account_id,
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                nonce,
+                Default::default(),
+                None,
+            ),
             account_id,
         })
     }
@@ -385,9 +407,14 @@ impl ChangePubKeyOp {
         data
     }
 
+    /// Returns the Ethereum signature followed by the time range, if it's a part of the signed message.
     pub fn get_eth_witness(&self) -> Vec<u8> {
         if let Some(eth_signature) = &self.tx.eth_signature {
-            eth_signature.serialize_packed().to_vec()
+            let mut witness = eth_signature.serialize_packed().to_vec();
+            if self.tx.time_range != TimeRange::default() {
+                witness.extend_from_slice(&self.tx.time_range.to_be_bytes());
+            }
+            witness
         } else {
             Vec::new()
         }
@@ -435,6 +462,7 @@ impl ChangePubKeyOp {
                 account,
                 new_pk_hash,
                 nonce,
+                time_range: Default::default(),
                 eth_signature: None,
             },
             account_id,
@@ -523,6 +551,201 @@ impl FullExitOp {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForcedExitOp {
+    pub tx: ForcedExit,
+    /// Account ID of the account to which ForcedExit is applied.
+    pub target_account_id: AccountId,
+    /// None if withdraw was unsuccessful
+    pub withdraw_amount: Option<BigUintSerdeWrapper>,
+}
+
+impl ForcedExitOp {
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x08;
+    pub const WITHDRAW_DATA_PREFIX: [u8; 1] = [1];
+
+    fn amount(&self) -> u128 {
+        self.withdraw_amount
+            .clone()
+            .map(|a| a.0.to_u128().unwrap())
+            .unwrap_or(0)
+    }
+
+    fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.tx.initiator_account_id.to_be_bytes());
+        data.extend_from_slice(&self.target_account_id.to_be_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&self.amount().to_be_bytes());
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.extend_from_slice(self.tx.target.as_bytes());
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    fn get_withdrawal_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&Self::WITHDRAW_DATA_PREFIX); // first byte is a bool variable 'addToPendingWithdrawalsQueue'
+        data.extend_from_slice(self.tx.target.as_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&self.amount().to_be_bytes());
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, failure::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for forced exit pubdata"
+        );
+
+        let initiator_account_id_offset = 1;
+        let target_account_id_offset = initiator_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_id_offset = target_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_id_offset + TOKEN_BIT_WIDTH / 8;
+        let fee_offset = amount_offset + BALANCE_BIT_WIDTH / 8;
+        let eth_address_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let initiator_account_id =
+            bytes_slice_to_uint32(&bytes[initiator_account_id_offset..target_account_id_offset])
+                .ok_or_else(|| {
+                    format_err!("Cant get initiator account id from forced exit pubdata")
+                })?;
+        let target_account_id = bytes_slice_to_uint32(
+            &bytes[target_account_id_offset..token_id_offset],
+        )
+        .ok_or_else(|| format_err!("Cant get target account id from forced exit pubdata"))?;
+        let token = bytes_slice_to_uint16(&bytes[token_id_offset..amount_offset])
+            .ok_or_else(|| format_err!("Cant get token id from forced exit pubdata"))?;
+        let amount = BigUint::from_u128(
+            bytes_slice_to_uint128(&bytes[amount_offset..fee_offset])
+                .ok_or_else(|| format_err!("Cant get amount from forced exit pubdata"))?,
+        )
+        .unwrap();
+        let fee = unpack_fee_amount(
+            &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get fee from forced exit pubdata"))?;
+        let target = Address::from_slice(
+            &bytes[eth_address_offset..eth_address_offset + ETH_ADDRESS_BIT_WIDTH / 8],
+        );
+        let nonce = 0; // From pubdata it is unknown
+
+        Ok(Self {
+            tx: ForcedExit::new(initiator_account_id, target, token, fee, nonce, None),
+            target_account_id,
+            withdraw_amount: Some(amount.into()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapOp {
+    pub tx: Swap,
+}
+
+impl SwapOp {
+    pub const CHUNKS: usize = 5;
+    pub const OP_CODE: u8 = 0x09;
+
+    fn get_public_data(&self) -> Vec<u8> {
+        let (first_order, second_order) = &self.tx.orders;
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.tx.submitter_id.to_be_bytes());
+        data.extend_from_slice(&first_order.account_id.to_be_bytes());
+        data.extend_from_slice(&second_order.account_id.to_be_bytes());
+        data.extend_from_slice(&first_order.token_sell.to_be_bytes());
+        data.extend_from_slice(&second_order.token_sell.to_be_bytes());
+        data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.amounts.0));
+        data.extend_from_slice(&pack_token_amount(&self.tx.amounts.1));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, failure::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for swap pubdata"
+        );
+
+        let amount_len = (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let submitter_id_offset = 1;
+        let first_account_id_offset = submitter_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let second_account_id_offset = first_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let first_token_offset = second_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let second_token_offset = first_token_offset + TOKEN_BIT_WIDTH / 8;
+        let fee_token_offset = second_token_offset + TOKEN_BIT_WIDTH / 8;
+        let first_amount_offset = fee_token_offset + TOKEN_BIT_WIDTH / 8;
+        let second_amount_offset = first_amount_offset + amount_len;
+        let fee_offset = second_amount_offset + amount_len;
+        let fee_end = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let submitter_id =
+            bytes_slice_to_uint32(&bytes[submitter_id_offset..first_account_id_offset])
+                .ok_or_else(|| format_err!("Cant get submitter account id from swap pubdata"))?;
+        let first_account_id =
+            bytes_slice_to_uint32(&bytes[first_account_id_offset..second_account_id_offset])
+                .ok_or_else(|| format_err!("Cant get first account id from swap pubdata"))?;
+        let second_account_id =
+            bytes_slice_to_uint32(&bytes[second_account_id_offset..first_token_offset])
+                .ok_or_else(|| format_err!("Cant get second account id from swap pubdata"))?;
+        let first_token = bytes_slice_to_uint16(&bytes[first_token_offset..second_token_offset])
+            .ok_or_else(|| format_err!("Cant get first token id from swap pubdata"))?;
+        let second_token = bytes_slice_to_uint16(&bytes[second_token_offset..fee_token_offset])
+            .ok_or_else(|| format_err!("Cant get second token id from swap pubdata"))?;
+        let fee_token = bytes_slice_to_uint16(&bytes[fee_token_offset..first_amount_offset])
+            .ok_or_else(|| format_err!("Cant get fee token id from swap pubdata"))?;
+        let first_amount = unpack_token_amount(&bytes[first_amount_offset..second_amount_offset])
+            .ok_or_else(|| format_err!("Cant get first amount from swap pubdata"))?;
+        let second_amount = unpack_token_amount(&bytes[second_amount_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get second amount from swap pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..fee_end])
+            .ok_or_else(|| format_err!("Cant get fee from swap pubdata"))?;
+
+        // Addresses, nonces and prices are unknown from pubdata.
+        // Orders amounts are equal to the swapped amounts.
+        let first_order = Order::new(
+            first_account_id,
+            Address::zero(),
+            0,
+            first_token,
+            second_token,
+            (BigUint::from(0u32), BigUint::from(0u32)),
+            first_amount.clone(),
+            Default::default(),
+            None,
+        );
+        let second_order = Order::new(
+            second_account_id,
+            Address::zero(),
+            0,
+            second_token,
+            first_token,
+            (BigUint::from(0u32), BigUint::from(0u32)),
+            second_amount.clone(),
+            Default::default(),
+            None,
+        );
+
+        Ok(Self {
+            tx: Swap::new(
+                submitter_id,
+                Address::zero(),
+                0,
+                (first_order, second_order),
+                (first_amount, second_amount),
+                fee,
+                fee_token,
+                None,
+            ),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum FranklinOp {
@@ -534,6 +757,8 @@ pub enum FranklinOp {
     Transfer(Box<TransferOp>),
     FullExit(Box<FullExitOp>),
     ChangePubKeyOffchain(Box<ChangePubKeyOp>),
+    ForcedExit(Box<ForcedExitOp>),
+    Swap(Box<SwapOp>),
 }
 
 impl FranklinOp {
@@ -547,6 +772,8 @@ impl FranklinOp {
             FranklinOp::Transfer(_) => TransferOp::CHUNKS,
             FranklinOp::FullExit(_) => FullExitOp::CHUNKS,
             FranklinOp::ChangePubKeyOffchain(_) => ChangePubKeyOp::CHUNKS,
+            FranklinOp::ForcedExit(_) => ForcedExitOp::CHUNKS,
+            FranklinOp::Swap(_) => SwapOp::CHUNKS,
         }
     }
 
@@ -560,6 +787,8 @@ impl FranklinOp {
             FranklinOp::Transfer(op) => op.get_public_data(),
             FranklinOp::FullExit(op) => op.get_public_data(),
             FranklinOp::ChangePubKeyOffchain(op) => op.get_public_data(),
+            FranklinOp::ForcedExit(op) => op.get_public_data(),
+            FranklinOp::Swap(op) => op.get_public_data(),
         }
     }
 
@@ -574,6 +803,7 @@ impl FranklinOp {
         match self {
             FranklinOp::Withdraw(op) => Some(op.get_withdrawal_data()),
             FranklinOp::FullExit(op) => Some(op.get_withdrawal_data()),
+            FranklinOp::ForcedExit(op) => Some(op.get_withdrawal_data()),
             _ => None,
         }
     }
@@ -603,6 +833,12 @@ impl FranklinOp {
             ChangePubKeyOp::OP_CODE => Ok(FranklinOp::ChangePubKeyOffchain(Box::new(
                 ChangePubKeyOp::from_public_data(&bytes)?,
             ))),
+            ForcedExitOp::OP_CODE => Ok(FranklinOp::ForcedExit(Box::new(
+                ForcedExitOp::from_public_data(&bytes)?,
+            ))),
+            SwapOp::OP_CODE => Ok(FranklinOp::Swap(Box::new(SwapOp::from_public_data(
+                &bytes,
+            )?))),
             _ => Err(format_err!("Wrong operation type: {}", &op_type)),
         }
     }
@@ -617,6 +853,8 @@ impl FranklinOp {
             TransferOp::OP_CODE => Ok(TransferOp::CHUNKS),
             FullExitOp::OP_CODE => Ok(FullExitOp::CHUNKS),
             ChangePubKeyOp::OP_CODE => Ok(ChangePubKeyOp::CHUNKS),
+            ForcedExitOp::OP_CODE => Ok(ForcedExitOp::CHUNKS),
+            SwapOp::OP_CODE => Ok(SwapOp::CHUNKS),
             _ => Err(format_err!("Wrong operation type: {}", &op_type)),
         }
         .map(|chunks| chunks * CHUNK_BYTES)
@@ -631,6 +869,8 @@ impl FranklinOp {
             FranklinOp::ChangePubKeyOffchain(op) => {
                 Ok(FranklinTx::ChangePubKey(Box::new(op.tx.clone())))
             }
+            FranklinOp::ForcedExit(op) => Ok(FranklinTx::ForcedExit(Box::new(op.tx.clone()))),
+            FranklinOp::Swap(op) => Ok(FranklinTx::Swap(Box::new(op.tx.clone()))),
             _ => Err(format_err!("Wrong tx type")),
         }
     }
@@ -691,3 +931,15 @@ impl From<ChangePubKeyOp> for FranklinOp {
         Self::ChangePubKeyOffchain(Box::new(op))
     }
 }
+
+impl From<ForcedExitOp> for FranklinOp {
+    fn from(op: ForcedExitOp) -> Self {
+        Self::ForcedExit(Box::new(op))
+    }
+}
+
+impl From<SwapOp> for FranklinOp {
+    fn from(op: SwapOp) -> Self {
+        Self::Swap(Box::new(op))
+    }
+}
@@ -84,9 +84,32 @@ pub struct TokenPrice {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Per-token settings of the fee calculation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenFeeSettings {
+    /// Multiplier applied to the fee paid in this token, used to account for the token liquidity.
+    #[serde(with = "UnsignedRatioSerializeAsDecimal")]
+    pub risk_factor: Ratio<BigUint>,
+    /// Whether the token can be used to pay fees.
+    pub fee_allowed: bool,
+}
+
+impl TokenFeeSettings {
+    /// Settings of the token which is accepted for fees with no additional risk.
+    pub fn risk_free() -> Self {
+        Self {
+            risk_factor: Ratio::from_integer(1u32.into()),
+            fee_allowed: true,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Hash, Eq)]
 pub enum TxFeeTypes {
     Withdraw,
     FastWithdraw,
     Transfer,
+    ForcedExit,
+    Swap,
 }
@@ -2,7 +2,8 @@ use super::{Nonce, TokenId};
 
 use crate::node::{
     is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount,
-    public_key_from_private, AccountId, CloseOp, TokenLike, TransferOp, TxFeeTypes, WithdrawOp,
+    public_key_from_private, AccountId, CloseOp, ForcedExitOp, SwapOp, TokenLike, TransferOp,
+    TxFeeTypes, WithdrawOp,
 };
 use crypto::{digest::Digest, sha2::Sha256};
 use num::{BigUint, ToPrimitive};
@@ -18,8 +19,11 @@ use crate::franklin_crypto::jubjub::FixedGenerators;
 use crate::franklin_crypto::rescue::RescueEngine;
 use crate::misc::utils::format_units;
 use crate::node::operations::ChangePubKeyOp;
-use crate::params::{max_account_id, max_token_id, JUBJUB_PARAMS, RESCUE_PARAMS};
-use crate::primitives::{pedersen_hash_tx_msg, rescue_hash_tx_msg, BigUintSerdeAsRadix10Str};
+use crate::params::{max_account_id, max_token_id, JUBJUB_PARAMS, PRICE_BIT_WIDTH, RESCUE_PARAMS};
+use crate::primitives::{
+    pedersen_hash_tx_msg, rescue_hash_tx_msg, BigUintPairSerdeAsRadix10Str,
+    BigUintSerdeAsRadix10Str,
+};
 use failure::{bail, ensure, format_err};
 use parity_crypto::publickey::{
     public_to_address, recover, sign, KeyPair, Signature as ETHSignature,
@@ -99,6 +103,78 @@ impl Default for VerifiedSignatureCache {
     }
 }
 
+/// Time range (UNIX timestamps in seconds) within which the transaction can be executed.
+///
+/// Both bounds are inclusive. Time range is a part of the signed transaction data,
+/// so it can't be changed after the transaction was signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+    #[serde(default)]
+    pub valid_from: u64,
+    #[serde(default = "TimeRange::max_valid_until")]
+    pub valid_until: u64,
+}
+
+impl TimeRange {
+    pub fn new(valid_from: u64, valid_until: u64) -> Self {
+        Self {
+            valid_from,
+            valid_until,
+        }
+    }
+
+    fn max_valid_until() -> u64 {
+        u64::max_value()
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 16] {
+        let mut out = [0u8; 16];
+        out[..8].copy_from_slice(&self.valid_from.to_be_bytes());
+        out[8..].copy_from_slice(&self.valid_until.to_be_bytes());
+        out
+    }
+
+    /// Checks whether the transaction can be executed at the given moment.
+    pub fn is_valid(&self, timestamp: u64) -> bool {
+        self.valid_from <= timestamp && timestamp <= self.valid_until
+    }
+
+    /// Checks whether the time range is already over, so the transaction
+    /// will never become valid.
+    pub fn is_expired(&self, timestamp: u64) -> bool {
+        self.valid_until < timestamp
+    }
+
+    /// Part of the Ethereum sign message describing the time range.
+    /// Empty for the default (unlimited) time range, so the messages for
+    /// such transactions remain unchanged.
+    fn get_ethereum_sign_message_part(&self) -> String {
+        if *self == Self::default() {
+            String::new()
+        } else {
+            format!(
+                "\nValid from: {}\nValid until: {}",
+                self.valid_from, self.valid_until
+            )
+        }
+    }
+
+    /// Checks whether the time range is not empty.
+    pub fn check_correctness(&self) -> bool {
+        self.valid_from <= self.valid_until
+    }
+}
+
+impl Default for TimeRange {
+    fn default() -> Self {
+        Self {
+            valid_from: 0,
+            valid_until: Self::max_valid_until(),
+        }
+    }
+}
+
 /// Signed by user.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -112,6 +188,8 @@ pub struct Transfer {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub fee: BigUint,
     pub nonce: Nonce,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
     pub signature: TxSignature,
     #[serde(skip)]
     cached_signer: VerifiedSignatureCache,
@@ -131,6 +209,7 @@ impl Transfer {
         amount: BigUint,
         fee: BigUint,
         nonce: Nonce,
+        time_range: TimeRange,
         signature: Option<TxSignature>,
     ) -> Self {
         let mut tx = Self {
@@ -141,6 +220,7 @@ impl Transfer {
             amount,
             fee,
             nonce,
+            time_range,
             signature: signature.clone().unwrap_or_default(),
             cached_signer: VerifiedSignatureCache::NotCached,
         };
@@ -160,9 +240,12 @@ impl Transfer {
         amount: BigUint,
         fee: BigUint,
         nonce: Nonce,
+        time_range: TimeRange,
         private_key: &PrivateKey<Engine>,
     ) -> Result<Self, failure::Error> {
-        let mut tx = Self::new(account_id, from, to, token, amount, fee, nonce, None);
+        let mut tx = Self::new(
+            account_id, from, to, token, amount, fee, nonce, time_range, None,
+        );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
             bail!("Transfer is incorrect, check amounts");
@@ -180,6 +263,7 @@ impl Transfer {
         out.extend_from_slice(&pack_token_amount(&self.amount));
         out.extend_from_slice(&pack_fee_amount(&self.fee));
         out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.to_be_bytes());
         out
     }
 
@@ -190,7 +274,8 @@ impl Transfer {
             && is_fee_amount_packable(&self.fee)
             && self.account_id <= max_account_id()
             && self.token <= max_token_id()
-            && self.to != Address::zero();
+            && self.to != Address::zero()
+            && self.time_range.check_correctness();
         if valid {
             let signer = self.verify_signature();
             valid = valid && signer.is_some();
@@ -211,7 +296,7 @@ impl Transfer {
 
     /// Get message that should be signed by Ethereum keys of the account for 2F authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        format!(
+        let mut message = format!(
             "Transfer {amount} {token}\n\
             To: {to:?}\n\
             Nonce: {nonce}\n\
@@ -223,7 +308,9 @@ impl Transfer {
             nonce = self.nonce,
             fee = format_units(&self.fee, decimals),
             account_id = self.account_id,
-        )
+        );
+        message.push_str(&self.time_range.get_ethereum_sign_message_part());
+        message
     }
 }
 
@@ -239,6 +326,8 @@ pub struct Withdraw {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub fee: BigUint,
     pub nonce: Nonce,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
     pub signature: TxSignature,
     #[serde(skip)]
     cached_signer: VerifiedSignatureCache,
@@ -262,6 +351,7 @@ impl Withdraw {
         amount: BigUint,
         fee: BigUint,
         nonce: Nonce,
+        time_range: TimeRange,
         signature: Option<TxSignature>,
     ) -> Self {
         let mut tx = Self {
@@ -272,6 +362,7 @@ impl Withdraw {
             amount,
             fee,
             nonce,
+            time_range,
             signature: signature.clone().unwrap_or_default(),
             cached_signer: VerifiedSignatureCache::NotCached,
             fast: false,
@@ -292,9 +383,12 @@ impl Withdraw {
         amount: BigUint,
         fee: BigUint,
         nonce: Nonce,
+        time_range: TimeRange,
         private_key: &PrivateKey<Engine>,
     ) -> Result<Self, failure::Error> {
-        let mut tx = Self::new(account_id, from, to, token, amount, fee, nonce, None);
+        let mut tx = Self::new(
+            account_id, from, to, token, amount, fee, nonce, time_range, None,
+        );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
             bail!("Transfer is incorrect, check amounts");
@@ -312,6 +406,7 @@ impl Withdraw {
         out.extend_from_slice(&self.amount.to_u128().unwrap().to_be_bytes());
         out.extend_from_slice(&pack_fee_amount(&self.fee));
         out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.to_be_bytes());
         out
     }
 
@@ -319,7 +414,8 @@ impl Withdraw {
         let mut valid = self.amount <= BigUint::from(u128::max_value())
             && is_fee_amount_packable(&self.fee)
             && self.account_id <= max_account_id()
-            && self.token <= max_token_id();
+            && self.token <= max_token_id()
+            && self.time_range.check_correctness();
 
         if valid {
             let signer = self.verify_signature();
@@ -341,7 +437,7 @@ impl Withdraw {
 
     /// Get message that should be signed by Ethereum keys of the account for 2F authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        format!(
+        let mut message = format!(
             "Withdraw {amount} {token}\n\
             To: {to:?}\n\
             Nonce: {nonce}\n\
@@ -353,7 +449,9 @@ impl Withdraw {
             nonce = self.nonce,
             fee = format_units(&self.fee, decimals),
             account_id = self.account_id,
-        )
+        );
+        message.push_str(&self.time_range.get_ethereum_sign_message_part());
+        message
     }
 }
 
@@ -396,6 +494,8 @@ pub struct ChangePubKey {
     pub account: Address,
     pub new_pk_hash: PubKeyHash,
     pub nonce: Nonce,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
     pub eth_signature: Option<PackedEthSignature>,
 }
 
@@ -410,53 +510,504 @@ impl ChangePubKey {
         out.extend_from_slice(&self.account.as_bytes());
         out.extend_from_slice(&self.new_pk_hash.data);
         out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.to_be_bytes());
         if let Some(sign) = &self.eth_signature {
             out.extend_from_slice(&sign.serialize_packed())
         }
         out
     }
 
+    /// Returns the message signed by the Ethereum key of the account to authorize the new public key.
+    ///
+    /// The time range is a part of the message unless it's the default (unlimited) one, so the messages
+    /// for such transactions remain unchanged. The contract recovers the time range from the eth witness.
     pub fn get_eth_signed_data(
         account_id: AccountId,
         nonce: Nonce,
         new_pubkey_hash: &PubKeyHash,
+        time_range: &TimeRange,
     ) -> Result<Vec<u8>, failure::Error> {
         const CHANGE_PUBKEY_SIGNATURE_LEN: usize = 152;
-        let mut eth_signed_msg = Vec::with_capacity(CHANGE_PUBKEY_SIGNATURE_LEN);
+        const CHANGE_PUBKEY_TIME_RANGE_SIGNATURE_LEN: usize = 215;
+
+        let time_range_part = if *time_range == TimeRange::default() {
+            String::new()
+        } else {
+            format!(
+                "\nvalid from: 0x{}\n\
+                 valid until: 0x{}",
+                hex::encode(&time_range.valid_from.to_be_bytes()),
+                hex::encode(&time_range.valid_until.to_be_bytes())
+            )
+        };
+        let expected_len = if time_range_part.is_empty() {
+            CHANGE_PUBKEY_SIGNATURE_LEN
+        } else {
+            CHANGE_PUBKEY_TIME_RANGE_SIGNATURE_LEN
+        };
+
+        let mut eth_signed_msg = Vec::with_capacity(expected_len);
         eth_signed_msg.extend_from_slice(b"Register zkSync pubkey:\n\n");
         eth_signed_msg.extend_from_slice(
             format!(
                 "{}\n\
                  nonce: 0x{}\n\
                  account id: 0x{}\
+                 {}\
                  \n\n",
                 hex::encode(&new_pubkey_hash.data).to_ascii_lowercase(),
                 hex::encode(&nonce.to_be_bytes()).to_ascii_lowercase(),
-                hex::encode(&account_id.to_be_bytes()).to_ascii_lowercase()
+                hex::encode(&account_id.to_be_bytes()).to_ascii_lowercase(),
+                time_range_part
             )
             .as_bytes(),
         );
         eth_signed_msg.extend_from_slice(b"Only sign this message for a trusted client!");
         ensure!(
-            eth_signed_msg.len() == CHANGE_PUBKEY_SIGNATURE_LEN,
+            eth_signed_msg.len() == expected_len,
             "Change pubkey signed message len is too big: {}, expected: {}",
             eth_signed_msg.len(),
-            CHANGE_PUBKEY_SIGNATURE_LEN
+            expected_len
         );
         Ok(eth_signed_msg)
     }
 
     pub fn verify_eth_signature(&self) -> Option<Address> {
         self.eth_signature.as_ref().and_then(|sign| {
-            Self::get_eth_signed_data(self.account_id, self.nonce, &self.new_pk_hash)
-                .ok()
-                .and_then(|msg| sign.signature_recover_signer(&msg).ok())
+            Self::get_eth_signed_data(
+                self.account_id,
+                self.nonce,
+                &self.new_pk_hash,
+                &self.time_range,
+            )
+            .ok()
+            .and_then(|msg| sign.signature_recover_signer(&msg).ok())
         })
     }
 
     pub fn check_correctness(&self) -> bool {
         (self.eth_signature.is_none() || self.verify_eth_signature() == Some(self.account))
             && self.account_id <= max_account_id()
+            && self.time_range.check_correctness()
+    }
+}
+
+/// `ForcedExit` transaction is used to withdraw funds from an unowned
+/// account to its corresponding L1 address.
+///
+/// Unowned account is an account that has no signing key set (i.e. its public
+/// key hash is zero), so its owner is not able to withdraw funds by themselves.
+/// Any account that has a signing key set can initiate such an operation and
+/// pay the fee for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExit {
+    /// Account ID of the transaction initiator.
+    pub initiator_account_id: AccountId,
+    /// Address of the account to withdraw funds from.
+    /// Also this field represents the address in L1 to which funds will be withdrawn.
+    pub target: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl ForcedExit {
+    const TX_TYPE: u8 = 8;
+
+    /// Creates transaction from parts
+    /// signature is optional, because sometimes we don't know it (i.e. data_restore)
+    pub fn new(
+        initiator_account_id: AccountId,
+        target: Address,
+        token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            initiator_account_id,
+            target,
+            token,
+            fee,
+            nonce,
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates signed transaction using private key, checks for correcteness
+    pub fn new_signed(
+        initiator_account_id: AccountId,
+        target: Address,
+        token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, failure::Error> {
+        let mut tx = Self::new(initiator_account_id, target, token, fee, nonce, None);
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            bail!("Forced exit is incorrect, check amounts");
+        }
+        Ok(tx)
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.initiator_account_id.to_be_bytes());
+        out.extend_from_slice(&self.target.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out
+    }
+
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = self.fee <= BigUint::from(u128::max_value())
+            && is_fee_amount_packable(&self.fee)
+            && self.initiator_account_id <= max_account_id()
+            && self.token <= max_token_id()
+            && self.target != Address::zero();
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            cached_signer.clone()
+        } else if let Some(pub_key) = self.signature.verify_musig(&self.get_bytes()) {
+            Some(PubKeyHash::from_pubkey(&pub_key))
+        } else {
+            None
+        }
+    }
+}
+
+/// `Order` is an intention of the account owner to exchange one token for another
+/// at a price that is not worse than the specified one.
+///
+/// Orders are not sent to the network on their own: two matching orders are settled
+/// atomically within a single `Swap` transaction, which can be submitted by any account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub account_id: AccountId,
+    /// Address of the order owner.
+    pub address: Address,
+    /// Nonce of the order owner. It is incremented once the order is filled,
+    /// so an order can't be filled more than once.
+    pub nonce: Nonce,
+    pub token_sell: TokenId,
+    pub token_buy: TokenId,
+    /// Limit price of the order: for every `ratio.0` units of `token_sell` the account
+    /// wants to receive at least `ratio.1` units of `token_buy`.
+    #[serde(with = "BigUintPairSerdeAsRadix10Str")]
+    pub ratio: (BigUint, BigUint),
+    /// Exact amount of `token_sell` to be sold, the order is filled by a single swap.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl Order {
+    /// Prefix of the signed message, distinguishes orders from the transactions.
+    pub const MSG_TYPE: u8 = b'o';
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates order from parts
+    /// signature is optional, because sometimes we don't know it (i.e. data_restore)
+    pub fn new(
+        account_id: AccountId,
+        address: Address,
+        nonce: Nonce,
+        token_sell: TokenId,
+        token_buy: TokenId,
+        ratio: (BigUint, BigUint),
+        amount: BigUint,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut order = Self {
+            account_id,
+            address,
+            nonce,
+            token_sell,
+            token_buy,
+            ratio,
+            amount,
+            time_range,
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            order.cached_signer = VerifiedSignatureCache::Cached(order.verify_signature());
+        }
+        order
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates signed order using private key, checks for correcteness
+    pub fn new_signed(
+        account_id: AccountId,
+        address: Address,
+        nonce: Nonce,
+        token_sell: TokenId,
+        token_buy: TokenId,
+        ratio: (BigUint, BigUint),
+        amount: BigUint,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, failure::Error> {
+        let mut order = Self::new(
+            account_id, address, nonce, token_sell, token_buy, ratio, amount, time_range, None,
+        );
+        order.signature = TxSignature::sign_musig(private_key, &order.get_bytes());
+        if !order.check_correctness() {
+            bail!("Order is incorrect, check amounts and price");
+        }
+        Ok(order)
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::MSG_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.address.as_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.token_sell.to_be_bytes());
+        out.extend_from_slice(&self.token_buy.to_be_bytes());
+        out.extend_from_slice(&pack_price(&self.ratio.0));
+        out.extend_from_slice(&pack_price(&self.ratio.1));
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&self.time_range.to_be_bytes());
+        out
+    }
+
+    /// Checks whether selling `sell_amount` of `token_sell` for `buy_amount` of `token_buy`
+    /// satisfies the limit price of the order.
+    pub fn is_price_satisfied(&self, sell_amount: &BigUint, buy_amount: &BigUint) -> bool {
+        buy_amount * &self.ratio.0 >= sell_amount * &self.ratio.1
+    }
+
+    /// Checks whether `sell_amount` of `token_sell` can be sold by the order.
+    pub fn is_amount_valid(&self, sell_amount: &BigUint) -> bool {
+        *sell_amount != BigUint::from(0u32) && *sell_amount == self.amount
+    }
+
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = self.amount != BigUint::from(0u32)
+            && self.amount <= BigUint::from(u128::max_value())
+            && is_token_amount_packable(&self.amount)
+            && self.ratio.0.bits() <= PRICE_BIT_WIDTH
+            && self.ratio.1.bits() <= PRICE_BIT_WIDTH
+            && self.account_id <= max_account_id()
+            && self.token_sell <= max_token_id()
+            && self.token_buy <= max_token_id()
+            && self.token_sell != self.token_buy
+            && self.time_range.check_correctness();
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        };
+        valid
+    }
+
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            cached_signer.clone()
+        } else if let Some(pub_key) = self.signature.verify_musig(&self.get_bytes()) {
+            Some(PubKeyHash::from_pubkey(&pub_key))
+        } else {
+            None
+        }
+    }
+}
+
+/// Encodes price as a fixed-width big-endian number.
+/// Prices that don't fit into `PRICE_BIT_WIDTH` are rejected by `Order::check_correctness`.
+fn pack_price(price: &BigUint) -> Vec<u8> {
+    let price_bytes = price.to_bytes_be();
+    let mut out = vec![0u8; PRICE_BIT_WIDTH / 8];
+    let len = std::cmp::min(price_bytes.len(), out.len());
+    let out_offset = out.len() - len;
+    out[out_offset..].copy_from_slice(&price_bytes[price_bytes.len() - len..]);
+    out
+}
+
+/// `Swap` transaction settles two matching orders atomically: the account of the
+/// first order sells `amounts.0` of its `token_sell` to the account of the second order,
+/// and gets `amounts.1` of the second order `token_sell` in return.
+///
+/// Swap is created and signed by the submitter account, which pays the fee in an
+/// arbitrary token. Submitter may be one of the order accounts as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Swap {
+    pub submitter_id: AccountId,
+    pub submitter_address: Address,
+    pub nonce: Nonce,
+    pub orders: (Order, Order),
+    /// Amounts of `token_sell` sold by the first and the second order accounts correspondingly.
+    #[serde(with = "BigUintPairSerdeAsRadix10Str")]
+    pub amounts: (BigUint, BigUint),
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub fee_token: TokenId,
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl Swap {
+    const TX_TYPE: u8 = 9;
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates transaction from parts
+    /// signature is optional, because sometimes we don't know it (i.e. data_restore)
+    pub fn new(
+        submitter_id: AccountId,
+        submitter_address: Address,
+        nonce: Nonce,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee: BigUint,
+        fee_token: TokenId,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            submitter_id,
+            submitter_address,
+            nonce,
+            orders,
+            amounts,
+            fee,
+            fee_token,
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates signed transaction using private key, checks for correcteness
+    pub fn new_signed(
+        submitter_id: AccountId,
+        submitter_address: Address,
+        nonce: Nonce,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee: BigUint,
+        fee_token: TokenId,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, failure::Error> {
+        let mut tx = Self::new(
+            submitter_id,
+            submitter_address,
+            nonce,
+            orders,
+            amounts,
+            fee,
+            fee_token,
+            None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            bail!("Swap is incorrect, check orders and amounts");
+        }
+        Ok(tx)
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.submitter_id.to_be_bytes());
+        out.extend_from_slice(&self.submitter_address.as_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.orders.0.account_id.to_be_bytes());
+        out.extend_from_slice(&self.orders.1.account_id.to_be_bytes());
+        out.extend_from_slice(&self.orders.0.token_sell.to_be_bytes());
+        out.extend_from_slice(&self.orders.1.token_sell.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amounts.0));
+        out.extend_from_slice(&pack_token_amount(&self.amounts.1));
+        out.extend_from_slice(&self.fee_token.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out
+    }
+
+    /// Returns the time range within which both orders of the swap are valid.
+    pub fn time_range(&self) -> TimeRange {
+        let (first, second) = (&self.orders.0.time_range, &self.orders.1.time_range);
+        TimeRange::new(
+            std::cmp::max(first.valid_from, second.valid_from),
+            std::cmp::min(first.valid_until, second.valid_until),
+        )
+    }
+
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = self.amounts.0 <= BigUint::from(u128::max_value())
+            && self.amounts.1 <= BigUint::from(u128::max_value())
+            && self.fee <= BigUint::from(u128::max_value())
+            && is_token_amount_packable(&self.amounts.0)
+            && is_token_amount_packable(&self.amounts.1)
+            && is_fee_amount_packable(&self.fee)
+            && self.submitter_id <= max_account_id()
+            && self.fee_token <= max_token_id()
+            && self.orders.0.check_correctness()
+            && self.orders.1.check_correctness()
+            && self.orders.0.account_id != self.orders.1.account_id
+            && self.orders.0.token_sell == self.orders.1.token_buy
+            && self.orders.0.token_buy == self.orders.1.token_sell
+            && self.orders.0.is_amount_valid(&self.amounts.0)
+            && self.orders.1.is_amount_valid(&self.amounts.1)
+            && self
+                .orders
+                .0
+                .is_price_satisfied(&self.amounts.0, &self.amounts.1)
+            && self
+                .orders
+                .1
+                .is_price_satisfied(&self.amounts.1, &self.amounts.0);
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        };
+        valid
+    }
+
+    /// Verifies the signature of the submitter.
+    /// Signatures of the orders are verified by `Order::verify_signature`.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            cached_signer.clone()
+        } else if let Some(pub_key) = self.signature.verify_musig(&self.get_bytes()) {
+            Some(PubKeyHash::from_pubkey(&pub_key))
+        } else {
+            None
+        }
     }
 }
 
@@ -467,6 +1018,8 @@ pub enum FranklinTx {
     Withdraw(Box<Withdraw>),
     Close(Box<Close>),
     ChangePubKey(Box<ChangePubKey>),
+    ForcedExit(Box<ForcedExit>),
+    Swap(Box<Swap>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -475,6 +1028,13 @@ pub struct EthSignData {
     pub message: String,
 }
 
+/// Creates the message to be signed with the Ethereum key in order to authorize
+/// a batch of transactions at once: messages of the individual transactions
+/// separated by empty lines.
+pub fn get_batch_ethereum_sign_message<'a>(messages: impl IntoIterator<Item = &'a str>) -> String {
+    messages.into_iter().collect::<Vec<_>>().join("\n\n")
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedFranklinTx {
     pub tx: FranklinTx,
@@ -505,6 +1065,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.get_bytes(),
             FranklinTx::Close(tx) => tx.get_bytes(),
             FranklinTx::ChangePubKey(tx) => tx.get_bytes(),
+            FranklinTx::ForcedExit(tx) => tx.get_bytes(),
+            FranklinTx::Swap(tx) => tx.get_bytes(),
         };
 
         let mut hasher = Sha256::new();
@@ -520,6 +1082,18 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.from,
             FranklinTx::Close(tx) => tx.account,
             FranklinTx::ChangePubKey(tx) => tx.account,
+            FranklinTx::ForcedExit(tx) => tx.target,
+            FranklinTx::Swap(tx) => tx.submitter_address,
+        }
+    }
+
+    /// Returns the id of the account that initiated the transaction on behalf of the `account`,
+    /// i.e. the `ForcedExit` initiator. Such an account pays the fee and its nonce is incremented,
+    /// but it is referred by the id only, so its address has to be resolved from the state.
+    pub fn initiator_account_id(&self) -> Option<AccountId> {
+        match self {
+            FranklinTx::ForcedExit(tx) => Some(tx.initiator_account_id),
+            _ => None,
         }
     }
 
@@ -529,6 +1103,20 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.nonce,
             FranklinTx::Close(tx) => tx.nonce,
             FranklinTx::ChangePubKey(tx) => tx.nonce,
+            FranklinTx::ForcedExit(tx) => tx.nonce,
+            FranklinTx::Swap(tx) => tx.nonce,
+        }
+    }
+
+    /// Returns the time range within which the transaction can be executed.
+    /// Transactions without such a range are valid at any moment.
+    pub fn time_range(&self) -> TimeRange {
+        match self {
+            FranklinTx::Transfer(tx) => tx.time_range,
+            FranklinTx::Withdraw(tx) => tx.time_range,
+            FranklinTx::ChangePubKey(tx) => tx.time_range,
+            FranklinTx::Swap(tx) => tx.time_range(),
+            FranklinTx::Close(_) | FranklinTx::ForcedExit(_) => TimeRange::default(),
         }
     }
 
@@ -538,6 +1126,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.check_correctness(),
             FranklinTx::Close(tx) => tx.check_correctness(),
             FranklinTx::ChangePubKey(tx) => tx.check_correctness(),
+            FranklinTx::ForcedExit(tx) => tx.check_correctness(),
+            FranklinTx::Swap(tx) => tx.check_correctness(),
         }
     }
 
@@ -547,6 +1137,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.get_bytes(),
             FranklinTx::Close(tx) => tx.get_bytes(),
             FranklinTx::ChangePubKey(tx) => tx.get_bytes(),
+            FranklinTx::ForcedExit(tx) => tx.get_bytes(),
+            FranklinTx::Swap(tx) => tx.get_bytes(),
         }
     }
 
@@ -556,6 +1148,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(_) => WithdrawOp::CHUNKS,
             FranklinTx::Close(_) => CloseOp::CHUNKS,
             FranklinTx::ChangePubKey(_) => ChangePubKeyOp::CHUNKS,
+            FranklinTx::ForcedExit(_) => ForcedExitOp::CHUNKS,
+            FranklinTx::Swap(_) => SwapOp::CHUNKS,
         }
     }
 
@@ -595,6 +1189,18 @@ impl FranklinTx {
                 transfer.to,
                 transfer.fee.clone(),
             )),
+            FranklinTx::ForcedExit(forced_exit) => Some((
+                TxFeeTypes::ForcedExit,
+                TokenLike::Id(forced_exit.token),
+                forced_exit.target,
+                forced_exit.fee.clone(),
+            )),
+            FranklinTx::Swap(swap) => Some((
+                TxFeeTypes::Swap,
+                TokenLike::Id(swap.fee_token),
+                swap.submitter_address,
+                swap.fee.clone(),
+            )),
             _ => None,
         }
     }
@@ -1029,6 +1635,7 @@ mod test {
             BigUint::from(12_340_000_000_000u64),
             BigUint::from(56_700_000_000u64),
             rng.gen(),
+            Default::default(),
             &key,
         )
         .expect("failed to sign transfer");
@@ -1052,6 +1659,14 @@ mod test {
             ("amount", pack_token_amount(&transfer.amount)),
             ("fee", pack_fee_amount(&transfer.fee)),
             ("nonce", transfer.nonce.to_be_bytes().to_vec()),
+            (
+                "validFrom",
+                transfer.time_range.valid_from.to_be_bytes().to_vec(),
+            ),
+            (
+                "validUntil",
+                transfer.time_range.valid_until.to_be_bytes().to_vec(),
+            ),
         ];
         println!("Signed transaction fields:");
         let mut field_concat = Vec::new();
@@ -1079,6 +1694,7 @@ mod test {
             BigUint::from(12_340_000_000_000u64),
             BigUint::from(56_700_000_000u64),
             rng.gen(),
+            Default::default(),
             &key,
         )
         .expect("failed to sign withdraw");
@@ -1105,6 +1721,14 @@ mod test {
             ),
             ("fee", pack_fee_amount(&withdraw.fee)),
             ("nonce", withdraw.nonce.to_be_bytes().to_vec()),
+            (
+                "validFrom",
+                withdraw.time_range.valid_from.to_be_bytes().to_vec(),
+            ),
+            (
+                "validUntil",
+                withdraw.time_range.valid_until.to_be_bytes().to_vec(),
+            ),
         ];
         println!("Signed transaction fields:");
         let mut field_concat = Vec::new();
@@ -1120,6 +1744,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_print_forced_exit_for_protocol() {
+        let mut rng = XorShiftRng::from_seed([3, 2, 3, 4]);
+        let key = gen_pk_and_msg().0;
+        let forced_exit = ForcedExit::new_signed(
+            gen_account_id(&mut rng),
+            Address::from(rng.gen::<[u8; 20]>()),
+            gen_token_id(&mut rng),
+            BigUint::from(56_700_000_000u64),
+            rng.gen(),
+            &key,
+        )
+        .expect("failed to sign forced exit");
+
+        println!(
+            "User representation:\n{}\n",
+            serde_json::to_string_pretty(&forced_exit).expect("json serialize")
+        );
+
+        println!("Signer:");
+        println!("Private key: {}", key.0.to_string());
+        let (pk_x, pk_y) = public_key_from_private(&key).0.into_xy();
+        println!("Public key: x: {}, y: {}\n", pk_x, pk_y);
+
+        let signed_fields = vec![
+            ("type", vec![ForcedExit::TX_TYPE]),
+            (
+                "initiatorAccountId",
+                forced_exit.initiator_account_id.to_be_bytes().to_vec(),
+            ),
+            ("target", forced_exit.target.as_bytes().to_vec()),
+            ("token", forced_exit.token.to_be_bytes().to_vec()),
+            ("fee", pack_fee_amount(&forced_exit.fee)),
+            ("nonce", forced_exit.nonce.to_be_bytes().to_vec()),
+        ];
+        println!("Signed transaction fields:");
+        let mut field_concat = Vec::new();
+        for (field, value) in signed_fields.into_iter() {
+            println!("{}: 0x{}", field, hex::encode(&value));
+            field_concat.extend(value.into_iter());
+        }
+        println!("Signed bytes: 0x{}", hex::encode(&field_concat));
+        assert_eq!(
+            field_concat,
+            forced_exit.get_bytes(),
+            "Protocol serialization mismatch"
+        );
+    }
+
     #[test]
     fn test_musig_rescue_signing_verification() {
         let (pk, messages) = gen_pk_and_msg();
@@ -1277,4 +1950,107 @@ mod test {
             assert_eq!(signature, correct_signature, "signature is incorrect");
         }
     }
+
+    #[test]
+    fn test_time_range() {
+        let time_range = TimeRange::new(100, 200);
+        assert!(time_range.check_correctness());
+        assert!(!time_range.is_valid(99));
+        assert!(time_range.is_valid(100));
+        assert!(time_range.is_valid(200));
+        assert!(!time_range.is_valid(201));
+        assert!(!time_range.is_expired(200));
+        assert!(time_range.is_expired(201));
+
+        assert!(!TimeRange::new(200, 100).check_correctness());
+        assert!(TimeRange::default().is_valid(u64::max_value()));
+    }
+
+    #[test]
+    fn test_change_pubkey_time_range_signature() {
+        let private_key = "0b43c0f5b5a13a7047408d1f8c8ad32ba5879902ea6212184e0a5d1157281d76"
+            .parse()
+            .unwrap();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let new_pk_hash = PubKeyHash::from_privkey(&gen_pk_and_msg().0);
+
+        let default_message =
+            ChangePubKey::get_eth_signed_data(1, 2, &new_pk_hash, &TimeRange::default()).unwrap();
+        assert_eq!(default_message.len(), 152);
+
+        let time_range = TimeRange::new(100, 200);
+        let message = ChangePubKey::get_eth_signed_data(1, 2, &new_pk_hash, &time_range).unwrap();
+        assert_eq!(message.len(), 215);
+        assert!(String::from_utf8(message.clone())
+            .unwrap()
+            .contains("\nvalid from: 0x0000000000000064\nvalid until: 0x00000000000000c8\n\n"));
+
+        let mut change_pubkey = ChangePubKey {
+            account_id: 1,
+            account: address,
+            new_pk_hash,
+            nonce: 2,
+            time_range,
+            eth_signature: Some(PackedEthSignature::sign(&private_key, &message).unwrap()),
+        };
+        assert_eq!(change_pubkey.verify_eth_signature(), Some(address));
+
+        // Time range can't be changed without invalidating the signature.
+        change_pubkey.time_range = TimeRange::new(100, 300);
+        assert_ne!(change_pubkey.verify_eth_signature(), Some(address));
+    }
+
+    #[test]
+    fn test_time_range_serialization() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let key = gen_pk_and_msg().0;
+        let transfer = Transfer::new_signed(
+            gen_account_id(&mut rng),
+            Address::from(rng.gen::<[u8; 20]>()),
+            Address::from(rng.gen::<[u8; 20]>()),
+            gen_token_id(&mut rng),
+            BigUint::from(12_340_000_000_000u64),
+            BigUint::from(56_700_000_000u64),
+            rng.gen(),
+            TimeRange::new(100, 200),
+            &key,
+        )
+        .expect("failed to sign transfer");
+
+        let mut json = serde_json::to_value(&transfer).expect("json serialize");
+        assert_eq!(json["validFrom"], 100);
+        assert_eq!(json["validUntil"], 200);
+
+        // Transactions without the time range are valid at any moment.
+        let object = json.as_object_mut().unwrap();
+        object.remove("validFrom");
+        object.remove("validUntil");
+        let transfer: Transfer = serde_json::from_value(json).expect("json deserialize");
+        assert_eq!(transfer.time_range, TimeRange::default());
+    }
+    #[test]
+    fn test_batch_ethereum_sign_message() {
+        let messages = vec![
+            "Transfer 1.0 ETH\nTo: 0x01\nNonce: 1\nFee: 0.1 ETH\nAccount Id: 1",
+            "Withdraw 2.0 DAI\nTo: 0x02\nNonce: 2\nFee: 0.2 DAI\nAccount Id: 1",
+        ];
+        assert_eq!(
+            get_batch_ethereum_sign_message(messages.clone()),
+            format!("{}\n\n{}", messages[0], messages[1])
+        );
+
+        // The message depends on the order of the batch transactions.
+        let reordered = vec![messages[1], messages[0]];
+        assert_ne!(
+            get_batch_ethereum_sign_message(reordered),
+            get_batch_ethereum_sign_message(messages.clone())
+        );
+
+        // Batch of a single transaction is signed with the message of this transaction.
+        assert_eq!(
+            get_batch_ethereum_sign_message(vec![messages[0]]),
+            messages[0]
+        );
+        assert_eq!(get_batch_ethereum_sign_message(Vec::new()), "");
+    }
 }
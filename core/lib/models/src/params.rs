@@ -85,6 +85,10 @@ pub const NEW_PUBKEY_HASH_WIDTH: usize = FR_ADDRESS_LEN * 8;
 pub const ADDRESS_WIDTH: usize = FR_ADDRESS_LEN * 8;
 /// Nonce bit width
 pub const NONCE_BIT_WIDTH: usize = 32;
+/// Timestamp bit width (used for `valid_from` / `valid_until` fields of the transactions)
+pub const TIMESTAMP_BIT_WIDTH: usize = 64;
+/// Bit width of the price components (`ratio` field of the swap order)
+pub const PRICE_BIT_WIDTH: usize = 120;
 //
 pub const CHUNK_BIT_WIDTH: usize = 72;
 pub const CHUNK_BYTES: usize = CHUNK_BIT_WIDTH / 8;
@@ -149,7 +153,8 @@ pub const SIGNED_WITHDRAW_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
     + BALANCE_BIT_WIDTH
     + FEE_EXPONENT_BIT_WIDTH
     + FEE_MANTISSA_BIT_WIDTH
-    + NONCE_BIT_WIDTH;
+    + NONCE_BIT_WIDTH
+    + 2 * TIMESTAMP_BIT_WIDTH;
 
 /// Size of the data that is signed for transfer tx
 pub const SIGNED_TRANSFER_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
@@ -160,6 +165,37 @@ pub const SIGNED_TRANSFER_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
     + AMOUNT_MANTISSA_BIT_WIDTH
     + FEE_EXPONENT_BIT_WIDTH
     + FEE_MANTISSA_BIT_WIDTH
+    + NONCE_BIT_WIDTH
+    + 2 * TIMESTAMP_BIT_WIDTH;
+
+/// Size of the data that is signed for swap order
+pub const SIGNED_ORDER_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
+    + ACCOUNT_ID_BIT_WIDTH
+    + ADDRESS_WIDTH
+    + NONCE_BIT_WIDTH
+    + 2 * TOKEN_BIT_WIDTH
+    + 2 * PRICE_BIT_WIDTH
+    + AMOUNT_EXPONENT_BIT_WIDTH
+    + AMOUNT_MANTISSA_BIT_WIDTH
+    + 2 * TIMESTAMP_BIT_WIDTH;
+
+/// Size of the data that is signed for swap tx
+pub const SIGNED_SWAP_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
+    + 3 * ACCOUNT_ID_BIT_WIDTH
+    + ADDRESS_WIDTH
+    + NONCE_BIT_WIDTH
+    + 3 * TOKEN_BIT_WIDTH
+    + 2 * (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH)
+    + FEE_EXPONENT_BIT_WIDTH
+    + FEE_MANTISSA_BIT_WIDTH;
+
+/// Size of the data that is signed for forced exit tx
+pub const SIGNED_FORCED_EXIT_BIT_WIDTH: usize = TX_TYPE_BIT_WIDTH
+    + ACCOUNT_ID_BIT_WIDTH
+    + ADDRESS_WIDTH
+    + TOKEN_BIT_WIDTH
+    + FEE_EXPONENT_BIT_WIDTH
+    + FEE_MANTISSA_BIT_WIDTH
     + NONCE_BIT_WIDTH;
 
 lazy_static! {
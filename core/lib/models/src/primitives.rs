@@ -7,10 +7,10 @@ use crate::franklin_crypto::bellman::pairing::ff::ScalarEngine;
 use crate::franklin_crypto::bellman::pairing::ff::{
     BitIterator, Field, PrimeField, PrimeFieldRepr,
 };
-use crate::franklin_crypto::bellman::pairing::{CurveAffine, Engine};
+use crate::franklin_crypto::bellman::pairing::{CurveAffine, EncodedPoint, Engine};
 use crate::franklin_crypto::jubjub::{edwards, JubjubEngine, Unknown};
 use bigdecimal::BigDecimal;
-use failure::bail;
+use failure::{bail, format_err};
 use num::{
     bigint::ToBigInt,
     rational::Ratio,
@@ -156,6 +156,40 @@ pub fn serialize_fe_for_ethereum(field_element: &<Bn256 as ScalarEngine>::Fr) ->
     U256::from_big_endian(&be_bytes[..])
 }
 
+/// Inverse of the `serialize_g1_for_ethereum`. Returns an error if the point
+/// is not on the curve.
+pub fn deserialize_g1_from_ethereum(
+    x: U256,
+    y: U256,
+) -> Result<<Bn256 as Engine>::G1Affine, failure::Error> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(<Bn256 as Engine>::G1Affine::zero());
+    }
+    let mut uncompressed = <<Bn256 as Engine>::G1Affine as CurveAffine>::Uncompressed::empty();
+
+    let uncompressed_slice = uncompressed.as_mut();
+    x.to_big_endian(&mut uncompressed_slice[0..32]);
+    y.to_big_endian(&mut uncompressed_slice[32..64]);
+
+    uncompressed
+        .into_affine()
+        .map_err(|e| format_err!("incorrect G1 point: {}", e))
+}
+
+/// Inverse of the `serialize_fe_for_ethereum`. Returns an error if the value
+/// is not less than the field modulus.
+pub fn deserialize_fe_from_ethereum(
+    value: U256,
+) -> Result<<Bn256 as ScalarEngine>::Fr, failure::Error> {
+    let mut be_bytes = [0u8; 32];
+    value.to_big_endian(&mut be_bytes);
+
+    let mut repr = <Bn256 as ScalarEngine>::Fr::zero().into_repr();
+    repr.read_be(&be_bytes[..])?;
+    <Bn256 as ScalarEngine>::Fr::from_repr(repr)
+        .map_err(|e| format_err!("incorrect field element: {}", e))
+}
+
 pub fn unpack_edwards_point<E: JubjubEngine>(
     serialized: [u8; 32],
     params: &E::Params,
@@ -538,6 +572,32 @@ impl BigUintSerdeAsRadix10Str {
     }
 }
 
+/// Used to serialize a pair of BigUint values as an array of two radix 10 strings.
+#[derive(Clone, Debug)]
+pub struct BigUintPairSerdeAsRadix10Str;
+
+impl BigUintPairSerdeAsRadix10Str {
+    pub fn serialize<S>(val: &(BigUint, BigUint), serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let pair = (
+            BigUintSerdeWrapper(val.0.clone()),
+            BigUintSerdeWrapper(val.1.clone()),
+        );
+        pair.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<(BigUint, BigUint), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (first, second) =
+            <(BigUintSerdeWrapper, BigUintSerdeWrapper)>::deserialize(deserializer)?;
+        Ok((first.0, second.0))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct BigUintSerdeWrapper(#[serde(with = "BigUintSerdeAsRadix10Str")] pub BigUint);
 
@@ -585,4 +645,23 @@ mod test {
         let out: Vec<bool> = BitIteratorLe::new(&test_vector).collect();
         assert_eq!(reference, out);
     }
+
+    #[test]
+    fn test_ethereum_serialization_roundtrip() {
+        type Fr = <Bn256 as ScalarEngine>::Fr;
+        type G1Affine = <Bn256 as Engine>::G1Affine;
+
+        let fe = Fr::from_str("1234567890").unwrap();
+        let serialized = serialize_fe_for_ethereum(&fe);
+        assert_eq!(deserialize_fe_from_ethereum(serialized).unwrap(), fe);
+        // Values exceeding the field modulus are rejected.
+        assert!(deserialize_fe_from_ethereum(U256::max_value()).is_err());
+
+        for point in &[G1Affine::one(), G1Affine::zero()] {
+            let (x, y) = serialize_g1_for_ethereum(point);
+            assert_eq!(deserialize_g1_from_ethereum(x, y).unwrap(), *point);
+        }
+        // Points not on the curve are rejected.
+        assert!(deserialize_g1_from_ethereum(U256::from(1), U256::from(1)).is_err());
+    }
 }
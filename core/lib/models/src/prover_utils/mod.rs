@@ -1,7 +1,11 @@
+use crate::franklin_crypto::bellman::pairing::bn256::G1Affine;
 use crate::franklin_crypto::bellman::Circuit;
 use crate::node::U256;
 use crate::node::{Engine, Fr};
-use crate::primitives::{serialize_fe_for_ethereum, serialize_g1_for_ethereum};
+use crate::primitives::{
+    deserialize_fe_from_ethereum, deserialize_g1_from_ethereum, serialize_fe_for_ethereum,
+    serialize_g1_for_ethereum,
+};
 use crate::prover_utils::fs_utils::{
     get_block_verification_key_path, get_exodus_verification_key_path,
 };
@@ -25,6 +29,15 @@ pub const SETUP_MAX_POW2: u32 = 26;
 
 pub struct PlonkVerificationKey(VerificationKey<Engine, PlonkCsWidth4WithNextStepParams>);
 
+impl std::fmt::Debug for PlonkVerificationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlonkVerificationKey")
+            .field("n", &self.0.n)
+            .field("num_inputs", &self.0.num_inputs)
+            .finish()
+    }
+}
+
 impl PlonkVerificationKey {
     pub fn read_verification_key_for_main_circuit(
         block_chunks: usize,
@@ -39,6 +52,24 @@ impl PlonkVerificationKey {
             VerificationKey::read(File::open(get_exodus_verification_key_path())?)?;
         Ok(Self(verification_key))
     }
+
+    /// Checks the proof received in the encoded form (e.g. from the prover).
+    /// Returns `Ok(false)` if the proof is well-formed, but is not valid.
+    pub fn verify_encoded_proof(&self, proof: &EncodedProofPlonk) -> Result<bool, failure::Error> {
+        let mut proof = deserialize_proof(proof)?;
+        // These values are not the part of the encoded proof, but they are checked by the verifier.
+        proof.n = self.0.n;
+        proof.num_inputs = self.0.num_inputs;
+        failure::ensure!(
+            proof.input_values.len() == proof.num_inputs,
+            "incorrect number of public inputs: {}, expected {}",
+            proof.input_values.len(),
+            proof.num_inputs
+        );
+
+        let valid = verify::<_, RollingKeccakTranscript<Fr>>(&proof, &self.0)?;
+        Ok(valid)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -203,6 +234,65 @@ pub fn serialize_proof(
     }
 }
 
+/// Inverse of the `serialize_proof`.
+///
+/// Note that the `n` and `num_inputs` fields are not encoded, so they're left set to zero.
+pub fn deserialize_proof(
+    encoded: &EncodedProofPlonk,
+) -> Result<Proof<Engine, PlonkCsWidth4WithNextStepParams>, failure::Error> {
+    const STATE_WIDTH: usize = 4;
+    // 11 points (wire, grand product and quotient commitments and 2 openings) encoded
+    // as 2 values each, and 11 field elements (evaluations at `z` and `z * omega`).
+    const ENCODED_PROOF_LEN: usize = 3 * (2 * STATE_WIDTH + 3);
+
+    fn next_fe(values: &mut impl Iterator<Item = U256>) -> Result<Fr, failure::Error> {
+        deserialize_fe_from_ethereum(values.next().expect("proof length is checked"))
+    }
+
+    fn next_g1(values: &mut impl Iterator<Item = U256>) -> Result<G1Affine, failure::Error> {
+        let x = values.next().expect("proof length is checked");
+        let y = values.next().expect("proof length is checked");
+        deserialize_g1_from_ethereum(x, y)
+    }
+
+    failure::ensure!(
+        encoded.proof.len() == ENCODED_PROOF_LEN,
+        "incorrect encoded proof length: {}, expected {}",
+        encoded.proof.len(),
+        ENCODED_PROOF_LEN
+    );
+
+    let mut proof = Proof::<Engine, PlonkCsWidth4WithNextStepParams>::empty();
+    proof.input_values = encoded
+        .inputs
+        .iter()
+        .map(|input| deserialize_fe_from_ethereum(*input))
+        .collect::<Result<_, _>>()?;
+
+    let values = &mut encoded.proof.iter().cloned();
+    for _ in 0..STATE_WIDTH {
+        proof.wire_commitments.push(next_g1(values)?);
+    }
+    proof.grand_product_commitment = next_g1(values)?;
+    for _ in 0..STATE_WIDTH {
+        proof.quotient_poly_commitments.push(next_g1(values)?);
+    }
+    for _ in 0..STATE_WIDTH {
+        proof.wire_values_at_z.push(next_fe(values)?);
+    }
+    proof.wire_values_at_z_omega.push(next_fe(values)?);
+    proof.grand_product_at_z_omega = next_fe(values)?;
+    proof.quotient_polynomial_at_z = next_fe(values)?;
+    proof.linearization_polynomial_at_z = next_fe(values)?;
+    for _ in 0..(STATE_WIDTH - 1) {
+        proof.permutation_polynomials_at_z.push(next_fe(values)?);
+    }
+    proof.opening_at_z_proof = next_g1(values)?;
+    proof.opening_at_z_omega_proof = next_g1(values)?;
+
+    Ok(proof)
+}
+
 /// Reads universal setup from disk or downloads from network.
 pub fn get_universal_setup_monomial_form(
     power_of_two: u32,
@@ -251,3 +341,26 @@ impl UniversalSetupCache {
 lazy_static! {
     static ref UNIVERSAL_SETUP_CACHE: UniversalSetupCache = UniversalSetupCache::new();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::franklin_crypto::bellman::pairing::CurveAffine;
+
+    /// Checks that the proof can be restored from its encoded form.
+    #[test]
+    fn encoded_proof_roundtrip() {
+        let mut encoded = EncodedProofPlonk::default();
+        let (x, y) = serialize_g1_for_ethereum(&G1Affine::one());
+        encoded.proof[0] = x;
+        encoded.proof[1] = y;
+        encoded.proof[20] = U256::from(42);
+
+        let proof = deserialize_proof(&encoded).expect("Correct proof can't be decoded");
+        assert_eq!(serialize_proof(&proof), encoded);
+
+        // Proofs of incorrect length should be rejected.
+        encoded.proof.pop();
+        assert!(deserialize_proof(&encoded).is_err());
+    }
+}
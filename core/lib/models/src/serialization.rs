@@ -4,9 +4,46 @@
 //! common `zksync` types.
 
 use super::node::Fr;
-use super::{fe_from_hex, fe_to_hex};
+use super::{fe_from_bytes, fe_from_hex, fe_to_bytes, fe_to_hex};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+/// Wrapper for `Fr` choosing its representation depending on the format.
+///
+/// Human-readable formats (e.g. JSON) get a hexadecimal string, while binary
+/// formats (e.g. CBOR) get the raw 32 bytes of the big-endian representation.
+#[derive(Clone, Copy)]
+struct SerdeFr(Fr);
+
+impl Serialize for SerdeFr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            String::serialize(&fe_to_hex(&self.0), serializer)
+        } else {
+            serde_bytes::Bytes::new(&fe_to_bytes(&self.0)).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SerdeFr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = if deserializer.is_human_readable() {
+            let hex_value = String::deserialize(deserializer)?;
+            fe_from_hex(&hex_value)
+        } else {
+            let bytes = serde_bytes::ByteBuf::deserialize(deserializer)?;
+            fe_from_bytes(&bytes)
+        };
+
+        value.map(SerdeFr).map_err(de::Error::custom)
+    }
+}
+
 /// Blanket structure implementing serializing/deserializing methods for `Fr`.
 ///
 /// This structure is required, since `Fr` does not originate in the current
@@ -32,23 +69,14 @@ impl FrSerde {
     where
         S: Serializer,
     {
-        // First, serialize `Fr` to hexadecimal string.
-        let hex_value = fe_to_hex(value);
-
-        // Then, serialize it using `Serialize` trait implementation for `String`.
-        String::serialize(&hex_value, serializer)
+        SerdeFr(*value).serialize(serializer)
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Fr, D::Error>
     where
         D: Deserializer<'de>,
     {
-        // First, deserialize a string value. It is expected to be a
-        // hexadecimal representation of `Fr`.
-        let deserialized_string = String::deserialize(deserializer)?;
-
-        // Then, parse hexadecimal string to obtain `Fr`.
-        fe_from_hex(&deserialized_string).map_err(de::Error::custom)
+        SerdeFr::deserialize(deserializer).map(|SerdeFr(value)| value)
     }
 }
 
@@ -74,23 +102,16 @@ impl OptionalFrSerde {
     where
         S: Serializer,
     {
-        let optional_hex_value = value.map(|a| fe_to_hex(&a));
-
-        Option::serialize(&optional_hex_value, serializer)
+        Option::serialize(&value.map(SerdeFr), serializer)
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Fr>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let optional_deserialized_string: Option<String> = Option::deserialize(deserializer)?;
-
-        // Apply `fe_from_hex` to the contents of `Option`, then transpose result to have
-        // `Result<Option<..>, ..>` and adapt error to the expected format.
-        optional_deserialized_string
-            .map(|v| fe_from_hex(&v))
-            .transpose()
-            .map_err(de::Error::custom)
+        let value: Option<SerdeFr> = Option::deserialize(deserializer)?;
+
+        Ok(value.map(|SerdeFr(value)| value))
     }
 }
 
@@ -116,11 +137,7 @@ impl VecOptionalFrSerde {
     where
         S: Serializer,
     {
-        let mut res = Vec::with_capacity(operations.len());
-        for value in operations.iter() {
-            let v = value.map(|a| fe_to_hex(&a));
-            res.push(v);
-        }
+        let res: Vec<_> = operations.iter().map(|value| value.map(SerdeFr)).collect();
         Vec::serialize(&res, ser)
     }
 
@@ -128,17 +145,12 @@ impl VecOptionalFrSerde {
     where
         D: Deserializer<'de>,
     {
-        let str_vec: Vec<Option<String>> = Vec::deserialize(deserializer)?;
-        let mut res = Vec::with_capacity(str_vec.len());
-        for s in str_vec.into_iter() {
-            if let Some(a) = s {
-                let v = fe_from_hex(&a).map_err(de::Error::custom)?;
-                res.push(Some(v));
-            } else {
-                res.push(None);
-            }
-        }
-        Ok(res)
+        let values: Vec<Option<SerdeFr>> = Vec::deserialize(deserializer)?;
+
+        Ok(values
+            .into_iter()
+            .map(|value| value.map(|SerdeFr(value)| value))
+            .collect())
     }
 }
 
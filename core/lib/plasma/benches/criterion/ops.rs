@@ -75,6 +75,7 @@ fn apply_transfer_to_new_op(b: &mut Bencher<'_>) {
         10u32.into(),
         1u32.into(),
         0,
+        Default::default(),
         private_key,
     )
     .expect("failed to sign transfer");
@@ -109,6 +110,7 @@ fn apply_transfer_tx(b: &mut Bencher<'_>) {
         10u32.into(),
         1u32.into(),
         0,
+        Default::default(),
         private_key,
     )
     .expect("failed to sign transfer");
@@ -194,6 +196,7 @@ fn apply_withdraw_tx(b: &mut Bencher<'_>) {
         10u32.into(),
         1u32.into(),
         0,
+        Default::default(),
         private_key,
     )
     .expect("failed to sign withdraw");
@@ -226,8 +229,13 @@ fn apply_change_pubkey_op(b: &mut Bencher<'_>) {
     let nonce = 0;
 
     let eth_signature = {
-        let sign_bytes = ChangePubKey::get_eth_signed_data(0, nonce, &to_change.pub_key_hash)
-            .expect("Failed to construct ChangePubKey signed message.");
+        let sign_bytes = ChangePubKey::get_eth_signed_data(
+            0,
+            nonce,
+            &to_change.pub_key_hash,
+            &Default::default(),
+        )
+        .expect("Failed to construct ChangePubKey signed message.");
         let eth_signature =
             PackedEthSignature::sign(eth_private_key, &sign_bytes).expect("Signing failed");
         Some(eth_signature)
@@ -238,6 +246,7 @@ fn apply_change_pubkey_op(b: &mut Bencher<'_>) {
         account: to_change.address,
         new_pk_hash: PubKeyHash::from_privkey(&new_sk),
         nonce,
+        time_range: Default::default(),
         eth_signature,
     };
 
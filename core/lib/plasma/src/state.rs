@@ -1,16 +1,19 @@
 use failure::{bail, ensure, format_err, Error};
 use log::trace;
 use models::node::operations::{
-    ChangePubKeyOp, CloseOp, DepositOp, FranklinOp, FullExitOp, TransferOp, TransferToNewOp,
-    WithdrawOp,
+    ChangePubKeyOp, CloseOp, DepositOp, ForcedExitOp, FranklinOp, FullExitOp, SwapOp, TransferOp,
+    TransferToNewOp, WithdrawOp,
 };
 use models::node::tx::ChangePubKey;
 use models::node::Address;
 use models::node::{
-    reverse_updates, AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, Fr, TokenId,
+    reverse_updates, AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, Fr, Nonce,
+    TokenId,
 };
 use models::node::{Account, AccountTree, FranklinPriorityOp, PubKeyHash};
-use models::node::{Close, Deposit, FranklinTx, FullExit, SignedFranklinTx, Transfer, Withdraw};
+use models::node::{
+    Close, Deposit, ForcedExit, FranklinTx, FullExit, SignedFranklinTx, Swap, Transfer, Withdraw,
+};
 use models::params;
 use models::params::max_account_id;
 use models::primitives::BigUintSerdeWrapper;
@@ -243,6 +246,8 @@ impl PlasmaState {
             FranklinTx::Withdraw(tx) => self.apply_withdraw(*tx),
             FranklinTx::Close(tx) => self.apply_close(*tx),
             FranklinTx::ChangePubKey(tx) => self.apply_change_pubkey(*tx),
+            FranklinTx::ForcedExit(tx) => self.apply_forced_exit(*tx),
+            FranklinTx::Swap(tx) => self.apply_swap(*tx),
         }
     }
 
@@ -497,6 +502,134 @@ impl PlasmaState {
         })
     }
 
+    fn create_forced_exit_op(&self, tx: ForcedExit) -> Result<ForcedExitOp, Error> {
+        ensure!(
+            tx.token <= params::max_token_id(),
+            "Token id is not supported"
+        );
+
+        // Check the initiator account: it must exist, be unlocked and own the signature.
+        let initiator_account = self
+            .get_account(tx.initiator_account_id)
+            .ok_or_else(|| format_err!("Initiator account does not exist"))?;
+        ensure!(
+            initiator_account.pub_key_hash != PubKeyHash::default(),
+            "Initiator account is locked"
+        );
+        ensure!(
+            tx.verify_signature() == Some(initiator_account.pub_key_hash),
+            "ForcedExit signature is incorrect"
+        );
+
+        // Check the target account: it must exist and must not have a signing key set.
+        let (target_account_id, target_account) = self
+            .get_account_by_address(&tx.target)
+            .ok_or_else(|| format_err!("Target account does not exist"))?;
+        ensure!(
+            target_account.pub_key_hash == PubKeyHash::default(),
+            "Target account is not locked; forced exit is forbidden"
+        );
+
+        // There is nothing to withdraw, so the initiator must not be charged for the operation.
+        let target_balance = target_account.get_balance(tx.token);
+        ensure!(
+            target_balance != BigUint::from(0u32),
+            "Target account has no balance in the token"
+        );
+
+        let withdraw_amount = Some(BigUintSerdeWrapper(target_balance));
+
+        Ok(ForcedExitOp {
+            tx,
+            target_account_id,
+            withdraw_amount,
+        })
+    }
+
+    fn apply_forced_exit(&mut self, tx: ForcedExit) -> Result<OpSuccess, Error> {
+        let forced_exit_op = self.create_forced_exit_op(tx)?;
+
+        let (fee, updates) = self.apply_forced_exit_op(&forced_exit_op)?;
+        Ok(OpSuccess {
+            fee: Some(fee),
+            updates,
+            executed_op: FranklinOp::ForcedExit(Box::new(forced_exit_op)),
+        })
+    }
+
+    fn create_swap_op(&self, tx: Swap) -> Result<SwapOp, Error> {
+        let (first_order, second_order) = &tx.orders;
+        ensure!(
+            first_order.token_sell <= params::max_token_id()
+                && second_order.token_sell <= params::max_token_id()
+                && tx.fee_token <= params::max_token_id(),
+            "Token id is not supported"
+        );
+        ensure!(
+            first_order.account_id != second_order.account_id,
+            "Orders of the swap must belong to different accounts"
+        );
+        ensure!(
+            first_order.token_sell == second_order.token_buy
+                && first_order.token_buy == second_order.token_sell,
+            "Orders tokens do not match"
+        );
+        ensure!(
+            first_order.is_amount_valid(&tx.amounts.0)
+                && second_order.is_amount_valid(&tx.amounts.1),
+            "Swap amounts do not match the orders"
+        );
+
+        // Submitter must exist, be unlocked and own the swap signature.
+        let submitter_account = self
+            .get_account(tx.submitter_id)
+            .ok_or_else(|| format_err!("Submitter account does not exist"))?;
+        ensure!(
+            submitter_account.address == tx.submitter_address,
+            "Submitter account address is incorrect"
+        );
+        ensure!(
+            submitter_account.pub_key_hash != PubKeyHash::default(),
+            "Submitter account is locked"
+        );
+        ensure!(
+            tx.verify_signature() == Some(submitter_account.pub_key_hash),
+            "Swap signature is incorrect"
+        );
+
+        // Every order must be signed by its account.
+        for order in &[first_order, second_order] {
+            let account = self
+                .get_account(order.account_id)
+                .ok_or_else(|| format_err!("Order account does not exist"))?;
+            ensure!(
+                account.address == order.address,
+                "Order account address is incorrect"
+            );
+            ensure!(
+                account.pub_key_hash != PubKeyHash::default(),
+                "Order account is locked"
+            );
+            ensure!(
+                order.verify_signature() == Some(account.pub_key_hash),
+                "Order signature is incorrect"
+            );
+        }
+
+        Ok(SwapOp { tx })
+    }
+
+    fn apply_swap(&mut self, tx: Swap) -> Result<OpSuccess, Error> {
+        let swap_op = self.create_swap_op(tx)?;
+
+        let (fee, updates) = self.apply_swap_op(&swap_op)?;
+        Ok(OpSuccess {
+            fee: Some(fee),
+            updates,
+            executed_op: FranklinOp::Swap(Box::new(swap_op)),
+        })
+    }
+
     pub fn collect_fee(&mut self, fees: &[CollectedFee], fee_account: AccountId) -> AccountUpdates {
         let mut updates = Vec::new();
 
@@ -700,6 +833,228 @@ impl PlasmaState {
         Ok((fee, updates))
     }
 
+    pub fn apply_forced_exit_op(
+        &mut self,
+        op: &ForcedExitOp,
+    ) -> Result<(CollectedFee, AccountUpdates), Error> {
+        let initiator_account_id = op.tx.initiator_account_id;
+        let target_account_id = op.target_account_id;
+        ensure!(
+            initiator_account_id <= max_account_id(),
+            "ForcedExit initiator account id is bigger than max supported"
+        );
+        ensure!(
+            target_account_id <= max_account_id(),
+            "ForcedExit target account id is bigger than max supported"
+        );
+
+        let amount = op
+            .withdraw_amount
+            .clone()
+            .map(|amount| amount.0)
+            .unwrap_or_default();
+
+        let mut updates = Vec::new();
+
+        // Initiator pays the fee and increments its nonce.
+        let mut initiator_account = self.get_account(initiator_account_id).unwrap();
+
+        let initiator_old_balance = initiator_account.get_balance(op.tx.token);
+        let initiator_old_nonce = initiator_account.nonce;
+
+        ensure!(op.tx.nonce == initiator_old_nonce, "Nonce mismatch");
+        ensure!(initiator_old_balance >= op.tx.fee, "Not enough balance");
+
+        initiator_account.sub_balance(op.tx.token, &op.tx.fee);
+        initiator_account.nonce += 1;
+
+        let initiator_new_balance = initiator_account.get_balance(op.tx.token);
+        let initiator_new_nonce = initiator_account.nonce;
+
+        self.insert_account(initiator_account_id, initiator_account);
+
+        updates.push((
+            initiator_account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, initiator_old_balance, initiator_new_balance),
+                old_nonce: initiator_old_nonce,
+                new_nonce: initiator_new_nonce,
+            },
+        ));
+
+        // Target account is withdrawn completely, its nonce stays the same.
+        let mut target_account = self.get_account(target_account_id).unwrap();
+
+        let target_old_balance = target_account.get_balance(op.tx.token);
+        let target_nonce = target_account.nonce;
+
+        ensure!(
+            target_old_balance == amount,
+            "Target account balance mismatch"
+        );
+
+        target_account.sub_balance(op.tx.token, &amount);
+
+        let target_new_balance = target_account.get_balance(op.tx.token);
+
+        self.insert_account(target_account_id, target_account);
+
+        updates.push((
+            target_account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, target_old_balance, target_new_balance),
+                old_nonce: target_nonce,
+                new_nonce: target_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.token,
+            amount: op.tx.fee.clone(),
+        };
+
+        Ok((fee, updates))
+    }
+
+    pub fn apply_swap_op(&mut self, op: &SwapOp) -> Result<(CollectedFee, AccountUpdates), Error> {
+        let (first_order, second_order) = &op.tx.orders;
+        for account_id in &[
+            op.tx.submitter_id,
+            first_order.account_id,
+            second_order.account_id,
+        ] {
+            ensure!(
+                *account_id <= max_account_id(),
+                "Swap account id is bigger than max supported"
+            );
+        }
+
+        // Balances are updated one by one in the same order as in the circuit,
+        // so if one of the steps fails, the previous ones have to be reverted.
+        let mut updates = Vec::new();
+        if let Err(error) = self.apply_swap_updates(op, &mut updates) {
+            reverse_updates(&mut updates);
+            self.apply_account_updates(updates);
+            return Err(error);
+        }
+
+        let fee = CollectedFee {
+            token: op.tx.fee_token,
+            amount: op.tx.fee.clone(),
+        };
+
+        Ok((fee, updates))
+    }
+
+    fn apply_swap_updates(
+        &mut self,
+        op: &SwapOp,
+        updates: &mut AccountUpdates,
+    ) -> Result<(), Error> {
+        let (first_order, second_order) = &op.tx.orders;
+
+        // Submitter pays the fee.
+        updates.push(self.charge_swap_account(
+            op.tx.submitter_id,
+            op.tx.fee_token,
+            &op.tx.fee,
+            op.tx.nonce,
+        )?);
+        // Both accounts sell their tokens...
+        updates.push(self.charge_swap_account(
+            first_order.account_id,
+            first_order.token_sell,
+            &op.tx.amounts.0,
+            first_order.nonce,
+        )?);
+        updates.push(self.charge_swap_account(
+            second_order.account_id,
+            second_order.token_sell,
+            &op.tx.amounts.1,
+            second_order.nonce,
+        )?);
+        // ...and receive tokens of the counterparty.
+        updates.push(self.credit_swap_account(
+            first_order.account_id,
+            second_order.token_sell,
+            &op.tx.amounts.1,
+        )?);
+        updates.push(self.credit_swap_account(
+            second_order.account_id,
+            first_order.token_sell,
+            &op.tx.amounts.0,
+        )?);
+
+        Ok(())
+    }
+
+    /// Subtracts `amount` of `token` from the account balance and increments
+    /// the account nonce, which is expected to be equal to `nonce`.
+    fn charge_swap_account(
+        &mut self,
+        account_id: AccountId,
+        token: TokenId,
+        amount: &BigUint,
+        nonce: Nonce,
+    ) -> Result<(AccountId, AccountUpdate), Error> {
+        let mut account = self
+            .get_account(account_id)
+            .ok_or_else(|| format_err!("Swap account does not exist"))?;
+
+        let old_balance = account.get_balance(token);
+        let old_nonce = account.nonce;
+
+        ensure!(nonce == old_nonce, "Nonce mismatch");
+        ensure!(old_balance >= *amount, "Not enough balance");
+
+        account.sub_balance(token, amount);
+        account.nonce += 1;
+
+        let new_balance = account.get_balance(token);
+        let new_nonce = account.nonce;
+
+        self.insert_account(account_id, account);
+
+        Ok((
+            account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token, old_balance, new_balance),
+                old_nonce,
+                new_nonce,
+            },
+        ))
+    }
+
+    /// Adds `amount` of `token` to the account balance.
+    fn credit_swap_account(
+        &mut self,
+        account_id: AccountId,
+        token: TokenId,
+        amount: &BigUint,
+    ) -> Result<(AccountId, AccountUpdate), Error> {
+        let mut account = self
+            .get_account(account_id)
+            .ok_or_else(|| format_err!("Swap account does not exist"))?;
+
+        let old_balance = account.get_balance(token);
+        let nonce = account.nonce;
+
+        account.add_balance(token, amount);
+
+        let new_balance = account.get_balance(token);
+
+        self.insert_account(account_id, account);
+
+        Ok((
+            account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token, old_balance, new_balance),
+                old_nonce: nonce,
+                new_nonce: nonce,
+            },
+        ))
+    }
+
     pub fn apply_close_op(
         &mut self,
         op: &CloseOp,
@@ -905,6 +1260,8 @@ impl PlasmaState {
                 .map(TransferOutcome::into_franklin_op),
             FranklinTx::Withdraw(tx) => self.create_withdraw_op(*tx).map(Into::into),
             FranklinTx::ChangePubKey(tx) => self.create_change_pubkey_op(*tx).map(Into::into),
+            FranklinTx::ForcedExit(tx) => self.create_forced_exit_op(*tx).map(Into::into),
+            FranklinTx::Swap(tx) => self.create_swap_op(*tx).map(Into::into),
             FranklinTx::Close(_) => failure::bail!("Close op is disabled"),
         }
     }
@@ -922,6 +1279,7 @@ impl PlasmaState {
 mod tests {
     use super::*;
     use crypto_exports::rand::{Rng, SeedableRng, XorShiftRng};
+    use models::node::Order;
 
     #[test]
     fn plasma_state_reversing_updates() {
@@ -1006,4 +1364,73 @@ mod tests {
             initial_plasma_state.root_hash()
         );
     }
+
+    /// Creates a swap of `first_amount` of token 1 for `second_amount` of token 2
+    /// between accounts 0 and 1. Account 0 is also a submitter paying the fee in token 1.
+    fn swap_op(first_amount: u32, second_amount: u32) -> SwapOp {
+        let order = |account_id, nonce, token_sell, token_buy, amount: u32| {
+            Order::new(
+                account_id,
+                Address::zero(),
+                nonce,
+                token_sell,
+                token_buy,
+                (BigUint::from(1u32), BigUint::from(1u32)),
+                amount.into(),
+                Default::default(),
+                None,
+            )
+        };
+
+        SwapOp {
+            tx: Swap::new(
+                0,
+                Address::zero(),
+                0,
+                (
+                    // Nonce of the account 0 is incremented by the submitter part first.
+                    order(0, 1, 1, 2, first_amount),
+                    order(1, 0, 2, 1, second_amount),
+                ),
+                (first_amount.into(), second_amount.into()),
+                1u32.into(),
+                1,
+                None,
+            ),
+        }
+    }
+
+    #[test]
+    fn swap_op_is_atomic() {
+        let mut accounts = AccountMap::default();
+        for id in 0..2 {
+            let mut account = Account::default_with_address(&Address::from([id as u8 + 1; 20]));
+            account.set_balance(1, 100u32.into());
+            account.set_balance(2, 100u32.into());
+            accounts.insert(id, account);
+        }
+        let initial_plasma_state = PlasmaState::from_acc_map(accounts, 0);
+
+        // Second account can't afford the swap, so all the updates must be reverted.
+        let mut plasma_state = initial_plasma_state.clone();
+        assert!(plasma_state.apply_swap_op(&swap_op(10, 200)).is_err());
+        assert_eq!(plasma_state.root_hash(), initial_plasma_state.root_hash());
+
+        let mut plasma_state = initial_plasma_state;
+        let (fee, updates) = plasma_state
+            .apply_swap_op(&swap_op(10, 20))
+            .expect("swap should be applied");
+        assert_eq!(fee.amount, 1u32.into());
+        assert_eq!(updates.len(), 5);
+
+        let first_account = plasma_state.get_account(0).unwrap();
+        assert_eq!(first_account.nonce, 2);
+        assert_eq!(first_account.get_balance(1), 89u32.into());
+        assert_eq!(first_account.get_balance(2), 120u32.into());
+
+        let second_account = plasma_state.get_account(1).unwrap();
+        assert_eq!(second_account.nonce, 1);
+        assert_eq!(second_account.get_balance(1), 110u32.into());
+        assert_eq!(second_account.get_balance(2), 80u32.into());
+    }
 }
@@ -0,0 +1,2 @@
+ALTER TABLE eth_operations
+    ADD COLUMN last_used_priority_fee NUMERIC DEFAULT NULL;
@@ -0,0 +1 @@
+DROP INDEX eth_ops_binding_eth_op_id_index;
@@ -0,0 +1,3 @@
+-- Several zkSync operations can be bound to one Ethereum operation (if blocks were aggregated
+-- into one transaction), so the bound operations are loaded by the Ethereum operation ID.
+CREATE INDEX eth_ops_binding_eth_op_id_index ON eth_ops_binding (eth_op_id);
@@ -0,0 +1,11 @@
+-- Tokens without settings are not accepted for paying fees.
+CREATE TABLE token_fee_settings (
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON UPDATE CASCADE,
+    risk_factor NUMERIC NOT NULL,
+    fee_allowed BOOLEAN NOT NULL,
+    PRIMARY KEY (token_id)
+);
+
+-- Keep the already known tokens acceptable for fees.
+INSERT INTO token_fee_settings (token_id, risk_factor, fee_allowed)
+SELECT id, 1, true FROM tokens;
@@ -0,0 +1,8 @@
+-- Proofs rejected by the prover server, along with the provers that sent them.
+CREATE TABLE invalid_proofs (
+    id serial PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    worker TEXT,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
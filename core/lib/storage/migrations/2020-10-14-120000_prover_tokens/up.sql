@@ -0,0 +1,8 @@
+-- Authentication tokens issued to the provers.
+-- Tokens themselves are not stored, the server only keeps track of the issued tokens IDs.
+CREATE TABLE prover_tokens (
+    id serial PRIMARY KEY,
+    worker TEXT NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now(),
+    revoked_at TIMESTAMP with time zone
+);
@@ -0,0 +1,8 @@
+-- Binary witnesses can't be represented in the old schema, they will be generated again.
+DELETE FROM block_witness WHERE witness IS NULL;
+ALTER TABLE block_witness
+    DROP CONSTRAINT block_witness_not_empty;
+ALTER TABLE block_witness
+    DROP COLUMN witness_binary;
+ALTER TABLE block_witness
+    ALTER COLUMN witness SET NOT NULL;
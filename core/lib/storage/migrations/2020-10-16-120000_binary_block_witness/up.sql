@@ -0,0 +1,10 @@
+-- Witnesses are stored in the compact binary form (gzip-compressed CBOR).
+-- Witnesses stored as JSON text before are kept and still can be served.
+ALTER TABLE block_witness
+    ALTER COLUMN witness DROP NOT NULL;
+ALTER TABLE block_witness
+    ADD COLUMN witness_binary BYTEA;
+-- Every stored witness must be present in one of the forms.
+ALTER TABLE block_witness
+    ADD CONSTRAINT block_witness_not_empty
+    CHECK (witness IS NOT NULL OR witness_binary IS NOT NULL);
@@ -0,0 +1,10 @@
+-- Scheduling parameters of the proof generation jobs.
+-- Blocks committed before this table was introduced are treated as regular ones.
+CREATE TABLE prover_job_priorities (
+    block_number BIGINT REFERENCES blocks (number) ON UPDATE CASCADE ON DELETE CASCADE,
+    -- `ProofPriority` of the block: 0 for the regular blocks, 1 for the blocks with fast withdrawals.
+    priority INTEGER NOT NULL,
+    -- Earliest Ethereum block by which the priority operations of the block must be processed.
+    deadline_block BIGINT,
+    PRIMARY KEY (block_number)
+);
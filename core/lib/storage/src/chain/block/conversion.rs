@@ -9,7 +9,7 @@ use std::convert::TryFrom;
 use models::{
     node::{
         block::{ExecutedPriorityOp, ExecutedTx},
-        BlockNumber, FranklinOp, FranklinTx, PriorityOp,
+        Address, BlockNumber, FranklinOp, FranklinTx, PriorityOp,
     },
     Action, ActionType, Operation,
 };
@@ -139,7 +139,15 @@ impl NewExecutedPriorityOperation {
 }
 
 impl NewExecutedTransaction {
-    pub fn prepare_stored_tx(exec_tx: ExecutedTx, block: BlockNumber) -> Self {
+    /// Prepares the transaction to be stored in the database.
+    ///
+    /// `initiator_address` is the address of the account referred by the transaction
+    /// as its initiator (see `FranklinTx::initiator_account_id`), if it is known.
+    pub fn prepare_stored_tx(
+        exec_tx: ExecutedTx,
+        block: BlockNumber,
+        initiator_address: Option<Address>,
+    ) -> Self {
         fn cut_prefix(input: &str) -> String {
             if input.starts_with("0x") {
                 input[2..].into()
@@ -167,12 +175,26 @@ impl NewExecutedTransaction {
                     serde_json::from_value(tx["account"].clone()).unwrap(),
                     serde_json::from_value(tx["account"].clone()).unwrap(),
                 ),
+                // The initiator may be unknown only for a failed transaction.
+                FranklinTx::ForcedExit(_) => (
+                    initiator_address
+                        .map(|address| format!("{:x}", address))
+                        .unwrap_or_else(|| serde_json::from_value(tx["target"].clone()).unwrap()),
+                    serde_json::from_value(tx["target"].clone()).unwrap(),
+                ),
+                FranklinTx::Swap(_) => (
+                    serde_json::from_value(tx["submitterAddress"].clone()).unwrap(),
+                    serde_json::from_value(tx["submitterAddress"].clone()).unwrap(),
+                ),
             };
 
         let from_account: Vec<u8> = hex::decode(cut_prefix(&from_account_hex)).unwrap();
         let to_account: Option<Vec<u8>> =
             to_account_hex.map(|value| hex::decode(cut_prefix(&value)).unwrap());
 
+        // Transaction belongs to the history of the account whose nonce it uses.
+        let primary_account_address = from_account.clone();
+
         let eth_sign_data = exec_tx.signed_tx.eth_sign_data.as_ref().map(|sign_data| {
             serde_json::to_value(sign_data).expect("Failed to encode EthSignData")
         });
@@ -187,7 +209,7 @@ impl NewExecutedTransaction {
             success: exec_tx.success,
             fail_reason: exec_tx.fail_reason,
             block_index: exec_tx.block_index.map(|idx| idx as i32),
-            primary_account_address: exec_tx.signed_tx.account().as_bytes().to_vec(),
+            primary_account_address,
             nonce: exec_tx.signed_tx.nonce() as i64,
             created_at: exec_tx.created_at,
             eth_sign_data,
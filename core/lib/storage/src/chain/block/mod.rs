@@ -15,6 +15,7 @@ use self::records::{
 };
 use crate::{
     chain::{
+        account::AccountSchema,
         operations::{
             records::{
                 NewExecutedPriorityOperation, NewExecutedTransaction, NewOperation,
@@ -96,8 +97,22 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         for block_tx in operations.into_iter() {
             match block_tx {
                 ExecutedOperations::Tx(tx) => {
+                    // Initiator of the transaction is referred by its id, so its address
+                    // is resolved from the state to store the transaction in its history.
+                    let initiator_address = match tx.signed_tx.initiator_account_id() {
+                        Some(account_id) => AccountSchema(self.0)
+                            .last_committed_state_for_account(account_id)
+                            .await?
+                            .map(|account| account.address),
+                        None => None,
+                    };
+
                     // Store the executed operation in the corresponding schema.
-                    let new_tx = NewExecutedTransaction::prepare_stored_tx(*tx, block_number);
+                    let new_tx = NewExecutedTransaction::prepare_stored_tx(
+                        *tx,
+                        block_number,
+                        initiator_address,
+                    );
                     OperationsSchema(self.0)
                         .store_executed_operation(new_tx)
                         .await?;
@@ -595,6 +610,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let block_size = block.block_chunks_size as i64;
         let commit_gas_limit = block.commit_gas_limit.as_u64() as i64;
         let verify_gas_limit = block.verify_gas_limit.as_u64() as i64;
+        let proof_priority = block.proof_priority();
+        let priority_ops_deadline = block.priority_ops_deadline();
 
         BlockSchema(&mut transaction)
             .save_block_transactions(block.block_number, block.block_transactions)
@@ -631,6 +648,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).execute(transaction.conn())
         .await?;
 
+        // Remember how urgent the proof for this block is.
+        ProverSchema(&mut transaction)
+            .store_job_priority(block.block_number, proof_priority, priority_ops_deadline)
+            .await?;
+
         transaction.commit().await?;
 
         Ok(())
@@ -143,6 +143,28 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     operation["to"].as_str().unwrap_or("unknown to").to_string(),
                     operation["fee"].as_str().map(|v| v.to_string()),
                 ),
+                "ForcedExit" => (
+                    operation["target"]
+                        .as_str()
+                        .unwrap_or("unknown from")
+                        .to_string(),
+                    operation["target"]
+                        .as_str()
+                        .unwrap_or("unknown to")
+                        .to_string(),
+                    operation["fee"].as_str().map(|v| v.to_string()),
+                ),
+                "Swap" => (
+                    operation["submitterAddress"]
+                        .as_str()
+                        .unwrap_or("unknown from")
+                        .to_string(),
+                    operation["submitterAddress"]
+                        .as_str()
+                        .unwrap_or("unknown to")
+                        .to_string(),
+                    operation["fee"].as_str().map(|v| v.to_string()),
+                ),
                 "ChangePubKey" | "ChangePubKeyOffchain" => (
                     operation["account"]
                         .as_str()
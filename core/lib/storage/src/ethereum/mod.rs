@@ -28,11 +28,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
     pub async fn load_unconfirmed_operations(&mut self) -> QueryResult<VecDeque<ETHOperation>> {
         // Load the operations with the associated Ethereum transactions
         // from the database.
-        // Here we obtain a sequence of one-to-one mappings (ETH tx) -> (operation ID).
-        // Each Ethereum transaction can have no more than one associated operation, and each
-        // operation is associated with exactly one Ethereum transaction. Note that there may
-        // be ETH transactions without an operation (e.g. `completeWithdrawals` call), but for
-        // every operation always there is an ETH transaction.
+        // Here we obtain a sequence of one-to-many mappings (ETH tx) -> (operation IDs).
+        // Each Ethereum transaction can have several associated operations (if the blocks
+        // were aggregated into one transaction), and each operation is associated with exactly
+        // one Ethereum transaction. Note that there may be ETH transactions without an operation
+        // (e.g. `completeWithdrawals` call), but for every operation always there is an ETH transaction.
 
         let mut transaction = self.0.start_transaction().await?;
 
@@ -54,14 +54,15 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         // Transform the `StoredOperation` to `Operation` and `StoredETHOperation` to `ETHOperation`.
         for eth_op in eth_ops {
-            let raw_op = sqlx::query_as!(
+            let raw_ops = sqlx::query_as!(
                 StoredOperation,
                 "SELECT operations.* FROM eth_ops_binding
                 LEFT JOIN operations ON operations.id = op_id
-                WHERE eth_op_id = $1",
+                WHERE eth_op_id = $1
+                ORDER BY operations.block_number ASC",
                 eth_op.id
             )
-            .fetch_optional(transaction.conn())
+            .fetch_all(transaction.conn())
             .await?;
 
             // Load the stored txs hashes ordered by their ID,
@@ -80,18 +81,20 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 "No hashes stored for the Ethereum operation"
             );
 
-            // If there is an operation, convert it to the `Operation` type.
-            let op = if let Some(raw_op) = raw_op {
-                Some(raw_op.into_op(&mut transaction).await?)
-            } else {
-                None
-            };
+            // Convert the associated operations (if any) to the `Operation` type.
+            let mut ops = Vec::with_capacity(raw_ops.len());
+            for raw_op in raw_ops {
+                ops.push(raw_op.into_op(&mut transaction).await?);
+            }
 
             // Convert the fields into expected format.
             let op_type = OperationType::from_str(eth_op.op_type.as_ref())
                 .expect("Stored operation type must have a valid value");
             let last_used_gas_price =
                 U256::from_str(&eth_op.last_used_gas_price.to_string()).unwrap();
+            let last_used_priority_fee = eth_op
+                .last_used_priority_fee
+                .map(|fee| U256::from_str(&fee.to_string()).unwrap());
             let used_tx_hashes = eth_tx_hashes
                 .iter()
                 .map(|entry| H256::from_slice(&entry.tx_hash))
@@ -101,10 +104,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             let eth_op = ETHOperation {
                 id: eth_op.id,
                 op_type,
-                op,
+                ops,
                 nonce: eth_op.nonce.into(),
                 last_deadline_block: eth_op.last_deadline_block as u64,
                 last_used_gas_price,
+                last_used_priority_fee,
                 used_tx_hashes,
                 encoded_tx_data: eth_op.raw_tx,
                 confirmed: eth_op.confirmed,
@@ -164,12 +168,18 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
     /// Stores the sent (but not confirmed yet) Ethereum transaction in the database.
     /// Returns the `ETHOperation` object containing the assigned nonce and operation ID.
+    ///
+    /// `op_ids` contains the IDs of all the operations sent within the Ethereum operation
+    /// (there may be several of them if the blocks were aggregated into one transaction).
+    ///
+    /// `last_used_priority_fee` must be provided for the EIP-1559 transactions only.
     pub async fn save_new_eth_tx(
         &mut self,
         op_type: OperationType,
-        op_id: Option<i64>,
+        op_ids: &[i64],
         last_deadline_block: i64,
         last_used_gas_price: BigUint,
+        last_used_priority_fee: Option<BigUint>,
         raw_tx: Vec<u8>,
     ) -> QueryResult<InsertedOperationResponse> {
         let mut transaction = self.0.start_transaction().await?;
@@ -182,13 +192,15 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         // Obtain the operation ID for the follow-up queried.
         let last_used_gas_price = BigDecimal::from(BigInt::from(last_used_gas_price));
+        let last_used_priority_fee =
+            last_used_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         let eth_op_id = sqlx::query!(
             "
-                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, last_used_priority_fee, raw_tx)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 RETURNING id
             ",
-            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, raw_tx,
+            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, last_used_priority_fee, raw_tx,
         )
         .fetch_one(transaction.conn())
         .await?
@@ -207,8 +219,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         //     "Wrong amount of updated rows (eth_tx_hashes)"
         // );
 
-        // If the operation ID was provided, we should also insert a binding entry.
-        if let Some(op_id) = op_id {
+        // If the operation IDs were provided, we should also insert the binding entries.
+        for &op_id in op_ids {
             sqlx::query!(
                 "INSERT INTO eth_ops_binding (op_id, eth_op_id) VALUES ($1, $2)",
                 op_id,
@@ -218,9 +230,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             .await?;
         }
 
-        // Update the stored stats.
+        // Update the stored stats. Operation without associated zkSync operations
+        // (e.g. `completeWithdrawals` call) is counted as well.
+        let ops_count = op_ids.len().max(1) as i64;
         EthereumSchema(&mut transaction)
-            .report_created_operation(op_type)
+            .report_created_operation(op_type, ops_count)
             .await?;
 
         // Return the assigned ID and nonce.
@@ -261,20 +275,23 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
     }
 
     /// Updates the Ethereum operation by adding a new tx data.
-    /// The new deadline block / gas value are placed instead of old values to the main entry.
+    /// The new deadline block / gas value / priority fee are placed instead of old values to the main entry.
     pub async fn update_eth_tx(
         &mut self,
         eth_op_id: i64,
         new_deadline_block: i64,
         new_gas_value: BigUint,
+        new_priority_fee: Option<BigUint>,
     ) -> QueryResult<()> {
         // Update the stored tx.
         let new_gas_price = BigDecimal::from(BigInt::from(new_gas_value));
+        let new_priority_fee = new_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         sqlx::query!(
             "UPDATE eth_operations 
-            SET last_used_gas_price = $1, last_deadline_block = $2
-            WHERE id = $3",
+            SET last_used_gas_price = $1, last_used_priority_fee = $2, last_deadline_block = $3
+            WHERE id = $4",
             new_gas_price,
+            new_priority_fee,
             new_deadline_block,
             eth_op_id
         )
@@ -284,15 +301,50 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Assigns a new nonce to the stored Ethereum operation.
+    ///
+    /// This method is used when the nonce previously assigned to the operation was
+    /// consumed without the operation being executed (e.g. transaction failed or was
+    /// replaced by another transaction). Hash entries of the transactions sent with
+    /// the old nonce are removed, since these transactions can't be mined anymore.
+    pub async fn assign_new_nonce(&mut self, eth_op_id: i64) -> QueryResult<U256> {
+        let mut transaction = self.0.start_transaction().await?;
+
+        let nonce = EthereumSchema(&mut transaction).get_next_nonce().await?;
+
+        sqlx::query!(
+            "UPDATE eth_operations SET nonce = $1 WHERE id = $2",
+            nonce,
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!("DELETE FROM eth_tx_hashes WHERE eth_op_id = $1", eth_op_id)
+            .execute(transaction.conn())
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(nonce.into())
+    }
+
     /// Updates the stats counter with the new operation reported.
     /// This method should be called once **per operation**. It means that if transaction
     /// for some operation was stuck, and another transaction was created for it, this method
     /// **should not** be invoked.
     ///
+    /// Stats count the zkSync operations rather than the Ethereum ones, so `ops_count`
+    /// is the amount of operations aggregated into the reported Ethereum operation.
+    ///
     /// This method expects the database to be initially prepared with inserting the actual
     /// stats values. Currently the script `db-insert-eth-data.sh` is responsible for that
     /// and it's invoked within `db-reset` subcommand.
-    async fn report_created_operation(&mut self, operation_type: OperationType) -> QueryResult<()> {
+    async fn report_created_operation(
+        &mut self,
+        operation_type: OperationType,
+        ops_count: i64,
+    ) -> QueryResult<()> {
         let mut transaction = self.0.start_transaction().await?;
 
         let mut current_stats = EthereumSchema(&mut transaction).load_eth_params().await?;
@@ -300,13 +352,13 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         // Increase the only one type of operations.
         match operation_type {
             OperationType::Commit => {
-                current_stats.commit_ops += 1;
+                current_stats.commit_ops += ops_count;
             }
             OperationType::Verify => {
-                current_stats.verify_ops += 1;
+                current_stats.verify_ops += ops_count;
             }
             OperationType::Withdraw => {
-                current_stats.withdraw_ops += 1;
+                current_stats.withdraw_ops += ops_count;
             }
         };
 
@@ -372,8 +424,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(params)
     }
 
-    /// Marks the stored Ethereum transaction as confirmed (and thus the associated `Operation`
-    /// is marked as confirmed as well).
+    /// Marks the stored Ethereum transaction as confirmed (and thus the associated `Operation`s
+    /// are marked as confirmed as well).
     pub async fn confirm_eth_tx(&mut self, hash: &H256) -> QueryResult<()> {
         let mut transaction = self.0.start_transaction().await?;
 
@@ -393,12 +445,12 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .await?
         .id;
 
-        // If there is a ZKSync operation, mark it as confirmed as well.
+        // If there are ZKSync operations, mark them as confirmed as well.
         sqlx::query!(
             "
             UPDATE operations
                 SET confirmed = $1
-                WHERE id = (SELECT op_id FROM eth_ops_binding WHERE eth_op_id = $2)",
+                WHERE id IN (SELECT op_id FROM eth_ops_binding WHERE eth_op_id = $2)",
             true,
             eth_op_id,
         )
@@ -13,6 +13,7 @@ pub struct StorageETHOperation {
     pub final_hash: Option<Vec<u8>>,
     pub last_deadline_block: i64,
     pub last_used_gas_price: BigDecimal,
+    pub last_used_priority_fee: Option<BigDecimal>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -1,13 +1,16 @@
 // Built-in deps
-use std::time;
+use std::{collections::HashMap, time};
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::Done;
 // Workspace imports
-use models::node::BlockNumber;
+use models::node::{block::ProofPriority, BlockNumber};
 use models::prover_utils::EncodedProofPlonk;
 // Local imports
-use self::records::{ActiveProver, ProverRun, StoredProof};
-use crate::prover::records::StorageBlockWitness;
+use self::records::{
+    ActiveProver, BlockWitness, InvalidProof, PendingJobsCount, ProverJob, ProverRun, ProverToken,
+    StorageBlockWitness, StoredProof,
+};
 use crate::{chain::block::BlockSchema, QueryResult, StorageProcessor};
 
 pub mod records;
@@ -82,6 +85,68 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(block_without_proofs as u32)
     }
 
+    /// Returns the average time of the proof generation for each block size,
+    /// calculated over the proofs stored since the provided moment.
+    ///
+    /// Proof generation time is measured from the start of the last prover run for the block
+    /// to the moment the proof was stored.
+    pub async fn average_proof_durations(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<HashMap<usize, time::Duration>> {
+        let durations = sqlx::query!(
+            r#"
+                SELECT
+                    blocks.block_size,
+                    AVG(EXTRACT(EPOCH FROM (proofs.created_at - runs.started_at)))::FLOAT8 as "duration_secs!"
+                FROM proofs
+                INNER JOIN blocks
+                    ON proofs.block_number = blocks.number
+                INNER JOIN
+                    (SELECT block_number, max(created_at) as started_at FROM prover_runs GROUP BY block_number) runs
+                    ON proofs.block_number = runs.block_number
+                WHERE proofs.created_at > $1
+                GROUP BY blocks.block_size
+            "#,
+            since
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let duration = time::Duration::from_secs_f64(row.duration_secs.max(0.0));
+            (row.block_size as usize, duration)
+        })
+        .collect();
+
+        Ok(durations)
+    }
+
+    /// Returns the amount of blocks of each size committed since the provided moment.
+    pub async fn commits_count_by_block_size(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<HashMap<usize, u64>> {
+        let commits = sqlx::query!(
+            r#"
+                SELECT blocks.block_size, COUNT(*) as "count!"
+                FROM operations
+                INNER JOIN blocks
+                    ON operations.block_number = blocks.number
+                WHERE operations.action_type = 'COMMIT' AND operations.created_at > $1
+                GROUP BY blocks.block_size
+            "#,
+            since
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.block_size as usize, row.count as u64))
+        .collect();
+
+        Ok(commits)
+    }
+
     /// Attempts to obtain an existing prover run given block number.
     pub async fn get_existing_prover_run(
         &mut self,
@@ -101,11 +166,17 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     /// Given the block size, chooses the next block to prove for the certain prover.
     /// Returns `None` if either there are no blocks of given size to prove, or
     /// there is already an ongoing job for non-proved block.
+    ///
+    /// Only the jobs with at least `min_priority` are handed out, so a prover supporting
+    /// several block sizes can take the latency-sensitive blocks first. Priority of the jobs
+    /// is calculated relative to the `current_eth_block` (see `pending_jobs`).
     pub async fn prover_run_for_next_commit(
         &mut self,
         worker_: &str,
         _prover_timeout: time::Duration,
         block_size: usize,
+        min_priority: ProofPriority,
+        current_eth_block: u64,
     ) -> QueryResult<Option<ProverRun>> {
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
@@ -114,46 +185,27 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
-        // Find the block that satisfies the following criteria:
-        // - Block number is greater than the index of last verified block.
-        // - There is no proof for block.
-        // - Either there is no ongoing job for the block, or the job exceeded the timeout.
-        // Return the index of such a block.
-
-        // TODO: Prover gone interval is hard-coded. Is it critical?
-        let job = sqlx::query!(
-            r#"
-                WITH unsized_blocks AS (
-                    SELECT * FROM operations o
-                    WHERE action_type = 'COMMIT'
-                        AND block_number >
-                            (SELECT COALESCE(max(block_number),0) FROM operations WHERE action_type = 'VERIFY')
-                        AND NOT EXISTS
-                            (SELECT * FROM proofs WHERE block_number = o.block_number)
-                        AND NOT EXISTS
-                            (SELECT * FROM prover_runs
-                                WHERE block_number = o.block_number AND (now() - updated_at) < interval '120 seconds')
-                )
-                SELECT min(block_number) FROM unsized_blocks
-                INNER JOIN blocks
-                    ON unsized_blocks.block_number = blocks.number AND blocks.block_size = $1
-            "#,
-            block_size as i64
-            )
-            .fetch_one(transaction.conn())
+        // Among the blocks of the requested size without an ongoing job, the oldest one
+        // is chosen: blocks are verified in order, so the proof for any later block is
+        // of no use until this one is proven. For the same reason, the oldest block has
+        // the highest priority, so if it's not high enough, none of the blocks is.
+        let job = ProverSchema(&mut transaction)
+            .pending_jobs(None, current_eth_block)
             .await?
-            .min;
+            .into_iter()
+            .find(|job| job.block_size == block_size && !job.in_progress)
+            .filter(|job| job.priority >= min_priority);
 
         // If there is a block to prove, create a job and store it
         // in the `prover_runs` table; otherwise do nothing and return `None`.
-        let result = if let Some(block_number) = job {
+        let result = if let Some(job) = job {
             let inserted_id = sqlx::query!(
                 r#"
                 INSERT INTO prover_runs ( block_number, worker )
                 VALUES ( $1, $2 )
                 RETURNING (id)
                 "#,
-                block_number,
+                i64::from(job.block_number),
                 worker_.to_string(),
             )
             .fetch_one(transaction.conn())
@@ -178,6 +230,127 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Gets the prover job by its ID.
+    pub async fn prover_run(&mut self, run_id: i32) -> QueryResult<Option<ProverRun>> {
+        let prover_run =
+            sqlx::query_as!(ProverRun, "SELECT * FROM prover_runs WHERE id = $1", run_id)
+                .fetch_optional(self.0.conn())
+                .await?;
+
+        Ok(prover_run)
+    }
+
+    /// Stores the scheduling parameters of the proof generation job for a block.
+    pub async fn store_job_priority(
+        &mut self,
+        block_number: BlockNumber,
+        priority: ProofPriority,
+        deadline_block: Option<u64>,
+    ) -> QueryResult<()> {
+        sqlx::query!(
+            "INSERT INTO prover_job_priorities (block_number, priority, deadline_block)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (block_number)
+            DO UPDATE SET priority = $2, deadline_block = $3",
+            i64::from(block_number),
+            priority_to_db(priority),
+            deadline_block.map(|block| block as i64),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns up to `limit` oldest blocks awaiting for proof (or all of them if there is
+    /// no limit), in the order they are handed out to the provers.
+    ///
+    /// Since blocks are verified in order, a block inherits the highest priority and
+    /// the earliest deadline of the blocks after it. Blocks with the deadline close to
+    /// the `current_eth_block` have the high priority.
+    pub async fn pending_jobs(
+        &mut self,
+        limit: Option<u32>,
+        current_eth_block: u64,
+    ) -> QueryResult<Vec<ProverJob>> {
+        // TODO: Prover gone interval is hard-coded. Is it critical?
+        let mut jobs: Vec<_> = sqlx::query!(
+            r#"
+                SELECT
+                    o.block_number as "block_number!",
+                    blocks.block_size as "block_size!",
+                    prover_job_priorities.priority as "priority?",
+                    prover_job_priorities.deadline_block as "deadline_block?",
+                    EXISTS
+                        (SELECT * FROM prover_runs
+                            WHERE block_number = o.block_number AND (now() - updated_at) < interval '120 seconds') as "in_progress!"
+                FROM operations o
+                INNER JOIN blocks
+                    ON o.block_number = blocks.number
+                LEFT JOIN prover_job_priorities
+                    ON o.block_number = prover_job_priorities.block_number
+                WHERE o.action_type = 'COMMIT'
+                    AND o.block_number >
+                        (SELECT COALESCE(max(block_number),0) FROM operations WHERE action_type = 'VERIFY')
+                    AND NOT EXISTS
+                        (SELECT * FROM proofs WHERE block_number = o.block_number)
+                ORDER BY o.block_number ASC
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|job| ProverJob {
+            block_number: job.block_number as BlockNumber,
+            block_size: job.block_size as usize,
+            priority: job
+                .priority
+                .map(priority_from_db)
+                .unwrap_or(ProofPriority::Normal),
+            deadline_block: job.deadline_block.map(|block| block as u64),
+            in_progress: job.in_progress,
+        })
+        .collect();
+
+        // Propagate the priorities and the deadlines from the latest blocks to the oldest ones.
+        let mut priority = ProofPriority::Normal;
+        let mut deadline_block = None;
+        for job in jobs.iter_mut().rev() {
+            priority = std::cmp::max(priority, job.priority);
+            deadline_block = match (deadline_block, job.deadline_block) {
+                (Some(later), Some(current)) => Some(std::cmp::min(later, current)),
+                (later, current) => later.or(current),
+            };
+
+            job.priority = priority.with_deadline(deadline_block, current_eth_block);
+            job.deadline_block = deadline_block;
+        }
+
+        if let Some(limit) = limit {
+            jobs.truncate(limit as usize);
+        }
+
+        Ok(jobs)
+    }
+
+    /// Returns the amount of blocks of each size awaiting for proof, along with the amount
+    /// of such blocks which have to be proven for some high-priority block to be verified.
+    pub async fn pending_jobs_count_by_block_size(
+        &mut self,
+        current_eth_block: u64,
+    ) -> QueryResult<HashMap<usize, PendingJobsCount>> {
+        let mut counts: HashMap<usize, PendingJobsCount> = HashMap::new();
+        for job in self.pending_jobs(None, current_eth_block).await? {
+            let count = counts.entry(job.block_size).or_default();
+            count.total += 1;
+            if job.priority == ProofPriority::High {
+                count.high_priority += 1;
+            }
+        }
+
+        Ok(counts)
+    }
+
     /// Updates the state of ongoing prover job.
     pub async fn record_prover_is_working(&mut self, job_id: i32) -> QueryResult<()> {
         sqlx::query!(
@@ -238,6 +411,50 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Records the authentication token issued to the prover and returns its ID.
+    pub async fn issue_prover_token(&mut self, worker: &str) -> QueryResult<i32> {
+        let token_id = sqlx::query!(
+            "INSERT INTO prover_tokens (worker)
+            VALUES ($1)
+            RETURNING id",
+            worker.to_string(),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        Ok(token_id)
+    }
+
+    /// Gets the prover token descriptor by its ID.
+    pub async fn prover_token(&mut self, token_id: i32) -> QueryResult<Option<ProverToken>> {
+        let token = sqlx::query_as!(
+            ProverToken,
+            "SELECT * FROM prover_tokens WHERE id = $1",
+            token_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Marks the prover token as revoked.
+    /// Returns `false` if there is no such token, or it is already revoked.
+    pub async fn revoke_prover_token(&mut self, token_id: i32) -> QueryResult<bool> {
+        let updated_rows = sqlx::query!(
+            "UPDATE prover_tokens
+            SET revoked_at = now()
+            WHERE id = $1 AND revoked_at IS NULL",
+            token_id
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        Ok(updated_rows > 0)
+    }
+
     /// Stores the proof for a block.
     pub async fn store_proof(
         &mut self,
@@ -257,6 +474,57 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(updated_rows)
     }
 
+    /// Records the proof for a block that was rejected by the server, along with the prover
+    /// which sent it. The block job of this prover is returned to the queue, i.e. the block
+    /// may be taken by any prover right away.
+    pub async fn record_invalid_proof(
+        &mut self,
+        block_number: BlockNumber,
+        worker: &str,
+        reason: &str,
+    ) -> QueryResult<()> {
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            "INSERT INTO invalid_proofs (block_number, worker, reason)
+            VALUES ($1, $2, $3)",
+            i64::from(block_number),
+            worker.to_string(),
+            reason.to_string(),
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        // Jobs of the other provers are not affected, since the proof may be sent by
+        // the prover which was not assigned to the block.
+        sqlx::query!(
+            "DELETE FROM prover_runs WHERE block_number = $1 AND worker = $2",
+            i64::from(block_number),
+            worker.to_string(),
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Loads the rejected proofs for a block.
+    pub async fn load_invalid_proofs(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<InvalidProof>> {
+        let proofs = sqlx::query_as!(
+            InvalidProof,
+            "SELECT * FROM invalid_proofs WHERE block_number = $1 ORDER BY id",
+            i64::from(block_number),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        Ok(proofs)
+    }
+
     /// Gets the stored proof for a block.
     pub async fn load_proof(
         &mut self,
@@ -274,7 +542,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(proof)
     }
 
-    /// Stores witness for a block
+    /// Stores witness for a block serialized to JSON.
     pub async fn store_witness(
         &mut self,
         block: BlockNumber,
@@ -295,11 +563,31 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores witness for a block in the compact binary form.
+    pub async fn store_binary_witness(
+        &mut self,
+        block: BlockNumber,
+        witness: &[u8],
+    ) -> QueryResult<()> {
+        sqlx::query!(
+            "INSERT INTO block_witness (block, witness_binary)
+            VALUES ($1, $2)
+            ON CONFLICT (block)
+            DO NOTHING",
+            i64::from(block),
+            witness
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        Ok(())
+    }
+
     /// Gets stored witness for a block
     pub async fn get_witness(
         &mut self,
         block_number: BlockNumber,
-    ) -> QueryResult<Option<serde_json::Value>> {
+    ) -> QueryResult<Option<BlockWitness>> {
         let block_witness = sqlx::query_as!(
             StorageBlockWitness,
             "SELECT * FROM block_witness WHERE block = $1",
@@ -307,8 +595,35 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
+        let block_witness = match block_witness {
+            Some(block_witness) => block_witness,
+            None => return Ok(None),
+        };
+
+        let witness = match (block_witness.witness_binary, block_witness.witness) {
+            (Some(witness), _) => BlockWitness::Binary(witness),
+            (None, Some(witness)) => BlockWitness::Json(serde_json::from_str(&witness)?),
+            // Prevented by the table constraint, checked just in case.
+            (None, None) => failure::bail!("Witness for block {} is empty", block_witness.block),
+        };
+
+        Ok(Some(witness))
+    }
+}
+
+/// Representation of the `ProofPriority` in the `prover_job_priorities` table.
+fn priority_to_db(priority: ProofPriority) -> i32 {
+    match priority {
+        ProofPriority::Normal => 0,
+        ProofPriority::High => 1,
+    }
+}
 
-        Ok(block_witness
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
+/// Restores the `ProofPriority` from its representation in the `prover_job_priorities` table.
+fn priority_from_db(priority: i32) -> ProofPriority {
+    if priority >= priority_to_db(ProofPriority::High) {
+        ProofPriority::High
+    } else {
+        ProofPriority::Normal
     }
 }
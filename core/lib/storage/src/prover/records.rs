@@ -3,6 +3,7 @@ use chrono::prelude::*;
 use serde_derive::{Deserialize, Serialize};
 use sqlx::FromRow;
 // Workspace imports
+use models::node::{block::ProofPriority, BlockNumber};
 // Local imports
 
 #[derive(Debug, FromRow)]
@@ -27,6 +28,28 @@ pub struct StoredProof {
     pub created_at: DateTime<Utc>,
 }
 
+/// Proof that was rejected by the server, e.g. because it failed the verification.
+#[derive(Debug, FromRow)]
+pub struct InvalidProof {
+    pub id: i32,
+    pub block_number: i64,
+    /// Name of the prover which was proving the block.
+    pub worker: Option<String>,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Authentication token issued to the prover.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProverToken {
+    pub id: i32,
+    /// Name of the prover the token is issued to.
+    pub worker: String,
+    pub created_at: DateTime<Utc>,
+    /// Revoked tokens are no longer accepted by the server.
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
 // Every time before a prover worker starts generating the proof, a prover run is recorded for monitoring purposes
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct ProverRun {
@@ -45,5 +68,38 @@ pub struct IntegerNumber {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct StorageBlockWitness {
     pub block: i64,
-    pub witness: String,
+    pub witness: Option<String>,
+    pub witness_binary: Option<Vec<u8>>,
+}
+
+/// Witness for a block in the form it is stored in the database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockWitness {
+    /// Witness serialized to JSON, generated by the older server versions.
+    Json(serde_json::Value),
+    /// Witness in the compact binary form (see `ProverData::encode_binary` in the prover crate).
+    Binary(Vec<u8>),
+}
+
+/// Block awaiting for the proof, as seen by the proof jobs dispatcher.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverJob {
+    pub block_number: BlockNumber,
+    pub block_size: usize,
+    /// Highest priority among this block and the pending blocks after it,
+    /// including the one required by the deadline of their priority operations.
+    pub priority: ProofPriority,
+    /// Earliest Ethereum block by which the priority operations of this block
+    /// or the pending blocks after it must be processed.
+    pub deadline_block: Option<u64>,
+    /// Whether some prover is working on the block at the moment.
+    pub in_progress: bool,
+}
+
+/// Amount of the blocks of a certain size awaiting for proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PendingJobsCount {
+    pub total: u64,
+    /// Blocks which have to be proven for some high-priority block to be verified.
+    pub high_priority: u64,
 }
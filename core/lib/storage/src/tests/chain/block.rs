@@ -281,6 +281,7 @@ async fn find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) -> Quer
                 Some(ethereum_op_id),
                 100,
                 100u32.into(),
+                None,
                 Default::default(),
             )
             .await?;
@@ -323,6 +324,7 @@ async fn find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) -> Quer
                         Some(ethereum_op_id),
                         100,
                         100u32.into(),
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -422,6 +424,7 @@ async fn block_range(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                 Some(ethereum_op_id),
                 100,
                 100u32.into(),
+                None,
                 Default::default(),
             )
             .await?;
@@ -451,6 +454,7 @@ async fn block_range(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                     Some(ethereum_op_id),
                     100,
                     100u32.into(),
+                    None,
                     Default::default(),
                 )
                 .await?;
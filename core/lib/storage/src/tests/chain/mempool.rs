@@ -28,6 +28,7 @@ fn franklin_txs() -> Vec<SignedFranklinTx> {
         100u32.into(),
         10u32.into(),
         10,
+        Default::default(),
         None,
     );
 
@@ -39,6 +40,7 @@ fn franklin_txs() -> Vec<SignedFranklinTx> {
         500u32.into(),
         20u32.into(),
         11,
+        Default::default(),
         None,
     );
 
@@ -50,6 +52,7 @@ fn franklin_txs() -> Vec<SignedFranklinTx> {
         100u32.into(),
         10u32.into(),
         12,
+        Default::default(),
         None,
     );
 
@@ -58,6 +61,7 @@ fn franklin_txs() -> Vec<SignedFranklinTx> {
         account: Address::random(),
         new_pk_hash: Default::default(),
         nonce: 13,
+        time_range: Default::default(),
         eth_signature: None,
     };
 
@@ -96,6 +100,7 @@ fn gen_transfers(n: usize) -> Vec<SignedFranklinTx> {
                 100u32.into(),
                 10u32.into(),
                 10,
+                Default::default(),
                 None,
             );
 
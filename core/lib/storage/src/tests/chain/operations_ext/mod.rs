@@ -2,6 +2,7 @@
 use std::collections::HashMap;
 // External imports
 // Workspace imports
+use models::node::AccountUpdate;
 // Local imports
 use self::setup::TransactionsHistoryTestSetup;
 use crate::{
@@ -205,3 +206,46 @@ async fn get_account_transactions_history_from(
 
     Ok(())
 }
+
+/// Checks that the `ForcedExit` transaction is stored in the history of both
+/// the initiator account (which pays the fee) and the target account.
+#[db_test]
+async fn get_forced_exit_transactions_history(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_forced_exit_block(1);
+
+    // The initiator address is resolved from the committed state.
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(
+            1,
+            &[(
+                setup.from_zksync_account.get_account_id().unwrap(),
+                AccountUpdate::Create {
+                    address: setup.from_zksync_account.address,
+                    nonce: 0,
+                },
+            )],
+        )
+        .await?;
+    commit_schema_data(&mut storage, &setup).await?;
+
+    for address in &[
+        setup.from_zksync_account.address,
+        setup.to_zksync_account.address,
+    ] {
+        let history = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history(address, 0, 10)
+            .await?;
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].tx["type"].as_str(), Some("ForcedExit"));
+    }
+
+    Ok(())
+}
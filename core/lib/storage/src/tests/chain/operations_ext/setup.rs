@@ -8,8 +8,8 @@ use models::node::block::{Block, ExecutedOperations, ExecutedPriorityOp, Execute
 use models::node::operations::{ChangePubKeyOp, FranklinOp};
 use models::node::priority_ops::PriorityOp;
 use models::node::{
-    Address, CloseOp, Deposit, DepositOp, Fr, FullExit, FullExitOp, Token, TransferOp,
-    TransferToNewOp, WithdrawOp,
+    Address, CloseOp, Deposit, DepositOp, ForcedExitOp, Fr, FullExit, FullExitOp, Token,
+    TransferOp, TransferToNewOp, WithdrawOp,
 };
 use testkit::zksync_account::ZksyncAccount;
 // Local imports
@@ -92,6 +92,25 @@ impl TransactionsHistoryTestSetup {
         self.blocks.push(block);
     }
 
+    /// Adds a block with a single `ForcedExit` initiated by the `from` account
+    /// and applied to the `to` account.
+    pub fn add_forced_exit_block(&mut self, block_id: u32) {
+        let executed_forced_exit_op = self.create_forced_exit_tx(Some(0));
+
+        let block = Block::new(
+            block_id,
+            Fr::zero(),
+            0,
+            vec![executed_forced_exit_op],
+            (0, 0), // Not important
+            100,
+            1_000_000.into(), // Not important
+            1_500_000.into(), // Not important
+        );
+
+        self.blocks.push(block);
+    }
+
     fn create_deposit_op(
         &mut self,
         serial_id: u64,
@@ -287,6 +306,32 @@ impl TransactionsHistoryTestSetup {
         ExecutedOperations::Tx(Box::new(executed_change_pubkey_op))
     }
 
+    fn create_forced_exit_tx(&mut self, block_index: Option<u32>) -> ExecutedOperations {
+        let forced_exit_op = FranklinOp::ForcedExit(Box::new(ForcedExitOp {
+            tx: self.from_zksync_account.sign_forced_exit(
+                self.tokens[2].id,
+                0u32.into(),
+                &self.to_zksync_account.address,
+                None,
+                true,
+            ),
+            target_account_id: self.to_zksync_account.get_account_id().unwrap(),
+            withdraw_amount: Some(self.amount.clone().into()),
+        }));
+
+        let executed_forced_exit_op = ExecutedTx {
+            signed_tx: forced_exit_op.try_get_tx().unwrap().into(),
+            success: true,
+            op: Some(forced_exit_op),
+            fail_reason: None,
+            block_index,
+            created_at: self.get_tx_time(),
+            batch_id: None,
+        };
+
+        ExecutedOperations::Tx(Box::new(executed_forced_exit_op))
+    }
+
     /// This method is important, since it seems that during database roundtrip timestamp
     /// can be rounded and loose several microseconds in precision, which have lead to the
     /// test failures (txs were using `chrono::Utc::now()` and had difference of 1-2 microsecond
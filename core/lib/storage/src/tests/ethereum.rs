@@ -6,11 +6,15 @@ use web3::types::{H256, U256};
 use models::{
     ethereum::{ETHOperation, OperationType},
     node::{block::Block, BlockNumber, Fr},
-    Action, Operation,
+    Action, ActionType, Operation,
 };
 // Local imports
 use crate::tests::db_test;
-use crate::{chain::block::BlockSchema, ethereum::EthereumSchema, QueryResult, StorageProcessor};
+use crate::{
+    chain::{block::BlockSchema, operations::OperationsSchema},
+    ethereum::EthereumSchema,
+    QueryResult, StorageProcessor,
+};
 use num::BigUint;
 
 /// Creates a sample operation to be stored in `operations` table.
@@ -42,6 +46,7 @@ pub struct EthereumTxParams {
     hash: H256,
     deadline_block: u64,
     gas_price: BigUint,
+    priority_fee: Option<BigUint>,
     raw_tx: Vec<u8>,
 }
 
@@ -54,6 +59,7 @@ impl EthereumTxParams {
             hash: H256::from_low_u64_ne(op_id),
             deadline_block: 100,
             gas_price: 1000u32.into(),
+            priority_fee: None,
             raw_tx: Default::default(),
         }
     }
@@ -62,15 +68,20 @@ impl EthereumTxParams {
         let op_type = OperationType::from_str(self.op_type.as_ref())
             .expect("Stored operation type must have a valid value");
         let last_used_gas_price = U256::from_str(&self.gas_price.to_string()).unwrap();
+        let last_used_priority_fee = self
+            .priority_fee
+            .as_ref()
+            .map(|fee| U256::from_str(&fee.to_string()).unwrap());
         let used_tx_hashes = vec![self.hash];
 
         ETHOperation {
             id: db_id,
             op_type,
-            op: Some(self.op.clone()),
+            ops: vec![self.op.clone()],
             nonce: nonce.into(),
             last_deadline_block: self.deadline_block,
             last_used_gas_price,
+            last_used_priority_fee,
             used_tx_hashes,
             encoded_tx_data: self.raw_tx.clone(),
             confirmed: false,
@@ -118,9 +129,10 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     let response = EthereumSchema(&mut storage)
         .save_new_eth_tx(
             OperationType::Commit,
-            Some(params.op.id.unwrap()),
+            &[params.op.id.unwrap()],
             params.deadline_block as i64,
             params.gas_price.clone(),
+            params.priority_fee.clone(),
             params.raw_tx.clone(),
         )
         .await?;
@@ -133,7 +145,7 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         .load_unconfirmed_operations()
         .await?;
     let eth_op = unconfirmed_operations[0].clone();
-    let op = eth_op.op.clone().expect("No Operation entry");
+    let op = eth_op.ops[0].clone();
     assert_eq!(op.id, operation.id);
     // Load the database ID, since we can't predict it for sure.
     assert_eq!(
@@ -152,9 +164,10 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     let response_2 = EthereumSchema(&mut storage)
         .save_new_eth_tx(
             OperationType::Commit,
-            Some(params_2.op.id.unwrap()),
+            &[params_2.op.id.unwrap()],
             params_2.deadline_block as i64,
             params_2.gas_price.clone(),
+            params_2.priority_fee.clone(),
             params_2.raw_tx.clone(),
         )
         .await?;
@@ -168,7 +181,7 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         .await?;
     assert_eq!(unconfirmed_operations.len(), 2);
     let eth_op = unconfirmed_operations[1].clone();
-    let op = eth_op.op.clone().expect("No Operation entry");
+    let op = eth_op.ops[0].clone();
     assert_eq!(op.id, operation_2.id);
     assert_eq!(
         eth_op,
@@ -196,6 +209,67 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that several operations aggregated into one Ethereum operation are
+/// stored, loaded and confirmed together.
+#[db_test]
+async fn ethereum_aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let mut operations = Vec::new();
+    for block_number in 1..=3 {
+        let operation = BlockSchema(&mut storage)
+            .execute_operation(get_operation(block_number))
+            .await?;
+        operations.push(operation);
+    }
+    let op_ids: Vec<i64> = operations.iter().map(|op| op.id.unwrap()).collect();
+
+    let params = EthereumTxParams::new("commit".into(), operations[0].clone());
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            OperationType::Commit,
+            &op_ids,
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    // All the operations should be loaded within one Ethereum operation in the blocks order.
+    let unconfirmed_operations = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations.len(), 1);
+    let loaded_ids: Vec<i64> = unconfirmed_operations[0]
+        .ops
+        .iter()
+        .map(|op| op.id.unwrap())
+        .collect();
+    assert_eq!(loaded_ids, op_ids);
+
+    // Stats should count every aggregated operation.
+    let stats = EthereumSchema(&mut storage).load_stats().await?;
+    assert_eq!(stats.commit_ops, 3);
+
+    // Confirming the Ethereum operation should confirm all the associated operations.
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&params.hash)
+        .await?;
+    for block_number in 1..=3 {
+        let stored_op = OperationsSchema(&mut storage)
+            .get_operation(block_number, ActionType::COMMIT)
+            .await
+            .expect("Operation should be stored");
+        assert!(stored_op.confirmed);
+    }
+
+    Ok(())
+}
+
 /// Check that stored nonce starts with 0 and is incremented after every getting.
 #[db_test]
 async fn eth_nonce(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -209,3 +283,107 @@ async fn eth_nonce(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that assigning a new nonce to the operation updates the stored nonce
+/// and removes the hashes of transactions sent with the old one.
+#[db_test]
+async fn eth_operation_new_nonce(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let operation = BlockSchema(&mut storage)
+        .execute_operation(get_operation(1))
+        .await?;
+
+    let params = EthereumTxParams::new("commit".into(), operation);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            OperationType::Commit,
+            &[params.op.id.unwrap()],
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let new_nonce = EthereumSchema(&mut storage)
+        .assign_new_nonce(response.id)
+        .await?;
+    assert_eq!(new_nonce, response.nonce + 1);
+
+    // Add a hash of the transaction sent with the new nonce.
+    let new_hash = H256::from_low_u64_ne(0xDEAD);
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &new_hash)
+        .await?;
+
+    // Operation is still unconfirmed, but now has a new nonce and only the new hash.
+    let unconfirmed_operations = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations.len(), 1);
+    assert_eq!(unconfirmed_operations[0].nonce, new_nonce);
+    assert_eq!(unconfirmed_operations[0].used_tx_hashes, vec![new_hash]);
+
+    Ok(())
+}
+
+/// Checks that the priority fee of the EIP-1559 transactions is stored and updated.
+#[db_test]
+async fn eth_operation_priority_fee(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let operation = BlockSchema(&mut storage)
+        .execute_operation(get_operation(1))
+        .await?;
+
+    let mut params = EthereumTxParams::new("commit".into(), operation);
+    params.priority_fee = Some(100u32.into());
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            OperationType::Commit,
+            &[params.op.id.unwrap()],
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let unconfirmed_operations = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(
+        unconfirmed_operations[0],
+        params.to_eth_op(response.id, response.nonce.low_u64())
+    );
+
+    // Update the fees, as it's done for the replacement of the stuck transaction.
+    params.gas_price = 1150u32.into();
+    params.priority_fee = Some(115u32.into());
+    EthereumSchema(&mut storage)
+        .update_eth_tx(
+            response.id,
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+        )
+        .await?;
+
+    let unconfirmed_operations = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations[0].last_used_gas_price, 1150.into());
+    assert_eq!(
+        unconfirmed_operations[0].last_used_priority_fee,
+        Some(115.into())
+    );
+
+    Ok(())
+}
@@ -1,11 +1,20 @@
 /// Std imports
 use std::time::Duration;
 // External imports
+use chrono::Utc;
 // Workspace imports
-use models::{node::block::PendingBlock, Action};
+use models::{
+    node::block::{PendingBlock, ProofPriority, PRIORITY_OPS_DEADLINE_THRESHOLD},
+    params::PRIORITY_EXPIRATION,
+    Action,
+};
 // Local imports
 use crate::tests::{chain::utils::get_operation, db_test};
-use crate::{chain::block::BlockSchema, prover::ProverSchema, QueryResult, StorageProcessor};
+use crate::{
+    chain::block::BlockSchema,
+    prover::{records::BlockWitness, ProverSchema},
+    QueryResult, StorageProcessor,
+};
 use models::config_options::ConfigurationOptions;
 use models::prover_utils::EncodedProofPlonk;
 
@@ -91,7 +100,13 @@ async fn prover_run(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     // Get a prover run.
     let maybe_run = ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(1), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
     let run = maybe_run.expect("Can't get a prover run with a block committed");
 
@@ -102,7 +117,13 @@ async fn prover_run(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     // Try to get another run.
     let maybe_run = ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(1), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
     assert!(
         maybe_run.is_none(),
@@ -118,7 +139,13 @@ async fn prover_run(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     // Try to get another run. There should be none, since there are no blocks to prover.
     let maybe_run = ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(1), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
     assert!(
         maybe_run.is_none(),
@@ -132,7 +159,13 @@ async fn prover_run(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     // Now we should get a prover run for the second block.
     let maybe_run = ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(1), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
     let run = maybe_run.expect("Can't get a prover run with a block committed");
 
@@ -143,6 +176,391 @@ async fn prover_run(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the prover jobs are handed out in the order of the block numbers regardless
+/// of their priorities and deadlines, and that a block inherits the priority and the deadline
+/// of the blocks after it, since it has to be proven for them to be verified.
+#[db_test]
+async fn prover_run_priorities(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let prover_name = "prover_10";
+    let block_size = ConfigurationOptions::from_env().available_block_chunk_sizes[0];
+
+    for block_number in 1..=4 {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation(
+                block_number,
+                Action::Commit,
+                Vec::new(),
+                block_size,
+            ))
+            .await?;
+    }
+    ProverSchema(&mut storage)
+        .store_job_priority(3, ProofPriority::High, None)
+        .await?;
+    ProverSchema(&mut storage)
+        .store_job_priority(4, ProofPriority::Normal, Some(PRIORITY_EXPIRATION))
+        .await?;
+
+    let pending_jobs = ProverSchema(&mut storage).pending_jobs(None, 0).await?;
+    let dispatch_order: Vec<_> = pending_jobs.iter().map(|job| job.block_number).collect();
+    assert_eq!(dispatch_order, vec![1, 2, 3, 4]);
+    let priorities: Vec<_> = pending_jobs.iter().map(|job| job.priority).collect();
+    assert_eq!(
+        priorities,
+        vec![
+            ProofPriority::High,
+            ProofPriority::High,
+            ProofPriority::High,
+            ProofPriority::Normal
+        ]
+    );
+    assert!(pending_jobs
+        .iter()
+        .all(|job| job.deadline_block == Some(PRIORITY_EXPIRATION)));
+    assert!(pending_jobs.iter().all(|job| !job.in_progress));
+
+    // Only the oldest jobs are reported.
+    let pending_jobs = ProverSchema(&mut storage).pending_jobs(Some(2), 0).await?;
+    assert_eq!(pending_jobs.len(), 2);
+    assert_eq!(pending_jobs[1].block_number, 2);
+
+    let counts = ProverSchema(&mut storage)
+        .pending_jobs_count_by_block_size(0)
+        .await?;
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[&block_size].total, 4);
+    assert_eq!(counts[&block_size].high_priority, 3);
+
+    for expected_block in dispatch_order {
+        let run = ProverSchema(&mut storage)
+            .prover_run_for_next_commit(
+                prover_name,
+                Duration::from_secs(1),
+                block_size,
+                ProofPriority::Normal,
+                0,
+            )
+            .await?
+            .expect("Can't get a prover run with a block committed");
+        assert_eq!(run.block_number, i64::from(expected_block));
+    }
+
+    // All the jobs are taken now.
+    let pending_jobs = ProverSchema(&mut storage).pending_jobs(None, 0).await?;
+    assert!(pending_jobs.iter().all(|job| job.in_progress));
+
+    Ok(())
+}
+
+/// Checks that the block with the priority operations close to their deadline is handed out
+/// to a prover asking for the high-priority jobs, while the bulk blocks of other size wait.
+#[db_test]
+async fn prover_run_deadline_priority(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let prover_name = "prover_10";
+    let block_sizes = ConfigurationOptions::from_env().available_block_chunk_sizes;
+    let (bulk_block_size, urgent_block_size) = (block_sizes[1], block_sizes[0]);
+
+    // Block 1 is latency-sensitive, blocks 2 and 3 are bulk ones.
+    BlockSchema(&mut storage)
+        .execute_operation(get_operation(
+            1,
+            Action::Commit,
+            Vec::new(),
+            urgent_block_size,
+        ))
+        .await?;
+    for block_number in 2..=3 {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation(
+                block_number,
+                Action::Commit,
+                Vec::new(),
+                bulk_block_size,
+            ))
+            .await?;
+    }
+    let deadline_block = PRIORITY_EXPIRATION;
+    ProverSchema(&mut storage)
+        .store_job_priority(1, ProofPriority::Normal, Some(deadline_block))
+        .await?;
+
+    // Far from the deadline, there are no high-priority jobs.
+    let current_eth_block = deadline_block - PRIORITY_OPS_DEADLINE_THRESHOLD - 1;
+    let counts = ProverSchema(&mut storage)
+        .pending_jobs_count_by_block_size(current_eth_block)
+        .await?;
+    assert_eq!(counts[&urgent_block_size].high_priority, 0);
+    assert!(ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            urgent_block_size,
+            ProofPriority::High,
+            current_eth_block,
+        )
+        .await?
+        .is_none());
+
+    // Close to the deadline, the block gets the high priority.
+    let current_eth_block = deadline_block - PRIORITY_OPS_DEADLINE_THRESHOLD;
+    let pending_jobs = ProverSchema(&mut storage)
+        .pending_jobs(None, current_eth_block)
+        .await?;
+    let priorities: Vec<_> = pending_jobs.iter().map(|job| job.priority).collect();
+    assert_eq!(
+        priorities,
+        vec![
+            ProofPriority::High,
+            ProofPriority::Normal,
+            ProofPriority::Normal
+        ]
+    );
+    let counts = ProverSchema(&mut storage)
+        .pending_jobs_count_by_block_size(current_eth_block)
+        .await?;
+    assert_eq!(counts[&urgent_block_size].high_priority, 1);
+    assert_eq!(counts[&bulk_block_size].high_priority, 0);
+
+    // Prover supporting both block sizes looks for the high-priority jobs first:
+    // there are none among the bulk blocks, so the urgent block is taken.
+    assert!(ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            bulk_block_size,
+            ProofPriority::High,
+            current_eth_block,
+        )
+        .await?
+        .is_none());
+    let run = ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            urgent_block_size,
+            ProofPriority::High,
+            current_eth_block,
+        )
+        .await?
+        .expect("Urgent block should be handed out");
+    assert_eq!(run.block_number, 1);
+
+    // Bulk blocks are handed out to the regular requests afterwards.
+    let run = ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            bulk_block_size,
+            ProofPriority::Normal,
+            current_eth_block,
+        )
+        .await?
+        .expect("Bulk block should be handed out");
+    assert_eq!(run.block_number, 2);
+
+    Ok(())
+}
+
+/// Checks that the statistics used by the prover scaler are gathered per block size.
+#[db_test]
+async fn prover_scaler_statistics(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let prover_name = "prover_10";
+    let block_size = ConfigurationOptions::from_env().available_block_chunk_sizes[0];
+    let since = Utc::now() - chrono::Duration::hours(1);
+
+    assert!(ProverSchema(&mut storage)
+        .commits_count_by_block_size(since)
+        .await?
+        .is_empty());
+
+    for block_number in 1..=2 {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation(
+                block_number,
+                Action::Commit,
+                Vec::new(),
+                block_size,
+            ))
+            .await?;
+    }
+    let commits = ProverSchema(&mut storage)
+        .commits_count_by_block_size(since)
+        .await?;
+    assert_eq!(commits.get(&block_size), Some(&2));
+
+    // Only the proven blocks are taken into account.
+    ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
+        .await?
+        .expect("Can't get a prover run with a block committed");
+    assert!(ProverSchema(&mut storage)
+        .average_proof_durations(since)
+        .await?
+        .is_empty());
+
+    ProverSchema(&mut storage)
+        .store_proof(1, &EncodedProofPlonk::default())
+        .await?;
+    let durations = ProverSchema(&mut storage)
+        .average_proof_durations(since)
+        .await?;
+    assert_eq!(durations.len(), 1);
+    assert!(durations.contains_key(&block_size));
+
+    Ok(())
+}
+
+/// Checks that the rejected proof is recorded along with the prover which sent it,
+/// and the block becomes available for proving again.
+#[db_test]
+async fn invalid_proof(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let prover_name = "prover_10";
+    let block_size = ConfigurationOptions::from_env().available_block_chunk_sizes[0]; //smallest block size
+
+    BlockSchema(&mut storage)
+        .execute_operation(get_operation(1, Action::Commit, Vec::new(), block_size))
+        .await?;
+
+    let run = ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
+        .await?
+        .expect("Can't get a prover run with a block committed");
+    assert_eq!(run.block_number, 1);
+
+    // Proof sent by a prover not assigned to the block doesn't affect the assigned one.
+    ProverSchema(&mut storage)
+        .record_invalid_proof(1, "prover_12", "proof verification failed")
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .get_existing_prover_run(1)
+        .await?
+        .is_some());
+
+    ProverSchema(&mut storage)
+        .record_invalid_proof(1, prover_name, "proof is invalid")
+        .await?;
+
+    let invalid_proofs = ProverSchema(&mut storage).load_invalid_proofs(1).await?;
+    assert_eq!(invalid_proofs.len(), 2);
+    assert_eq!(invalid_proofs[0].worker, Some("prover_12".into()));
+    assert_eq!(invalid_proofs[1].worker, Some(prover_name.into()));
+    assert_eq!(invalid_proofs[1].reason, "proof is invalid");
+
+    // Job should be returned to the queue, so another prover can take it immediately.
+    assert!(ProverSchema(&mut storage)
+        .get_existing_prover_run(1)
+        .await?
+        .is_none());
+    let run = ProverSchema(&mut storage)
+        .prover_run_for_next_commit(
+            "prover_11",
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
+        .await?
+        .expect("Block with the rejected proof was not returned to the queue");
+    assert_eq!(run.block_number, 1);
+
+    Ok(())
+}
+
+/// Checks the prover tokens workflow: issuing and revoking the token.
+#[db_test]
+async fn prover_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Attempt to load the non-existent token should return `None`.
+    assert!(ProverSchema(&mut storage).prover_token(1).await?.is_none());
+
+    let token_id = ProverSchema(&mut storage)
+        .issue_prover_token("prover_10")
+        .await?;
+    let token = ProverSchema(&mut storage)
+        .prover_token(token_id)
+        .await?
+        .expect("Issued token was not stored");
+    assert_eq!(token.worker, "prover_10");
+    assert!(token.revoked_at.is_none());
+
+    assert!(
+        ProverSchema(&mut storage)
+            .revoke_prover_token(token_id)
+            .await?
+    );
+    let token = ProverSchema(&mut storage)
+        .prover_token(token_id)
+        .await?
+        .expect("Revoked token was removed");
+    assert!(token.revoked_at.is_some());
+
+    // Token can't be revoked twice.
+    assert!(
+        !ProverSchema(&mut storage)
+            .revoke_prover_token(token_id)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that witnesses can be stored and loaded both in the JSON and the binary forms.
+#[db_test]
+async fn block_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let block_size = ConfigurationOptions::from_env().available_block_chunk_sizes[0];
+    for block_number in 1..=3 {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation(
+                block_number,
+                Action::Commit,
+                Vec::new(),
+                block_size,
+            ))
+            .await?;
+    }
+
+    assert!(ProverSchema(&mut storage).get_witness(1).await?.is_none());
+
+    let json_witness = serde_json::json!({ "witness": "json" });
+    ProverSchema(&mut storage)
+        .store_witness(1, json_witness.clone())
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage).get_witness(1).await?,
+        Some(BlockWitness::Json(json_witness))
+    );
+
+    let binary_witness = vec![1u8, 2, 3];
+    ProverSchema(&mut storage)
+        .store_binary_witness(2, &binary_witness)
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage).get_witness(2).await?,
+        Some(BlockWitness::Binary(binary_witness))
+    );
+
+    // Witness can't be stored without the contents.
+    // Note that the failed query aborts the test transaction, so it must be the last one.
+    let empty_witness = sqlx::query("INSERT INTO block_witness (block) VALUES ($1)")
+        .bind(3i64)
+        .execute(storage.conn())
+        .await;
+    assert!(empty_witness.is_err());
+
+    Ok(())
+}
+
 /// Checks that `unstarted_jobs_count` method of schema returns the amount
 /// of blocks for which proof is not generating (or generated) yet.
 #[db_test]
@@ -175,7 +593,13 @@ async fn unstarted_prover_jobs_count(mut storage: StorageProcessor<'_>) -> Query
 
     // Create a prover run.
     ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(1), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(1),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
 
     // Now, as the job started, the number of not started jobs must be 2.
@@ -195,7 +619,13 @@ async fn unstarted_prover_jobs_count(mut storage: StorageProcessor<'_>) -> Query
 
     // Create next run & repeat checks.
     ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(2), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(2),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
 
     let blocks_count = ProverSchema(&mut storage).unstarted_jobs_count().await?;
@@ -210,7 +640,13 @@ async fn unstarted_prover_jobs_count(mut storage: StorageProcessor<'_>) -> Query
 
     // And finally store the proof for the third block.
     ProverSchema(&mut storage)
-        .prover_run_for_next_commit(prover_name, Duration::from_secs(3), block_size)
+        .prover_run_for_next_commit(
+            prover_name,
+            Duration::from_secs(3),
+            block_size,
+            ProofPriority::Normal,
+            0,
+        )
         .await?;
 
     let blocks_count = ProverSchema(&mut storage).unstarted_jobs_count().await?;
@@ -3,7 +3,8 @@
 // Local imports
 use crate::tests::db_test;
 use crate::{tokens::TokensSchema, QueryResult, StorageProcessor};
-use models::node::{Token, TokenLike};
+use models::node::{Token, TokenFeeSettings, TokenLike};
+use num::rational::Ratio;
 
 /// Verifies the token save & load mechanism.
 #[db_test]
@@ -79,3 +80,71 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Verifies the token fee settings save & load mechanism.
+#[db_test]
+async fn tokens_fee_settings(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Only Ethereum main token is accepted for fees by default.
+    let settings = TokensSchema(&mut storage)
+        .load_tokens_fee_settings()
+        .await?;
+    assert_eq!(settings.len(), 1);
+    assert_eq!(settings[&0], TokenFeeSettings::risk_free());
+
+    let token = Token {
+        id: 1,
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    TokensSchema(&mut storage)
+        .store_token(token.clone())
+        .await?;
+
+    // New tokens are not accepted for fees by default.
+    let settings = TokensSchema(&mut storage)
+        .load_tokens_fee_settings()
+        .await?;
+    assert_eq!(
+        settings[&1],
+        TokenFeeSettings {
+            fee_allowed: false,
+            ..TokenFeeSettings::risk_free()
+        }
+    );
+
+    let token_settings = TokenFeeSettings {
+        risk_factor: Ratio::new(3u32.into(), 2u32.into()),
+        fee_allowed: true,
+    };
+    TokensSchema(&mut storage)
+        .store_token_fee_settings(1, token_settings.clone())
+        .await?;
+    let settings = TokensSchema(&mut storage)
+        .load_tokens_fee_settings()
+        .await?;
+    assert_eq!(settings[&1], token_settings);
+
+    // Settings are replaced on update.
+    let token_settings = TokenFeeSettings {
+        fee_allowed: false,
+        ..token_settings
+    };
+    TokensSchema(&mut storage)
+        .store_token_fee_settings(1, token_settings.clone())
+        .await?;
+    let settings = TokensSchema(&mut storage)
+        .load_tokens_fee_settings()
+        .await?;
+    assert_eq!(settings.len(), 2);
+    assert_eq!(settings[&1], token_settings);
+
+    // Settings are kept when the token is updated.
+    TokensSchema(&mut storage).store_token(token).await?;
+    let settings = TokensSchema(&mut storage)
+        .load_tokens_fee_settings()
+        .await?;
+    assert_eq!(settings[&1], token_settings);
+
+    Ok(())
+}
@@ -2,9 +2,9 @@
 use std::collections::HashMap;
 // External imports
 // Workspace imports
-use models::node::{Token, TokenId, TokenLike, TokenPrice};
+use models::node::{Token, TokenFeeSettings, TokenId, TokenLike, TokenPrice};
 // Local imports
-use self::records::{DbTickerPrice, DbToken};
+use self::records::{DbTickerPrice, DbToken, DbTokenFeeSettings};
 use crate::tokens::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
 use models::primitives::ratio_to_big_decimal;
@@ -14,6 +14,8 @@ mod utils;
 
 /// Precision of the USD price per token
 const STORED_USD_PRICE_PRECISION: usize = 6;
+/// Precision of the token risk factor
+const STORED_RISK_FACTOR_PRECISION: usize = 6;
 
 /// Tokens schema handles the `tokens` table, providing methods to
 /// get and store new tokens.
@@ -22,7 +24,11 @@ pub struct TokensSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Persists the token in the database.
+    /// New tokens get the default fee settings: they're not accepted for paying fees until
+    /// the settings are updated via `store_token_fee_settings`. Settings of the already
+    /// stored tokens are kept.
     pub async fn store_token(&mut self, token: Token) -> QueryResult<()> {
+        let mut transaction = self.0.start_transaction().await?;
         sqlx::query!(
             r#"
             INSERT INTO tokens ( id, address, symbol, decimals )
@@ -36,8 +42,20 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             token.symbol,
             i16::from(token.decimals),
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
         .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO token_fee_settings ( token_id, risk_factor, fee_allowed )
+            VALUES ( $1, 1, false )
+            ON CONFLICT (token_id)
+            DO NOTHING
+            "#,
+            i32::from(token.id),
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
 
         Ok(())
     }
@@ -168,4 +186,51 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
 
         Ok(())
     }
+
+    /// Loads the fee settings of all the tokens that have them.
+    /// Tokens without settings (or with `fee_allowed` unset) are not accepted for paying fees.
+    pub async fn load_tokens_fee_settings(
+        &mut self,
+    ) -> QueryResult<HashMap<TokenId, TokenFeeSettings>> {
+        let settings = sqlx::query_as!(
+            DbTokenFeeSettings,
+            r#"
+            SELECT * FROM token_fee_settings
+            ORDER BY token_id ASC
+            "#,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        Ok(settings
+            .into_iter()
+            .map(|s| (s.token_id as TokenId, s.into()))
+            .collect())
+    }
+
+    /// Persists the fee settings of the token, replacing the existing ones.
+    pub async fn store_token_fee_settings(
+        &mut self,
+        token_id: TokenId,
+        settings: TokenFeeSettings,
+    ) -> QueryResult<()> {
+        let risk_factor_rounded =
+            ratio_to_big_decimal(&settings.risk_factor, STORED_RISK_FACTOR_PRECISION);
+        sqlx::query!(
+            r#"
+            INSERT INTO token_fee_settings ( token_id, risk_factor, fee_allowed )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET risk_factor = $2, fee_allowed = $3
+            "#,
+            i32::from(token_id),
+            risk_factor_rounded,
+            settings.fee_allowed
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        Ok(())
+    }
 }
@@ -6,7 +6,7 @@ use sqlx::FromRow;
 use crate::tokens::utils::{address_to_stored_string, stored_str_address_to_address};
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
-use models::node::tokens::TokenPrice;
+use models::node::tokens::{TokenFeeSettings, TokenPrice};
 use models::node::{Token, TokenId};
 use models::primitives::big_decimal_to_ratio;
 
@@ -55,3 +55,20 @@ impl Into<TokenPrice> for DbTickerPrice {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenFeeSettings {
+    pub token_id: i32,
+    pub risk_factor: BigDecimal,
+    pub fee_allowed: bool,
+}
+
+impl Into<TokenFeeSettings> for DbTokenFeeSettings {
+    fn into(self) -> TokenFeeSettings {
+        TokenFeeSettings {
+            risk_factor: big_decimal_to_ratio(&self.risk_factor)
+                .expect("Risk factor could not be negative"),
+            fee_allowed: self.fee_allowed,
+        }
+    }
+}
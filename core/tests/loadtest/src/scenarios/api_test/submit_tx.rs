@@ -208,6 +208,7 @@ impl<'a> SubmitTxTester<'a> {
             max_transfer_amount,
             transfer_fee,
             main_account.zk_acc.nonce(),
+            Default::default(),
             None,
         );
         tx.signature = TxSignature::sign_musig(&main_account.zk_acc.private_key, &tx.get_bytes());
@@ -252,6 +253,7 @@ impl<'a> SubmitTxTester<'a> {
             transfer_amount,
             transfer_fee,
             main_account.zk_acc.nonce(),
+            Default::default(),
             None,
         );
         tx.signature = TxSignature::sign_musig(&main_account.zk_acc.private_key, &tx.get_bytes());
@@ -292,6 +294,7 @@ impl<'a> SubmitTxTester<'a> {
             token_amount,
             transfer_fee,
             main_account.zk_acc.nonce(),
+            Default::default(),
             None,
         );
 
@@ -370,6 +373,7 @@ impl<'a> SubmitTxTester<'a> {
             amount,
             fee,
             from.nonce(),
+            Default::default(),
             None,
         );
         tx.signature = TxSignature::sign_musig(&from.private_key, &tx.get_bytes());
@@ -13,8 +13,9 @@ use crate::external_commands::{deploy_test_contracts, get_test_accounts};
 use crate::zksync_account::ZksyncAccount;
 use crypto_exports::rand::{Rng, SeedableRng, XorShiftRng};
 use models::node::{
-    pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount, ChangePubKeyOp,
-    DepositOp, FullExitOp, TransferOp, TransferToNewOp, WithdrawOp,
+    pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount, Address,
+    ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, SwapOp, TransferOp, TransferToNewOp,
+    WithdrawOp,
 };
 use models::params::{
     AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, FEE_EXPONENT_BIT_WIDTH,
@@ -248,6 +249,14 @@ async fn gas_price_test() {
         base_cost.base_commit_cost, base_cost.base_verify_cost, base_cost.base_withdraw_cost
     );
 
+    commit_cost_of_multiple_empty_blocks(&mut test_setup, 5)
+        .await
+        .report(
+            &base_cost,
+            "additional block in multiple blocks call",
+            false,
+        );
+
     commit_cost_of_deposits(&mut test_setup, 100, Token(0), rng)
         .await
         .report(&base_cost, "deposit ETH", true);
@@ -265,6 +274,9 @@ async fn gas_price_test() {
     commit_cost_of_transfers_to_new(&mut test_setup, 500, rng)
         .await
         .report(&base_cost, "transfer to new", false);
+    commit_cost_of_swaps(&mut test_setup, 200, rng)
+        .await
+        .report(&base_cost, "swap", false);
     commit_cost_of_full_exits(&mut test_setup, 100, Token(0))
         .await
         .report(&base_cost, "full exit ETH", true);
@@ -279,6 +291,12 @@ async fn gas_price_test() {
     commit_cost_of_withdrawals(&mut test_setup, withdrawals_amount, Token(1), rng)
         .await
         .report(&base_cost, "withdrawals ERC20", false);
+    commit_cost_of_forced_exits(&mut test_setup, withdrawals_amount, Token(0), rng)
+        .await
+        .report(&base_cost, "forced exits ETH", false);
+    commit_cost_of_forced_exits(&mut test_setup, withdrawals_amount, Token(1), rng)
+        .await
+        .report(&base_cost, "forced exits ERC20", false);
 
     stop_state_keeper_sender.send(()).expect("sk stop send");
     sk_thread_handle.join().expect("sk thread join");
@@ -464,6 +482,157 @@ async fn commit_cost_of_withdrawals(
     CostsSample::new(n_withdrawals, U256::from(0), withdraws_execute_result)
 }
 
+async fn commit_cost_of_forced_exits(
+    test_setup: &mut TestSetup,
+    n_forced_exits: usize,
+    token: Token,
+    rng: &mut impl Rng,
+) -> CostsSample {
+    assert!(
+        n_forced_exits <= MAX_WITHDRAWALS_PER_BLOCK as usize,
+        "{} forced exits would not fit in one block, max amount is {}",
+        n_forced_exits,
+        MAX_WITHDRAWALS_PER_BLOCK
+    );
+
+    let mut targets = Vec::new();
+    let mut targets_amount = Vec::new();
+    let mut forced_exits_fee = Vec::new();
+    let mut deposit_amount = BigUint::from(0u32);
+    for _ in 0..n_forced_exits {
+        let amount = gen_packable_amount(rng);
+        let fee = gen_packable_fee(rng);
+        deposit_amount += &amount + &fee;
+        targets.push(Address::from_slice(&rng.gen::<[u8; 20]>()));
+        targets_amount.push(amount);
+        forced_exits_fee.push(fee);
+    }
+
+    // Targets of the forced exits are new accounts without a signing key set.
+    test_setup.start_block();
+    test_setup
+        .deposit(ETHAccountId(1), ZKSyncAccountId(1), token, deposit_amount)
+        .await;
+    test_setup.change_pubkey_with_tx(ZKSyncAccountId(1)).await;
+    for i in 0..n_forced_exits {
+        test_setup
+            .transfer_to_address(
+                ZKSyncAccountId(1),
+                &targets[i],
+                token,
+                targets_amount[i].clone(),
+                BigUint::from(0u32),
+            )
+            .await;
+    }
+    test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+
+    test_setup.start_block();
+    for i in 0..n_forced_exits {
+        test_setup
+            .forced_exit(
+                ZKSyncAccountId(1),
+                &targets[i],
+                token,
+                forced_exits_fee[i].clone(),
+            )
+            .await;
+    }
+    let forced_exits_execute_result = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+    assert_eq!(
+        forced_exits_execute_result.block_size_chunks,
+        n_forced_exits * ForcedExitOp::CHUNKS,
+        "block size mismatch"
+    );
+    CostsSample::new(n_forced_exits, U256::from(0), forced_exits_execute_result)
+}
+
+async fn commit_cost_of_swaps(
+    test_setup: &mut TestSetup,
+    n_swaps: usize,
+    rng: &mut impl Rng,
+) -> CostsSample {
+    let tokens = (Token(0), Token(1));
+
+    let mut swaps_amounts = Vec::new();
+    let mut swaps_fee = Vec::new();
+    let mut deposit_amounts = (BigUint::from(0u32), BigUint::from(0u32));
+    let mut deposit_fee = BigUint::from(0u32);
+    for _ in 0..n_swaps {
+        // Orders can't be filled with zero amount.
+        let amounts = (
+            gen_packable_amount(rng).max(BigUint::from(1u32)),
+            gen_packable_amount(rng).max(BigUint::from(1u32)),
+        );
+        let fee = gen_packable_fee(rng);
+        deposit_amounts.0 += &amounts.0;
+        deposit_amounts.1 += &amounts.1;
+        deposit_fee += &fee;
+        swaps_amounts.push(amounts);
+        swaps_fee.push(fee);
+    }
+
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(1),
+            ZKSyncAccountId(1),
+            tokens.0,
+            deposit_amounts.0,
+        )
+        .await;
+    test_setup
+        .deposit(
+            ETHAccountId(2),
+            ZKSyncAccountId(2),
+            tokens.1,
+            deposit_amounts.1,
+        )
+        .await;
+    test_setup
+        .deposit(ETHAccountId(3), ZKSyncAccountId(3), tokens.0, deposit_fee)
+        .await;
+    for account in 1..=3 {
+        test_setup
+            .change_pubkey_with_tx(ZKSyncAccountId(account))
+            .await;
+    }
+    test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+
+    test_setup.start_block();
+    for i in 0..n_swaps {
+        test_setup
+            .swap(
+                ZKSyncAccountId(3),
+                (ZKSyncAccountId(1), ZKSyncAccountId(2)),
+                tokens,
+                swaps_amounts[i].clone(),
+                swaps_fee[i].clone(),
+                tokens.0,
+            )
+            .await;
+    }
+    let swaps_execute_result = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+    assert_eq!(
+        swaps_execute_result.block_size_chunks,
+        n_swaps * SwapOp::CHUNKS,
+        "block size mismatch"
+    );
+    CostsSample::new(n_swaps, U256::from(0), swaps_execute_result)
+}
+
 async fn commit_cost_of_deposits(
     test_setup: &mut TestSetup,
     n_deposits: usize,
@@ -567,6 +736,27 @@ async fn commit_cost_of_empty_block(test_setup: &mut TestSetup) -> BaseCost {
     }
 }
 
+/// Cost of every additional empty block committed and verified within one call
+/// together with other blocks, the first block of the call is paid by the base cost.
+async fn commit_cost_of_multiple_empty_blocks(
+    test_setup: &mut TestSetup,
+    n_blocks: usize,
+) -> CostsSample {
+    assert!(n_blocks > 1, "at least two blocks are needed");
+
+    test_setup.start_block();
+    let blocks_exec_results = test_setup
+        .execute_commit_and_verify_multiple_blocks(n_blocks)
+        .await
+        .expect("Blocks execution failed");
+    assert_eq!(
+        blocks_exec_results.block_size_chunks,
+        n_blocks * MIN_BLOCK_SIZE_CHUNKS,
+        "blocks size mismatch"
+    );
+    CostsSample::new(n_blocks - 1, U256::from(0), blocks_exec_results)
+}
+
 async fn commit_cost_of_change_pubkey(
     test_setup: &mut TestSetup,
     n_change_pubkeys: usize,
@@ -1,5 +1,5 @@
 use crate::external_commands::js_revert_reason;
-use eth_client::ETHClient;
+use eth_client::{ethereum_signer::PrivateKeySigner, ETHClient};
 use ethabi::ParamType;
 use failure::{bail, ensure, format_err};
 use futures::compat::Future01CompatExt;
@@ -10,6 +10,7 @@ use models::prover_utils::EncodedProofPlonk;
 use num::{BigUint, ToPrimitive};
 use std::convert::TryFrom;
 use std::str::FromStr;
+use std::sync::Arc;
 use web3::api::Eth;
 use web3::contract::{Contract, Options};
 use web3::types::{
@@ -70,11 +71,13 @@ impl<T: Transport> EthereumAccount<T> {
         chain_id: u8,
         gas_price_factor: f64,
     ) -> Self {
+        let signer =
+            Arc::new(PrivateKeySigner::new(private_key).expect("Incorrect Ethereum private key"));
         let main_contract_eth_client = ETHClient::new(
             transport,
             zksync_contract(),
             address,
-            private_key,
+            signer,
             contract_address,
             chain_id,
             gas_price_factor,
@@ -312,7 +315,7 @@ impl<T: Transport> EthereumAccount<T> {
             self.main_contract_eth_client.web3.transport().clone(),
             erc20_contract(),
             self.address,
-            self.private_key,
+            Arc::new(PrivateKeySigner::new(self.private_key)?),
             token_contract,
             self.main_contract_eth_client.chain_id,
             self.main_contract_eth_client.gas_price_factor,
@@ -389,6 +392,58 @@ impl<T: Transport> EthereumAccount<T> {
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client.web3).await)
     }
 
+    /// Commits several consecutive blocks within one `commitMultipleBlocks` call.
+    pub async fn commit_multiple_blocks(
+        &self,
+        blocks: &[Block],
+    ) -> Result<ETHExecResult, failure::Error> {
+        ensure!(!blocks.is_empty(), "At least one block should be committed");
+
+        let mut fee_accounts = Vec::with_capacity(blocks.len());
+        let mut roots = Vec::with_capacity(blocks.len());
+        let mut public_data = Vec::new();
+        let mut public_data_sizes = Vec::with_capacity(blocks.len());
+        let mut eth_witness = Vec::new();
+        let mut eth_witness_sizes = Vec::new();
+        let mut eth_witness_sizes_counts = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let block_public_data = block.get_eth_public_data();
+            let (mut block_witness, block_witness_sizes) = block.get_eth_witness_data();
+
+            fee_accounts.push(u64::from(block.fee_account));
+            roots.push(block.get_eth_encoded_root());
+            public_data_sizes.push(block_public_data.len() as u64);
+            public_data.extend(block_public_data);
+            eth_witness.append(&mut block_witness);
+            eth_witness_sizes_counts.push(block_witness_sizes.len() as u64);
+            eth_witness_sizes.extend(block_witness_sizes);
+        }
+
+        let signed_tx = self
+            .main_contract_eth_client
+            .sign_call_tx(
+                "commitMultipleBlocks",
+                (
+                    u64::from(blocks[0].block_number),
+                    fee_accounts,
+                    roots,
+                    public_data,
+                    public_data_sizes,
+                    eth_witness,
+                    eth_witness_sizes,
+                    eth_witness_sizes_counts,
+                ),
+                Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
+            )
+            .await
+            .map_err(|e| format_err!("Commit multiple blocks send err: {}", e))?;
+
+        let eth = self.main_contract_eth_client.web3.eth();
+        let receipt = send_raw_tx_wait_confirmation(eth, signed_tx.raw_tx).await?;
+
+        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client.web3).await)
+    }
+
     // Verifies block using empty proof. (`DUMMY_VERIFIER` should be enabled on the contract).
     pub async fn verify_block(&self, block: &Block) -> Result<ETHExecResult, failure::Error> {
         let signed_tx = self
@@ -409,6 +464,43 @@ impl<T: Transport> EthereumAccount<T> {
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client.web3).await)
     }
 
+    // Verifies several consecutive blocks within one `verifyMultipleBlocks` call using empty proofs.
+    // (`DUMMY_VERIFIER` should be enabled on the contract).
+    pub async fn verify_multiple_blocks(
+        &self,
+        blocks: &[Block],
+    ) -> Result<ETHExecResult, failure::Error> {
+        ensure!(!blocks.is_empty(), "At least one block should be verified");
+
+        let proof_size = 10;
+        let mut withdrawals_data = Vec::new();
+        let mut withdrawals_data_sizes = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let block_withdrawals_data = block.get_withdrawals_data();
+            withdrawals_data_sizes.push(block_withdrawals_data.len() as u64);
+            withdrawals_data.extend(block_withdrawals_data);
+        }
+
+        let signed_tx = self
+            .main_contract_eth_client
+            .sign_call_tx(
+                "verifyMultipleBlocks",
+                (
+                    u64::from(blocks[0].block_number),
+                    vec![U256::default(); proof_size * blocks.len()],
+                    vec![proof_size as u64; blocks.len()],
+                    withdrawals_data,
+                    withdrawals_data_sizes,
+                ),
+                Options::with(|f| f.gas = Some(U256::from(10 * 10u64.pow(6)))),
+            )
+            .await
+            .map_err(|e| format_err!("Verify multiple blocks send err: {}", e))?;
+        let eth = self.main_contract_eth_client.web3.eth();
+        let receipt = send_raw_tx_wait_confirmation(eth, signed_tx.raw_tx).await?;
+        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client.web3).await)
+    }
+
     // Completes pending withdrawals.
     pub async fn complete_withdrawals(&self) -> Result<ETHExecResult, failure::Error> {
         let max_withdrawals_to_complete: u64 = 999;
@@ -10,9 +10,9 @@ use futures::{
 };
 use models::config_options::ConfigurationOptions;
 use models::node::{
-    mempool::SignedTxVariant, tx::SignedFranklinTx, Account, AccountId, AccountMap, Address,
-    DepositOp, FranklinTx, FullExitOp, Nonce, PriorityOp, TokenId, TransferOp, TransferToNewOp,
-    WithdrawOp,
+    block::Block, mempool::SignedTxVariant, tx::SignedFranklinTx, Account, AccountId, AccountMap,
+    Address, DepositOp, FranklinTx, FullExitOp, Nonce, PriorityOp, TokenId, TransferOp,
+    TransferToNewOp, WithdrawOp,
 };
 use models::{BlockCommitRequest, CommitRequest};
 use num::BigUint;
@@ -166,17 +166,42 @@ impl<T: Transport> AccountSet<T> {
         increment_nonce: bool,
         rng: &mut impl Rng,
     ) -> FranklinTx {
-        let from = &self.zksync_accounts[from.0];
-
         let to_address = Address::from_slice(&rng.gen::<[u8; 20]>());
 
+        self.transfer_to_address(
+            from,
+            &to_address,
+            token_id,
+            amount,
+            fee,
+            nonce,
+            increment_nonce,
+        )
+    }
+
+    /// Create signed transfer from zksync account to arbitrary address
+    /// `nonce` optional nonce override
+    /// `increment_nonce` - flag for `from` account nonce increment
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_to_address(
+        &self,
+        from: ZKSyncAccountId,
+        to_address: &Address,
+        token_id: Token,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> FranklinTx {
+        let from = &self.zksync_accounts[from.0];
+
         FranklinTx::Transfer(Box::new(
             from.sign_transfer(
                 token_id.0,
                 "",
                 amount,
                 fee,
-                &to_address,
+                to_address,
                 nonce,
                 increment_nonce,
             )
@@ -246,6 +271,73 @@ impl<T: Transport> AccountSet<T> {
         ))
     }
 
+    /// Create forced exit of the target account initiated by zksync account
+    /// `nonce` optional nonce override
+    /// `increment_nonce` - flag for `initiator` account nonce increment
+    pub fn forced_exit(
+        &self,
+        initiator: ZKSyncAccountId,
+        target: &Address,
+        token_id: Token,
+        fee: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> FranklinTx {
+        let initiator = &self.zksync_accounts[initiator.0];
+
+        FranklinTx::ForcedExit(Box::new(initiator.sign_forced_exit(
+            token_id.0,
+            fee,
+            target,
+            nonce,
+            increment_nonce,
+        )))
+    }
+
+    /// Create swap of the orders of two zksync accounts submitted by another zksync account,
+    /// the first account sells `amounts.0` of `tokens.0` for `amounts.1` of `tokens.1`
+    /// `nonce` optional nonce override
+    /// `increment_nonce` - flag for `submitter` account nonce increment
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        &self,
+        submitter: ZKSyncAccountId,
+        accounts: (ZKSyncAccountId, ZKSyncAccountId),
+        tokens: (Token, Token),
+        amounts: (BigUint, BigUint),
+        fee: BigUint,
+        fee_token: Token,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> FranklinTx {
+        let submitter = &self.zksync_accounts[submitter.0];
+        let first_order = self.zksync_accounts[(accounts.0).0].sign_order(
+            (tokens.0).0,
+            (tokens.1).0,
+            (amounts.0.clone(), amounts.1.clone()),
+            amounts.0.clone(),
+            None,
+            true,
+        );
+        let second_order = self.zksync_accounts[(accounts.1).0].sign_order(
+            (tokens.1).0,
+            (tokens.0).0,
+            (amounts.1.clone(), amounts.0.clone()),
+            amounts.1.clone(),
+            None,
+            true,
+        );
+
+        FranklinTx::Swap(Box::new(submitter.sign_swap(
+            (first_order, second_order),
+            amounts,
+            fee,
+            fee_token.0,
+            nonce,
+            increment_nonce,
+        )))
+    }
+
     /// Create full exit from zksync account to eth account
     /// `nonce` optional nonce override
     /// `increment_nonce` - flag for `from` account nonce increment
@@ -1033,6 +1125,40 @@ impl TestSetup {
         self.execute_tx(transfer).await;
     }
 
+    /// Transfers funds to the given address, which is expected to not be tracked
+    /// by the test setup (e.g. to create an account without a signing key set).
+    pub async fn transfer_to_address(
+        &mut self,
+        from: ZKSyncAccountId,
+        to_address: &Address,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+    ) {
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(from, token.0)
+            .await;
+        zksync0_old -= &amount;
+        zksync0_old -= &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((from, token.0), zksync0_old);
+
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+            .await;
+        zksync0_old += &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
+
+        let transfer = self
+            .accounts
+            .transfer_to_address(from, to_address, token, amount, fee, None, true);
+
+        self.execute_tx(transfer).await;
+    }
+
     fn increase_block_withdraws_amount(&mut self) {
         self.expected_changes_for_current_block.withdraw_ops += 1;
 
@@ -1121,6 +1247,95 @@ impl TestSetup {
         self.execute_tx(withdraw).await;
     }
 
+    /// Withdraws the whole balance of the target account, which is expected to not be tracked
+    /// by the test setup (see `transfer_to_address`), fee is paid by the initiator.
+    pub async fn forced_exit(
+        &mut self,
+        initiator: ZKSyncAccountId,
+        target: &Address,
+        token: Token,
+        fee: BigUint,
+    ) {
+        self.increase_block_withdraws_amount();
+
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(initiator, token.0)
+            .await;
+        zksync0_old -= &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((initiator, token.0), zksync0_old);
+
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+            .await;
+        zksync0_old += &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
+
+        let forced_exit = self
+            .accounts
+            .forced_exit(initiator, target, token, fee, None, true);
+
+        self.execute_tx(forced_exit).await;
+    }
+
+    /// Swaps tokens of two accounts, fee is paid by the submitter.
+    /// Submitter must not be one of the swapping accounts.
+    pub async fn swap(
+        &mut self,
+        submitter: ZKSyncAccountId,
+        accounts: (ZKSyncAccountId, ZKSyncAccountId),
+        tokens: (Token, Token),
+        amounts: (BigUint, BigUint),
+        fee: BigUint,
+        fee_token: Token,
+    ) {
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(submitter, fee_token.0)
+            .await;
+        zksync0_old -= &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((submitter, fee_token.0), zksync0_old);
+
+        for &(account, sell, buy) in &[
+            (accounts.0, (tokens.0, &amounts.0), (tokens.1, &amounts.1)),
+            (accounts.1, (tokens.1, &amounts.1), (tokens.0, &amounts.0)),
+        ] {
+            let mut zksync0_old = self
+                .get_expected_zksync_account_balance(account, (sell.0).0)
+                .await;
+            zksync0_old -= sell.1;
+            self.expected_changes_for_current_block
+                .sync_accounts_state
+                .insert((account, (sell.0).0), zksync0_old);
+
+            let mut zksync0_old = self
+                .get_expected_zksync_account_balance(account, (buy.0).0)
+                .await;
+            zksync0_old += buy.1;
+            self.expected_changes_for_current_block
+                .sync_accounts_state
+                .insert((account, (buy.0).0), zksync0_old);
+        }
+
+        let mut zksync0_old = self
+            .get_expected_zksync_account_balance(self.accounts.fee_account_id, fee_token.0)
+            .await;
+        zksync0_old += &fee;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((self.accounts.fee_account_id, fee_token.0), zksync0_old);
+
+        let swap = self.accounts.swap(
+            submitter, accounts, tokens, amounts, fee, fee_token, None, true,
+        );
+
+        self.execute_tx(swap).await;
+    }
+
     /// Waits for `CommitRequest::Block` to appear on proposed blocks receiver, ignoring
     /// the pending blocks.
     async fn await_for_block_commit_request(&mut self) -> BlockCommitRequest {
@@ -1208,6 +1423,66 @@ impl TestSetup {
             .expect_success();
         let block_chunks = new_block.block.block_chunks_size;
 
+        self.check_expected_changes(std::slice::from_ref(&new_block.block))
+            .await?;
+
+        Ok(BlockExecutionResult::new(
+            commit_result,
+            verify_result,
+            withdrawals_result,
+            block_chunks,
+        ))
+    }
+
+    /// Seals `n_blocks` blocks (all of them except the first one are empty), then commits
+    /// and verifies them within one `commitMultipleBlocks` and one `verifyMultipleBlocks` call.
+    pub async fn execute_commit_and_verify_multiple_blocks(
+        &mut self,
+        n_blocks: usize,
+    ) -> Result<BlockExecutionResult, failure::Error> {
+        let mut blocks = Vec::with_capacity(n_blocks);
+        for _ in 0..n_blocks {
+            self.state_keeper_request_sender
+                .clone()
+                .send(StateKeeperRequest::SealBlock)
+                .await
+                .expect("sk receiver dropped");
+
+            blocks.push(self.await_for_block_commit_request().await.block);
+        }
+
+        let commit_result = self
+            .commit_account
+            .commit_multiple_blocks(&blocks)
+            .await
+            .expect("blocks commit send tx")
+            .expect_success();
+        let verify_result = self
+            .commit_account
+            .verify_multiple_blocks(&blocks)
+            .await
+            .expect("blocks verify send tx")
+            .expect_success();
+        let withdrawals_result = self
+            .commit_account
+            .complete_withdrawals()
+            .await
+            .expect("complete withdrawal send tx")
+            .expect_success();
+        let block_chunks = blocks.iter().map(|block| block.block_chunks_size).sum();
+
+        self.check_expected_changes(&blocks).await?;
+
+        Ok(BlockExecutionResult::new(
+            commit_result,
+            verify_result,
+            withdrawals_result,
+            block_chunks,
+        ))
+    }
+
+    /// Compares the expected balances changes with the actual state after the blocks execution.
+    async fn check_expected_changes(&mut self, blocks: &[Block]) -> Result<(), failure::Error> {
         let mut block_checks_failed = false;
         for ((eth_account, token), expeted_balance) in
             &self.expected_changes_for_current_block.eth_accounts_state
@@ -1238,10 +1513,12 @@ impl TestSetup {
         }
 
         if block_checks_failed {
-            println!(
-                "Failed block exec_operations: {:#?}",
-                new_block.block.block_transactions
-            );
+            for block in blocks {
+                println!(
+                    "Failed block exec_operations: {:#?}",
+                    block.block_transactions
+                );
+            }
             bail!("Block checks failed")
         }
 
@@ -1250,12 +1527,7 @@ impl TestSetup {
                 .set_account_id(self.get_zksync_account_id(ZKSyncAccountId(zk_id)).await);
         }
 
-        Ok(BlockExecutionResult::new(
-            commit_result,
-            verify_result,
-            withdrawals_result,
-            block_chunks,
-        ))
+        Ok(())
     }
 
     pub async fn get_zksync_account_committed_state(
@@ -7,8 +7,8 @@ use web3::types::H256;
 use crypto_exports::rand::{thread_rng, Rng};
 use models::node::tx::{ChangePubKey, PackedEthSignature, TxSignature};
 use models::node::{
-    priv_key_from_fs, AccountId, Address, Close, Nonce, PrivateKey, PubKeyHash, TokenId, Transfer,
-    Withdraw,
+    priv_key_from_fs, AccountId, Address, Close, ForcedExit, Nonce, Order, PrivateKey, PubKeyHash,
+    Swap, TokenId, Transfer, Withdraw,
 };
 
 /// Structure used to sign ZKSync transactions, keeps tracks of its nonce internally
@@ -122,6 +122,7 @@ impl ZksyncAccount {
             amount,
             fee,
             nonce.unwrap_or_else(|| *stored_nonce),
+            Default::default(),
             &self.private_key,
         )
         .expect("Failed to sign transfer");
@@ -163,6 +164,7 @@ impl ZksyncAccount {
             amount,
             fee,
             nonce.unwrap_or_else(|| *stored_nonce),
+            Default::default(),
             &self.private_key,
         )
         .expect("Failed to sign withdraw");
@@ -181,6 +183,101 @@ impl ZksyncAccount {
         (withdraw, eth_signature)
     }
 
+    pub fn sign_forced_exit(
+        &self,
+        token_id: TokenId,
+        fee: BigUint,
+        target: &Address,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> ForcedExit {
+        let mut stored_nonce = self.nonce.lock().unwrap();
+        let forced_exit = ForcedExit::new_signed(
+            self.account_id
+                .lock()
+                .unwrap()
+                .expect("can't sign tx withoud account id"),
+            *target,
+            token_id,
+            fee,
+            nonce.unwrap_or_else(|| *stored_nonce),
+            &self.private_key,
+        )
+        .expect("Failed to sign forced exit");
+
+        if increment_nonce {
+            *stored_nonce += 1;
+        }
+
+        forced_exit
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign_order(
+        &self,
+        token_sell: TokenId,
+        token_buy: TokenId,
+        ratio: (BigUint, BigUint),
+        amount: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> Order {
+        let mut stored_nonce = self.nonce.lock().unwrap();
+        let order = Order::new_signed(
+            self.account_id
+                .lock()
+                .unwrap()
+                .expect("can't sign tx withoud account id"),
+            self.address,
+            nonce.unwrap_or_else(|| *stored_nonce),
+            token_sell,
+            token_buy,
+            ratio,
+            amount,
+            Default::default(),
+            &self.private_key,
+        )
+        .expect("Failed to sign order");
+
+        if increment_nonce {
+            *stored_nonce += 1;
+        }
+
+        order
+    }
+
+    pub fn sign_swap(
+        &self,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee: BigUint,
+        fee_token: TokenId,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> Swap {
+        let mut stored_nonce = self.nonce.lock().unwrap();
+        let swap = Swap::new_signed(
+            self.account_id
+                .lock()
+                .unwrap()
+                .expect("can't sign tx withoud account id"),
+            self.address,
+            nonce.unwrap_or_else(|| *stored_nonce),
+            orders,
+            amounts,
+            fee,
+            fee_token,
+            &self.private_key,
+        )
+        .expect("Failed to sign swap");
+
+        if increment_nonce {
+            *stored_nonce += 1;
+        }
+
+        swap
+    }
+
     pub fn sign_close(&self, nonce: Option<Nonce>, increment_nonce: bool) -> Close {
         let mut stored_nonce = self.nonce.lock().unwrap();
         let mut close = Close {
@@ -212,9 +309,13 @@ impl ZksyncAccount {
         let eth_signature = if auth_onchain {
             None
         } else {
-            let sign_bytes =
-                ChangePubKey::get_eth_signed_data(account_id, nonce, &self.pubkey_hash)
-                    .expect("Failed to construct change pubkey signed message.");
+            let sign_bytes = ChangePubKey::get_eth_signed_data(
+                account_id,
+                nonce,
+                &self.pubkey_hash,
+                &Default::default(),
+            )
+            .expect("Failed to construct change pubkey signed message.");
             let eth_signature = PackedEthSignature::sign(&self.eth_private_key, &sign_bytes)
                 .expect("Signature should succeed");
             Some(eth_signature)
@@ -224,6 +325,7 @@ impl ZksyncAccount {
             account: self.address,
             new_pk_hash: self.pubkey_hash.clone(),
             nonce,
+            time_range: Default::default(),
             eth_signature,
         };
 
@@ -45,9 +45,10 @@ pub struct DeployTestOpts {
 pub struct GovernanceAddOpts {
     #[structopt(name = "address", short = "a", long = "address")]
     pub address: String,
-    // private key
+    /// Private key of the governor account.
+    /// If not set, the operator Ethereum signer from the environment is used.
     #[structopt(name = "key", short = "k", long = "key")]
-    pub key: String,
+    pub key: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -2,6 +2,9 @@ mod cli;
 mod token;
 mod utils;
 
+// Built-in deps
+use std::sync::Arc;
+
 // External uses
 use anyhow::{Context, Result};
 use structopt::StructOpt;
@@ -10,11 +13,14 @@ use web3::{
     transports::Http,
 };
 
+// Workspace uses
+use eth_client::{ethereum_signer::PrivateKeySigner, signer_from_source, EthereumSigner};
+
 // Local uses
 use cli::App;
 use models::config_options::{AdminServerOptions, ConfigurationOptions};
 use token::Token;
-use utils::{get_matches_from_lines, run_external_command, str_to_address};
+use utils::{run_external_command, str_to_address};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -41,9 +47,24 @@ async fn main() -> Result<()> {
         }
         App::GovernanceAdd(cmd) => {
             let addr = str_to_address(&cmd.address)?;
-            let key = cmd.key.parse().context("Error parse private key value")?;
+            let signer = match cmd.key {
+                Some(key) => {
+                    let key = key.parse().context("Error parse private key value")?;
+                    let signer = PrivateKeySigner::new(key)
+                        .map_err(|e| anyhow::anyhow!("Incorrect private key: {}", e))?;
+                    Arc::new(signer) as Arc<dyn EthereumSigner>
+                }
+                None => {
+                    let source = config_opts
+                        .operator_eth_signer
+                        .as_ref()
+                        .context("Neither private key nor operator Ethereum signer is set")?;
+                    signer_from_source(source, config_opts.operator_commit_eth_addr)
+                        .map_err(|e| anyhow::anyhow!("Error create Ethereum signer: {}", e))?
+                }
+            };
 
-            Token::add_to_governance(addr, key).await?;
+            Token::add_to_governance(addr, signer, &config_opts).await?;
         }
         App::ServerAdd(cmd) => {
             let addr = str_to_address(&cmd.address)?;
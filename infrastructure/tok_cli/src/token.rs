@@ -1,9 +1,9 @@
-use crate::get_matches_from_lines;
 use crate::run_external_command;
 use crate::utils::encode_auth_token;
 
 // Built-in deps
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // External uses
 use anyhow::Result;
@@ -15,12 +15,22 @@ use web3::{
     contract::Options,
     futures::Future,
     transports::Http,
-    types::{Address, H256},
+    types::{Address, U256, U64},
 };
 
 // Workspace uses
+use eth_client::{ETHClient, EthereumSigner};
+use models::abi::governance_contract;
+use models::config_options::ConfigurationOptions;
 use models::node::{tokens, TokenId};
 
+/// Gas limit for the `addToken` governance contract call.
+const ADD_TOKEN_GAS_LIMIT: u64 = 500_000;
+/// Interval between the checks of the transaction receipt.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum amount of receipt checks before giving up.
+const RECEIPT_POLL_ATTEMPTS: usize = 300;
+
 /// Token that contains information to add to the server
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct AddTokenRequest {
@@ -96,18 +106,62 @@ impl Token {
         serde_json::from_str(&stdout).map_err(|_e| anyhow::anyhow!("Error decode token from json"))
     }
 
-    pub async fn add_to_governance(address: Address, key: H256) -> Result<()> {
-        let stdout = run_external_command(
-            "governance-add-erc20.sh",
-            &[&address.to_string()["0x".len()..], &key.to_string()],
-        )?;
-
-        let line = get_matches_from_lines(&stdout, "STATUS=")?;
-        if &line["STATUS=".len()..] == "SUCCESS" {
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Error add token to governance"))
+    /// Sends the `addToken` transaction to the governance contract on behalf of the signer's account
+    /// and waits until it is mined.
+    pub async fn add_to_governance(
+        address: Address,
+        signer: Arc<dyn EthereumSigner>,
+        config: &ConfigurationOptions,
+    ) -> Result<()> {
+        let (_event_loop, transport) = Http::new(&config.web3_url)?;
+        let sender = signer.address();
+
+        let eth_client = ETHClient::new(
+            transport,
+            governance_contract(),
+            sender,
+            signer,
+            config.governance_eth_addr,
+            config.chain_id,
+            config.gas_price_factor,
+        );
+
+        let options = Options::with(|opt| opt.gas = Some(U256::from(ADD_TOKEN_GAS_LIMIT)));
+        let signed_tx = eth_client
+            .sign_call_tx("addToken", address, options)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error sign governance transaction: {}", e))?;
+        let tx_hash = eth_client
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error send governance transaction: {}", e))?;
+
+        for _ in 0..RECEIPT_POLL_ATTEMPTS {
+            let receipt = eth_client
+                .web3
+                .eth()
+                .transaction_receipt(tx_hash)
+                .wait()
+                .map_err(|e| anyhow::anyhow!("Error get transaction receipt: {}", e))?;
+
+            if let Some(receipt) = receipt {
+                return if receipt.status == Some(U64::from(1)) {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Error add token to governance, tx {:#x} failed",
+                        tx_hash
+                    ))
+                };
+            }
+
+            tokio::time::delay_for(RECEIPT_POLL_INTERVAL).await;
         }
+
+        Err(anyhow::anyhow!(
+            "Error add token to governance, tx {:#x} was not mined in time",
+            tx_hash
+        ))
     }
 
     pub async fn add_to_server(
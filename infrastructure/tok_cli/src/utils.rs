@@ -30,20 +30,6 @@ pub fn str_to_address(value: &str) -> Result<Address> {
     Ok(str_addr)
 }
 
-pub fn get_matches_from_lines(stream: &str, pattern: &str) -> Result<String> {
-    let lines = stream.split_whitespace().collect::<Vec<_>>();
-
-    for std_out_line in lines {
-        if std_out_line.starts_with(pattern) {
-            return Ok(std_out_line.to_string());
-        }
-    }
-    Err(anyhow::anyhow!(
-        "error of finding the pattern '{}' in stream",
-        pattern
-    ))
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
     sub: String, // Subject (whom auth token refers to)
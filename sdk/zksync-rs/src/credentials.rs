@@ -1,13 +1,37 @@
-use crate::{error::ClientError, types::Network, utils::private_key_from_seed};
-use models::node::{tx::PackedEthSignature, PrivateKey};
+use std::sync::Arc;
+
+use crate::{
+    error::ClientError, eth_signer::EthereumSigner, types::Network, utils::private_key_from_seed,
+};
+use models::node::{
+    tx::{PackedEthSignature, TxEthSignature},
+    PrivateKey,
+};
 use web3::types::{Address, H256};
 
 pub struct WalletCredentials {
     pub(crate) eth_private_key: Option<H256>,
+    /// External signer used to authorize transactions instead of the Ethereum private key.
+    pub(crate) eth_signer: Option<Arc<dyn EthereumSigner>>,
     pub(crate) eth_address: Address,
     pub(crate) zksync_private_key: PrivateKey,
 }
 
+/// Returns the pre-defined message signed by the Ethereum account
+/// to generate the zkSync private key.
+fn seed_message(network: Network) -> Vec<u8> {
+    const MESSAGE: &str = "Access zkSync account.\n\nOnly sign this message for a trusted client!";
+
+    // Add chain_id to the message to prevent replay attacks between networks
+    // This is added for testnets only
+    if let Network::Mainnet = network {
+        MESSAGE.into()
+    } else {
+        format!("{}\nChainID: {}.", MESSAGE, network.chain_id())
+    }
+    .into_bytes()
+}
+
 impl std::fmt::Debug for WalletCredentials {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("WalletCredentials")
@@ -30,17 +54,7 @@ impl WalletCredentials {
         network: Network,
     ) -> Result<Self, ClientError> {
         // Pre-defined message to generate seed from.
-        const MESSAGE: &str =
-            "Access zkSync account.\n\nOnly sign this message for a trusted client!";
-
-        // Add chain_id to the message to prevent replay attacks between networks
-        // This is added for testnets only
-        let eth_sign_message = if let Network::Mainnet = network {
-            MESSAGE.into()
-        } else {
-            format!("{}\nChainID: {}.", MESSAGE, network.chain_id())
-        }
-        .into_bytes();
+        let eth_sign_message = seed_message(network);
 
         // Check that private key is correct and corresponds to the provided address.
         let address_from_pk = PackedEthSignature::address_from_private_key(&eth_private_key);
@@ -60,6 +74,7 @@ impl WalletCredentials {
 
         Ok(Self {
             eth_private_key: Some(eth_private_key),
+            eth_signer: None,
             eth_address,
             zksync_private_key: zksync_pk,
         })
@@ -79,6 +94,7 @@ impl WalletCredentials {
 
         Ok(Self {
             eth_private_key: None,
+            eth_signer: None,
             eth_address,
             zksync_private_key: zksync_pk,
         })
@@ -99,7 +115,96 @@ impl WalletCredentials {
         Self {
             eth_address,
             eth_private_key,
+            eth_signer: None,
+            zksync_private_key: private_key,
+        }
+    }
+
+    /// Creates wallet credentials using the external Ethereum signer (e.g. a custody
+    /// service or a node with the unlocked account). zkSync private key is generated from the
+    /// signature of the pre-defined message, the same way as in `from_eth_pk`.
+    /// Wallet created with such credentials won't be capable of performing on-chain operations,
+    /// such as deposits and full exits.
+    ///
+    /// The same zkSync private key is obtained every time only if the signer produces
+    /// deterministic signatures, as the RFC 6979 ECDSA signers (e.g. `geth`) do.
+    /// Signatures of the smart contract wallets (EIP-1271) are not guaranteed to be
+    /// deterministic, so such signers are refused: use `from_eth_signer_and_pk` for them.
+    ///
+    /// ## Arguments
+    ///
+    /// - `eth_signer`: Signer of the messages on behalf of the Ethereum account.
+    /// - `network`: Network this wallet is used on.
+    pub async fn from_eth_signer(
+        eth_signer: Arc<dyn EthereumSigner>,
+        network: Network,
+    ) -> Result<Self, ClientError> {
+        let signature = eth_signer
+            .sign_message(&seed_message(network))
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        let signature_bytes = match signature {
+            TxEthSignature::EthereumSignature(signature) => signature.serialize_packed(),
+            TxEthSignature::EIP1271Signature(_) => return Err(ClientError::UnsupportedEthSigner),
+        };
+        let zksync_pk = private_key_from_seed(&signature_bytes)?;
+
+        Ok(Self::from_eth_signer_and_pk(eth_signer, zksync_pk))
+    }
+
+    /// Creates wallet credentials using the external Ethereum signer (e.g. a smart contract
+    /// wallet) and the provided zkSync private key.
+    /// Wallet created with such credentials won't be capable of performing on-chain operations,
+    /// such as deposits and full exits.
+    ///
+    /// ## Arguments
+    ///
+    /// - `eth_signer`: Signer of the messages on behalf of the Ethereum account.
+    /// - `private_key`: Private key of a zkSync account.
+    pub fn from_eth_signer_and_pk(
+        eth_signer: Arc<dyn EthereumSigner>,
+        private_key: PrivateKey,
+    ) -> Self {
+        Self {
+            eth_private_key: None,
+            eth_address: eth_signer.address(),
+            eth_signer: Some(eth_signer),
             zksync_private_key: private_key,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_signer::{EIP1271Signer, PrivateKeySigner};
+
+    #[tokio::test]
+    async fn credentials_from_eth_signer() {
+        let eth_private_key = H256::repeat_byte(1);
+        let eth_signer = Arc::new(PrivateKeySigner::new(eth_private_key).unwrap());
+        let eth_address = eth_signer.address();
+
+        // Key is derived the same way as from the Ethereum private key.
+        let credentials =
+            WalletCredentials::from_eth_signer(eth_signer.clone(), Network::Localhost)
+                .await
+                .expect("credentials from the private key signer");
+        let expected =
+            WalletCredentials::from_eth_pk(eth_address, eth_private_key, Network::Localhost)
+                .unwrap();
+        assert_eq!(credentials.eth_address, eth_address);
+        assert_eq!(
+            credentials.zksync_private_key.0,
+            expected.zksync_private_key.0
+        );
+
+        // Smart contract wallet signatures can't be used to derive the key.
+        let wallet_signer = Arc::new(EIP1271Signer::new(Address::repeat_byte(2), eth_signer));
+        let err = WalletCredentials::from_eth_signer(wallet_signer, Network::Localhost)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::UnsupportedEthSigner));
+    }
+}
@@ -1,5 +1,6 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
 use thiserror::Error;
+use web3::types::H256;
 
 #[derive(Debug, Error)]
 pub enum ClientError {
@@ -9,13 +10,21 @@ pub enum ClientError {
     MalformedResponse(String),
     #[error("RPC error: {0:?}")]
     RpcError(RpcFailure),
+    #[error("Server error {code:?}: {message}")]
+    ServerError { code: RpcErrorCode, message: String },
     #[error("Network error: {0}")]
     NetworkError(String),
+    #[error("Provider is not connected to the WebSocket API")]
+    NoWebSocketConnection,
 
     #[error("Provided account credentials are incorrect")]
     IncorrectCredentials,
     #[error("Seed too short, must be at least 32 bytes long")]
     SeedTooShort,
+    #[error(
+        "zkSync private key can't be derived from the signatures of the provided Ethereum signer"
+    )]
+    UnsupportedEthSigner,
     #[error("Token is not supported by zkSync")]
     UnknownToken,
     #[error("Incorrect address")]
@@ -33,15 +42,81 @@ pub enum ClientError {
 
     #[error("Ethereum private key was not provided for this wallet")]
     NoEthereumPrivateKey,
+    #[error("Ethereum transaction {0:?} failed")]
+    EthereumTxFailed(H256),
 
     #[error("Provided value is not packable")]
     NotPackableValue,
 }
 
+/// Error codes returned by the zkSync server for the failed RPC calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorCode {
+    NonceMismatch,
+    IncorrectTx,
+    FeeTooLow,
+    TxExpired,
+    ReplacementFeeTooLow,
+    FeeTokenNotAllowed,
+
+    MissingEthSignature,
+    EIP1271SignatureVerificationFail,
+    IncorrectEthSignature,
+    ChangePkNotAuthorized,
+
+    Other,
+    AccountCloseDisabled,
+    OperationsLimitReached,
+    UnsupportedFastProcessing,
+}
+
+impl RpcErrorCode {
+    /// Converts the numeric error code returned by server.
+    /// Returns `None` if the code is not specific to zkSync (e.g. it's a generic JSON RPC error).
+    pub fn from_code(code: i64) -> Option<Self> {
+        let code = match code {
+            101 => Self::NonceMismatch,
+            103 => Self::IncorrectTx,
+            104 => Self::FeeTooLow,
+            105 => Self::TxExpired,
+            106 => Self::ReplacementFeeTooLow,
+            107 => Self::FeeTokenNotAllowed,
+
+            200 => Self::MissingEthSignature,
+            201 => Self::EIP1271SignatureVerificationFail,
+            202 => Self::IncorrectEthSignature,
+            203 => Self::ChangePkNotAuthorized,
+
+            300 => Self::Other,
+            301 => Self::AccountCloseDisabled,
+            302 => Self::OperationsLimitReached,
+            303 => Self::UnsupportedFastProcessing,
+            _ => return None,
+        };
+
+        Some(code)
+    }
+}
+
+impl ClientError {
+    /// Creates an error from the failed RPC call response, recognizing zkSync-specific errors.
+    pub fn from_rpc_failure(failure: RpcFailure) -> Self {
+        match RpcErrorCode::from_code(failure.error.code.code()) {
+            Some(code) => Self::ServerError {
+                code,
+                message: failure.error.message,
+            },
+            None => Self::RpcError(failure),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum SignerError {
     #[error("Ethereum private key required to perform an operation")]
     MissingEthPrivateKey,
+    #[error("Ethereum signer required to perform an operation")]
+    MissingEthSigner,
     #[error("Signing failed: {0}")]
     SigningFailed(String),
     #[error("Signing key is not set in account")]
@@ -0,0 +1,321 @@
+//! Signers of the messages authorizing zkSync transactions on behalf of the Ethereum account.
+//!
+//! Ethereum account may be controlled by the private key available to the SDK, by the
+//! external service (e.g. a custody solution or a node with the unlocked account), or
+//! it may be a smart contract wallet verifying signatures according to EIP-1271.
+
+// Built-in imports
+use std::{fmt, sync::Arc};
+
+// External uses
+use async_trait::async_trait;
+use futures::compat::Future01CompatExt;
+use web3::{
+    transports::{EventLoopHandle, Http},
+    types::{Address, Bytes, H256},
+    Transport,
+};
+
+// Workspace uses
+use models::node::tx::{EIP1271Signature, PackedEthSignature, TxEthSignature};
+
+// Local uses
+use crate::error::SignerError;
+
+fn signing_failed_error(err: impl ToString) -> SignerError {
+    SignerError::SigningFailed(err.to_string())
+}
+
+/// Signer of the messages on behalf of a single Ethereum account.
+#[async_trait]
+pub trait EthereumSigner: Send + Sync {
+    /// Returns the address of the account the signatures are made for.
+    fn address(&self) -> Address;
+
+    /// Signs the message in the same way as the `personal_sign` JSON-RPC method does
+    /// (i.e. with the "Ethereum Signed Message" prefix).
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError>;
+}
+
+/// Signer holding the private key of the Ethereum account.
+pub struct PrivateKeySigner {
+    private_key: H256,
+    address: Address,
+}
+
+impl fmt::Debug for PrivateKeySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateKeySigner")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl PrivateKeySigner {
+    pub fn new(private_key: H256) -> Result<Self, SignerError> {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .map_err(signing_failed_error)?;
+
+        Ok(Self {
+            private_key,
+            address,
+        })
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for PrivateKeySigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        let signature =
+            PackedEthSignature::sign(&self.private_key, message).map_err(signing_failed_error)?;
+        Ok(TxEthSignature::EthereumSignature(signature))
+    }
+}
+
+/// Signer delegating the signing to the external service via the `personal_sign`
+/// JSON-RPC method.
+pub struct JsonRpcSigner {
+    address: Address,
+    transport: Http,
+    // We have to prevent handle from drop, since it will cause event loop termination.
+    _event_loop: EventLoopHandle,
+}
+
+impl fmt::Debug for JsonRpcSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonRpcSigner")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl JsonRpcSigner {
+    /// Creates a signer for the account with the given address, which must be
+    /// managed by the service available at `url`.
+    pub fn new(url: impl AsRef<str>, address: Address) -> Result<Self, SignerError> {
+        let (_event_loop, transport) = Http::new(url.as_ref()).map_err(signing_failed_error)?;
+
+        Ok(Self {
+            address,
+            transport,
+            _event_loop,
+        })
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for JsonRpcSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        let params = vec![
+            serde_json::to_value(Bytes(message.to_vec())).expect("serialization fail"),
+            serde_json::to_value(self.address).expect("serialization fail"),
+        ];
+
+        let response = self
+            .transport
+            .execute("personal_sign", params)
+            .compat()
+            .await
+            .map_err(signing_failed_error)?;
+        let signature: Bytes = serde_json::from_value(response).map_err(signing_failed_error)?;
+        let signature =
+            PackedEthSignature::deserialize_packed(&signature.0).map_err(signing_failed_error)?;
+
+        Ok(TxEthSignature::EthereumSignature(signature))
+    }
+}
+
+/// Signer for the smart contract wallets supporting EIP-1271.
+///
+/// Messages are signed by the owner of the wallet, and the signature is checked by the
+/// server via the `isValidSignature` method of the wallet contract.
+pub struct EIP1271Signer {
+    wallet_address: Address,
+    owner: Arc<dyn EthereumSigner>,
+}
+
+impl fmt::Debug for EIP1271Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EIP1271Signer")
+            .field("wallet_address", &self.wallet_address)
+            .field("owner", &self.owner.address())
+            .finish()
+    }
+}
+
+impl EIP1271Signer {
+    /// Creates a signer for the wallet contract at `wallet_address`,
+    /// signing the messages with the `owner` signer.
+    pub fn new(wallet_address: Address, owner: Arc<dyn EthereumSigner>) -> Self {
+        Self {
+            wallet_address,
+            owner,
+        }
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for EIP1271Signer {
+    fn address(&self) -> Address {
+        self.wallet_address
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        let signature = match self.owner.sign_message(message).await? {
+            TxEthSignature::EthereumSignature(signature) => signature.serialize_packed().to_vec(),
+            TxEthSignature::EIP1271Signature(signature) => signature.0,
+        };
+
+        Ok(TxEthSignature::EIP1271Signature(EIP1271Signature(
+            signature,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const MESSAGE: &[u8] = b"Transfer 1.0 ETH\nTo: 0x01\nNonce: 0\nFee: 0.1 ETH\nAccount Id: 1";
+
+    fn test_signer(seed: u8) -> PrivateKeySigner {
+        PrivateKeySigner::new(H256::repeat_byte(seed)).expect("correct private key")
+    }
+
+    /// Reads a single HTTP request and returns its body.
+    async fn read_http_body(socket: &mut tokio::net::TcpStream) -> Vec<u8> {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        loop {
+            let read = socket.read(&mut buffer).await.expect("read request");
+            assert_ne!(read, 0, "connection closed before the request was read");
+            request.extend_from_slice(&buffer[..read]);
+
+            let headers_end = match request.windows(4).position(|window| window == b"\r\n\r\n") {
+                Some(position) => position + 4,
+                None => continue,
+            };
+            let headers = String::from_utf8_lossy(&request[..headers_end]).to_lowercase();
+            let content_length: usize = headers
+                .lines()
+                .find(|line| line.starts_with("content-length:"))
+                .map(|line| line["content-length:".len()..].trim().parse().unwrap())
+                .unwrap_or_default();
+
+            if request.len() >= headers_end + content_length {
+                return request[headers_end..headers_end + content_length].to_vec();
+            }
+        }
+    }
+
+    /// Serves a single `personal_sign` request, signing the message with the `signer`.
+    async fn serve_personal_sign(mut listener: TcpListener, signer: PrivateKeySigner) {
+        let (mut socket, _) = listener.accept().await.expect("accept connection");
+        let request: serde_json::Value =
+            serde_json::from_slice(&read_http_body(&mut socket).await).expect("json request");
+
+        assert_eq!(request["method"], "personal_sign");
+        let message: Bytes = serde_json::from_value(request["params"][0].clone()).unwrap();
+        let address: Address = serde_json::from_value(request["params"][1].clone()).unwrap();
+        assert_eq!(address, signer.address());
+
+        let signature = match signer.sign_message(&message.0).await.unwrap() {
+            TxEthSignature::EthereumSignature(signature) => signature.serialize_packed().to_vec(),
+            TxEthSignature::EIP1271Signature(_) => unreachable!(),
+        };
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": Bytes(signature),
+        })
+        .to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket
+            .write_all(response.as_bytes())
+            .await
+            .expect("write response");
+    }
+
+    #[tokio::test]
+    async fn private_key_signer() {
+        let signer = test_signer(1);
+        assert_eq!(
+            signer.address(),
+            PackedEthSignature::address_from_private_key(&H256::repeat_byte(1)).unwrap()
+        );
+
+        let signature = match signer.sign_message(MESSAGE).await.unwrap() {
+            TxEthSignature::EthereumSignature(signature) => signature,
+            signature => panic!("Unexpected signature type: {:?}", signature),
+        };
+        assert_eq!(
+            signature.signature_recover_signer(MESSAGE).unwrap(),
+            signer.address()
+        );
+        // Signatures are deterministic, so the zkSync key derived from them is the same every time.
+        assert_eq!(
+            signer.sign_message(MESSAGE).await.unwrap(),
+            TxEthSignature::EthereumSignature(signature)
+        );
+
+        assert!(PrivateKeySigner::new(H256::zero()).is_err());
+    }
+
+    #[tokio::test]
+    async fn json_rpc_signer() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let account = test_signer(1);
+        let address = account.address();
+        let server = tokio::spawn(serve_personal_sign(listener, account));
+
+        let signer = JsonRpcSigner::new(url, address).unwrap();
+        assert_eq!(signer.address(), address);
+
+        let signature = match signer.sign_message(MESSAGE).await.unwrap() {
+            TxEthSignature::EthereumSignature(signature) => signature,
+            signature => panic!("Unexpected signature type: {:?}", signature),
+        };
+        assert_eq!(
+            signature.signature_recover_signer(MESSAGE).unwrap(),
+            address
+        );
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn eip1271_signer() {
+        let owner = Arc::new(test_signer(1));
+        let wallet_address = Address::repeat_byte(2);
+        let signer = EIP1271Signer::new(wallet_address, owner.clone());
+        assert_eq!(signer.address(), wallet_address);
+
+        // Signature is made by the owner, but is checked against the wallet contract.
+        let owner_signature = match owner.sign_message(MESSAGE).await.unwrap() {
+            TxEthSignature::EthereumSignature(signature) => signature,
+            signature => panic!("Unexpected signature type: {:?}", signature),
+        };
+        assert_eq!(
+            signer.sign_message(MESSAGE).await.unwrap(),
+            TxEthSignature::EIP1271Signature(EIP1271Signature(
+                owner_signature.serialize_packed().to_vec()
+            ))
+        );
+    }
+}
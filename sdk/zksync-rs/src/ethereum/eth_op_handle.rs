@@ -0,0 +1,177 @@
+use std::{convert::TryFrom, fmt, time::Duration};
+
+use futures::compat::Future01CompatExt;
+use models::{abi, node::PriorityOp, ActionType};
+use web3::types::{TransactionReceipt, H256};
+
+use crate::{error::ClientError, ethereum::EthereumProvider, types::EthOpInfo};
+
+/// Handle for the priority operation (such as `Deposit` or `FullExit`) sent to
+/// the zkSync contract, providing an interface to await its execution.
+///
+/// Awaiting for the operation first waits for the Ethereum transaction to be mined,
+/// obtains the operation serial ID from the transaction logs, and then tracks the
+/// operation status in zkSync.
+///
+/// By default, awaiting for operation may run up to forever, and the polling is
+/// performed once a second.
+pub struct EthOpHandle<'a> {
+    eth_tx_hash: H256,
+    ethereum: &'a EthereumProvider,
+    polling_interval: Duration,
+    commit_timeout: Option<Duration>,
+    verify_timeout: Option<Duration>,
+}
+
+impl fmt::Debug for EthOpHandle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EthOpHandle")
+            .field("eth_tx_hash", &self.eth_tx_hash)
+            .field("polling_interval", &self.polling_interval)
+            .field("commit_timeout", &self.commit_timeout)
+            .field("verify_timeout", &self.verify_timeout)
+            .finish()
+    }
+}
+
+impl<'a> EthOpHandle<'a> {
+    pub fn new(eth_tx_hash: H256, ethereum: &'a EthereumProvider) -> Self {
+        Self {
+            eth_tx_hash,
+            ethereum,
+            polling_interval: Duration::from_secs(1), // 1 second.
+            commit_timeout: None,                     // Wait until forever
+            verify_timeout: None,                     // Wait until forever
+        }
+    }
+
+    /// Returns the hash of the Ethereum transaction.
+    pub fn eth_tx_hash(&self) -> H256 {
+        self.eth_tx_hash
+    }
+
+    /// Sets the polling interval. Must be at least 200 milliseconds.
+    pub fn polling_interval(&mut self, polling_interval: Duration) -> Result<(), ClientError> {
+        if polling_interval >= Duration::from_millis(200) {
+            self.polling_interval = polling_interval;
+            Ok(())
+        } else {
+            Err(ClientError::PollingIntervalIsTooSmall)
+        }
+    }
+
+    /// Sets the timeout for commit operation.
+    /// With this value set, `EthOpHandle::wait_for_commit` will return a `ClientError::OperationTimeout`
+    /// error if block will not be committed within provided time range.
+    pub fn commit_timeout(mut self, commit_timeout: Duration) -> Self {
+        self.commit_timeout = Some(commit_timeout);
+        self
+    }
+
+    /// Sets the timeout for verify operation.
+    /// With this value set, `EthOpHandle::wait_for_verify` will return a `ClientError::OperationTimeout`
+    /// error if block will not be verified within provided time range.
+    pub fn verify_timeout(mut self, verify_timeout: Duration) -> Self {
+        self.verify_timeout = Some(verify_timeout);
+        self
+    }
+
+    /// Awaits for the Ethereum transaction to be mined and returns the serial ID
+    /// of the priority operation created by it.
+    pub async fn serial_id(&self) -> Result<u64, ClientError> {
+        let receipt = self.wait_for_receipt().await?;
+        if receipt.status != Some(1u64.into()) {
+            return Err(ClientError::EthereumTxFailed(self.eth_tx_hash));
+        }
+
+        let priority_op_event_topic = abi::zksync_contract()
+            .event("NewPriorityRequest")
+            .expect("main contract abi error")
+            .signature();
+        let contract_address = self.ethereum.contract_address();
+
+        receipt
+            .logs
+            .into_iter()
+            .find(|log| {
+                log.address == contract_address
+                    && log.topics.first() == Some(&priority_op_event_topic)
+            })
+            .ok_or_else(|| {
+                ClientError::MalformedResponse(
+                    "Ethereum transaction did not create a priority operation".into(),
+                )
+            })
+            .and_then(|log| {
+                PriorityOp::try_from(log)
+                    .map_err(|err| ClientError::MalformedResponse(err.to_string()))
+            })
+            .map(|priority_op| priority_op.serial_id)
+    }
+
+    /// Awaits for the operation commit and returns the information about execution.
+    pub async fn wait_for_commit(&self) -> Result<EthOpInfo, ClientError> {
+        self.wait_for(ActionType::COMMIT, self.commit_timeout).await
+    }
+
+    /// Awaits for the operation verification and returns the information about execution.
+    pub async fn wait_for_verify(&self) -> Result<EthOpInfo, ClientError> {
+        self.wait_for(ActionType::VERIFY, self.verify_timeout).await
+    }
+
+    async fn wait_for(
+        &self,
+        action: ActionType,
+        timeout: Option<Duration>,
+    ) -> Result<EthOpInfo, ClientError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.wait_for_action(action))
+                .await
+                .map_err(|_| ClientError::OperationTimeout)?,
+            None => self.wait_for_action(action).await,
+        }
+    }
+
+    async fn wait_for_action(&self, action: ActionType) -> Result<EthOpInfo, ClientError> {
+        let serial_id = self.serial_id().await?;
+
+        let mut timer = tokio::time::interval(self.polling_interval);
+        loop {
+            timer.tick().await;
+
+            let response = self.ethereum.provider.ethop_info(serial_id as u32).await?;
+            if let Some(block) = &response.block {
+                let done = match action {
+                    ActionType::COMMIT => block.committed,
+                    ActionType::VERIFY => block.verified,
+                };
+                if done {
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    async fn wait_for_receipt(&self) -> Result<TransactionReceipt, ClientError> {
+        let mut timer = tokio::time::interval(self.polling_interval);
+        loop {
+            timer.tick().await;
+
+            let receipt = self
+                .ethereum
+                .web3()
+                .eth()
+                .transaction_receipt(self.eth_tx_hash)
+                .compat()
+                .await
+                .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+
+            // Receipt of the pending transaction may be returned without the block number.
+            if let Some(receipt) = receipt {
+                if receipt.block_number.is_some() {
+                    return Ok(receipt);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,13 @@
 //! Utilities for the on-chain operations, such as `Deposit` and `FullExit`.
 
-use eth_client::ETHClient;
+use eth_client::{ethereum_signer::PrivateKeySigner, ETHClient};
 use futures::compat::Future01CompatExt;
 use models::{
     abi,
     node::{AccountId, TokenLike},
 };
 use std::str::FromStr;
+use std::sync::Arc;
 use web3::contract::tokens::Tokenize;
 use web3::contract::{Contract, Options};
 use web3::transports::{EventLoopHandle, Http};
@@ -15,6 +16,10 @@ use web3::Web3;
 
 use crate::{error::ClientError, provider::Provider, tokens_cache::TokensCache, types::Network};
 
+pub use self::eth_op_handle::EthOpHandle;
+
+mod eth_op_handle;
+
 const IERC20_INTERFACE: &str = include_str!("abi/IERC20.json");
 
 impl Network {
@@ -30,10 +35,13 @@ impl Network {
 }
 
 /// `EthereumProvider` gains access to on-chain operations, such as deposits and full exits.
-/// Methods to interact with Ethereum return corresponding Ethereum transaction hash.
+/// Methods to interact with Ethereum return corresponding Ethereum transaction hash,
+/// while methods creating zkSync priority operations return an `EthOpHandle` to await
+/// the operation execution.
 /// In order to monitor transaction execution, an Etherereum node `web3` API is exposed
 /// via `EthereumProvider::web3` method.
 pub struct EthereumProvider {
+    provider: Provider,
     tokens_cache: TokensCache,
     eth_client: ETHClient<Http>,
     erc20_abi: ethabi::Contract,
@@ -62,11 +70,14 @@ impl EthereumProvider {
             &address_response.main_contract
         };
 
+        let signer = PrivateKeySigner::new(eth_private_key)
+            .map_err(|_| ClientError::IncorrectCredentials)?;
+
         let eth_client = ETHClient::new(
             transport,
             abi::zksync_contract(),
             eth_addr,
-            eth_private_key,
+            Arc::new(signer),
             contract_address
                 .parse()
                 .map_err(|err| ClientError::MalformedResponse(format!("{}", err)))?,
@@ -82,6 +93,7 @@ impl EthereumProvider {
         let erc20_abi = ethabi::Contract::load(abi_string.as_bytes()).unwrap();
 
         Ok(Self {
+            provider: provider.clone(),
             eth_client,
             erc20_abi,
             tokens_cache,
@@ -232,12 +244,13 @@ impl EthereumProvider {
 
     /// Performs a deposit in zkSync network.
     /// For ERC20 tokens, a deposit must be approved beforehand via the `EthereumProvider::approve_erc20_token_deposits` method.
+    /// Returns the handle to await the deposit execution in zkSync.
     pub async fn deposit(
         &self,
         token: impl Into<TokenLike>,
         amount: U256,
         sync_address: H160,
-    ) -> Result<H256, ClientError> {
+    ) -> Result<EthOpHandle<'_>, ClientError> {
         let token = token.into();
         let token_info = self
             .tokens_cache
@@ -254,10 +267,10 @@ impl EthereumProvider {
                 .map_err(|_| ClientError::IncorrectCredentials)?
         } else {
             let mut options = Options::default();
-            options.gas = Some(200_000.into());
+            options.gas = Some(300_000.into());
             let params = (token_info.address, amount, sync_address);
             self.eth_client
-                .sign_call_tx("depositETH", params, options)
+                .sign_call_tx("depositERC20", params, options)
                 .await
                 .map_err(|_| ClientError::IncorrectCredentials)?
         };
@@ -268,15 +281,16 @@ impl EthereumProvider {
             .await
             .map_err(|err| ClientError::NetworkError(err.to_string()))?;
 
-        Ok(transaction_hash)
+        Ok(EthOpHandle::new(transaction_hash, self))
     }
 
     /// Performs a full exit for a certain token.
+    /// Returns the handle to await the full exit execution in zkSync.
     pub async fn full_exit(
         &self,
         token: impl Into<TokenLike>,
         account_id: AccountId,
-    ) -> Result<H256, ClientError> {
+    ) -> Result<EthOpHandle<'_>, ClientError> {
         let token = token.into();
         let token = self
             .tokens_cache
@@ -293,12 +307,12 @@ impl EthereumProvider {
             .await
             .map_err(|_| ClientError::IncorrectCredentials)?;
 
-        let transactin_hash = self
+        let transaction_hash = self
             .eth_client
             .send_raw_tx(signed_tx.raw_tx)
             .await
             .map_err(|err| ClientError::NetworkError(err.to_string()))?;
 
-        Ok(transactin_hash)
+        Ok(EthOpHandle::new(transaction_hash, self))
     }
 }
@@ -1,9 +1,11 @@
 pub mod credentials;
 pub mod error;
+pub mod eth_signer;
 pub mod ethereum;
 pub mod operations;
 pub mod provider;
 pub mod signer;
+pub mod subscriptions;
 pub mod tokens_cache;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,241 @@
+use models::node::{
+    closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable, Address,
+    FranklinTx, Nonce, Token, TokenLike, TxFeeTypes,
+};
+use num::BigUint;
+
+use crate::{error::ClientError, operations::SyncTransactionHandle, wallet::Wallet};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BatchTxKind {
+    Transfer,
+    Withdraw,
+}
+
+#[derive(Debug)]
+struct BatchTx {
+    kind: BatchTxKind,
+    token: Token,
+    amount: BigUint,
+    to: Address,
+}
+
+impl BatchTx {
+    fn fee_type(&self) -> TxFeeTypes {
+        match self.kind {
+            BatchTxKind::Transfer => TxFeeTypes::Transfer,
+            BatchTxKind::Withdraw => TxFeeTypes::Withdraw,
+        }
+    }
+}
+
+/// Builder for the batch of transfers and withdrawals. Transactions of the batch
+/// are either all executed or all rejected by the server.
+///
+/// The combined fee for the whole batch is paid in the token of the first transaction,
+/// and the batch is authorized by a single Ethereum signature.
+#[derive(Debug)]
+pub struct BatchBuilder<'a> {
+    wallet: &'a Wallet,
+    txs: Vec<BatchTx>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a> BatchBuilder<'a> {
+    /// Initializes a batch building process.
+    pub fn new(wallet: &'a Wallet) -> Self {
+        Self {
+            wallet,
+            txs: Vec::new(),
+            fee: None,
+            nonce: None,
+        }
+    }
+
+    /// Signs and sends the batch, returning the handles for every transaction
+    /// in the order they were added.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle>, ClientError> {
+        let fee_token = self
+            .txs
+            .first()
+            .map(|tx| tx.token.clone())
+            .ok_or_else(|| ClientError::MissingRequiredField("transactions".into()))?;
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let tx_types = self.txs.iter().map(BatchTx::fee_type).collect();
+                let addresses = self.txs.iter().map(|tx| tx.to).collect();
+                let total_fee = self
+                    .wallet
+                    .provider
+                    .get_txs_batch_fee(tx_types, addresses, fee_token.id)
+                    .await?;
+                closest_packable_fee_amount(&total_fee)
+            }
+        };
+
+        let mut txs = Vec::with_capacity(self.txs.len());
+        let mut messages = Vec::with_capacity(self.txs.len());
+        for (idx, batch_tx) in self.txs.into_iter().enumerate() {
+            // The whole fee is paid by the first transaction.
+            let tx_fee = if idx == 0 {
+                fee.clone()
+            } else {
+                BigUint::from(0u32)
+            };
+            let tx_nonce = nonce + idx as Nonce;
+
+            let (tx, message) = match batch_tx.kind {
+                BatchTxKind::Transfer => {
+                    let (transfer, _) = self
+                        .wallet
+                        .signer
+                        .sign_transfer(
+                            batch_tx.token.clone(),
+                            batch_tx.amount,
+                            tx_fee,
+                            batch_tx.to,
+                            tx_nonce,
+                        )
+                        .await
+                        .map_err(ClientError::SigningError)?;
+                    let message = transfer
+                        .get_ethereum_sign_message(&batch_tx.token.symbol, batch_tx.token.decimals);
+                    (FranklinTx::Transfer(Box::new(transfer)), message)
+                }
+                BatchTxKind::Withdraw => {
+                    let (withdraw, _) = self
+                        .wallet
+                        .signer
+                        .sign_withdraw(
+                            batch_tx.token.clone(),
+                            batch_tx.amount,
+                            tx_fee,
+                            batch_tx.to,
+                            tx_nonce,
+                        )
+                        .await
+                        .map_err(ClientError::SigningError)?;
+                    let message = withdraw
+                        .get_ethereum_sign_message(&batch_tx.token.symbol, batch_tx.token.decimals);
+                    (FranklinTx::Withdraw(Box::new(withdraw)), message)
+                }
+            };
+
+            txs.push((tx, None));
+            messages.push(message);
+        }
+
+        let eth_signature = self
+            .wallet
+            .signer
+            .sign_batch(&messages)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        let tx_hashes = self
+            .wallet
+            .provider
+            .send_txs_batch(txs, eth_signature)
+            .await?;
+
+        let handles = tx_hashes
+            .into_iter()
+            .map(|tx_hash| SyncTransactionHandle::new(tx_hash, self.wallet.provider.clone()))
+            .collect();
+
+        Ok(handles)
+    }
+
+    /// Adds a transfer to the batch. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    /// Returns an error if token is not supported by zkSync.
+    pub fn add_transfer(
+        self,
+        token: impl Into<TokenLike>,
+        amount: impl Into<BigUint>,
+        to: Address,
+    ) -> Result<Self, ClientError> {
+        self.add_tx(BatchTxKind::Transfer, token.into(), amount.into(), to)
+    }
+
+    /// Adds a withdrawal to the batch. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    /// Returns an error if token is not supported by zkSync.
+    pub fn add_withdraw(
+        self,
+        token: impl Into<TokenLike>,
+        amount: impl Into<BigUint>,
+        to: Address,
+    ) -> Result<Self, ClientError> {
+        self.add_tx(BatchTxKind::Withdraw, token.into(), amount.into(), to)
+    }
+
+    /// Set the total fee of the batch, paid in the token of the first transaction.
+    /// If the provided fee is not packable, rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the total fee of the batch, paid in the token of the first transaction.
+    /// If the provided fee is not packable, returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the nonce of the first transaction. Next transactions get sequential nonces.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    fn add_tx(
+        mut self,
+        kind: BatchTxKind,
+        token: TokenLike,
+        amount: BigUint,
+        to: Address,
+    ) -> Result<Self, ClientError> {
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token)
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.txs.push(BatchTx {
+            kind,
+            token,
+            amount: closest_packable_token_amount(&amount),
+            to,
+        });
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,57 @@
+use models::node::{FranklinTx, Nonce};
+
+use crate::{error::ClientError, operations::SyncTransactionHandle, wallet::Wallet};
+
+/// Builder for the `Close` transaction, which removes the account from the zkSync state.
+/// The account must have no balances left at the moment of the transaction execution.
+///
+/// Note that the server may reject the transaction if closing accounts is disabled.
+#[derive(Debug)]
+pub struct CloseBuilder<'a> {
+    wallet: &'a Wallet,
+    nonce: Option<Nonce>,
+}
+
+impl<'a> CloseBuilder<'a> {
+    /// Initializes a close transaction building process.
+    pub fn new(wallet: &'a Wallet) -> Self {
+        Self {
+            wallet,
+            nonce: None,
+        }
+    }
+
+    /// Sends the transaction, returning the handle for its awaiting.
+    pub async fn send(self) -> Result<SyncTransactionHandle, ClientError> {
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let close = self
+            .wallet
+            .signer
+            .sign_close(nonce)
+            .map_err(ClientError::SigningError)?;
+
+        let tx = FranklinTx::Close(Box::new(close));
+        let tx_hash = self.wallet.provider.send_tx(tx, None).await?;
+
+        let handle = SyncTransactionHandle::new(tx_hash, self.wallet.provider.clone());
+
+        Ok(handle)
+    }
+
+    /// Sets the transaction nonce.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
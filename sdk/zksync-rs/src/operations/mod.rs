@@ -1,17 +1,27 @@
 //! This file contains representation of not signed transactions and builders for them.
 
 use crate::{error::ClientError, provider::Provider, types::TransactionInfo};
-use models::node::tx::TxHash;
-use std::time::{Duration, Instant};
+use futures::{
+    future::{self, Either},
+    StreamExt,
+};
+use models::{node::tx::TxHash, ActionType};
+use std::time::Duration;
 
+mod batch;
 mod change_pubkey;
+mod close;
 mod transfer;
 mod withdraw;
 
 pub use self::{
-    change_pubkey::ChangePubKeyBuilder, transfer::TransferBuilder, withdraw::WithdrawBuilder,
+    batch::BatchBuilder, change_pubkey::ChangePubKeyBuilder, close::CloseBuilder,
+    transfer::TransferBuilder, withdraw::WithdrawBuilder,
 };
 
+/// When push notifications are used, transaction status is polled this many times less often.
+const POLLING_INTERVAL_WITH_SUBSCRIPTION_FACTOR: u32 = 10;
+
 /// Handle for transaction, providing an interface to control its execution.
 /// For obtained handle it's possible to set the polling interval, commit timeout
 /// and verify timeout values.
@@ -70,45 +80,80 @@ impl SyncTransactionHandle {
     }
 
     /// Awaits for the transaction commit and returns the information about execution.
+    ///
+    /// If the provider is connected to the WebSocket API, push notifications are used,
+    /// and the status is polled only occasionally in case a notification was missed.
     pub async fn wait_for_commit(&self) -> Result<TransactionInfo, ClientError> {
-        let mut timer = tokio::time::interval(self.polling_interval);
-        let start = Instant::now();
-
-        loop {
-            timer.tick().await;
+        self.wait_for(ActionType::COMMIT, self.commit_timeout).await
+    }
 
-            if let Some(commit_timeout) = self.commit_timeout {
-                if start.elapsed() >= commit_timeout {
-                    return Err(ClientError::OperationTimeout);
-                }
-            }
+    /// Awaits for the transaction verification and returns the information about execution.
+    ///
+    /// If the provider is connected to the WebSocket API, push notifications are used,
+    /// and the status is polled only occasionally in case a notification was missed.
+    pub async fn wait_for_verify(&self) -> Result<TransactionInfo, ClientError> {
+        self.wait_for(ActionType::VERIFY, self.verify_timeout).await
+    }
 
-            let response = self.provider.tx_info(self.hash.clone()).await?;
-            if let Some(block) = &response.block {
-                if block.committed {
-                    return Ok(response);
-                }
-            }
+    async fn wait_for(
+        &self,
+        action: ActionType,
+        timeout: Option<Duration>,
+    ) -> Result<TransactionInfo, ClientError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.wait_for_action(action))
+                .await
+                .map_err(|_| ClientError::OperationTimeout)?,
+            None => self.wait_for_action(action).await,
         }
     }
 
-    /// Awaits for the transaction verification and returns the information about execution.
-    pub async fn wait_for_verify(&self) -> Result<TransactionInfo, ClientError> {
-        let mut timer = tokio::time::interval(self.polling_interval);
-        let start = Instant::now();
+    async fn wait_for_action(&self, action: ActionType) -> Result<TransactionInfo, ClientError> {
+        // Failing to subscribe is not critical, since the transaction status can be polled.
+        let mut subscription = match self.provider.subscriptions() {
+            Some(client) => client.subscribe_tx(self.hash.clone(), action).await.ok(),
+            None => None,
+        };
+        let polling_interval = if subscription.is_some() {
+            self.polling_interval * POLLING_INTERVAL_WITH_SUBSCRIPTION_FACTOR
+        } else {
+            self.polling_interval
+        };
+        let mut timer = tokio::time::interval(polling_interval);
 
         loop {
-            timer.tick().await;
-
-            if let Some(verify_timeout) = self.verify_timeout {
-                if start.elapsed() >= verify_timeout {
-                    return Err(ClientError::OperationTimeout);
+            let notification = match subscription.as_mut() {
+                Some(subscription) => {
+                    match future::select(subscription.next(), Box::pin(timer.tick())).await {
+                        Either::Left((notification, _)) => Some(notification),
+                        Either::Right(_) => None,
+                    }
+                }
+                None => {
+                    timer.tick().await;
+                    None
+                }
+            };
+
+            match notification {
+                // Notification is only sent once the requested action is performed.
+                Some(Some(Ok(response))) => return Ok(response),
+                // Subscription is broken, fall back to polling.
+                Some(_) => {
+                    subscription = None;
+                    timer = tokio::time::interval(self.polling_interval);
+                    continue;
                 }
+                None => {}
             }
 
             let response = self.provider.tx_info(self.hash.clone()).await?;
             if let Some(block) = &response.block {
-                if block.verified {
+                let done = match action {
+                    ActionType::COMMIT => block.committed,
+                    ActionType::VERIFY => block.verified,
+                };
+                if done {
                     return Ok(response);
                 }
             }
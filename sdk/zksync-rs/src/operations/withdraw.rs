@@ -14,6 +14,7 @@ pub struct WithdrawBuilder<'a> {
     fee: Option<BigUint>,
     to: Option<Address>,
     nonce: Option<Nonce>,
+    fast: bool,
 }
 
 impl<'a> WithdrawBuilder<'a> {
@@ -26,6 +27,7 @@ impl<'a> WithdrawBuilder<'a> {
             fee: None,
             to: None,
             nonce: None,
+            fast: false,
         }
     }
 
@@ -53,13 +55,18 @@ impl<'a> WithdrawBuilder<'a> {
             }
         };
 
+        let fee_type = if self.fast {
+            TxFeeTypes::FastWithdraw
+        } else {
+            TxFeeTypes::Withdraw
+        };
         let fee = match self.fee {
             Some(fee) => fee,
             None => {
                 let fee = self
                     .wallet
                     .provider
-                    .get_tx_fee(TxFeeTypes::Withdraw, to, token.id)
+                    .get_tx_fee(fee_type, to, token.id)
                     .await?;
                 fee.total_fee
             }
@@ -69,10 +76,15 @@ impl<'a> WithdrawBuilder<'a> {
             .wallet
             .signer
             .sign_withdraw(token, amount, fee, to, nonce)
+            .await
             .map_err(ClientError::SigningError)?;
 
         let tx = FranklinTx::Withdraw(Box::new(withdraw));
-        let tx_hash = self.wallet.provider.send_tx(tx, eth_signature).await?;
+        let tx_hash = if self.fast {
+            self.wallet.provider.send_tx_fast(tx, eth_signature).await?
+        } else {
+            self.wallet.provider.send_tx(tx, eth_signature).await?
+        };
 
         let handle = SyncTransactionHandle::new(tx_hash, self.wallet.provider.clone());
 
@@ -168,4 +180,12 @@ impl<'a> WithdrawBuilder<'a> {
         self.nonce = Some(nonce);
         self
     }
+
+    /// Requests the fast processing of the withdrawal: the block containing it
+    /// will be sealed and sent to Ethereum without waiting for it to fill up.
+    /// Fast withdrawals have a higher fee, which is used by default.
+    pub fn fast(mut self) -> Self {
+        self.fast = true;
+        self
+    }
 }
@@ -2,19 +2,29 @@
 // from: https://github.com/matter-labs/zksync-dev/blob/dev/core/loadtest/src/rpc_client.rs
 
 // Built-in imports
+use std::{sync::Arc, time::Duration};
 
 // External uses
+use bigdecimal::BigDecimal;
 use jsonrpc_core::types::response::Output;
+use num::BigUint;
 
 // Workspace uses
-use models::node::{
-    tx::{FranklinTx, PackedEthSignature, TxHash},
-    Address, TokenLike, TxFeeTypes,
+use models::{
+    node::{
+        tx::{FranklinTx, TxEthSignature, TxHash},
+        Address, TokenLike, TxFeeTypes,
+    },
+    ActionType,
 };
 
 // Local uses
 use self::messages::JsonRpcRequest;
-use crate::{error::ClientError, types::*};
+use crate::{
+    error::ClientError,
+    subscriptions::{Subscription, SubscriptionClient},
+    types::*,
+};
 
 /// Returns a corresponding address for a provided network name.
 pub fn get_rpc_addr(network: Network) -> &'static str {
@@ -27,12 +37,20 @@ pub fn get_rpc_addr(network: Network) -> &'static str {
     }
 }
 
+/// Default amount of retries for the requests failed due to network errors.
+const DEFAULT_MAX_RETRIES: usize = 3;
+/// Default interval between the retries of the failed requests.
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
 /// `Provider` is capable of interacting with the ZKSync node via its
 /// JSON RPC interface.
 #[derive(Debug, Clone)]
 pub struct Provider {
     rpc_addr: String,
     client: reqwest::Client,
+    max_retries: usize,
+    retry_interval: Duration,
+    subscriptions: Option<Arc<SubscriptionClient>>,
     pub network: Network,
 }
 
@@ -42,6 +60,9 @@ impl Provider {
         Self {
             rpc_addr: get_rpc_addr(network).into(),
             client: reqwest::Client::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            subscriptions: None,
             network,
         }
     }
@@ -51,10 +72,71 @@ impl Provider {
         Self {
             rpc_addr: rpc_addr.into(),
             client: reqwest::Client::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            subscriptions: None,
             network: Network::Unknown,
         }
     }
 
+    /// Sets the amount of retries and the interval between them for the requests
+    /// failed due to network errors. Errors returned by the server are never retried.
+    ///
+    /// Only the read-only requests are retried: a network error does not mean that the
+    /// submitted transaction wasn't accepted by the server, so the submission methods
+    /// return the error and leave it to the caller to check the transaction status.
+    pub fn with_retries(mut self, max_retries: usize, retry_interval: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Connects to the WebSocket API of the zkSync server at the given address,
+    /// enabling subscriptions to the server events.
+    ///
+    /// Address of the WebSocket API for the known networks can be obtained via
+    /// `subscriptions::get_ws_addr` function.
+    pub fn with_ws(mut self, ws_addr: impl AsRef<str>) -> Result<Self, ClientError> {
+        let subscriptions = SubscriptionClient::connect(ws_addr)?;
+        self.subscriptions = Some(Arc::new(subscriptions));
+        Ok(self)
+    }
+
+    /// Returns the WebSocket client if the `Provider` is connected to the WebSocket API.
+    pub fn subscriptions(&self) -> Option<&SubscriptionClient> {
+        self.subscriptions.as_deref()
+    }
+
+    /// Subscribes to the transaction execution status updates.
+    /// Requires the `Provider` to be connected to the WebSocket API.
+    pub async fn subscribe_tx(
+        &self,
+        tx_hash: TxHash,
+        action: ActionType,
+    ) -> Result<Subscription<TransactionInfo>, ClientError> {
+        self.ws()?.subscribe_tx(tx_hash, action).await
+    }
+
+    /// Subscribes to the priority operation execution status updates.
+    /// Requires the `Provider` to be connected to the WebSocket API.
+    pub async fn subscribe_ethop(
+        &self,
+        serial_id: u64,
+        action: ActionType,
+    ) -> Result<Subscription<EthOpInfo>, ClientError> {
+        self.ws()?.subscribe_ethop(serial_id, action).await
+    }
+
+    /// Subscribes to the account state updates.
+    /// Requires the `Provider` to be connected to the WebSocket API.
+    pub async fn subscribe_account(
+        &self,
+        address: Address,
+        action: ActionType,
+    ) -> Result<Subscription<AccountState>, ClientError> {
+        self.ws()?.subscribe_account(address, action).await
+    }
+
     /// Obtains minimum fee required to process transaction in zkSync network.
     pub async fn get_tx_fee(
         &self,
@@ -77,16 +159,96 @@ impl Provider {
     pub async fn send_tx(
         &self,
         tx: FranklinTx,
-        eth_signature: Option<PackedEthSignature>,
+        eth_signature: Option<TxEthSignature>,
     ) -> Result<TxHash, ClientError> {
-        let msg = JsonRpcRequest::submit_tx(tx, eth_signature);
+        self.submit_tx(tx, eth_signature, None).await
+    }
 
-        let ret = self.post(&msg).await?;
+    /// Submits a transaction to the zkSync network and requests its fast processing,
+    /// i.e. the block with the transaction will be sealed without waiting for it to fill.
+    /// Fast processing is only available for `Withdraw` transactions.
+    /// Returns the hash of created transaction.
+    pub async fn send_tx_fast(
+        &self,
+        tx: FranklinTx,
+        eth_signature: Option<TxEthSignature>,
+    ) -> Result<TxHash, ClientError> {
+        self.submit_tx(tx, eth_signature, Some(true)).await
+    }
+
+    async fn submit_tx(
+        &self,
+        tx: FranklinTx,
+        eth_signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+    ) -> Result<TxHash, ClientError> {
+        let msg = JsonRpcRequest::submit_tx(tx, eth_signature, fast_processing);
+
+        let ret = self.post_once(&msg).await?;
         let tx_hash = serde_json::from_value(ret)
             .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
         Ok(tx_hash)
     }
 
+    /// Obtains minimum fee required to process the batch of transactions in zkSync network.
+    pub async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike>,
+    ) -> Result<BigUint, ClientError> {
+        let token = token.into();
+        let msg = JsonRpcRequest::get_txs_batch_fee_in_wei(tx_types, addresses, token);
+
+        let ret = self.post(&msg).await?;
+        let fee: BatchFee = serde_json::from_value(ret)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+
+        Ok(fee.total_fee)
+    }
+
+    /// Submits a batch of transactions to the zkSync network.
+    /// Transactions of the batch are either all executed or all rejected.
+    /// Transactions without their own Ethereum signature are authorized by the
+    /// signature of the whole batch, if provided.
+    /// Returns the hashes of created transactions.
+    pub async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(FranklinTx, Option<TxEthSignature>)>,
+        eth_signature: Option<TxEthSignature>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        let msg = JsonRpcRequest::submit_txs_batch(txs_signed, eth_signature);
+
+        let ret = self.post_once(&msg).await?;
+        let tx_hashes = serde_json::from_value(ret)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        Ok(tx_hashes)
+    }
+
+    /// Requests and returns the USD price of the token.
+    pub async fn get_token_price(
+        &self,
+        token: impl Into<TokenLike>,
+    ) -> Result<BigDecimal, ClientError> {
+        let msg = JsonRpcRequest::get_token_price(token.into());
+
+        let ret = self.post(&msg).await?;
+        let price = serde_json::from_value(ret)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        Ok(price)
+    }
+
+    /// Requests and returns the amount of Ethereum blocks the server waits for
+    /// before processing the priority operation.
+    pub async fn get_confirmations_for_eth_op_amount(&self) -> Result<u64, ClientError> {
+        let msg = JsonRpcRequest::get_confirmations_for_eth_op_amount();
+
+        let ret = self.post(&msg).await?;
+        let confirmations = serde_json::from_value(ret)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        Ok(confirmations)
+    }
+
     /// Requests and returns information about a ZKSync account given its address.
     pub async fn account_info(&self, address: Address) -> Result<AccountInfo, ClientError> {
         let msg = JsonRpcRequest::account_info(address);
@@ -137,20 +299,58 @@ impl Provider {
         Ok(tx_info)
     }
 
+    fn ws(&self) -> Result<&SubscriptionClient, ClientError> {
+        self.subscriptions()
+            .ok_or(ClientError::NoWebSocketConnection)
+    }
+
     /// Performs a POST query to the JSON RPC endpoint,
     /// and decodes the response, returning the decoded `serde_json::Value`.
     /// `Ok` is returned only for successful calls, for any kind of error
     /// the `Err` variant is returned (including the failed RPC method
     /// execution response).
+    /// Errors specific to zkSync are returned as `ClientError::ServerError`.
+    ///
+    /// Requests failed due to network errors are retried, so this method must
+    /// only be used for the read-only requests.
     async fn post(&self, message: impl serde::Serialize) -> Result<serde_json::Value, ClientError> {
+        let reply: Output = self.post_with_retries(message).await?;
+        Self::decode_reply(reply)
+    }
+
+    /// Same as `post`, but the request is never retried.
+    /// Used for the requests which are not safe to be sent twice, e.g. transaction submission.
+    async fn post_once(
+        &self,
+        message: impl serde::Serialize,
+    ) -> Result<serde_json::Value, ClientError> {
         let reply: Output = self.post_raw(message).await?;
+        Self::decode_reply(reply)
+    }
 
-        let ret = match reply {
-            Output::Success(success) => success.result,
-            Output::Failure(failure) => return Err(ClientError::RpcError(failure)),
-        };
+    fn decode_reply(reply: Output) -> Result<serde_json::Value, ClientError> {
+        match reply {
+            Output::Success(success) => Ok(success.result),
+            Output::Failure(failure) => Err(ClientError::from_rpc_failure(failure)),
+        }
+    }
 
-        Ok(ret)
+    /// Performs a POST query to the JSON RPC endpoint, retrying it if
+    /// the request failed due to network error.
+    async fn post_with_retries(
+        &self,
+        message: impl serde::Serialize,
+    ) -> Result<Output, ClientError> {
+        let mut retries = 0;
+        loop {
+            match self.post_raw(&message).await {
+                Err(ClientError::NetworkError(_)) if retries < self.max_retries => {
+                    retries += 1;
+                    tokio::time::delay_for(self.retry_interval).await;
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Performs a POST query to the JSON RPC endpoint,
@@ -184,11 +384,13 @@ impl Provider {
 
 mod messages {
     use models::node::{
-        tx::{FranklinTx, PackedEthSignature, TxEthSignature, TxHash},
+        tx::{FranklinTx, TxEthSignature, TxHash},
         Address, TokenLike, TxFeeTypes,
     };
     use serde_derive::Serialize;
 
+    use crate::types::TxWithSignature;
+
     #[derive(Debug, Serialize)]
     pub struct JsonRpcRequest {
         pub id: String,
@@ -213,16 +415,33 @@ mod messages {
             Self::create("account_info", params)
         }
 
-        pub fn submit_tx(tx: FranklinTx, eth_signature: Option<PackedEthSignature>) -> Self {
+        pub fn submit_tx(
+            tx: FranklinTx,
+            eth_signature: Option<TxEthSignature>,
+            fast_processing: Option<bool>,
+        ) -> Self {
             let mut params = Vec::new();
             params.push(serde_json::to_value(tx).expect("serialization fail"));
-            params.push(
-                serde_json::to_value(eth_signature.map(TxEthSignature::EthereumSignature))
-                    .expect("serialization fail"),
-            );
+            params.push(serde_json::to_value(eth_signature).expect("serialization fail"));
+            params.push(serde_json::to_value(fast_processing).expect("serialization fail"));
             Self::create("tx_submit", params)
         }
 
+        pub fn submit_txs_batch(
+            txs_signed: Vec<(FranklinTx, Option<TxEthSignature>)>,
+            eth_signature: Option<TxEthSignature>,
+        ) -> Self {
+            let txs: Vec<_> = txs_signed
+                .into_iter()
+                .map(|(tx, signature)| TxWithSignature { tx, signature })
+                .collect();
+
+            let mut params = Vec::new();
+            params.push(serde_json::to_value(txs).expect("serialization fail"));
+            params.push(serde_json::to_value(eth_signature).expect("serialization fail"));
+            Self::create("submit_txs_batch", params)
+        }
+
         pub fn ethop_info(serial_id: u32) -> Self {
             let mut params = Vec::new();
             params.push(serde_json::to_value(serial_id).expect("serialization fail"));
@@ -252,5 +471,28 @@ mod messages {
             params.push(serde_json::to_value(token_symbol).expect("serialization fail"));
             Self::create("get_tx_fee", params)
         }
+
+        pub fn get_txs_batch_fee_in_wei(
+            tx_types: Vec<TxFeeTypes>,
+            addresses: Vec<Address>,
+            token_symbol: TokenLike,
+        ) -> Self {
+            let mut params = Vec::new();
+            params.push(serde_json::to_value(tx_types).expect("serialization fail"));
+            params.push(serde_json::to_value(addresses).expect("serialization fail"));
+            params.push(serde_json::to_value(token_symbol).expect("serialization fail"));
+            Self::create("get_txs_batch_fee_in_wei", params)
+        }
+
+        pub fn get_token_price(token_symbol: TokenLike) -> Self {
+            let mut params = Vec::new();
+            params.push(serde_json::to_value(token_symbol).expect("serialization fail"));
+            Self::create("get_token_price", params)
+        }
+
+        pub fn get_confirmations_for_eth_op_amount() -> Self {
+            let params = Vec::new();
+            Self::create("get_confirmations_for_eth_op_amount", params)
+        }
     }
 }
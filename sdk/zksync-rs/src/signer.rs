@@ -1,13 +1,20 @@
 // Built-in imports
-use std::fmt;
+use std::{fmt, sync::Arc};
 // External uses
 use num::BigUint;
 use web3::types::H256;
 // Workspace uses
-use models::node::tx::{ChangePubKey, PackedEthSignature};
-use models::node::{AccountId, Address, Nonce, PrivateKey, PubKeyHash, Token, Transfer, Withdraw};
-
-use crate::error::SignerError;
+use models::node::tx::{
+    get_batch_ethereum_sign_message, ChangePubKey, Close, TxEthSignature, TxSignature,
+};
+use models::node::{
+    AccountId, Address, Nonce, PrivateKey, PubKeyHash, TimeRange, Token, Transfer, Withdraw,
+};
+
+use crate::{
+    error::SignerError,
+    eth_signer::{EthereumSigner, PrivateKeySigner},
+};
 
 fn signing_failed_error(err: impl ToString) -> SignerError {
     SignerError::SigningFailed(err.to_string())
@@ -17,7 +24,9 @@ pub struct Signer {
     pub pubkey_hash: PubKeyHash,
     pub address: Address,
     pub(crate) private_key: PrivateKey,
+    /// Private key is only required to perform on-chain operations.
     pub(crate) eth_private_key: Option<H256>,
+    pub(crate) eth_signer: Option<Arc<dyn EthereumSigner>>,
     pub(crate) account_id: Option<AccountId>,
 }
 
@@ -35,13 +44,40 @@ impl fmt::Debug for Signer {
 }
 
 impl Signer {
-    pub fn new(private_key: PrivateKey, address: Address, eth_private_key: Option<H256>) -> Self {
+    pub fn new(
+        private_key: PrivateKey,
+        address: Address,
+        eth_private_key: Option<H256>,
+    ) -> Result<Self, SignerError> {
         let pubkey_hash = PubKeyHash::from_privkey(&private_key);
-        Self {
+        let eth_signer = match eth_private_key {
+            Some(eth_private_key) => {
+                let eth_signer = PrivateKeySigner::new(eth_private_key)?;
+                Some(Arc::new(eth_signer) as Arc<dyn EthereumSigner>)
+            }
+            None => None,
+        };
+
+        Ok(Self {
             private_key,
             pubkey_hash,
             address,
             eth_private_key,
+            eth_signer,
+            account_id: None,
+        })
+    }
+
+    /// Creates a signer which authorizes transactions via the provided Ethereum signer
+    /// (e.g. an external service or a smart contract wallet).
+    pub fn with_eth_signer(private_key: PrivateKey, eth_signer: Arc<dyn EthereumSigner>) -> Self {
+        let pubkey_hash = PubKeyHash::from_privkey(&private_key);
+        Self {
+            private_key,
+            pubkey_hash,
+            address: eth_signer.address(),
+            eth_private_key: None,
+            eth_signer: Some(eth_signer),
             account_id: None,
         }
     }
@@ -58,53 +94,65 @@ impl Signer {
         self.account_id
     }
 
-    pub fn sign_change_pubkey_tx(
+    pub async fn sign_change_pubkey_tx(
         &self,
         nonce: Nonce,
         auth_onchain: bool,
     ) -> Result<ChangePubKey, SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+        let time_range = TimeRange::default();
 
         let eth_signature = if auth_onchain {
             None
         } else {
-            let eth_private_key = self
-                .eth_private_key
-                .ok_or(SignerError::MissingEthPrivateKey)?;
-
-            let sign_bytes =
-                ChangePubKey::get_eth_signed_data(account_id, nonce, &self.pubkey_hash)
-                    .map_err(signing_failed_error)?;
-            let eth_signature = PackedEthSignature::sign(&eth_private_key, &sign_bytes)
-                .map_err(signing_failed_error)?;
-            Some(eth_signature)
+            let eth_signer = self
+                .eth_signer
+                .as_ref()
+                .ok_or(SignerError::MissingEthSigner)?;
+
+            let sign_bytes = ChangePubKey::get_eth_signed_data(
+                account_id,
+                nonce,
+                &self.pubkey_hash,
+                &time_range,
+            )
+            .map_err(signing_failed_error)?;
+            match eth_signer.sign_message(&sign_bytes).await? {
+                TxEthSignature::EthereumSignature(eth_signature) => Some(eth_signature),
+                // Contract wallets have to authorize the new key on-chain.
+                TxEthSignature::EIP1271Signature(_) => {
+                    return Err(SignerError::SigningFailed(
+                        "ChangePubKey can't be authorized by the EIP-1271 signature".into(),
+                    ))
+                }
+            }
         };
         let change_pubkey = ChangePubKey {
             account_id,
             account: self.address,
             new_pk_hash: self.pubkey_hash.clone(),
             nonce,
+            time_range,
             eth_signature,
         };
 
-        if !auth_onchain {
-            assert!(
-                change_pubkey.verify_eth_signature() == Some(self.address),
-                "eth signature is incorrect"
-            );
+        if !auth_onchain && change_pubkey.verify_eth_signature() != Some(self.address) {
+            return Err(SignerError::SigningFailed(
+                "Ethereum signature is incorrect".into(),
+            ));
         }
 
         Ok(change_pubkey)
     }
 
-    pub fn sign_transfer(
+    pub async fn sign_transfer(
         &self,
         token: Token,
         amount: BigUint,
         fee: BigUint,
         to: Address,
         nonce: Nonce,
-    ) -> Result<(Transfer, Option<PackedEthSignature>), SignerError> {
+    ) -> Result<(Transfer, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let transfer = Transfer::new_signed(
@@ -115,30 +163,30 @@ impl Signer {
             amount,
             fee,
             nonce,
+            Default::default(),
             &self.private_key,
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = self
-            .eth_private_key
-            .map(|pk| {
+        let eth_signature = match &self.eth_signer {
+            Some(eth_signer) => {
                 let msg = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
-                PackedEthSignature::sign(&pk, msg.as_bytes())
-            })
-            .transpose()
-            .map_err(signing_failed_error)?;
+                Some(eth_signer.sign_message(msg.as_bytes()).await?)
+            }
+            None => None,
+        };
 
         Ok((transfer, eth_signature))
     }
 
-    pub fn sign_withdraw(
+    pub async fn sign_withdraw(
         &self,
         token: Token,
         amount: BigUint,
         fee: BigUint,
         eth_address: Address,
         nonce: Nonce,
-    ) -> Result<(Withdraw, Option<PackedEthSignature>), SignerError> {
+    ) -> Result<(Withdraw, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let withdraw = Withdraw::new_signed(
@@ -149,19 +197,51 @@ impl Signer {
             amount,
             fee,
             nonce,
+            Default::default(),
             &self.private_key,
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = self
-            .eth_private_key
-            .map(|pk| {
+        let eth_signature = match &self.eth_signer {
+            Some(eth_signer) => {
                 let msg = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
-                PackedEthSignature::sign(&pk, msg.as_bytes())
-            })
-            .transpose()
-            .map_err(signing_failed_error)?;
+                Some(eth_signer.sign_message(msg.as_bytes()).await?)
+            }
+            None => None,
+        };
 
         Ok((withdraw, eth_signature))
     }
+
+    pub fn sign_close(&self, nonce: Nonce) -> Result<Close, SignerError> {
+        let mut close = Close {
+            account: self.address,
+            nonce,
+            signature: TxSignature::default(),
+        };
+        close.signature = TxSignature::sign_musig(&self.private_key, &close.get_bytes());
+
+        if !close.check_correctness() {
+            return Err(SignerError::SigningFailed(
+                "Close transaction is incorrect".into(),
+            ));
+        }
+
+        Ok(close)
+    }
+
+    /// Signs the messages of the batch transactions with the Ethereum key,
+    /// so that one signature authorizes the whole batch.
+    pub async fn sign_batch(
+        &self,
+        messages: &[String],
+    ) -> Result<Option<TxEthSignature>, SignerError> {
+        match &self.eth_signer {
+            Some(eth_signer) => {
+                let msg = get_batch_ethereum_sign_message(messages.iter().map(String::as_str));
+                Ok(Some(eth_signer.sign_message(msg.as_bytes()).await?))
+            }
+            None => Ok(None),
+        }
+    }
 }
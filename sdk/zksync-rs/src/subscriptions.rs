@@ -0,0 +1,333 @@
+//! Client for the zkSync server WebSocket API, which provides push notifications
+//! about transactions, priority operations and account state changes.
+
+// Built-in imports
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// External uses
+use futures::{
+    compat::{Future01CompatExt, Stream01CompatExt},
+    future::{BoxFuture, FutureExt},
+    stream::{BoxStream, Stream, StreamExt},
+};
+use serde::de::DeserializeOwned;
+use web3::{
+    transports::{EventLoopHandle, WebSocket},
+    types::SubscriptionId,
+    DuplexTransport, Transport,
+};
+
+// Workspace uses
+use models::{
+    node::{tx::TxHash, Address},
+    ActionType,
+};
+
+// Local uses
+use crate::{error::ClientError, types::*};
+
+/// Returns a corresponding WebSocket API address for a provided network name.
+pub fn get_ws_addr(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "wss://api.zksync.io/jsrpc-ws",
+        Network::Rinkeby => "wss://rinkeby-api.zksync.io/jsrpc-ws",
+        Network::Ropsten => "wss://ropsten-api.zksync.io/jsrpc-ws",
+        Network::Localhost => "ws://127.0.0.1:3031",
+        Network::Unknown => panic!("Attempt to create a provider from an unknown network"),
+    }
+}
+
+fn ws_error(err: web3::Error) -> ClientError {
+    ClientError::NetworkError(err.to_string())
+}
+
+/// `SubscriptionClient` keeps the WebSocket connection to the zkSync server
+/// and creates subscriptions to the server events.
+pub struct SubscriptionClient {
+    transport: WebSocket,
+    // Event loop must be alive as long as the connection is used.
+    _event_loop: EventLoopHandle,
+}
+
+impl fmt::Debug for SubscriptionClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubscriptionClient").finish()
+    }
+}
+
+impl SubscriptionClient {
+    /// Connects to the WebSocket API of the zkSync server.
+    pub fn connect(ws_addr: impl AsRef<str>) -> Result<Self, ClientError> {
+        let (event_loop, transport) = WebSocket::new(ws_addr.as_ref()).map_err(ws_error)?;
+
+        Ok(Self {
+            transport,
+            _event_loop: event_loop,
+        })
+    }
+
+    /// Subscribes to the transaction execution status updates.
+    /// Notification is sent once the transaction is committed or verified
+    /// (depending on the `action`).
+    pub async fn subscribe_tx(
+        &self,
+        tx_hash: TxHash,
+        action: ActionType,
+    ) -> Result<Subscription<TransactionInfo>, ClientError> {
+        let params = vec![to_value(tx_hash), to_value(action)];
+        self.subscribe("tx_subscribe", "tx_unsubscribe", params)
+            .await
+    }
+
+    /// Subscribes to the priority operation execution status updates.
+    /// Notification is sent once the operation is committed or verified
+    /// (depending on the `action`).
+    pub async fn subscribe_ethop(
+        &self,
+        serial_id: u64,
+        action: ActionType,
+    ) -> Result<Subscription<EthOpInfo>, ClientError> {
+        let params = vec![to_value(serial_id), to_value(action)];
+        self.subscribe("ethop_subscribe", "ethop_unsubscribe", params)
+            .await
+    }
+
+    /// Subscribes to the account state updates.
+    /// Notification is sent every time the account state is changed in the
+    /// committed or verified (depending on the `action`) block.
+    pub async fn subscribe_account(
+        &self,
+        address: Address,
+        action: ActionType,
+    ) -> Result<Subscription<AccountState>, ClientError> {
+        let params = vec![to_value(address), to_value(action)];
+        self.subscribe("account_subscribe", "account_unsubscribe", params)
+            .await
+    }
+
+    async fn subscribe<T: DeserializeOwned + Send + 'static>(
+        &self,
+        subscribe_method: &str,
+        unsubscribe_method: &'static str,
+        params: Vec<serde_json::Value>,
+    ) -> Result<Subscription<T>, ClientError> {
+        let id = self
+            .transport
+            .execute(subscribe_method, params)
+            .compat()
+            .await
+            .map_err(ws_error)?;
+        let id: String = serde_json::from_value(id)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        let id = SubscriptionId::from(id);
+
+        let stream = self
+            .transport
+            .subscribe(&id)
+            .compat()
+            .map(|notification| {
+                let notification = notification.map_err(ws_error)?;
+                serde_json::from_value(notification)
+                    .map_err(|err| ClientError::MalformedResponse(err.to_string()))
+            })
+            .boxed();
+
+        Ok(Subscription::new(
+            id,
+            unsubscribe_method,
+            Box::new(self.transport.clone()),
+            stream,
+        ))
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> serde_json::Value {
+    serde_json::to_value(value).expect("serialization fail")
+}
+
+/// Transport capable of cancelling the subscriptions.
+/// Abstracted from the WebSocket connection, so the subscriptions can be tested without the server.
+trait Unsubscribe: Send + Sync {
+    /// Stops handling the notifications locally and requests the server to cancel the subscription.
+    /// The request is sent before the returned future is polled, so the future may be dropped
+    /// if the server response is not needed.
+    fn unsubscribe(
+        &self,
+        method: &'static str,
+        id: &SubscriptionId,
+    ) -> BoxFuture<'static, Result<(), ClientError>>;
+}
+
+impl Unsubscribe for WebSocket {
+    fn unsubscribe(
+        &self,
+        method: &'static str,
+        id: &SubscriptionId,
+    ) -> BoxFuture<'static, Result<(), ClientError>> {
+        DuplexTransport::unsubscribe(self, id);
+        // WebSocket transport sends the request once it's executed, not when the response is polled.
+        let response = self.execute(method, vec![to_value(id)]).compat();
+        async move {
+            response.await.map_err(ws_error)?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// Stream of the notifications for a certain subscription.
+/// Once the subscription is dropped, notifications are no longer received and the
+/// subscription is cancelled on the server side. `Subscription::unsubscribe` can be used
+/// instead to wait for the server to confirm the cancellation.
+pub struct Subscription<T> {
+    id: SubscriptionId,
+    unsubscribe_method: &'static str,
+    transport: Box<dyn Unsubscribe>,
+    unsubscribed: bool,
+    stream: BoxStream<'static, Result<T, ClientError>>,
+}
+
+impl<T> fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<T> Subscription<T> {
+    fn new(
+        id: SubscriptionId,
+        unsubscribe_method: &'static str,
+        transport: Box<dyn Unsubscribe>,
+        stream: BoxStream<'static, Result<T, ClientError>>,
+    ) -> Self {
+        Self {
+            id,
+            unsubscribe_method,
+            transport,
+            unsubscribed: false,
+            stream,
+        }
+    }
+
+    /// Cancels the subscription on the server side.
+    pub async fn unsubscribe(mut self) -> Result<(), ClientError> {
+        self.unsubscribed = true;
+        self.transport
+            .unsubscribe(self.unsubscribe_method, &self.id)
+            .await
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = Result<T, ClientError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if !self.unsubscribed {
+            // Subscription is dropped without waiting for the server response, e.g. when
+            // the transaction awaiting has timed out.
+            drop(
+                self.transport
+                    .unsubscribe(self.unsubscribe_method, &self.id),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    /// Transport recording the cancelled subscriptions.
+    #[derive(Debug, Clone, Default)]
+    struct MockTransport {
+        unsubscribed: Arc<Mutex<Vec<(&'static str, SubscriptionId)>>>,
+    }
+
+    impl MockTransport {
+        fn subscription(&self, id: &str) -> Subscription<TransactionInfo> {
+            Subscription::new(
+                SubscriptionId::from(id.to_string()),
+                "tx_unsubscribe",
+                Box::new(self.clone()),
+                futures::stream::pending().boxed(),
+            )
+        }
+
+        fn unsubscribed(&self) -> Vec<(&'static str, SubscriptionId)> {
+            self.unsubscribed.lock().unwrap().clone()
+        }
+    }
+
+    impl Unsubscribe for MockTransport {
+        fn unsubscribe(
+            &self,
+            method: &'static str,
+            id: &SubscriptionId,
+        ) -> BoxFuture<'static, Result<(), ClientError>> {
+            self.unsubscribed.lock().unwrap().push((method, id.clone()));
+            futures::future::ok(()).boxed()
+        }
+    }
+
+    #[test]
+    fn dropped_subscription_is_cancelled() {
+        let transport = MockTransport::default();
+
+        let subscription = transport.subscription("1");
+        assert!(transport.unsubscribed().is_empty());
+        drop(subscription);
+
+        assert_eq!(
+            transport.unsubscribed(),
+            vec![("tx_unsubscribe", SubscriptionId::from("1".to_string()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn timed_out_subscription_is_cancelled() {
+        let transport = MockTransport::default();
+        let mut subscription = transport.subscription("1");
+
+        let notification =
+            tokio::time::timeout(Duration::from_millis(10), subscription.next()).await;
+        assert!(notification.is_err(), "no notifications are expected");
+        drop(subscription);
+
+        assert_eq!(
+            transport.unsubscribed(),
+            vec![("tx_unsubscribe", SubscriptionId::from("1".to_string()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscription_is_cancelled_once() {
+        let transport = MockTransport::default();
+
+        transport
+            .subscription("1")
+            .unsubscribe()
+            .await
+            .expect("unsubscribe failed");
+
+        assert_eq!(
+            transport.unsubscribed(),
+            vec![("tx_unsubscribe", SubscriptionId::from("1".to_string()))]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use models::{
-    node::{AccountId, Address, Nonce, PubKeyHash, Token},
+    node::{
+        tx::{FranklinTx, TxEthSignature},
+        AccountId, Address, Nonce, PubKeyHash, Token,
+    },
     primitives::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper},
 };
 use num::BigUint;
@@ -134,6 +137,9 @@ pub enum OutputFeeType {
     Transfer,
     TransferToNew,
     Withdraw,
+    FastWithdraw,
+    ForcedExit,
+    Swap,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -150,4 +156,23 @@ pub struct Fee {
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Signed fee quote, which can be attached to the transaction to have the fee
+    /// accepted even if the prices change.
+    #[serde(default)]
+    pub quote: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFee {
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub total_fee: BigUint,
+}
+
+/// Transaction along with its Ethereum signature, used to submit transactions batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxWithSignature {
+    pub tx: FranklinTx,
+    pub signature: Option<TxEthSignature>,
 }
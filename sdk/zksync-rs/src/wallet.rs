@@ -17,11 +17,15 @@ impl Wallet {
         provider: Provider,
         credentials: WalletCredentials,
     ) -> Result<Self, ClientError> {
-        let mut signer = Signer::new(
-            credentials.zksync_private_key,
-            credentials.eth_address,
-            credentials.eth_private_key,
-        );
+        let mut signer = match credentials.eth_signer {
+            Some(eth_signer) => Signer::with_eth_signer(credentials.zksync_private_key, eth_signer),
+            None => Signer::new(
+                credentials.zksync_private_key,
+                credentials.eth_address,
+                credentials.eth_private_key,
+            )
+            .map_err(ClientError::SigningError)?,
+        };
 
         let account_info = provider.account_info(credentials.eth_address).await?;
         signer.set_account_id(account_info.id);
@@ -95,6 +99,16 @@ impl Wallet {
         WithdrawBuilder::new(self)
     }
 
+    /// Initializes `Close` transaction sending.
+    pub fn start_close(&self) -> CloseBuilder<'_> {
+        CloseBuilder::new(self)
+    }
+
+    /// Initializes the batch of transactions sending.
+    pub fn start_batch(&self) -> BatchBuilder<'_> {
+        BatchBuilder::new(self)
+    }
+
     /// Creates an `EthereumProvider` to interact with the Ethereum network.
     ///
     /// Returns an error if wallet was created without providing an Ethereum private key.
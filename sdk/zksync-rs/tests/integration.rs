@@ -12,6 +12,7 @@
 use futures::compat::Future01CompatExt;
 use std::time::{Duration, Instant};
 use zksync::{
+    subscriptions::get_ws_addr,
     web3::types::{H160, H256, U256},
     zksync_models::node::tx::PackedEthSignature,
     EthereumProvider, Network, Provider, Wallet, WalletCredentials,
@@ -62,28 +63,6 @@ async fn wait_for_eth_tx(ethereum: &EthereumProvider, hash: H256) {
     }
 }
 
-async fn wait_for_deposit_and_update_account_id(wallet: &mut Wallet) {
-    let timeout = Duration::from_secs(60);
-    let mut poller = tokio::time::interval(std::time::Duration::from_millis(100));
-    let start = Instant::now();
-    while wallet
-        .provider
-        .account_info(wallet.address())
-        .await
-        .unwrap()
-        .id
-        .is_none()
-    {
-        if start.elapsed() > timeout {
-            panic!("Timeout elapsed while waiting for Ethereum transaction");
-        }
-        poller.tick().await;
-    }
-
-    wallet.update_account_id().await.unwrap();
-    assert!(wallet.account_id().is_some(), "Account ID was not set");
-}
-
 async fn transfer_eth_to(to: H160) {
     let (main_eth_address, main_eth_private_key) = eth_main_account_credentials();
 
@@ -109,22 +88,30 @@ async fn simple_workflow() -> Result<(), anyhow::Error> {
     // account in subsequent test runs).
     transfer_eth_to(eth_address).await;
 
-    let provider = Provider::new(Network::Localhost);
+    // WebSocket connection is used to await the transactions via push notifications.
+    let provider = Provider::new(Network::Localhost)
+        .with_ws(get_ws_addr(Network::Localhost))
+        .unwrap();
     let credentials =
         WalletCredentials::from_eth_pk(eth_address, eth_private_key, Network::Localhost).unwrap();
 
     let mut wallet = Wallet::new(provider, credentials).await.unwrap();
     let ethereum = wallet.ethereum(LOCALHOST_WEB3_ADDR).await.unwrap();
 
-    let deposit_tx_hash = ethereum
+    let deposit_handle = ethereum
         .deposit("ETH", one_ether() / 2, wallet.address())
         .await
         .unwrap();
 
-    wait_for_eth_tx(&ethereum, deposit_tx_hash).await;
+    deposit_handle
+        .commit_timeout(Duration::from_secs(60))
+        .wait_for_commit()
+        .await
+        .unwrap();
 
     // Update stored wallet ID after we initialized a wallet via deposit.
-    wait_for_deposit_and_update_account_id(&mut wallet).await;
+    wallet.update_account_id().await.unwrap();
+    assert!(wallet.account_id().is_some(), "Account ID was not set");
 
     if !wallet.is_signing_key_set().await.unwrap() {
         let handle = wallet.start_change_pubkey().send().await.unwrap();
@@ -153,5 +140,43 @@ async fn simple_workflow() -> Result<(), anyhow::Error> {
         .await
         .unwrap();
 
+    // Perform a batch of transfer and withdrawal to self.
+    let handles = wallet
+        .start_batch()
+        .add_transfer("ETH", 1_000_000u64, wallet.address())
+        .unwrap()
+        .add_withdraw("ETH", 1_000_000u64, wallet.address())
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(handles.len(), 2);
+
+    for handle in handles {
+        handle
+            .commit_timeout(Duration::from_secs(60))
+            .wait_for_commit()
+            .await
+            .unwrap();
+    }
+
+    // Perform a fast withdrawal to self.
+    let handle = wallet
+        .start_withdraw()
+        .to(wallet.address())
+        .token("ETH")
+        .unwrap()
+        .amount(1_000_000u64)
+        .fast()
+        .send()
+        .await
+        .unwrap();
+
+    handle
+        .verify_timeout(Duration::from_secs(180))
+        .wait_for_verify()
+        .await
+        .unwrap();
+
     Ok(())
 }
@@ -10,7 +10,8 @@ import {
     serializeAmountPacked,
     serializeFeePacked,
     serializeNonce,
-    serializeAmountFull
+    serializeAmountFull,
+    serializeTimeRange
 } from "./utils";
 import { Address, EthSignerType, PubKeyHash, Transfer, Withdraw } from "./types";
 
@@ -33,6 +34,8 @@ export class Signer {
         amount: BigNumberish;
         fee: BigNumberish;
         nonce: number;
+        validFrom?: number;
+        validUntil?: number;
     }): Transfer {
         const type = new Uint8Array([5]); // tx type
         const accountId = serializeAccountId(transfer.accountId);
@@ -42,7 +45,8 @@ export class Signer {
         const amount = serializeAmountPacked(transfer.amount);
         const fee = serializeFeePacked(transfer.fee);
         const nonce = serializeNonce(transfer.nonce);
-        const msgBytes = ethers.utils.concat([type, accountId, from, to, token, amount, fee, nonce]);
+        const timeRange = serializeTimeRange(transfer.validFrom, transfer.validUntil);
+        const msgBytes = ethers.utils.concat([type, accountId, from, to, token, amount, fee, nonce, timeRange]);
 
         const signature = signTransactionBytes(this.privateKey, msgBytes);
 
@@ -55,6 +59,8 @@ export class Signer {
             amount: BigNumber.from(transfer.amount).toString(),
             fee: BigNumber.from(transfer.fee).toString(),
             nonce: transfer.nonce,
+            validFrom: transfer.validFrom,
+            validUntil: transfer.validUntil,
             signature
         };
     }
@@ -67,6 +73,8 @@ export class Signer {
         amount: BigNumberish;
         fee: BigNumberish;
         nonce: number;
+        validFrom?: number;
+        validUntil?: number;
     }): Withdraw {
         const typeBytes = new Uint8Array([3]);
         const accountId = serializeAccountId(withdraw.accountId);
@@ -76,6 +84,7 @@ export class Signer {
         const amountBytes = serializeAmountFull(withdraw.amount);
         const feeBytes = serializeFeePacked(withdraw.fee);
         const nonceBytes = serializeNonce(withdraw.nonce);
+        const timeRangeBytes = serializeTimeRange(withdraw.validFrom, withdraw.validUntil);
         const msgBytes = ethers.utils.concat([
             typeBytes,
             accountId,
@@ -84,7 +93,8 @@ export class Signer {
             tokenIdBytes,
             amountBytes,
             feeBytes,
-            nonceBytes
+            nonceBytes,
+            timeRangeBytes
         ]);
         const signature = signTransactionBytes(this.privateKey, msgBytes);
         return {
@@ -96,6 +106,8 @@ export class Signer {
             amount: BigNumber.from(withdraw.amount).toString(),
             fee: BigNumber.from(withdraw.fee).toString(),
             nonce: withdraw.nonce,
+            validFrom: withdraw.validFrom,
+            validUntil: withdraw.validUntil,
             signature
         };
     }
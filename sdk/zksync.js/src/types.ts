@@ -73,6 +73,10 @@ export interface Transfer {
     amount: BigNumberish;
     fee: BigNumberish;
     nonce: number;
+    // Time range (UNIX timestamps in seconds) within which the transaction can be executed.
+    // Omitted bounds mean that the transaction is valid at any moment.
+    validFrom?: number;
+    validUntil?: number;
     signature: Signature;
 }
 
@@ -85,6 +89,10 @@ export interface Withdraw {
     amount: BigNumberish;
     fee: BigNumberish;
     nonce: number;
+    // Time range (UNIX timestamps in seconds) within which the transaction can be executed.
+    // Omitted bounds mean that the transaction is valid at any moment.
+    validFrom?: number;
+    validUntil?: number;
     signature: Signature;
 }
 
@@ -94,6 +102,8 @@ export interface ChangePubKey {
     account: Address;
     newPkHash: PubKeyHash;
     nonce: number;
+    validFrom?: number;
+    validUntil?: number;
     ethSignature: string;
 }
 
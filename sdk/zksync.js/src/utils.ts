@@ -20,6 +20,10 @@ export const ERC20_APPROVE_TRESHOLD = "57896044618658097711785492504343953926634
 
 export const ERC20_DEPOSIT_GAS_LIMIT = BigNumber.from("300000"); // 300k
 
+// Default (unlimited) time range of the transaction, the upper bound is 2^64 - 1.
+export const MIN_TIMESTAMP = 0;
+export const MAX_TIMESTAMP = "18446744073709551615";
+
 const AMOUNT_EXPONENT_BIT_WIDTH = 5;
 const AMOUNT_MANTISSA_BIT_WIDTH = 35;
 const FEE_EXPONENT_BIT_WIDTH = 5;
@@ -279,15 +283,26 @@ export class TokenSet {
     }
 }
 
-export function getChangePubkeyMessage(pubKeyHash: PubKeyHash, nonce: number, accountId: number): string {
+export function getChangePubkeyMessage(
+    pubKeyHash: PubKeyHash,
+    nonce: number,
+    accountId: number,
+    validFrom: BigNumberish = MIN_TIMESTAMP,
+    validUntil: BigNumberish = MAX_TIMESTAMP
+): string {
     const msgNonce = utils.hexlify(serializeNonce(nonce));
     const msgAccId = utils.hexlify(serializeAccountId(accountId));
     const pubKeyHashHex = pubKeyHash.replace("sync:", "").toLowerCase();
+    // The time range is a part of the message only if it's limited.
+    const msgTimeRange = isDefaultTimeRange(validFrom, validUntil)
+        ? ""
+        : `\nvalid from: ${utils.hexlify(serializeTimestamp(validFrom))}` +
+          `\nvalid until: ${utils.hexlify(serializeTimestamp(validUntil))}`;
     const message =
         `Register zkSync pubkey:\n\n` +
         `${pubKeyHashHex}\n` +
         `nonce: ${msgNonce}\n` +
-        `account id: ${msgAccId}\n\n` +
+        `account id: ${msgAccId}${msgTimeRange}\n\n` +
         `Only sign this message for a trusted client!`;
     return message;
 }
@@ -425,6 +440,25 @@ export function serializeNonce(nonce: number): Uint8Array {
     return numberToBytesBE(nonce, 4);
 }
 
+export function serializeTimestamp(time: BigNumberish): Uint8Array {
+    const bnTime = BigNumber.from(time);
+    if (bnTime.lt(MIN_TIMESTAMP) || bnTime.gt(MAX_TIMESTAMP)) {
+        throw new Error("Timestamp is out of the u64 range");
+    }
+    return utils.zeroPad(utils.arrayify(bnTime), 8);
+}
+
+export function serializeTimeRange(
+    validFrom: BigNumberish = MIN_TIMESTAMP,
+    validUntil: BigNumberish = MAX_TIMESTAMP
+): Uint8Array {
+    return utils.concat([serializeTimestamp(validFrom), serializeTimestamp(validUntil)]);
+}
+
+export function isDefaultTimeRange(validFrom: BigNumberish, validUntil: BigNumberish): boolean {
+    return BigNumber.from(validFrom).eq(MIN_TIMESTAMP) && BigNumber.from(validUntil).eq(MAX_TIMESTAMP);
+}
+
 function numberToBytesBE(number: number, bytes: number): Uint8Array {
     const result = new Uint8Array(bytes);
     for (let i = bytes - 1; i >= 0; i--) {
@@ -2,11 +2,13 @@ import { expect } from "chai";
 import {
     closestPackableTransactionAmount,
     closestPackableTransactionFee,
+    getChangePubkeyMessage,
     isTransactionAmountPackable,
     isTransactionFeePackable,
+    serializeTimeRange,
     TokenSet
 } from "../src/utils";
-import { BigNumber } from "ethers";
+import { BigNumber, utils } from "ethers";
 
 describe("Packing and unpacking", function() {
     it("Test basic fee packing/unpacking", function() {
@@ -54,3 +56,20 @@ describe("Token cache resolve", function() {
         expect(() => tokenCache.resolveTokenId("ERC20-2")).to.throw();
     });
 });
+
+describe("Time range", function() {
+    it("Test time range serialization", function() {
+        expect(utils.hexlify(serializeTimeRange())).eq("0x0000000000000000ffffffffffffffff", "default time range");
+        expect(utils.hexlify(serializeTimeRange(100, 200))).eq("0x000000000000006400000000000000c8", "time range");
+        expect(() => serializeTimeRange(-1)).to.throw();
+    });
+
+    it("Test change pubkey message with time range", function() {
+        const pubKeyHash = "sync:fefefefefefefefefefefefefefefefefefefefe";
+        expect(getChangePubkeyMessage(pubKeyHash, 1, 2).length).eq(152, "default time range message");
+
+        const message = getChangePubkeyMessage(pubKeyHash, 1, 2, 100, 200);
+        expect(message.length).eq(215, "limited time range message");
+        expect(message).contains("\nvalid from: 0x0000000000000064\nvalid until: 0x00000000000000c8\n\n");
+    });
+});